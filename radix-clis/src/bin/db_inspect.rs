@@ -0,0 +1,12 @@
+#[cfg(windows)]
+use colored::*;
+use radix_clis::db_inspect;
+use radix_clis::error::exit_with_error;
+
+pub fn main() {
+    #[cfg(windows)]
+    control::set_virtual_terminal(true).unwrap();
+    if let Err(msg) = db_inspect::run() {
+        exit_with_error(msg, 1)
+    }
+}
@@ -0,0 +1,8 @@
+use radix_clis::error::exit_with_error;
+use radix_clis::rtm_lsp;
+
+pub fn main() {
+    if let Err(msg) = rtm_lsp::run() {
+        exit_with_error(msg, 1)
+    }
+}
@@ -0,0 +1,12 @@
+#[cfg(windows)]
+use colored::*;
+use radix_clis::error::exit_with_error;
+use radix_clis::subintent;
+
+pub fn main() {
+    #[cfg(windows)]
+    control::set_virtual_terminal(true).unwrap();
+    if let Err(msg) = subintent::run() {
+        exit_with_error(msg, 1)
+    }
+}
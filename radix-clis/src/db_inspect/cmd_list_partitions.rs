@@ -0,0 +1,23 @@
+use crate::db_inspect::*;
+use crate::prelude::*;
+use radix_substate_store_interface::interface::*;
+
+/// List the partitions in the database, by entity
+#[derive(Parser, Debug)]
+pub struct ListPartitions {
+    #[clap(flatten)]
+    pub database: DatabaseArgs,
+}
+
+impl ListPartitions {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        let database = self.database.open()?;
+        let encoder = AddressBech32Encoder::new(&self.database.network()?);
+
+        for (node_id, partition_number) in database.partitions().read_partition_keys() {
+            writeln!(out, "{} {}", node_id.display(&encoder), partition_number.0)
+                .map_err(Error::IOError)?;
+        }
+        Ok(())
+    }
+}
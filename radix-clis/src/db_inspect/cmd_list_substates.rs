@@ -0,0 +1,42 @@
+use crate::db_inspect::*;
+use crate::prelude::*;
+use radix_substate_store_interface::interface::*;
+
+/// List the raw substates of a partition, as hex sort keys and values
+#[derive(Parser, Debug)]
+pub struct ListSubstates {
+    #[clap(flatten)]
+    pub database: DatabaseArgs,
+
+    /// The entity, as an address or a hex node id
+    pub node_id: String,
+
+    /// The partition number
+    pub partition_number: u8,
+
+    /// The maximum number of substates to list
+    #[clap(short, long)]
+    pub limit: Option<usize>,
+}
+
+impl ListSubstates {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        let database = self.database.open()?;
+        let node_id = parse_node_id(&self.node_id)
+            .ok_or_else(|| Error::InvalidNodeId(self.node_id.clone()))?;
+
+        for (sort_key, value) in database
+            .substates()
+            .list_raw_values(
+                node_id,
+                PartitionNumber(self.partition_number),
+                None::<SubstateKey>,
+            )
+            .take(self.limit.unwrap_or(usize::MAX))
+        {
+            writeln!(out, "{} {}", hex::encode(sort_key.0), hex::encode(value))
+                .map_err(Error::IOError)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use crate::db_inspect::*;
+use crate::prelude::*;
+use radix_substate_store_interface::interface::*;
+
+/// Show the state version of the database, and how many entities and partitions it holds
+#[derive(Parser, Debug)]
+pub struct Summary {
+    #[clap(flatten)]
+    pub database: DatabaseArgs,
+}
+
+impl Summary {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        let database = self.database.open()?;
+
+        writeln!(out, "Open mode: {:?}", database.open_mode()).map_err(Error::IOError)?;
+        if let Some((version, root_hash)) = database.state_version() {
+            writeln!(out, "State version: {}", version).map_err(Error::IOError)?;
+            writeln!(out, "State root hash: {}", root_hash).map_err(Error::IOError)?;
+        }
+
+        let mut nodes: IndexSet<NodeId> = index_set_new();
+        let mut partitions = 0usize;
+        for (node_id, _) in database.partitions().read_partition_keys() {
+            nodes.insert(node_id);
+            partitions += 1;
+        }
+        writeln!(out, "Entities: {}", nodes.len()).map_err(Error::IOError)?;
+        writeln!(out, "Partitions: {}", partitions).map_err(Error::IOError)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::db_inspect::*;
+use crate::prelude::*;
+use std::thread;
+use std::time::Duration;
+
+/// Follow the state version of a replay database while another process commits to it
+#[derive(Parser, Debug)]
+pub struct Tail {
+    #[clap(flatten)]
+    pub database: DatabaseArgs,
+
+    /// How often to catch up with the writing process, in milliseconds
+    #[clap(short, long, default_value = "1000")]
+    pub interval_ms: u64,
+
+    /// Stop after catching up this many times, rather than following until interrupted
+    #[clap(short, long)]
+    pub max_polls: Option<u64>,
+}
+
+impl Tail {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        if self.database.secondary.is_none() {
+            return Err(Error::NotSecondary.into());
+        }
+        let database = self.database.open()?;
+
+        let mut last_version = None;
+        let mut polls = 0;
+        loop {
+            database.try_catch_up_with_primary()?;
+            let (version, root_hash) = database.state_version().ok_or(Error::NoStateTree)?;
+            if last_version != Some(version) {
+                writeln!(out, "{} {}", version, root_hash).map_err(Error::IOError)?;
+                out.flush().map_err(Error::IOError)?;
+                last_version = Some(version);
+            }
+
+            polls += 1;
+            if self.max_polls.is_some_and(|max_polls| polls >= max_polls) {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(self.interval_ms));
+        }
+    }
+}
@@ -0,0 +1,189 @@
+mod cmd_list_partitions;
+mod cmd_list_substates;
+mod cmd_summary;
+mod cmd_tail;
+
+pub use cmd_list_partitions::*;
+pub use cmd_list_substates::*;
+pub use cmd_summary::*;
+pub use cmd_tail::*;
+
+use crate::prelude::*;
+use clap::Subcommand;
+use radix_substate_store_impls::rocks_db::{RocksDBError, RocksDBOpenMode, RocksdbSubstateStore};
+use radix_substate_store_impls::rocks_db_with_merkle_tree::RocksDBWithMerkleTreeSubstateStore;
+use radix_substate_store_impls::state_tree::tree_store::StateTreeRetention;
+use radix_substate_store_interface::interface::*;
+
+/// Inspect a resim or replay database, including while another process is using it
+///
+/// Databases are opened read-only, or as a RocksDB secondary instance with `--secondary`, and
+/// are never written to.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None, name = "db-inspect")]
+pub struct DbInspectCli {
+    #[clap(subcommand)]
+    pub(crate) command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    Summary(Summary),
+    ListPartitions(ListPartitions),
+    ListSubstates(ListSubstates),
+    Tail(Tail),
+}
+
+impl Command {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        match self {
+            Command::Summary(cmd) => cmd.run(out),
+            Command::ListPartitions(cmd) => cmd.run(out),
+            Command::ListSubstates(cmd) => cmd.run(out),
+            Command::Tail(cmd) => cmd.run(out),
+        }
+    }
+}
+
+pub fn run() -> Result<(), String> {
+    let cli = DbInspectCli::parse();
+
+    let mut out = std::io::stdout();
+
+    cli.command.run(&mut out)
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IOError(std::io::Error),
+    DatabaseError(RocksDBError),
+    ParseNetworkError(ParseNetworkError),
+    InvalidNodeId(String),
+    /// The command requires a replay database, which tracks the state version with a state tree.
+    NoStateTree,
+    /// The command requires following the database's writes, which needs `--secondary`.
+    NotSecondary,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<Error> for String {
+    fn from(err: Error) -> String {
+        err.to_string()
+    }
+}
+
+/// The arguments to choose which database is inspected, and how it is opened.
+#[derive(clap::Args, Debug)]
+pub struct DatabaseArgs {
+    /// Path to the database directory
+    pub database_dir: PathBuf,
+
+    /// The database is a resim ledger, rather than a replay database with a state tree
+    #[clap(long)]
+    pub simulator: bool,
+
+    /// Open the database as a RocksDB secondary instance, keeping its own files in the given
+    /// directory, instead of read-only. This allows following the writes of another process.
+    #[clap(long)]
+    pub secondary: Option<PathBuf>,
+
+    /// The network of the addresses, defaults to Simulator for resim ledgers and Mainnet otherwise
+    #[clap(short, long)]
+    pub network: Option<String>,
+}
+
+impl DatabaseArgs {
+    pub fn open_mode(&self) -> RocksDBOpenMode {
+        match &self.secondary {
+            Some(secondary_path) => RocksDBOpenMode::Secondary {
+                secondary_path: secondary_path.clone(),
+            },
+            None => RocksDBOpenMode::ReadOnly,
+        }
+    }
+
+    pub fn open(&self) -> Result<InspectedDatabase, Error> {
+        let open_mode = self.open_mode();
+        let database = if self.simulator {
+            InspectedDatabase::Simulator(
+                RocksdbSubstateStore::with_options_and_mode(
+                    &Default::default(),
+                    self.database_dir.clone(),
+                    open_mode,
+                )
+                .map_err(Error::DatabaseError)?,
+            )
+        } else {
+            InspectedDatabase::MerkleTree(
+                RocksDBWithMerkleTreeSubstateStore::with_options_and_mode(
+                    &Default::default(),
+                    self.database_dir.clone(),
+                    StateTreeRetention::CURRENT_VERSION_ONLY,
+                    open_mode,
+                )
+                .map_err(Error::DatabaseError)?,
+            )
+        };
+        Ok(database)
+    }
+
+    pub fn network(&self) -> Result<NetworkDefinition, Error> {
+        match &self.network {
+            Some(n) => NetworkDefinition::from_str(n).map_err(Error::ParseNetworkError),
+            None if self.simulator => Ok(NetworkDefinition::simulator()),
+            None => Ok(NetworkDefinition::mainnet()),
+        }
+    }
+}
+
+/// A database opened for inspection, of either of the kinds written by the CLIs.
+pub enum InspectedDatabase {
+    Simulator(RocksdbSubstateStore),
+    MerkleTree(RocksDBWithMerkleTreeSubstateStore),
+}
+
+impl InspectedDatabase {
+    pub fn substates(&self) -> &dyn SubstateDatabase {
+        match self {
+            InspectedDatabase::Simulator(db) => db,
+            InspectedDatabase::MerkleTree(db) => db,
+        }
+    }
+
+    pub fn partitions(&self) -> &dyn ListableSubstateDatabase {
+        match self {
+            InspectedDatabase::Simulator(db) => db,
+            InspectedDatabase::MerkleTree(db) => db,
+        }
+    }
+
+    /// Returns the current state version and state root hash, if the database has a state tree.
+    pub fn state_version(&self) -> Option<(u64, Hash)> {
+        match self {
+            InspectedDatabase::Simulator(_) => None,
+            InspectedDatabase::MerkleTree(db) => {
+                Some((db.get_current_version(), db.get_current_root_hash()))
+            }
+        }
+    }
+
+    pub fn open_mode(&self) -> &RocksDBOpenMode {
+        match self {
+            InspectedDatabase::Simulator(db) => db.open_mode(),
+            InspectedDatabase::MerkleTree(db) => db.open_mode(),
+        }
+    }
+
+    pub fn try_catch_up_with_primary(&self) -> Result<(), Error> {
+        match self {
+            InspectedDatabase::Simulator(db) => db.try_catch_up_with_primary(),
+            InspectedDatabase::MerkleTree(db) => db.try_catch_up_with_primary(),
+        }
+        .map_err(Error::DatabaseError)
+    }
+}
@@ -1,17 +1,25 @@
 #![allow(clippy::result_large_err, clippy::too_many_arguments)]
 
+/// Database inspection CLI.
+pub mod db_inspect;
 /// Transaction replay.
 pub mod replay;
 /// Radix Engine Simulator CLI.
 pub mod resim;
+/// Radix transaction manifest language server.
+pub mod rtm_lsp;
 /// Radix transaction manifest compiler CLI.
 pub mod rtmc;
 /// Radix transaction manifest decompiler CLI.
 pub mod rtmd;
+/// Radix transaction manifest formatter CLI.
+pub mod rtmfmt;
 /// Scrypto CLI.
 pub mod scrypto;
 /// Stubs Generator CLI.
 pub mod scrypto_bindgen;
+/// Subintent signing coordination CLI.
+pub mod subintent;
 /// Utility functions.
 pub mod utils;
 
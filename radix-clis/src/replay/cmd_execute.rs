@@ -1,12 +1,15 @@
-use super::ledger_transaction_execution::execute_ledger_transaction;
-use super::txn_reader::TxnReader;
+use super::history_queries::*;
+use super::ledger_transaction_execution::{create_vm_modules, execute_ledger_transaction};
+use super::txn_reader::{ExpectedStateRoots, TxnReader};
 use super::Error;
 use clap::Parser;
 use flate2::read::GzDecoder;
 use flume;
 use radix_common::prelude::*;
-use radix_engine::vm::VmModules;
-use radix_substate_store_impls::rocks_db_with_merkle_tree::RocksDBWithMerkleTreeSubstateStore;
+use radix_substate_store_impls::rocks_db_with_merkle_tree::{
+    compute_state_tree_update, RocksDBWithMerkleTreeSubstateStore,
+};
+use radix_substate_store_interface::db_key_mapper::*;
 use radix_substate_store_interface::interface::*;
 use std::fs::File;
 use std::path::PathBuf;
@@ -29,9 +32,24 @@ pub struct TxnExecute {
     #[clap(short, long)]
     pub max_version: Option<u64>,
 
+    /// A side-car file with the expected state root hashes, one `<version> <state_root_hash>` pair
+    /// per line. Execution stops with a divergence report at the first mismatching version.
+    /// Only the state roots are verified, not the transaction and receipt roots of the ledger
+    /// hashes.
+    #[clap(long)]
+    pub expected_state_roots: Option<PathBuf>,
+
     /// Trace transaction execution
     #[clap(long)]
     pub trace: bool,
+
+    /// A directory for caching the code instrumented when packages are published, which can be
+    /// reused across runs
+    #[clap(long)]
+    pub instrumented_code_cache: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub history: HistoryArgs,
 }
 
 impl TxnExecute {
@@ -41,15 +59,24 @@ impl TxnExecute {
             None => NetworkDefinition::mainnet(),
         };
 
+        let history_queries = self.history.parse_queries()?;
+
         let cur_version = {
             let database = RocksDBWithMerkleTreeSubstateStore::standard(self.database_dir.clone());
             let cur_version = database.get_current_version();
             if cur_version >= self.max_version.unwrap_or(u64::MAX) {
+                for (version, node_id) in history_queries {
+                    print_substates_at_version(&database, version, &node_id);
+                }
                 return Ok(());
             }
             cur_version
         };
         let to_version = self.max_version;
+        let expected_state_roots = match &self.expected_state_roots {
+            Some(path) => ExpectedStateRoots::from_file(path)?,
+            None => ExpectedStateRoots::default(),
+        };
 
         let start = std::time::Instant::now();
         let (tx, rx) = flume::bounded(10);
@@ -69,10 +96,12 @@ impl TxnExecute {
             thread::spawn(move || txn_reader.read(cur_version, to_version, tx));
 
         // txn executor
-        let mut database = RocksDBWithMerkleTreeSubstateStore::standard(self.database_dir.clone());
+        let mut database = self.history.open_database(self.database_dir.clone());
         let trace = self.trace;
-        let txn_write_thread_handle = thread::spawn(move || {
-            let vm_modules = VmModules::default();
+        let instrumented_code_cache_dir = self.instrumented_code_cache.clone();
+        let txn_write_thread_handle = thread::spawn(move || -> Result<(), Error> {
+            let vm_modules = create_vm_modules(instrumented_code_cache_dir);
+            let mut last_verified_version = None;
             let iter = rx.iter();
             for tx_payload in iter {
                 let (_hash, receipt) = execute_ledger_transaction(
@@ -84,6 +113,30 @@ impl TxnExecute {
                 );
                 let state_updates = receipt.into_state_updates();
                 let database_updates = state_updates.create_database_updates();
+
+                // The state tree update is computed upfront, so that a divergent version never
+                // gets committed and can be re-executed (e.g. with tracing) after a fix
+                let current_version = database.get_current_version();
+                if let Some(expected) = expected_state_roots.get(current_version + 1) {
+                    let (_, actual) =
+                        compute_state_tree_update(&database, current_version, &database_updates);
+                    if actual != *expected {
+                        print_divergence_report(
+                            &network,
+                            current_version + 1,
+                            *expected,
+                            actual,
+                            last_verified_version,
+                            &database_updates,
+                        );
+                        return Err(Error::StateRootMismatch {
+                            version: current_version + 1,
+                            expected: *expected,
+                            actual,
+                        });
+                    }
+                    last_verified_version = Some(current_version + 1);
+                }
                 database.commit(&database_updates);
 
                 let new_state_root_hash = database.get_current_root_hash();
@@ -98,10 +151,22 @@ impl TxnExecute {
             println!("Time elapsed: {:?}", duration);
             println!("State version: {}", database.get_current_version());
             println!("State root hash: {}", database.get_current_root_hash());
+            if !expected_state_roots.is_empty() {
+                println!(
+                    "Last verified version: {}",
+                    last_verified_version.map_or("None".to_owned(), |v| v.to_string())
+                );
+            }
+
+            for (version, node_id) in history_queries {
+                print_substates_at_version(&database, version, &node_id);
+            }
+
+            Ok(())
         });
 
         txn_read_thread_handle.join().unwrap()?;
-        txn_write_thread_handle.join().unwrap();
+        txn_write_thread_handle.join().unwrap()?;
 
         Ok(())
     }
@@ -116,3 +181,71 @@ fn print_progress(duration: Duration, new_version: u64, new_root: Hash) {
         new_version, new_root, hours, minutes, seconds
     );
 }
+
+/// Prints the partitions and substates updated by the transaction at the first diverging version.
+///
+/// If the state root at the previous version was verified, the divergence is fully contained in
+/// these updates; otherwise, it may also stem from any transaction since the last verified version.
+fn print_divergence_report(
+    network: &NetworkDefinition,
+    version: u64,
+    expected: Hash,
+    actual: Hash,
+    last_verified_version: Option<u64>,
+    database_updates: &DatabaseUpdates,
+) {
+    let address_encoder = AddressBech32Encoder::new(network);
+    println!("State root divergence at version {}", version);
+    println!("Expected state root hash: {}", expected);
+    println!("Actual state root hash: {}", actual);
+    match last_verified_version {
+        Some(last_verified_version) if last_verified_version + 1 == version => {
+            println!("Previous version {} matched", last_verified_version)
+        }
+        Some(last_verified_version) => println!(
+            "Last matching version: {}, the divergence may stem from any transaction after it",
+            last_verified_version
+        ),
+        None => println!("No earlier version was verified"),
+    }
+    println!("Updated partitions:");
+    for (db_node_key, node_updates) in &database_updates.node_updates {
+        let node_id = SpreadPrefixKeyMapper::from_db_node_key(db_node_key);
+        let node_id = address_encoder
+            .encode(node_id.as_bytes())
+            .unwrap_or_else(|_| node_id.to_hex());
+        for (partition_num, partition_updates) in &node_updates.partition_updates {
+            match partition_updates {
+                PartitionDatabaseUpdates::Delta { substate_updates } => {
+                    println!("  {} partition {}:", node_id, partition_num);
+                    for (sort_key, update) in substate_updates {
+                        match update {
+                            DatabaseUpdate::Set(value) => println!(
+                                "    set {} ({} bytes): {}",
+                                hex::encode(&sort_key.0),
+                                value.len(),
+                                hex::encode(value)
+                            ),
+                            DatabaseUpdate::Delete => {
+                                println!("    delete {}", hex::encode(&sort_key.0))
+                            }
+                        }
+                    }
+                }
+                PartitionDatabaseUpdates::Reset {
+                    new_substate_values,
+                } => {
+                    println!("  {} partition {} (reset):", node_id, partition_num);
+                    for (sort_key, value) in new_substate_values {
+                        println!(
+                            "    set {} ({} bytes): {}",
+                            hex::encode(&sort_key.0),
+                            value.len(),
+                            hex::encode(value)
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
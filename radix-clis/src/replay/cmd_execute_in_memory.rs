@@ -1,11 +1,10 @@
-use super::ledger_transaction_execution::execute_ledger_transaction;
+use super::ledger_transaction_execution::{create_vm_modules, execute_ledger_transaction};
 use super::txn_reader::TxnReader;
 use super::Error;
 use clap::Parser;
 use flate2::read::GzDecoder;
 use flume;
 use radix_common::prelude::*;
-use radix_engine::vm::VmModules;
 use radix_substate_store_impls::memory_db::InMemorySubstateDatabase;
 use radix_substate_store_impls::state_tree_support::StateTreeUpdatingDatabase;
 use radix_substate_store_interface::interface::*;
@@ -35,6 +34,11 @@ pub struct TxnExecuteInMemory {
     /// Trace transaction execution
     #[clap(long)]
     pub trace: bool,
+
+    /// A directory for caching the code instrumented when packages are published, which can be
+    /// reused across runs
+    #[clap(long)]
+    pub instrumented_code_cache: Option<PathBuf>,
 }
 
 impl TxnExecuteInMemory {
@@ -83,8 +87,9 @@ impl TxnExecuteInMemory {
         let substate_database = InMemorySubstateDatabase::standard();
         let mut database = StateTreeUpdatingDatabase::new(substate_database);
         let trace = self.trace;
+        let instrumented_code_cache_dir = self.instrumented_code_cache.clone();
         let txn_write_thread_handle = thread::spawn(move || {
-            let vm_modules = VmModules::default();
+            let vm_modules = create_vm_modules(instrumented_code_cache_dir);
             let iter = rx.iter();
             for tx_payload in iter {
                 let (_hash, receipt) = execute_ledger_transaction(
@@ -1,12 +1,13 @@
-use super::ledger_transaction_execution::execute_ledger_transaction;
+use super::history_queries::*;
+use super::ledger_transaction_execution::{create_vm_modules, execute_ledger_transaction};
 use super::Error;
 use clap::Parser;
 use flume;
 use flume::Sender;
 use radix_common::prelude::*;
-use radix_engine::vm::VmModules;
 use radix_substate_store_impls::rocks_db_with_merkle_tree::RocksDBWithMerkleTreeSubstateStore;
 use radix_substate_store_interface::interface::*;
+use radix_substate_store_queries::event_index::*;
 use radix_transactions::prelude::*;
 use rocksdb::{Direction, IteratorMode, Options, DB};
 use std::path::PathBuf;
@@ -31,6 +32,18 @@ pub struct TxnSync {
     /// Trace transaction execution
     #[clap(long)]
     pub trace: bool,
+
+    /// A directory for caching the code instrumented when packages are published, which can be
+    /// reused across runs
+    #[clap(long)]
+    pub instrumented_code_cache: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub history: HistoryArgs,
+
+    /// Index the events of the executed transactions by state version, in the database
+    #[clap(long)]
+    pub index_events: bool,
 }
 
 impl TxnSync {
@@ -40,10 +53,15 @@ impl TxnSync {
             None => NetworkDefinition::mainnet(),
         };
 
+        let history_queries = self.history.parse_queries()?;
+
         let cur_version = {
             let database = RocksDBWithMerkleTreeSubstateStore::standard(self.database_dir.clone());
             let cur_version = database.get_current_version();
             if cur_version >= self.max_version.unwrap_or(u64::MAX) {
+                for (version, node_id) in history_queries {
+                    print_substates_at_version(&database, version, &node_id);
+                }
                 return Ok(());
             }
             cur_version
@@ -59,10 +77,12 @@ impl TxnSync {
             thread::spawn(move || txn_reader.read(cur_version, to_version, tx));
 
         // txn executor
-        let mut database = RocksDBWithMerkleTreeSubstateStore::standard(self.database_dir.clone());
+        let mut database = self.history.open_database(self.database_dir.clone());
         let trace = self.trace;
+        let instrumented_code_cache_dir = self.instrumented_code_cache.clone();
+        let index_events = self.index_events;
         let txn_write_thread_handle = thread::spawn(move || {
-            let vm_modules = VmModules::default();
+            let vm_modules = create_vm_modules(instrumented_code_cache_dir);
             let iter = rx.iter();
             for (tx_payload, expected_state_root_hash) in iter {
                 let (_hash, receipt) = execute_ledger_transaction(
@@ -72,6 +92,11 @@ impl TxnSync {
                     &tx_payload,
                     trace,
                 );
+                let events = if index_events {
+                    receipt.application_events().to_vec()
+                } else {
+                    vec![]
+                };
                 let state_updates = receipt.into_state_updates();
                 let database_updates = state_updates.create_database_updates();
 
@@ -90,7 +115,12 @@ impl TxnSync {
                         new_version, expected_state_root_hash, new_state_root_hash
                     );
                 }
-                database.commit(&database_updates);
+                if index_events {
+                    let events = resolve_uncommitted_events(&database, &state_updates, &events);
+                    database.commit_and_index_events(&database_updates, new_version, events);
+                } else {
+                    database.commit(&database_updates);
+                }
 
                 // print progress
                 if new_version < 1000 || new_version.is_multiple_of(1000) {
@@ -102,6 +132,10 @@ impl TxnSync {
             println!("Time elapsed: {:?}", duration);
             println!("State version: {}", database.get_current_version());
             println!("State root hash: {}", database.get_current_root_hash());
+
+            for (version, node_id) in history_queries {
+                print_substates_at_version(&database, version, &node_id);
+            }
         });
 
         txn_read_thread_handle.join().unwrap()?;
@@ -1,4 +1,4 @@
-use radix_common::prelude::ParseNetworkError;
+use radix_common::prelude::{Hash, ParseNetworkError};
 use std::fmt;
 
 #[derive(Debug)]
@@ -9,6 +9,13 @@ pub enum Error {
     InvalidTransactionArchive,
     InvalidTransactionSource,
     InvalidBreakpoints(String),
+    InvalidHistoryQueries(String),
+    InvalidExpectedStateRoots(usize, String),
+    StateRootMismatch {
+        version: u64,
+        expected: Hash,
+        actual: Hash,
+    },
 }
 
 impl fmt::Display for Error {
@@ -0,0 +1,84 @@
+use super::Error;
+use crate::utils::parse_node_id;
+use radix_common::prelude::*;
+use radix_substate_store_impls::rocks_db_with_merkle_tree::RocksDBWithMerkleTreeSubstateStore;
+use radix_substate_store_impls::state_tree::tree_store::StateTreeRetention;
+use radix_substate_store_interface::db_key_mapper::*;
+use std::path::PathBuf;
+
+/// The arguments to query the substates of entities as they were at past state versions.
+#[derive(clap::Args, Debug, Default, Clone)]
+pub struct HistoryArgs {
+    /// The number of most recent state versions (including the current one) to retain the state
+    /// tree and the substate values of, so that they can be queried. By default, only the current
+    /// version is retained.
+    ///
+    /// Note: only the values upserted while retaining past versions are recorded.
+    #[clap(long)]
+    pub retained_versions: Option<u64>,
+
+    /// Entities to print the substates of, as they were at a retained version, once the execution
+    /// completes, in format of comma separated `<version>:<address or hex node id>`
+    #[clap(long)]
+    pub history_queries: Option<String>,
+}
+
+impl HistoryArgs {
+    /// Opens the database for executing transactions, with the configured retention.
+    pub fn open_database(&self, root: PathBuf) -> RocksDBWithMerkleTreeSubstateStore {
+        match self.retained_versions {
+            Some(count) => RocksDBWithMerkleTreeSubstateStore::with_options_and_retention(
+                &Default::default(),
+                root,
+                StateTreeRetention::RecentVersions(count),
+            )
+            .with_historical_values(),
+            None => RocksDBWithMerkleTreeSubstateStore::standard(root),
+        }
+    }
+
+    pub fn parse_queries(&self) -> Result<Vec<(u64, NodeId)>, Error> {
+        let Some(queries) = &self.history_queries else {
+            return Ok(vec![]);
+        };
+        queries
+            .split(",")
+            .map(|query| {
+                query
+                    .trim()
+                    .split_once(":")
+                    .and_then(|(version, node_id)| {
+                        Some((u64::from_str(version).ok()?, parse_node_id(node_id)?))
+                    })
+                    .ok_or_else(|| Error::InvalidHistoryQueries(queries.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Prints the substates of the given entity, as they were at the given state version.
+pub fn print_substates_at_version(
+    database: &RocksDBWithMerkleTreeSubstateStore,
+    version: u64,
+    node_id: &NodeId,
+) {
+    println!(
+        "Substates of {} at version {}:",
+        hex::encode(node_id.0),
+        version
+    );
+    let node_key = SpreadPrefixKeyMapper::to_db_node_key(node_id);
+    let Some(substates) = database.list_entity_substate_values_at_version(version, &node_key)
+    else {
+        println!("Version not retained");
+        return;
+    };
+    for (partition_key, sort_key, value) in substates {
+        println!(
+            "{} {} {}",
+            partition_key.partition_num,
+            hex::encode(sort_key.0),
+            value.map_or("(not recorded)".to_owned(), hex::encode)
+        );
+    }
+}
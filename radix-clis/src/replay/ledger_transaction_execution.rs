@@ -2,12 +2,27 @@ use radix_common::prelude::*;
 use radix_engine::system::bootstrap::*;
 use radix_engine::transaction::{
     execute_transaction, ExecutionConfig, TransactionFeeSummary, TransactionReceipt,
+    TransactionResult,
 };
+use radix_engine::vm::wasm::PersistentInstrumentedCodeCache;
 use radix_engine::vm::*;
-use radix_engine_interface::prelude::system_execution;
+use radix_engine_interface::prelude::{system_execution, EventTypeIdentifier};
 use radix_substate_store_interface::interface::SubstateDatabase;
 use radix_transactions::prelude::*;
 use radix_transactions::validation::*;
+use std::path::PathBuf;
+
+/// Creates the VMs, optionally caching the instrumented code of the published packages in the
+/// given directory, so that a restarted replay does not need to re-instrument them.
+pub fn create_vm_modules(instrumented_code_cache_dir: Option<PathBuf>) -> DefaultVmModules {
+    let mut vm_modules = VmModules::default();
+    if let Some(directory) = instrumented_code_cache_dir {
+        vm_modules.scrypto_vm = vm_modules
+            .scrypto_vm
+            .with_instrumented_code_cache(PersistentInstrumentedCodeCache::new(directory));
+    }
+    vm_modules
+}
 
 pub enum LedgerTransactionReceipt {
     Flash(Box<FlashReceipt>),
@@ -26,6 +41,16 @@ impl LedgerTransactionReceipt {
         }
     }
 
+    pub fn application_events(&self) -> &[(EventTypeIdentifier, Vec<u8>)] {
+        match self {
+            LedgerTransactionReceipt::Standard(receipt) => match &receipt.result {
+                TransactionResult::Commit(commit) => &commit.application_events,
+                _ => &[],
+            },
+            _ => &[],
+        }
+    }
+
     pub fn fee_summary(&self) -> Option<&TransactionFeeSummary> {
         match self {
             LedgerTransactionReceipt::Flash(_) => None,
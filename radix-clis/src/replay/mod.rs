@@ -1,3 +1,4 @@
+pub mod history_queries;
 pub mod ledger_transaction_execution;
 pub mod txn_reader;
 
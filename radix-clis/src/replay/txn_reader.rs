@@ -7,7 +7,7 @@ use radix_transactions::model::RawLedgerTransaction;
 use rocksdb::{Direction, IteratorMode, Options, DB};
 use std::fs::File;
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::Duration;
 use tar::Archive;
@@ -48,7 +48,10 @@ impl TxnReader {
                         }
                     }
 
-                    tx.send(RawLedgerTransaction::from_vec(tx_payload)).unwrap();
+                    // The receiver is gone if the execution has stopped early
+                    if tx.send(RawLedgerTransaction::from_vec(tx_payload)).is_err() {
+                        break;
+                    }
                 }
             }
             TxnReader::StateManagerDatabaseDir(db_dir) => {
@@ -79,8 +82,12 @@ impl TxnReader {
                     );
                     for next_txn in txn_iter {
                         let next_txn = next_txn.unwrap();
-                        tx.send(RawLedgerTransaction::from_vec(next_txn.1.to_vec()))
-                            .unwrap();
+                        if tx
+                            .send(RawLedgerTransaction::from_vec(next_txn.1.to_vec()))
+                            .is_err()
+                        {
+                            return Ok(());
+                        }
                     }
                     thread::sleep(Duration::from_secs(1));
                 }
@@ -90,3 +97,83 @@ impl TxnReader {
         Ok(())
     }
 }
+
+/// The state root hashes expected after committing the transactions at given state versions.
+///
+/// Of the ledger hashes which a node commits to at each version, only the state root can be
+/// verified, as it is the root of the state tree maintained by the substate store. The transaction
+/// and receipt roots are roots of the node's accumulator trees, which aren't maintained here, so
+/// a divergence in e.g. the recorded receipts of a transaction which leaves the state untouched
+/// goes unnoticed.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedStateRoots(BTreeMap<u64, Hash>);
+
+impl ExpectedStateRoots {
+    /// Reads a side-car file with one `<version> <state_root_hash>` pair per line.
+    ///
+    /// Empty lines and lines starting with `#` are ignored. Versions don't need to be contiguous,
+    /// e.g. a file may only contain the state roots at every epoch change.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path).map_err(Error::IOError)?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let mut state_roots = BTreeMap::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut tokens = line.split_whitespace();
+            let entry = match (tokens.next(), tokens.next(), tokens.next()) {
+                (Some(version), Some(hash), None) => {
+                    u64::from_str(version).ok().zip(Hash::from_str(hash).ok())
+                }
+                _ => None,
+            };
+            let (version, hash) = entry
+                .ok_or_else(|| Error::InvalidExpectedStateRoots(index + 1, line.to_owned()))?;
+            state_roots.insert(version, hash);
+        }
+        Ok(Self(state_roots))
+    }
+
+    pub fn get(&self, version: u64) -> Option<&Hash> {
+        self.0.get(&version)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_expected_state_roots() {
+        let hash = Hash([7u8; Hash::LENGTH]);
+        let state_roots =
+            ExpectedStateRoots::parse(&format!("# version root\n\n1 {}\n  42\t{}  \n", hash, hash))
+                .unwrap();
+        assert_eq!(state_roots.len(), 2);
+        assert_eq!(state_roots.get(1), Some(&hash));
+        assert_eq!(state_roots.get(42), Some(&hash));
+        assert_eq!(state_roots.get(2), None);
+
+        assert!(matches!(
+            ExpectedStateRoots::parse(&format!("1 {}\n2\n", hash)),
+            Err(Error::InvalidExpectedStateRoots(2, _))
+        ));
+        assert!(matches!(
+            ExpectedStateRoots::parse("1 not-a-hash"),
+            Err(Error::InvalidExpectedStateRoots(1, _))
+        ));
+    }
+}
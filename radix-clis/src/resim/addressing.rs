@@ -148,6 +148,53 @@ impl fmt::Debug for SimulatorComponentAddress {
     }
 }
 
+/// The address of any entity, including internal ones such as vaults.
+#[derive(Clone)]
+pub struct SimulatorNodeId(pub NodeId);
+
+impl From<SimulatorNodeId> for NodeId {
+    fn from(simulator_node_id: SimulatorNodeId) -> Self {
+        simulator_node_id.0
+    }
+}
+
+impl From<NodeId> for SimulatorNodeId {
+    fn from(node_id: NodeId) -> Self {
+        Self(node_id)
+    }
+}
+
+impl FromStr for SimulatorNodeId {
+    type Err = AddressError;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        hex::decode(address)
+            .ok()
+            .or(AddressBech32Decoder::for_simulator()
+                .validate_and_decode(address)
+                .ok()
+                .map(|(_, data)| data))
+            .and_then(|data| <[u8; NodeId::LENGTH]>::try_from(data).ok())
+            .ok_or(AddressError::InvalidAddress(address.to_string()))
+            .map(|bytes| Self(NodeId(bytes)))
+    }
+}
+
+impl fmt::Display for SimulatorNodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match AddressBech32Encoder::for_simulator().encode(self.0.as_bytes()) {
+            Ok(address) => write!(f, "{}", address),
+            Err(_) => write!(f, "{}", hex::encode(self.0.as_bytes())),
+        }
+    }
+}
+
+impl fmt::Debug for SimulatorNodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self)
+    }
+}
+
 #[derive(Clone)]
 pub struct SimulatorNonFungibleGlobalId(pub NonFungibleGlobalId);
 
@@ -42,7 +42,11 @@ pub struct CallFunction {
 }
 
 impl CallFunction {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
         let address_bech32_decoder = AddressBech32Decoder::for_simulator();
 
         let default_account = get_default_account()?;
@@ -61,6 +65,7 @@ impl CallFunction {
         }
         let manifest = self
             .add_call_function_instruction_with_schema(
+                session,
                 builder,
                 &address_bech32_decoder,
                 self.package_address.0,
@@ -72,6 +77,7 @@ impl CallFunction {
             .try_deposit_entire_worktop_or_refund(default_account, None)
             .build();
         handle_manifest(
+            session,
             manifest.into(),
             &self.signing_keys,
             &self.network,
@@ -92,6 +98,7 @@ impl CallFunction {
     /// otherwise, they will be taken from transaction worktop.
     pub fn add_call_function_instruction_with_schema(
         &self,
+        session: &mut SimulatorSession,
         builder: ManifestBuilder,
         address_bech32_decoder: &AddressBech32Decoder,
         package_address: PackageAddress,
@@ -100,7 +107,7 @@ impl CallFunction {
         args: Vec<String>,
         account: Option<ComponentAddress>,
     ) -> Result<ManifestBuilder, Error> {
-        let bp_interface = export_blueprint_interface(package_address, &blueprint_name)?;
+        let bp_interface = export_blueprint_interface(session, package_address, &blueprint_name)?;
 
         let function_schema = bp_interface
             .find_function(function_name.as_str())
@@ -112,7 +119,7 @@ impl CallFunction {
 
         let (schema, index) = match function_schema.input {
             BlueprintPayloadDef::Static(ScopedTypeId(hash, index)) => {
-                let schema = export_schema(package_address.as_node_id(), hash)?;
+                let schema = export_schema(session, package_address.as_node_id(), hash)?;
                 (schema, index)
             }
             BlueprintPayloadDef::Generic(_instance_index) => {
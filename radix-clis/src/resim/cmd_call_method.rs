@@ -40,7 +40,11 @@ pub struct CallMethod {
 }
 
 impl CallMethod {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
         let address_bech32_decoder = AddressBech32Decoder::for_simulator();
 
         let default_account = get_default_account()?;
@@ -59,6 +63,7 @@ impl CallMethod {
 
         let manifest = self
             .add_call_method_instruction_with_schema(
+                session,
                 builder,
                 &address_bech32_decoder,
                 self.component_address.0,
@@ -69,6 +74,7 @@ impl CallMethod {
             .try_deposit_entire_worktop_or_refund(default_account, None)
             .build();
         handle_manifest(
+            session,
             manifest.into(),
             &self.signing_keys,
             &self.network,
@@ -89,6 +95,7 @@ impl CallMethod {
     /// otherwise, they will be taken from transaction worktop.
     pub fn add_call_method_instruction_with_schema(
         &self,
+        session: &mut SimulatorSession,
         builder: ManifestBuilder,
         address_bech32_decoder: &AddressBech32Decoder,
         component_address: ComponentAddress,
@@ -96,11 +103,11 @@ impl CallMethod {
         args: Vec<String>,
         account: Option<ComponentAddress>,
     ) -> Result<ManifestBuilder, Error> {
-        let object_info = export_object_info(component_address)?;
+        let object_info = export_object_info(session, component_address)?;
         let bp_info = object_info.blueprint_info;
         let bp_id = bp_info.blueprint_id;
         let bp_interface =
-            export_blueprint_interface(bp_id.package_address, &bp_id.blueprint_name)?;
+            export_blueprint_interface(session, bp_id.package_address, &bp_id.blueprint_name)?;
 
         let function_schema = bp_interface
             .find_method(method_name.as_str())
@@ -112,7 +119,8 @@ impl CallMethod {
 
         let (schema, index) = match function_schema.input {
             BlueprintPayloadDef::Static(ScopedTypeId(schema_hash, index)) => {
-                let schema = export_schema(bp_id.package_address.as_node_id(), schema_hash)?;
+                let schema =
+                    export_schema(session, bp_id.package_address.as_node_id(), schema_hash)?;
                 (schema, index)
             }
             BlueprintPayloadDef::Generic(generic_index) => {
@@ -123,7 +131,8 @@ impl CallMethod {
 
                 match type_subst_ref {
                     GenericSubstitution::Local(type_id) => {
-                        let schema = export_schema(bp_id.package_address.as_node_id(), type_id.0)?;
+                        let schema =
+                            export_schema(session, bp_id.package_address.as_node_id(), type_id.0)?;
                         (schema, type_id.1)
                     }
                     GenericSubstitution::Remote(_) => {
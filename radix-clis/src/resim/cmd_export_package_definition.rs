@@ -18,8 +18,12 @@ pub struct ExportPackageDefinition {
 }
 
 impl ExportPackageDefinition {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
-        match export_package_schema(self.package_address.0) {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
+        match export_package_schema(session, self.package_address.0) {
             Ok(schema) => {
                 write_ensuring_folder_exists(
                     &self.output,
@@ -0,0 +1,64 @@
+use clap::Parser;
+use radix_common::prelude::*;
+use radix_engine::system::system_db_reader::SystemDatabaseReader;
+use scrypto_bindgen::json_schema::*;
+
+use crate::resim::*;
+use crate::scrypto_bindgen::SchemaResolver;
+
+/// Export the JSON Schema of the payloads (function inputs and outputs, events and state) of a
+/// package's blueprints
+#[derive(Parser, Debug)]
+pub struct ExportPackageJsonSchema {
+    /// The package ID
+    pub package_address: SimulatorPackageAddress,
+
+    /// The output file
+    pub output: PathBuf,
+
+    /// Output an OpenAPI document (with the schemas under `components/schemas`), instead of a
+    /// standalone JSON Schema document
+    #[clap(long)]
+    pub open_api: bool,
+}
+
+impl ExportPackageJsonSchema {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
+        let SimulatorEnvironment { db, .. } = session.env()?;
+        let package_address = self.package_address.0;
+
+        let definition = SystemDatabaseReader::new(&*db).get_package_definition(package_address);
+        if definition.is_empty() {
+            return Err(Error::PackageNotFound(package_address).into());
+        }
+        let format = if self.open_api {
+            JsonSchemaDocumentFormat::OpenApi
+        } else {
+            JsonSchemaDocumentFormat::JsonSchema
+        };
+        let document = package_json_schema_from_package_definition(
+            definition,
+            &SchemaResolver::new(package_address, &*db),
+            &AddressBech32Encoder::for_simulator(),
+            format,
+        )
+        .map_err(Error::PackageSchemaError)?;
+
+        write_ensuring_folder_exists(
+            &self.output,
+            serde_json::to_string_pretty(&document).unwrap(),
+        )
+        .map_err(Error::IOError)?;
+        writeln!(
+            out,
+            "Package JSON schema exported to {}",
+            self.output.to_str().unwrap()
+        )
+        .map_err(Error::IOError)?;
+        Ok(())
+    }
+}
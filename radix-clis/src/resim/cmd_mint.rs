@@ -35,7 +35,11 @@ pub struct Mint {
 }
 
 impl Mint {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
         let address_bech32_decoder = AddressBech32Decoder::for_simulator();
 
         let default_account = get_default_account()?;
@@ -56,6 +60,7 @@ impl Mint {
             .try_deposit_entire_worktop_or_refund(default_account, None)
             .build();
         handle_manifest(
+            session,
             manifest.into(),
             &self.signing_keys,
             &self.network,
@@ -28,7 +28,11 @@ pub struct NewAccount {
 }
 
 impl NewAccount {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
         let secret = rand::thread_rng().gen::<[u8; 32]>();
         let private_key = Secp256k1PrivateKey::from_bytes(&secret).unwrap();
         let public_key = private_key.public_key();
@@ -40,6 +44,7 @@ impl NewAccount {
             .build();
 
         let receipt = handle_manifest(
+            session,
             manifest.into(),
             &Some("".to_string()), // explicit empty signer public keys
             &self.network,
@@ -78,6 +83,7 @@ impl NewAccount {
                 .try_deposit_entire_worktop_or_refund(account, None)
                 .build();
             let receipt = handle_manifest(
+                session,
                 manifest.into(),
                 &Some("".to_string()), // explicit empty signer public keys
                 &self.network,
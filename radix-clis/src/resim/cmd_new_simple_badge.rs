@@ -50,6 +50,7 @@ pub struct NewSimpleBadge {
 impl NewSimpleBadge {
     pub fn run<O: std::io::Write>(
         &self,
+        session: &mut SimulatorSession,
         out: &mut O,
     ) -> Result<Option<NonFungibleGlobalId>, String> {
         let network_definition = NetworkDefinition::simulator();
@@ -89,6 +90,7 @@ impl NewSimpleBadge {
             .try_deposit_entire_worktop_or_refund(default_account, None)
             .build();
         let receipt = handle_manifest(
+            session,
             manifest.into(),
             &self.signing_keys,
             &self.network,
@@ -50,7 +50,11 @@ pub struct NewTokenFixed {
 }
 
 impl NewTokenFixed {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
         let default_account = get_default_account()?;
         let mut metadata = BTreeMap::new();
         if let Some(symbol) = self.symbol.clone() {
@@ -89,6 +93,7 @@ impl NewTokenFixed {
             .try_deposit_entire_worktop_or_refund(default_account, None)
             .build();
         handle_manifest(
+            session,
             manifest.into(),
             &self.signing_keys,
             &self.network,
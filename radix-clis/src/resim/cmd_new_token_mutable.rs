@@ -49,7 +49,11 @@ pub struct NewTokenMutable {
 }
 
 impl NewTokenMutable {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
         let mut metadata = BTreeMap::new();
         if let Some(symbol) = self.symbol.clone() {
             metadata.insert("symbol".to_string(), MetadataValue::String(symbol));
@@ -86,6 +90,7 @@ impl NewTokenMutable {
             .new_token_mutable(metadata, self.minter_badge.clone().into())
             .build();
         handle_manifest(
+            session,
             manifest.into(),
             &self.signing_keys,
             &self.network,
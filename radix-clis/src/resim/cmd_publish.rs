@@ -57,7 +57,11 @@ pub struct Publish {
 }
 
 impl Publish {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
         // Load wasm code
         let (code_path, definition_path) = if self.path.extension() != Some(OsStr::new("wasm")) {
             let build_artifacts = build_package(
@@ -92,7 +96,7 @@ impl Publish {
         .map_err(Error::PackageDefinitionConversionError)?;
 
         if let Some(package_address) = self.package_address.clone() {
-            let SimulatorEnvironment { mut db, .. } = SimulatorEnvironment::new()?;
+            let SimulatorEnvironment { db, .. } = session.env()?;
 
             let node_id: NodeId = package_address.0.into();
 
@@ -333,6 +337,7 @@ impl Publish {
                 .build();
 
             let receipt = handle_manifest(
+                session,
                 manifest.into(),
                 &None,
                 &self.network,
@@ -2,13 +2,17 @@ use clap::Parser;
 
 use crate::resim::*;
 
-/// Reset this simulator
+/// Reset this simulator, keeping the saved snapshots
 #[derive(Parser, Debug)]
 pub struct Reset {}
 
 impl Reset {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
-        SimulatorEnvironment::new_reset()?;
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
+        session.reset()?;
         writeln!(out, "Data directory cleared.").map_err(Error::IOError)?;
         Ok(())
     }
@@ -2,9 +2,11 @@ use crate::resim::*;
 use clap::Parser;
 use radix_engine::utils::*;
 use radix_transactions::manifest::*;
+use radix_transactions::signing::ExternalSigner;
 use regex::{Captures, Regex};
 use std::env;
 use std::path::PathBuf;
+use std::process::Command;
 
 /// Compiles, signs and runs a transaction manifest
 #[derive(Parser, Debug)]
@@ -24,6 +26,15 @@ pub struct Run {
     #[clap(short, long)]
     pub signing_keys: Option<String>,
 
+    /// A program which signs over its standard input and output, such as a hardware wallet
+    /// bridge, in addition to the signing keys
+    #[clap(long)]
+    pub signer_command: Option<String>,
+
+    /// An argument to pass to the signer command, which may be repeated
+    #[clap(long, requires = "signer-command", allow_hyphen_values = true)]
+    pub signer_arg: Vec<String>,
+
     /// Turn on tracing
     #[clap(short, long)]
     pub trace: bool,
@@ -42,7 +53,11 @@ impl Run {
         .into()
     }
 
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
         let manifest = std::fs::read_to_string(&self.path).map_err(Error::IOError)?;
         let pre_processed_manifest = Self::pre_process_manifest(&manifest);
         let network = match &self.network {
@@ -68,9 +83,20 @@ impl Run {
         validate_call_arguments_to_native_components(&manifest)
             .map_err(Error::InstructionSchemaValidationError)?;
 
-        handle_manifest(
+        let external_signer = self
+            .signer_command
+            .as_ref()
+            .map(|signer_command| {
+                ExternalSigner::spawn(Command::new(signer_command).args(&self.signer_arg))
+            })
+            .transpose()
+            .map_err(Error::SignerError)?;
+
+        handle_manifest_with_external_signer(
+            session,
             manifest,
             &self.signing_keys,
+            external_signer.as_ref(),
             &self.network,
             &None,
             self.trace,
@@ -0,0 +1,464 @@
+use clap::Parser;
+use radix_common::prelude::*;
+use radix_substate_store_queries::query::ResourceAccounter;
+use std::fs;
+
+use crate::resim::*;
+
+/// Run a script of resim commands and expectations in a single process
+///
+/// Each line of the script is one of the following statements (empty lines and lines starting
+/// with `#` are ignored):
+///
+/// * `<command> [args...]` runs a resim command (e.g. `publish .`), which must succeed.
+///
+/// * `$name = <command> [args...] [| <label>]` runs a resim command, and captures the value it
+///   printed after `<label>:` into the `$name` variable. The label defaults to the created
+///   entity for `new-account`, `publish`, `call-function`, `new-simple-badge` and the
+///   `new-token-*` / `new-badge-*` commands.
+///
+/// * `expect-failure <command> [args...]` runs a resim command, which must fail.
+///
+/// * `expect-output <text>` checks that the output (or error) of the previous command contains
+///   the given text.
+///
+/// * `expect-balance <component_address> <resource_address> <amount>` checks the amount of a
+///   resource held by a component (the number of non-fungibles, for a non-fungible resource).
+///
+/// Arguments are separated by whitespace, and can be quoted with `"` or `'`. Variables are
+/// referred to as `$name` or `${name}`, except within single quotes.
+#[derive(Parser, Debug)]
+pub struct Script {
+    /// The path to the script file
+    pub path: PathBuf,
+}
+
+/// An error in a resim script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptError {
+    UnclosedQuote,
+    InvalidVariableName(String),
+    UnknownVariable(String),
+    UnexpectedPipe,
+    MissingCommand,
+    InvalidCommand(String),
+    NestedScriptNotSupported,
+    CommandFailed(String),
+    UnexpectedSuccess,
+    NoDefaultCaptureLabel,
+    CapturedValueNotFound(String),
+    OutputNotFound(String),
+    InvalidArguments(String),
+    BalanceMismatch { expected: Decimal, actual: Decimal },
+}
+
+impl Script {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
+        let script = fs::read_to_string(&self.path)
+            .map_err(|err| Error::IOErrorAtPath(err, self.path.clone()))?;
+
+        let mut variables = IndexMap::<String, String>::new();
+        let mut last_output = String::new();
+        for (index, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            writeln!(out, "> {}", line).map_err(Error::IOError)?;
+            run_statement(session, line, &mut variables, &mut last_output, out)
+                .map_err(|error| Error::ScriptError(index + 1, error))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn run_statement<O: std::io::Write>(
+    session: &mut SimulatorSession,
+    line: &str,
+    variables: &mut IndexMap<String, String>,
+    last_output: &mut String,
+    out: &mut O,
+) -> Result<(), ScriptError> {
+    let (target, statement) = split_assignment(line)?;
+    let tokens = tokenize(statement, variables)?;
+    let (arguments, label) = match tokens.iter().position(|token| *token == Token::Pipe) {
+        Some(position) => (&tokens[..position], Some(&tokens[position + 1..])),
+        None => (&tokens[..], None),
+    };
+    let mut arguments = arguments
+        .iter()
+        .map(|token| match token {
+            Token::Word(word) => Ok(word.clone()),
+            Token::Pipe => Err(ScriptError::UnexpectedPipe),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let label = label
+        .map(|label| {
+            label
+                .iter()
+                .map(|token| match token {
+                    Token::Word(word) => Ok(word.as_str()),
+                    Token::Pipe => Err(ScriptError::UnexpectedPipe),
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(|words| words.join(" "))
+        })
+        .transpose()?;
+    if label.is_some() && target.is_none() {
+        return Err(ScriptError::UnexpectedPipe);
+    }
+    if arguments.is_empty() {
+        return Err(ScriptError::MissingCommand);
+    }
+
+    let keyword = arguments[0].as_str();
+    match keyword {
+        "expect-output" if target.is_none() => {
+            let expected = arguments[1..].join(" ");
+            if !last_output.contains(&expected) {
+                return Err(ScriptError::OutputNotFound(expected));
+            }
+        }
+        "expect-balance" if target.is_none() => {
+            let [_, component_address, resource_address, amount] = arguments.as_slice() else {
+                return Err(ScriptError::InvalidArguments(arguments.join(" ")));
+            };
+            let expected = Decimal::from_str(amount)
+                .map_err(|_| ScriptError::InvalidArguments(amount.clone()))?;
+            let actual = get_balance(session, component_address, resource_address)?;
+            if actual != expected {
+                return Err(ScriptError::BalanceMismatch { expected, actual });
+            }
+        }
+        "expect-failure" if target.is_none() => {
+            arguments.remove(0);
+            let (_, result, output) = run_command(session, &arguments)?;
+            write!(out, "{}", output).map_err(|err| ScriptError::CommandFailed(err.to_string()))?;
+            match result {
+                Ok(()) => return Err(ScriptError::UnexpectedSuccess),
+                Err(error) => {
+                    writeln!(out, "Failed as expected: {}", error)
+                        .map_err(|err| ScriptError::CommandFailed(err.to_string()))?;
+                    *last_output = format!("{}{}", strip_ansi_codes(&output), error);
+                }
+            }
+        }
+        _ => {
+            let (command, result, output) = run_command(session, &arguments)?;
+            write!(out, "{}", output).map_err(|err| ScriptError::CommandFailed(err.to_string()))?;
+            result.map_err(ScriptError::CommandFailed)?;
+            *last_output = strip_ansi_codes(&output);
+
+            if let Some(target) = target {
+                let label = match label {
+                    Some(label) => label,
+                    None => default_capture_label(&command)
+                        .ok_or(ScriptError::NoDefaultCaptureLabel)?
+                        .to_string(),
+                };
+                let value = find_labelled_value(last_output, &label)
+                    .ok_or(ScriptError::CapturedValueNotFound(label))?;
+                variables.insert(target.to_string(), value);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Splits a `$name = <statement>` line into the variable name and the statement.
+fn split_assignment(line: &str) -> Result<(Option<&str>, &str), ScriptError> {
+    let Some(rest) = line.strip_prefix('$') else {
+        return Ok((None, line));
+    };
+    let Some((name, statement)) = rest.split_once('=') else {
+        return Ok((None, line));
+    };
+    let name = name.trim();
+    if !is_variable_name(name) {
+        return Err(ScriptError::InvalidVariableName(name.to_string()));
+    }
+    Ok((Some(name), statement.trim()))
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(is_variable_char)
+}
+
+fn is_variable_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Pipe,
+}
+
+/// Splits a statement into words, resolving quotes and variables.
+fn tokenize(
+    statement: &str,
+    variables: &IndexMap<String, String>,
+) -> Result<Vec<Token>, ScriptError> {
+    let mut tokens = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = statement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                tokens.extend(word.take().map(Token::Word));
+            }
+            '|' => {
+                tokens.extend(word.take().map(Token::Word));
+                tokens.push(Token::Pipe);
+            }
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(ScriptError::UnclosedQuote),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('$') => word.push_str(&read_variable(&mut chars, variables)?),
+                        Some(c) => word.push(c),
+                        None => return Err(ScriptError::UnclosedQuote),
+                    }
+                }
+            }
+            '$' => {
+                let value = read_variable(&mut chars, variables)?;
+                word.get_or_insert_with(String::new).push_str(&value);
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    tokens.extend(word.take().map(Token::Word));
+    Ok(tokens)
+}
+
+/// Reads a variable reference (after its `$`) and returns its value.
+fn read_variable(
+    chars: &mut core::iter::Peekable<core::str::Chars>,
+    variables: &IndexMap<String, String>,
+) -> Result<String, ScriptError> {
+    let mut name = String::new();
+    if chars.next_if_eq(&'{').is_some() {
+        loop {
+            match chars.next() {
+                Some('}') => break,
+                Some(c) => name.push(c),
+                None => return Err(ScriptError::InvalidVariableName(name)),
+            }
+        }
+    } else {
+        while let Some(c) = chars.next_if(|c| is_variable_char(*c)) {
+            name.push(c);
+        }
+    }
+    if !is_variable_name(&name) {
+        return Err(ScriptError::InvalidVariableName(name));
+    }
+    variables
+        .get(&name)
+        .cloned()
+        .ok_or(ScriptError::UnknownVariable(name))
+}
+
+/// Runs a resim command in the session of the script, capturing its output.
+fn run_command(
+    session: &mut SimulatorSession,
+    arguments: &[String],
+) -> Result<(Command, Result<(), String>, String), ScriptError> {
+    let cli = ResimCli::try_parse_from(
+        core::iter::once("resim").chain(arguments.iter().map(String::as_str)),
+    )
+    .map_err(|err| ScriptError::InvalidCommand(err.to_string()))?;
+    if let Command::Script(_) = cli.command {
+        return Err(ScriptError::NestedScriptNotSupported);
+    }
+
+    let mut output = Vec::new();
+    let result = cli.command.run_in_session(session, &mut output);
+    Ok((
+        cli.command,
+        result,
+        String::from_utf8_lossy(&output).into_owned(),
+    ))
+}
+
+fn default_capture_label(command: &Command) -> Option<&'static str> {
+    match command {
+        Command::NewAccount(_) => Some("Account component address"),
+        Command::Publish(_) => Some("Package"),
+        Command::CallFunction(_) => Some("Component"),
+        Command::NewSimpleBadge(_) => Some("NonFungibleGlobalId"),
+        Command::NewBadgeFixed(_)
+        | Command::NewBadgeMutable(_)
+        | Command::NewTokenFixed(_)
+        | Command::NewTokenMutable(_) => Some("Resource"),
+        _ => None,
+    }
+}
+
+/// Finds the first line of the form `... <label>: <value>`, and returns the last word of the
+/// value.
+fn find_labelled_value(output: &str, label: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (prefix, value) = line.split_once(": ")?;
+        if prefix.trim_end().ends_with(label) {
+            value.split_whitespace().last().map(str::to_string)
+        } else {
+            None
+        }
+    })
+}
+
+fn strip_ansi_codes(output: &str) -> String {
+    let mut stripped = String::with_capacity(output.len());
+    let mut chars = output.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skips a control sequence, such as `ESC[32m`
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+fn get_balance(
+    session: &mut SimulatorSession,
+    component_address: &str,
+    resource_address: &str,
+) -> Result<Decimal, ScriptError> {
+    let component_address = SimulatorComponentAddress::from_str(component_address)
+        .map_err(|_| ScriptError::InvalidArguments(component_address.to_string()))?;
+    let resource_address = SimulatorResourceAddress::from_str(resource_address)
+        .map_err(|_| ScriptError::InvalidArguments(resource_address.to_string()))?;
+
+    let SimulatorEnvironment { db, .. } = session
+        .env()
+        .map_err(|err| ScriptError::CommandFailed(err.into()))?;
+    let mut accounter = ResourceAccounter::new(&*db);
+    accounter.traverse(*component_address.0.as_node_id());
+    let accounting = accounter.close();
+
+    let balance = if resource_address.0.is_fungible() {
+        accounting
+            .balances
+            .get(&resource_address.0)
+            .cloned()
+            .unwrap_or_default()
+    } else {
+        accounting
+            .non_fungibles
+            .get(&resource_address.0)
+            .map(|ids| Decimal::from(ids.len()))
+            .unwrap_or_default()
+    };
+    Ok(balance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let variables = indexmap!(
+            "account".to_string() => "account_sim1abc".to_string(),
+            "amount".to_string() => "10".to_string(),
+        );
+        let words = |words: &[&str]| {
+            words
+                .iter()
+                .map(|word| Token::Word(word.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            tokenize("show $account", &variables),
+            Ok(words(&["show", "account_sim1abc"]))
+        );
+        assert_eq!(
+            tokenize("transfer xrd:${amount}0 \"$account\"", &variables),
+            Ok(words(&["transfer", "xrd:100", "account_sim1abc"]))
+        );
+        assert_eq!(
+            tokenize("new-token-fixed 1 --name 'My $token'", &variables),
+            Ok(words(&["new-token-fixed", "1", "--name", "My $token"]))
+        );
+        assert_eq!(
+            tokenize("new-account | Public key", &variables),
+            Ok(vec![
+                Token::Word("new-account".to_string()),
+                Token::Pipe,
+                Token::Word("Public".to_string()),
+                Token::Word("key".to_string()),
+            ])
+        );
+        assert_eq!(
+            tokenize("show $unknown", &variables),
+            Err(ScriptError::UnknownVariable("unknown".to_string()))
+        );
+        assert_eq!(
+            tokenize("show \"abc", &variables),
+            Err(ScriptError::UnclosedQuote)
+        );
+    }
+
+    #[test]
+    fn test_split_assignment() {
+        assert_eq!(
+            split_assignment("$pkg = publish ."),
+            Ok((Some("pkg"), "publish ."))
+        );
+        assert_eq!(split_assignment("publish ."), Ok((None, "publish .")));
+        assert_eq!(
+            split_assignment("$my-pkg = publish ."),
+            Err(ScriptError::InvalidVariableName("my-pkg".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_find_labelled_value() {
+        let output = strip_ansi_codes(
+            "Transaction Status: \u{1b}[32mCOMMITTED SUCCESS\u{1b}[0m\n\
+             New Entities: 1\n\
+             └─ Component: \u{1b}[32mcomponent_sim1abc\u{1b}[0m\n\
+             Success! New Package: package_sim1def\n",
+        );
+
+        assert_eq!(
+            find_labelled_value(&output, "Component"),
+            Some("component_sim1abc".to_string())
+        );
+        assert_eq!(
+            find_labelled_value(&output, "Package"),
+            Some("package_sim1def".to_string())
+        );
+        assert_eq!(
+            find_labelled_value(&output, "Transaction Status"),
+            Some("SUCCESS".to_string())
+        );
+        assert_eq!(find_labelled_value(&output, "Resource"), None);
+    }
+}
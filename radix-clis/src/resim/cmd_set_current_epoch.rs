@@ -11,8 +11,12 @@ pub struct SetCurrentEpoch {
 }
 
 impl SetCurrentEpoch {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
-        db_upsert_epoch(Epoch::of(self.epoch_number))?;
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
+        db_upsert_epoch(session, Epoch::of(self.epoch_number))?;
         writeln!(out, "Epoch set successfully").map_err(Error::IOError)?;
         Ok(())
     }
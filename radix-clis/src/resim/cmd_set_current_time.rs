@@ -15,9 +15,14 @@ pub struct SetCurrentTime {
 }
 
 impl SetCurrentTime {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
         let instant = self.date_time.to_instant();
         db_upsert_timestamps(
+            session,
             ProposerMilliTimestampSubstate {
                 epoch_milli: instant.seconds_since_unix_epoch * 1000,
             },
@@ -8,20 +8,28 @@ pub struct Show {
     /// The address of a package, component or resource manager, if no
     /// address is provided, then we default to `show <DEFAULT_ACCOUNT_ADDRESS>`.
     pub address: Option<String>,
+
+    #[clap(flatten)]
+    pub ledger: LedgerOpenArgs,
 }
 
 impl Show {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
-        let SimulatorEnvironment { db, .. } = SimulatorEnvironment::new()?;
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
+        let SimulatorEnvironment { db, .. } =
+            session.env_with_open_mode(self.ledger.open_mode())?;
 
         let result = match &self.address {
             Some(address) => {
                 if let Ok(a) = SimulatorPackageAddress::from_str(address) {
-                    dump_package(a.0, &db, out).map_err(Error::LedgerDumpError)
+                    dump_package(a.0, &*db, out).map_err(Error::LedgerDumpError)
                 } else if let Ok(a) = SimulatorComponentAddress::from_str(address) {
-                    dump_component(a.0, &db, out).map_err(Error::LedgerDumpError)
+                    dump_component(a.0, &*db, out).map_err(Error::LedgerDumpError)
                 } else if let Ok(a) = SimulatorResourceAddress::from_str(address) {
-                    dump_resource_manager(a.0, &db, out).map_err(Error::LedgerDumpError)
+                    dump_resource_manager(a.0, &*db, out).map_err(Error::LedgerDumpError)
                 } else {
                     Err(Error::InvalidId(address.clone()))
                 }
@@ -32,7 +40,7 @@ impl Show {
                         EntityDumpError::NoAddressProvidedAndNotDefaultAccountSet,
                     ))
                 })
-                .and_then(|x| dump_component(x, &db, out).map_err(Error::LedgerDumpError)),
+                .and_then(|x| dump_component(x, &*db, out).map_err(Error::LedgerDumpError)),
         };
         result.map_err(|err| err.into())
     }
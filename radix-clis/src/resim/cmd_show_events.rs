@@ -0,0 +1,105 @@
+use crate::resim::*;
+use crate::utils::*;
+use clap::Parser;
+use colored::*;
+use radix_engine::system::system_db_reader::SystemDatabaseReader;
+use radix_engine_interface::api::ModuleId;
+use radix_substate_store_queries::event_index::*;
+
+/// Show the events of the committed transactions, as indexed in the ledger when the
+/// `ENABLE_EVENT_INDEX` environment variable is set
+#[derive(Parser, Debug)]
+pub struct ShowEvents {
+    /// The address of the entity which emitted the events, e.g. a component or a vault
+    #[clap(long)]
+    pub emitter: Option<SimulatorNodeId>,
+
+    /// The module of the emitter which emitted the events: main, metadata, royalty or
+    /// role-assignment
+    #[clap(long, requires = "emitter", parse(try_from_str = parse_module_id))]
+    pub module: Option<ModuleId>,
+
+    /// The package of the blueprint of the emitter
+    #[clap(long, requires = "blueprint-name")]
+    pub package_address: Option<SimulatorPackageAddress>,
+
+    /// The name of the blueprint of the emitter
+    #[clap(long, requires = "package-address")]
+    pub blueprint_name: Option<String>,
+
+    /// The name of the events, e.g. `WithdrawEvent`
+    #[clap(long)]
+    pub event_name: Option<String>,
+
+    /// The first transaction version to show the events of
+    #[clap(long)]
+    pub from_version: Option<u64>,
+
+    /// The last transaction version to show the events of
+    #[clap(long)]
+    pub to_version: Option<u64>,
+
+    #[clap(flatten)]
+    pub ledger: LedgerOpenArgs,
+}
+
+impl ShowEvents {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
+        let SimulatorEnvironment { db, .. } =
+            session.env_with_open_mode(self.ledger.open_mode())?;
+
+        let mut query = EventQuery {
+            from_version: self.from_version,
+            to_version: self.to_version,
+            ..EventQuery::new()
+        };
+        if let Some(emitter) = &self.emitter {
+            query = query.method_emitter(emitter.0, self.module.unwrap_or(ModuleId::Main));
+        }
+        if let (Some(package_address), Some(blueprint_name)) =
+            (&self.package_address, &self.blueprint_name)
+        {
+            query = query.blueprint(BlueprintId::new(&package_address.0, blueprint_name));
+        }
+        if let Some(event_name) = &self.event_name {
+            query = query.event_name(event_name);
+        }
+
+        let encoder = AddressBech32Encoder::for_simulator();
+        let reader = SystemDatabaseReader::new(&*db);
+        let events = db.query_events(&query).collect::<Vec<_>>();
+        writeln!(out, "{}: {}", "Events".green().bold(), events.len()).map_err(Error::IOError)?;
+        for (last, event) in events.iter().identify_last() {
+            let data = event
+                .to_annotated_string(&reader, Some(&encoder))
+                .unwrap_or_else(|_| hex::encode(&event.payload));
+            writeln!(
+                out,
+                "{} Version: {}, Index: {}\n   Emitter: {}\n   Name: {}\n   Data: {}",
+                list_item_prefix(last),
+                event.version,
+                event.index,
+                event.emitter().display(&encoder),
+                event.event_name(),
+                data,
+            )
+            .map_err(Error::IOError)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_module_id(module: &str) -> Result<ModuleId, String> {
+    match module {
+        "main" => Ok(ModuleId::Main),
+        "metadata" => Ok(ModuleId::Metadata),
+        "royalty" => Ok(ModuleId::Royalty),
+        "role-assignment" => Ok(ModuleId::RoleAssignment),
+        _ => Err(format!("Unknown module: {}", module)),
+    }
+}
@@ -3,6 +3,9 @@ use clap::Parser;
 use colored::*;
 use radix_common::time::Instant;
 use radix_common::time::UtcDateTime;
+use radix_engine::blueprints::consensus_manager::*;
+use radix_engine::system::system_db_reader::*;
+use radix_engine_interface::api::ModuleId;
 use radix_engine_interface::blueprints::consensus_manager::*;
 use radix_substate_store_impls::rocks_db::RocksdbSubstateStore;
 use radix_substate_store_interface::interface::*;
@@ -11,16 +14,35 @@ use crate::resim::*;
 
 /// Show entries in the ledger state
 #[derive(Parser, Debug)]
-pub struct ShowLedger {}
+pub struct ShowLedger {
+    #[clap(flatten)]
+    pub ledger: LedgerOpenArgs,
+}
 
 impl ShowLedger {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
-        {
-            let SimulatorEnvironment { db, .. } = SimulatorEnvironment::new()?;
-            Self::list_entries(out, &db)?;
-        }
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
+        let open_mode = self.ledger.open_mode();
+        let (current_epoch, instant) = if open_mode.is_writable() {
+            Self::list_entries(out, &session.env()?.db)?;
+            (
+                Self::get_current_epoch(session, out)?,
+                Self::get_current_time(session, out, TimePrecisionV1::Minute)?,
+            )
+        } else {
+            // The ledger can't be queried with transactions without writing to it, so the epoch
+            // and time are read directly from the consensus manager's substates instead.
+            let SimulatorEnvironment { db, .. } = session.env_with_open_mode(open_mode)?;
+            Self::list_entries(out, db)?;
+            (
+                Self::read_current_epoch(db)?,
+                Self::read_current_minute_time(db)?,
+            )
+        };
 
-        let current_epoch = Self::get_current_epoch(out)?;
         writeln!(
             out,
             "{}: {}",
@@ -29,7 +51,6 @@ impl ShowLedger {
         )
         .map_err(Error::IOError)?;
 
-        let instant = Self::get_current_time(out, TimePrecisionV1::Minute)?;
         let date_time = UtcDateTime::from_instant(&instant).unwrap();
         writeln!(out, "{}: {}", "Current Time".green().bold(), date_time)
             .map_err(Error::IOError)?;
@@ -95,7 +116,10 @@ impl ShowLedger {
         Ok(())
     }
 
-    pub fn get_current_epoch<O: std::io::Write>(out: &mut O) -> Result<Epoch, Error> {
+    pub fn get_current_epoch<O: std::io::Write>(
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<Epoch, Error> {
         let manifest = ManifestBuilder::new_system_v1()
             .call_method(
                 CONSENSUS_MANAGER,
@@ -104,11 +128,13 @@ impl ShowLedger {
             )
             .build();
         let initial_proofs = btreeset![];
-        let receipt = handle_system_transaction(manifest, initial_proofs, false, false, out)?;
+        let receipt =
+            handle_system_transaction(session, manifest, initial_proofs, false, false, out)?;
         Ok(receipt.expect_commit(true).output(0))
     }
 
     pub fn get_current_time<O: std::io::Write>(
+        session: &mut SimulatorSession,
         out: &mut O,
         precision: TimePrecisionV1,
     ) -> Result<Instant, Error> {
@@ -120,7 +146,33 @@ impl ShowLedger {
             )
             .build();
         let initial_proofs = btreeset![];
-        let receipt = handle_system_transaction(manifest, initial_proofs, false, false, out)?;
+        let receipt =
+            handle_system_transaction(session, manifest, initial_proofs, false, false, out)?;
         Ok(receipt.expect_commit(true).output(0))
     }
+
+    pub fn read_current_epoch(substate_db: &RocksdbSubstateStore) -> Result<Epoch, Error> {
+        let state = SystemDatabaseReader::new(substate_db)
+            .read_typed_object_field::<ConsensusManagerStateFieldPayload>(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::State.field_index(),
+            )
+            .map_err(|_| Error::ComponentNotFound(CONSENSUS_MANAGER))?
+            .fully_update_and_into_latest_version();
+        Ok(state.epoch)
+    }
+
+    /// Reads the current time, rounded down to the minute as with [`TimePrecisionV1::Minute`].
+    pub fn read_current_minute_time(substate_db: &RocksdbSubstateStore) -> Result<Instant, Error> {
+        let timestamp = SystemDatabaseReader::new(substate_db)
+            .read_typed_object_field::<ConsensusManagerProposerMinuteTimestampFieldPayload>(
+                CONSENSUS_MANAGER.as_node_id(),
+                ModuleId::Main,
+                ConsensusManagerField::ProposerMinuteTimestamp.field_index(),
+            )
+            .map_err(|_| Error::ComponentNotFound(CONSENSUS_MANAGER))?
+            .fully_update_and_into_latest_version();
+        Ok(Instant::new(timestamp.epoch_minute as i64 * 60))
+    }
 }
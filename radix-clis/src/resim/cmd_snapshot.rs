@@ -0,0 +1,175 @@
+use clap::{Parser, Subcommand};
+use colored::*;
+use std::fs;
+use std::path::Path;
+
+use crate::resim::*;
+
+/// Save, load or list snapshots of the simulator ledger and configs
+#[derive(Parser, Debug)]
+pub struct Snapshot {
+    #[clap(subcommand)]
+    pub command: SnapshotCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SnapshotCommand {
+    /// Save the current ledger and configs under the given name, replacing any existing snapshot
+    /// with the same name
+    Save {
+        /// The name of the snapshot
+        name: String,
+    },
+    /// Restore the ledger and configs from the snapshot with the given name
+    Load {
+        /// The name of the snapshot
+        name: String,
+    },
+    /// List the saved snapshots
+    List,
+}
+
+impl Snapshot {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
+        match &self.command {
+            SnapshotCommand::Save { name } => {
+                let snapshot_dir = get_snapshot_dir(name)?;
+                // Bootstraps the ledger, if it does not exist yet, and closes it before copying
+                session.env()?;
+                session.close();
+
+                let temp_dir = snapshot_dir.with_extension("tmp");
+                if temp_dir.exists() {
+                    fs::remove_dir_all(&temp_dir).map_err(Error::IOError)?;
+                }
+                copy_ledger_data(&get_data_dir()?, &temp_dir)?;
+                if snapshot_dir.exists() {
+                    fs::remove_dir_all(&snapshot_dir).map_err(Error::IOError)?;
+                }
+                fs::rename(&temp_dir, &snapshot_dir).map_err(Error::IOError)?;
+
+                writeln!(out, "Snapshot {} saved.", name.green()).map_err(Error::IOError)?;
+            }
+            SnapshotCommand::Load { name } => {
+                let snapshot_dir = get_snapshot_dir(name)?;
+                if !snapshot_dir.is_dir() {
+                    return Err(Error::SnapshotNotFound(name.clone()).into());
+                }
+
+                session.close();
+
+                // The snapshot is copied next to the data dir first, so that a failed or
+                // interrupted copy never leaves a partially restored ledger behind
+                let data_dir = get_data_dir()?;
+                let restore_dir = data_dir.with_extension("restore");
+                if restore_dir.exists() {
+                    fs::remove_dir_all(&restore_dir).map_err(Error::IOError)?;
+                }
+                copy_ledger_data(&snapshot_dir, &restore_dir)?;
+                replace_ledger_data(&restore_dir, &data_dir)?;
+
+                writeln!(out, "Snapshot {} loaded.", name.green()).map_err(Error::IOError)?;
+            }
+            SnapshotCommand::List => {
+                let snapshots_dir = get_snapshots_dir()?;
+                let mut names = Vec::new();
+                if snapshots_dir.is_dir() {
+                    for entry in fs::read_dir(&snapshots_dir).map_err(Error::IOError)? {
+                        let entry = entry.map_err(Error::IOError)?;
+                        let name = entry.file_name().to_string_lossy().into_owned();
+                        if entry.path().is_dir() && is_valid_snapshot_name(&name) {
+                            names.push(name);
+                        }
+                    }
+                }
+                names.sort();
+
+                writeln!(out, "{}: {}", "Snapshots".green().bold(), names.len())
+                    .map_err(Error::IOError)?;
+                for (last, name) in names.iter().identify_last() {
+                    let configs = get_configs_at(&snapshots_dir.join(name))?;
+                    writeln!(
+                        out,
+                        "{} {} (default account: {}, next nonce: {})",
+                        list_item_prefix(last),
+                        name,
+                        match configs.default_account {
+                            Some(account) => account
+                                .display(&AddressBech32Encoder::for_simulator())
+                                .to_string(),
+                            None => "None".to_owned(),
+                        },
+                        configs.nonce
+                    )
+                    .map_err(Error::IOError)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn get_snapshot_dir(name: &str) -> Result<PathBuf, Error> {
+    if !is_valid_snapshot_name(name) {
+        return Err(Error::InvalidSnapshotName(name.to_string()));
+    }
+    let mut path = get_snapshots_dir()?;
+    path.push(name);
+    Ok(path)
+}
+
+fn is_valid_snapshot_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Copies the ledger database and the configs from one directory to another, skipping the saved
+/// snapshots and the instrumented code cache.
+fn copy_ledger_data(from: &Path, to: &Path) -> Result<(), Error> {
+    fs::create_dir_all(to).map_err(Error::IOError)?;
+    for entry in fs::read_dir(from).map_err(Error::IOError)? {
+        let entry = entry.map_err(Error::IOError)?;
+        if entry.file_name() == SNAPSHOTS_DIR_NAME
+            || entry.file_name() == INSTRUMENTED_CODE_CACHE_DIR_NAME
+        {
+            continue;
+        }
+        let target = to.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_ledger_data(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .map_err(|err| Error::IOErrorAtPath(err, entry.path()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the data dir with the given fully restored dir, by renaming it into place. The saved
+/// snapshots and the instrumented code cache are moved over from the old data dir.
+fn replace_ledger_data(restore_dir: &Path, data_dir: &Path) -> Result<(), Error> {
+    let old_dir = data_dir.with_extension("old");
+    if old_dir.exists() {
+        fs::remove_dir_all(&old_dir).map_err(Error::IOError)?;
+    }
+    fs::rename(data_dir, &old_dir)
+        .map_err(|err| Error::IOErrorAtPath(err, data_dir.to_path_buf()))?;
+    for name in [SNAPSHOTS_DIR_NAME, INSTRUMENTED_CODE_CACHE_DIR_NAME] {
+        let kept = old_dir.join(name);
+        if kept.exists() {
+            fs::rename(&kept, restore_dir.join(name))
+                .map_err(|err| Error::IOErrorAtPath(err, kept.clone()))?;
+        }
+    }
+    fs::rename(restore_dir, data_dir)
+        .map_err(|err| Error::IOErrorAtPath(err, restore_dir.to_path_buf()))?;
+    fs::remove_dir_all(&old_dir).map_err(|err| Error::IOErrorAtPath(err, old_dir))?;
+    Ok(())
+}
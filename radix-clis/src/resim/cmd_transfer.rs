@@ -35,7 +35,11 @@ pub struct Transfer {
 }
 
 impl Transfer {
-    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+    pub fn run<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
         let address_bech32_decoder = AddressBech32Decoder::for_simulator();
 
         let default_account = get_default_account()?;
@@ -68,6 +72,7 @@ impl Transfer {
             .try_deposit_entire_worktop_or_refund(self.recipient.0, None)
             .build();
         handle_manifest(
+            session,
             manifest.into(),
             &self.signing_keys,
             &self.network,
@@ -1,10 +1,12 @@
 use crate::resim::*;
 use radix_common::prelude::*;
 use radix_engine::updates::*;
+use radix_engine::vm::wasm::PersistentInstrumentedCodeCache;
 use radix_engine::vm::*;
+use radix_substate_store_impls::rocks_db::RocksDBOpenMode;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// The environment that the simulator runs in.
 pub struct SimulatorEnvironment {
@@ -18,7 +20,7 @@ pub struct SimulatorEnvironment {
 impl SimulatorEnvironment {
     pub fn new_reset() -> Result<Self, Error> {
         let dir = get_data_dir()?;
-        std::fs::remove_dir_all(dir).map_err(Error::IOError)?;
+        clear_ledger_data(&dir)?;
 
         Self::new()
     }
@@ -28,7 +30,7 @@ impl SimulatorEnvironment {
         let db = RocksdbSubstateStore::standard(get_data_dir()?);
 
         // Create the VMs
-        let vm_modules = VmModules::default();
+        let vm_modules = create_vm_modules()?;
 
         let mut env = Self {
             db,
@@ -40,6 +42,27 @@ impl SimulatorEnvironment {
         Ok(env)
     }
 
+    /// Opens the simulator ledger in the given mode. Unless the ledger is opened for writing, it
+    /// must already exist, and it isn't bootstrapped or migrated.
+    pub fn new_with_open_mode(open_mode: RocksDBOpenMode) -> Result<Self, Error> {
+        if open_mode.is_writable() {
+            return Self::new();
+        }
+
+        let db = RocksdbSubstateStore::with_options_and_mode(
+            &Default::default(),
+            get_data_dir()?,
+            open_mode,
+        )
+        .map_err(Error::DatabaseError)?;
+
+        Ok(Self {
+            db,
+            vm_modules: create_vm_modules()?,
+            network_definition: NetworkDefinition::simulator(),
+        })
+    }
+
     fn bootstrap(&mut self) {
         // Note: from_current_to_latest discovers the current status from the database,
         // and auto-migrates
@@ -49,6 +72,76 @@ impl SimulatorEnvironment {
     }
 }
 
+/// The simulator environment of a resim process, which is opened on first use and then shared by
+/// all the commands the process runs - e.g. by all the statements of a script.
+#[derive(Default)]
+pub struct SimulatorSession {
+    env: Option<SimulatorEnvironment>,
+    is_writable: bool,
+}
+
+impl SimulatorSession {
+    /// Returns the environment, opening (and bootstrapping) the ledger if it is not open yet.
+    pub fn env(&mut self) -> Result<&mut SimulatorEnvironment, Error> {
+        self.env_with_open_mode(RocksDBOpenMode::ReadWrite)
+    }
+
+    /// Returns the environment, opening the ledger in the given mode if it is not open yet (or
+    /// only open read-only, while the given mode is writable).
+    pub fn env_with_open_mode(
+        &mut self,
+        open_mode: RocksDBOpenMode,
+    ) -> Result<&mut SimulatorEnvironment, Error> {
+        let is_writable = open_mode.is_writable();
+        if self.env.is_none() || (is_writable && !self.is_writable) {
+            // Any read-only handle is closed before the ledger is opened for writing
+            self.env = None;
+            self.env = Some(SimulatorEnvironment::new_with_open_mode(open_mode)?);
+            self.is_writable = is_writable;
+        }
+        Ok(self.env.as_mut().unwrap())
+    }
+
+    /// Clears the ledger, and opens it again.
+    pub fn reset(&mut self) -> Result<&mut SimulatorEnvironment, Error> {
+        self.close();
+        self.env = Some(SimulatorEnvironment::new_reset()?);
+        self.is_writable = true;
+        Ok(self.env.as_mut().unwrap())
+    }
+
+    /// Closes the ledger (if open), e.g. so that its files can be replaced.
+    pub fn close(&mut self) {
+        self.env = None;
+        self.is_writable = false;
+    }
+}
+
+/// The arguments to choose how commands which only read the ledger open it.
+#[derive(clap::Args, Debug, Default, Clone)]
+pub struct LedgerOpenArgs {
+    /// Open the ledger read-only, so that it can be inspected while another process is using it
+    #[clap(long)]
+    pub read_only: bool,
+
+    /// Open the ledger as a RocksDB secondary instance, keeping its own files in the given
+    /// directory, so that it can be inspected while another process is using it
+    #[clap(long, conflicts_with = "read-only")]
+    pub secondary: Option<PathBuf>,
+}
+
+impl LedgerOpenArgs {
+    pub fn open_mode(&self) -> RocksDBOpenMode {
+        match &self.secondary {
+            Some(secondary_path) => RocksDBOpenMode::Secondary {
+                secondary_path: secondary_path.clone(),
+            },
+            None if self.read_only => RocksDBOpenMode::ReadOnly,
+            None => RocksDBOpenMode::ReadWrite,
+        }
+    }
+}
+
 /// Simulator configurations.
 #[derive(Debug, Clone, Default, ScryptoSbor)]
 pub struct Configs {
@@ -58,7 +151,7 @@ pub struct Configs {
     pub nonce: u32,
 }
 
-fn get_data_dir() -> Result<PathBuf, Error> {
+pub fn get_data_dir() -> Result<PathBuf, Error> {
     let path = match env::var(ENV_DATA_DIR) {
         Ok(value) => std::path::PathBuf::from(value),
         Err(..) => {
@@ -73,14 +166,64 @@ fn get_data_dir() -> Result<PathBuf, Error> {
     Ok(path)
 }
 
-pub fn get_configs_path() -> Result<PathBuf, Error> {
+/// Creates the VMs, caching the instrumented code of published packages in the data directory if
+/// enabled with the `ENABLE_INSTRUMENTED_CODE_CACHE` environment variable.
+pub fn create_vm_modules() -> Result<DefaultVmModules, Error> {
+    let mut vm_modules = VmModules::default();
+    if env::var(ENV_ENABLE_INSTRUMENTED_CODE_CACHE).is_ok() {
+        let cache = PersistentInstrumentedCodeCache::new(get_instrumented_code_cache_dir()?);
+        vm_modules.scrypto_vm = vm_modules.scrypto_vm.with_instrumented_code_cache(cache);
+    }
+    Ok(vm_modules)
+}
+
+pub fn get_instrumented_code_cache_dir() -> Result<PathBuf, Error> {
     let mut path = get_data_dir()?;
-    path.push("config");
-    Ok(path.with_extension("sbor"))
+    path.push(INSTRUMENTED_CODE_CACHE_DIR_NAME);
+    Ok(path)
+}
+
+pub fn get_snapshots_dir() -> Result<PathBuf, Error> {
+    let mut path = get_data_dir()?;
+    path.push(SNAPSHOTS_DIR_NAME);
+    Ok(path)
+}
+
+/// Removes the ledger database and the configs from the given data directory, keeping the saved
+/// snapshots and the instrumented code cache.
+pub fn clear_ledger_data(dir: &Path) -> Result<(), Error> {
+    for entry in fs::read_dir(dir).map_err(Error::IOError)? {
+        let entry = entry.map_err(Error::IOError)?;
+        if entry.file_name() == SNAPSHOTS_DIR_NAME
+            || entry.file_name() == INSTRUMENTED_CODE_CACHE_DIR_NAME
+        {
+            continue;
+        }
+        if entry.path().is_dir() {
+            fs::remove_dir_all(entry.path())
+        } else {
+            fs::remove_file(entry.path())
+        }
+        .map_err(|err| Error::IOErrorAtPath(err, entry.path()))?;
+    }
+    Ok(())
+}
+
+pub fn get_configs_path() -> Result<PathBuf, Error> {
+    Ok(get_configs_path_at(&get_data_dir()?))
+}
+
+fn get_configs_path_at(dir: &Path) -> PathBuf {
+    dir.join("config").with_extension("sbor")
 }
 
 pub fn get_configs() -> Result<Configs, Error> {
-    let path = get_configs_path()?;
+    get_configs_at(&get_data_dir()?)
+}
+
+/// Reads the configs stored in the given data directory.
+pub fn get_configs_at(dir: &Path) -> Result<Configs, Error> {
+    let path = get_configs_path_at(dir);
     if path.exists() {
         scrypto_decode(fs::read(path).map_err(Error::IOError)?.as_ref())
             .map_err(Error::SborDecodeError)
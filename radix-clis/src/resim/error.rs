@@ -3,9 +3,11 @@ use crate::resim::EntityDumpError;
 use radix_engine::errors::*;
 use radix_engine::transaction::AbortReason;
 use radix_engine::vm::wasm::PrepareError as WasmPrepareError;
+use radix_substate_store_impls::rocks_db::RocksDBError;
 use radix_transactions::errors::*;
 use radix_transactions::manifest::DecompileError;
 use radix_transactions::model::PrepareError as TransactionPrepareError;
+use radix_transactions::signing::ExternalSignerError;
 use std::io;
 
 /// Represents a resim error.
@@ -26,6 +28,8 @@ pub enum Error {
 
     IOErrorAtPath(io::Error, PathBuf),
 
+    DatabaseError(RocksDBError),
+
     SborDecodeError(DecodeError),
 
     SborEncodeError(EncodeError),
@@ -74,6 +78,16 @@ pub enum Error {
     RemoteGenericSubstitutionNotSupported,
 
     PackageDefinitionConversionError(ConversionError),
+
+    PackageSchemaError(scrypto_bindgen::schema::SchemaError),
+
+    ScriptError(usize, crate::resim::ScriptError),
+
+    SnapshotNotFound(String),
+
+    InvalidSnapshotName(String),
+
+    SignerError(ExternalSignerError),
 }
 
 impl fmt::Display for Error {
@@ -137,12 +151,16 @@ impl Debug for Error {
                 .field(err)
                 .field(path)
                 .finish(),
+            Self::DatabaseError(err) => f.debug_tuple("DatabaseError").field(err).finish(),
             Self::SborDecodeError(err) => f.debug_tuple("SborDecodeError").field(err).finish(),
             Self::SborEncodeError(err) => f.debug_tuple("SborEncodeError").field(err).finish(),
             Self::PackageDefinitionConversionError(err) => f
                 .debug_tuple("PackageDefinitionConversionError")
                 .field(err)
                 .finish(),
+            Self::PackageSchemaError(err) => {
+                f.debug_tuple("PackageSchemaError").field(err).finish()
+            }
             Self::BuildError(err) => f.debug_tuple("BuildError").field(err).finish(),
             Self::ExtractSchemaError(err) => {
                 f.debug_tuple("ExtractSchemaError").field(err).finish()
@@ -186,6 +204,14 @@ impl Debug for Error {
             Self::RemoteGenericSubstitutionNotSupported => {
                 write!(f, "RemoteGenericSubstitutionNotSupported")
             }
+            Self::ScriptError(line, err) => {
+                f.debug_tuple("ScriptError").field(line).field(err).finish()
+            }
+            Self::SnapshotNotFound(name) => f.debug_tuple("SnapshotNotFound").field(name).finish(),
+            Self::InvalidSnapshotName(name) => {
+                f.debug_tuple("InvalidSnapshotName").field(name).finish()
+            }
+            Self::SignerError(error) => f.debug_tuple("SignerError").field(error).finish(),
         }
     }
 }
@@ -2,6 +2,7 @@ mod addressing;
 mod cmd_call_function;
 mod cmd_call_method;
 mod cmd_export_package_definition;
+mod cmd_export_package_json_schema;
 mod cmd_generate_key_pair;
 mod cmd_mint;
 mod cmd_new_account;
@@ -13,12 +14,15 @@ mod cmd_new_token_mutable;
 mod cmd_publish;
 mod cmd_reset;
 mod cmd_run;
+mod cmd_script;
 mod cmd_set_current_epoch;
 mod cmd_set_current_time;
 mod cmd_set_default_account;
 mod cmd_show;
 mod cmd_show_configs;
+mod cmd_show_events;
 mod cmd_show_ledger;
+mod cmd_snapshot;
 mod cmd_transfer;
 mod config;
 mod dumper;
@@ -28,6 +32,7 @@ pub use addressing::*;
 pub use cmd_call_function::CallFunction;
 pub use cmd_call_method::CallMethod;
 pub use cmd_export_package_definition::*;
+pub use cmd_export_package_json_schema::*;
 pub use cmd_generate_key_pair::*;
 pub use cmd_new_account::*;
 pub use cmd_new_badge_fixed::*;
@@ -38,12 +43,15 @@ pub use cmd_new_token_mutable::*;
 pub use cmd_publish::*;
 pub use cmd_reset::*;
 pub use cmd_run::*;
+pub use cmd_script::*;
 pub use cmd_set_current_epoch::*;
 pub use cmd_set_current_time::*;
 pub use cmd_set_default_account::*;
 pub use cmd_show::*;
 pub use cmd_show_configs::*;
+pub use cmd_show_events::*;
 pub use cmd_show_ledger::*;
+pub use cmd_snapshot::*;
 pub use cmd_transfer::*;
 pub use config::*;
 pub use dumper::*;
@@ -51,6 +59,10 @@ pub use error::*;
 
 pub const DEFAULT_SCRYPTO_DIR_UNDER_HOME: &str = ".scrypto";
 pub const ENV_DATA_DIR: &str = "DATA_DIR";
+pub const SNAPSHOTS_DIR_NAME: &str = "snapshots";
+pub const INSTRUMENTED_CODE_CACHE_DIR_NAME: &str = "instrumented-code-cache";
+pub const ENV_ENABLE_INSTRUMENTED_CODE_CACHE: &str = "ENABLE_INSTRUMENTED_CODE_CACHE";
+pub const ENV_ENABLE_EVENT_INDEX: &str = "ENABLE_EVENT_INDEX";
 pub const ENV_DISABLE_MANIFEST_OUTPUT: &str = "DISABLE_MANIFEST_OUTPUT";
 
 use crate::prelude::*;
@@ -59,9 +71,12 @@ use radix_engine::blueprints::consensus_manager::*;
 use radix_engine::blueprints::models::FieldPayload;
 use radix_engine::system::system_db_reader::*;
 use radix_engine::transaction::*;
+use radix_engine::vm::DefaultVmModules;
 use radix_engine_interface::api::ModuleId;
 use radix_engine_interface::blueprints::package::*;
 use radix_substate_store_impls::rocks_db::RocksdbSubstateStore;
+use radix_substate_store_queries::event_index::*;
+use radix_transactions::signing::{ExternalSigner, ProcessConnection, TrySigner};
 use radix_transactions::validation::TransactionValidator;
 
 /// Build fast, reward everyone, and scale without friction
@@ -83,6 +98,7 @@ pub enum Command {
     CallFunction(CallFunction),
     CallMethod(CallMethod),
     ExportPackageDefinition(ExportPackageDefinition),
+    ExportPackageJsonSchema(ExportPackageJsonSchema),
     GenerateKeyPair(GenerateKeyPair),
     Mint(crate::resim::cmd_mint::Mint),
     NewAccount(NewAccount),
@@ -94,86 +110,108 @@ pub enum Command {
     Publish(Publish),
     Reset(Reset),
     Run(Run),
+    Script(Script),
     SetCurrentEpoch(SetCurrentEpoch),
     SetCurrentTime(SetCurrentTime),
     SetDefaultAccount(SetDefaultAccount),
     ShowConfigs(ShowConfigs),
+    ShowEvents(ShowEvents),
     ShowLedger(ShowLedger),
+    Snapshot(Snapshot),
     Show(Show),
     Transfer(Transfer),
 }
 
+impl Command {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        self.run_in_session(&mut SimulatorSession::default(), out)
+    }
+
+    /// Runs the command with the simulator environment of the given session.
+    pub fn run_in_session<O: std::io::Write>(
+        &self,
+        session: &mut SimulatorSession,
+        out: &mut O,
+    ) -> Result<(), String> {
+        match self {
+            Command::CallFunction(cmd) => cmd.run(session, out),
+            Command::CallMethod(cmd) => cmd.run(session, out),
+            Command::ExportPackageDefinition(cmd) => cmd.run(session, out),
+            Command::ExportPackageJsonSchema(cmd) => cmd.run(session, out),
+            Command::GenerateKeyPair(cmd) => cmd.run(out),
+            Command::Mint(cmd) => cmd.run(session, out),
+            Command::NewAccount(cmd) => cmd.run(session, out),
+            Command::NewSimpleBadge(cmd) => cmd.run(session, out).map(|_| ()),
+            Command::NewBadgeFixed(cmd) => cmd.run(session, out),
+            Command::NewBadgeMutable(cmd) => cmd.run(session, out),
+            Command::NewTokenFixed(cmd) => cmd.run(session, out),
+            Command::NewTokenMutable(cmd) => cmd.run(session, out),
+            Command::Publish(cmd) => cmd.run(session, out),
+            Command::Reset(cmd) => cmd.run(session, out),
+            Command::Run(cmd) => cmd.run(session, out),
+            Command::Script(cmd) => cmd.run(session, out),
+            Command::SetCurrentEpoch(cmd) => cmd.run(session, out),
+            Command::SetCurrentTime(cmd) => cmd.run(session, out),
+            Command::SetDefaultAccount(cmd) => cmd.run(out),
+            Command::ShowConfigs(cmd) => cmd.run(out),
+            Command::ShowEvents(cmd) => cmd.run(session, out),
+            Command::ShowLedger(cmd) => cmd.run(session, out),
+            Command::Snapshot(cmd) => cmd.run(session, out),
+            Command::Show(cmd) => cmd.run(session, out),
+            Command::Transfer(cmd) => cmd.run(session, out),
+        }
+    }
+}
+
 pub fn run() -> Result<(), String> {
     let cli = ResimCli::parse();
 
     let mut out = std::io::stdout();
 
-    match cli.command {
-        Command::CallFunction(cmd) => cmd.run(&mut out),
-        Command::CallMethod(cmd) => cmd.run(&mut out),
-        Command::ExportPackageDefinition(cmd) => cmd.run(&mut out),
-        Command::GenerateKeyPair(cmd) => cmd.run(&mut out),
-        Command::Mint(cmd) => cmd.run(&mut out),
-        Command::NewAccount(cmd) => cmd.run(&mut out),
-        Command::NewSimpleBadge(cmd) => cmd.run(&mut out).map(|_| ()),
-        Command::NewBadgeFixed(cmd) => cmd.run(&mut out),
-        Command::NewBadgeMutable(cmd) => cmd.run(&mut out),
-        Command::NewTokenFixed(cmd) => cmd.run(&mut out),
-        Command::NewTokenMutable(cmd) => cmd.run(&mut out),
-        Command::Publish(cmd) => cmd.run(&mut out),
-        Command::Reset(cmd) => cmd.run(&mut out),
-        Command::Run(cmd) => cmd.run(&mut out),
-        Command::SetCurrentEpoch(cmd) => cmd.run(&mut out),
-        Command::SetCurrentTime(cmd) => cmd.run(&mut out),
-        Command::SetDefaultAccount(cmd) => cmd.run(&mut out),
-        Command::ShowConfigs(cmd) => cmd.run(&mut out),
-        Command::ShowLedger(cmd) => cmd.run(&mut out),
-        Command::Show(cmd) => cmd.run(&mut out),
-        Command::Transfer(cmd) => cmd.run(&mut out),
-    }
+    cli.command.run(&mut out)
 }
 
 pub fn handle_system_transaction<O: std::io::Write>(
+    session: &mut SimulatorSession,
     manifest: SystemTransactionManifestV1,
     initial_proofs: BTreeSet<NonFungibleGlobalId>,
     trace: bool,
     print_receipt: bool,
     out: &mut O,
 ) -> Result<TransactionReceipt, Error> {
-    let SimulatorEnvironment {
-        mut db, vm_modules, ..
-    } = SimulatorEnvironment::new()?;
+    let SimulatorEnvironment { db, vm_modules, .. } = session.env()?;
 
     let nonce = get_nonce()?;
     let unique_hash = hash(format!("Simulator system transaction: {}", nonce));
     let transaction = manifest.into_transaction(unique_hash);
-    let validator = TransactionValidator::new(&db, &NetworkDefinition::simulator());
+    let validator = TransactionValidator::new(&*db, &NetworkDefinition::simulator());
 
-    let receipt = execute_and_commit_transaction(
-        &mut db,
-        &vm_modules,
+    let receipt = execute_and_commit_transaction_with_events(
+        db,
+        vm_modules,
         &ExecutionConfig::for_system_transaction(NetworkDefinition::simulator())
             .with_kernel_trace(trace),
         transaction
             .with_proofs(initial_proofs)
             .into_executable(&validator)
             .map_err(Error::TransactionPrepareError)?,
+        nonce,
     );
 
     if print_receipt {
         let encoder = AddressBech32Encoder::for_simulator();
         let display_context = TransactionReceiptDisplayContextBuilder::new()
             .encoder(&encoder)
-            .schema_lookup_from_db(&db)
+            .schema_lookup_from_db(&*db)
             .build();
         writeln!(out, "{}", receipt.display(display_context)).map_err(Error::IOError)?;
     }
-    drop(db);
 
     process_receipt(receipt)
 }
 
 pub fn handle_manifest<O: std::io::Write>(
+    session: &mut SimulatorSession,
     manifest: AnyManifest,
     signing_keys: &Option<String>,
     network: &Option<String>,
@@ -181,6 +219,34 @@ pub fn handle_manifest<O: std::io::Write>(
     trace: bool,
     print_receipt: bool,
     out: &mut O,
+) -> Result<Option<TransactionReceipt>, String> {
+    handle_manifest_with_external_signer(
+        session,
+        manifest,
+        signing_keys,
+        None,
+        network,
+        write_manifest,
+        trace,
+        print_receipt,
+        out,
+    )
+}
+
+/// Like [`handle_manifest`], but also signs with an [`ExternalSigner`].
+///
+/// The simulator doesn't check signatures, so the external signer is asked to sign the hash of the
+/// transaction as an approval, and its key is then used like the signing keys.
+pub fn handle_manifest_with_external_signer<O: std::io::Write>(
+    session: &mut SimulatorSession,
+    manifest: AnyManifest,
+    signing_keys: &Option<String>,
+    external_signer: Option<&ExternalSigner<ProcessConnection>>,
+    network: &Option<String>,
+    write_manifest: &Option<PathBuf>,
+    trace: bool,
+    print_receipt: bool,
+    out: &mut O,
 ) -> Result<Option<TransactionReceipt>, String> {
     let network = match network {
         Some(n) => NetworkDefinition::from_str(n).map_err(Error::ParseNetworkError)?,
@@ -209,38 +275,47 @@ pub fn handle_manifest<O: std::io::Write>(
             Ok(None)
         }
         None => {
-            let SimulatorEnvironment {
-                mut db, vm_modules, ..
-            } = SimulatorEnvironment::new()?;
+            let SimulatorEnvironment { db, vm_modules, .. } = session.env()?;
 
             let sks = get_signing_keys(signing_keys)?;
-            let initial_proofs = sks
+            let mut initial_proofs = sks
                 .into_iter()
                 .map(|e| NonFungibleGlobalId::from_public_key(e.public_key()))
                 .collect::<BTreeSet<NonFungibleGlobalId>>();
+            if let Some(external_signer) = external_signer {
+                initial_proofs.insert(NonFungibleGlobalId::from_public_key(
+                    external_signer.public_key(),
+                ));
+            }
             let nonce = get_nonce()?;
-            let validator = TransactionValidator::new(&db, &NetworkDefinition::simulator());
+            let validator = TransactionValidator::new(&*db, &NetworkDefinition::simulator());
             let transaction =
                 TestTransaction::new_from_any_manifest(manifest, nonce, initial_proofs)?;
+            let executable = transaction
+                .into_executable(&validator)
+                .map_err(Error::TransactionPrepareError)?;
+            if let Some(external_signer) = external_signer {
+                external_signer
+                    .try_sign_without_public_key(executable.unique_hash())
+                    .map_err(Error::SignerError)?;
+            }
 
-            let receipt = execute_and_commit_transaction(
-                &mut db,
-                &vm_modules,
+            let receipt = execute_and_commit_transaction_with_events(
+                db,
+                vm_modules,
                 &ExecutionConfig::for_test_transaction().with_kernel_trace(trace),
-                transaction
-                    .into_executable(&validator)
-                    .map_err(Error::TransactionPrepareError)?,
+                executable,
+                nonce,
             );
 
             if print_receipt {
                 let encoder = AddressBech32Encoder::for_simulator();
                 let display_context = TransactionReceiptDisplayContextBuilder::new()
                     .encoder(&encoder)
-                    .schema_lookup_from_db(&db)
+                    .schema_lookup_from_db(&*db)
                     .build();
                 writeln!(out, "{}", receipt.display(display_context)).map_err(Error::IOError)?;
             }
-            drop(db);
 
             process_receipt(receipt)
                 .map(Option::Some)
@@ -249,6 +324,35 @@ pub fn handle_manifest<O: std::io::Write>(
     }
 }
 
+/// Executes a transaction and commits it to the ledger, together with the index of its application
+/// events if enabled with the `ENABLE_EVENT_INDEX` environment variable, so that they can be queried
+/// with `resim show-events`.
+///
+/// The events are indexed under the version of the transaction in the ledger, which is the number
+/// of transactions committed by the simulator up to and including it, i.e. its nonce plus one.
+pub fn execute_and_commit_transaction_with_events(
+    db: &mut RocksdbSubstateStore,
+    vm_modules: &DefaultVmModules,
+    execution_config: &ExecutionConfig,
+    executable: impl AsRef<ExecutableTransaction>,
+    nonce: u32,
+) -> TransactionReceipt {
+    if env::var(ENV_ENABLE_EVENT_INDEX).is_err() {
+        return execute_and_commit_transaction(db, vm_modules, execution_config, executable);
+    }
+    let receipt = execute_transaction(&*db, vm_modules, execution_config, executable);
+    if let TransactionResult::Commit(commit) = &receipt.result {
+        let events =
+            resolve_uncommitted_events(&*db, &commit.state_updates, &commit.application_events);
+        db.commit_and_index_events(
+            &commit.state_updates.create_database_updates(),
+            nonce as u64 + 1,
+            events,
+        );
+    }
+    receipt
+}
+
 pub fn process_receipt(receipt: TransactionReceipt) -> Result<TransactionReceipt, Error> {
     match &receipt.result {
         TransactionResult::Commit(commit) => {
@@ -293,31 +397,36 @@ pub fn get_signing_keys(signing_keys: &Option<String>) -> Result<Vec<Secp256k1Pr
 }
 
 pub fn export_package_schema(
+    session: &mut SimulatorSession,
     package_address: PackageAddress,
 ) -> Result<BTreeMap<BlueprintVersionKey, BlueprintDefinition>, Error> {
-    let SimulatorEnvironment { db, .. } = SimulatorEnvironment::new()?;
+    let SimulatorEnvironment { db, .. } = session.env()?;
 
-    let system_reader = SystemDatabaseReader::new(&db);
+    let system_reader = SystemDatabaseReader::new(&*db);
     let package_definition = system_reader.get_package_definition(package_address);
     Ok(package_definition)
 }
 
-pub fn export_object_info(component_address: ComponentAddress) -> Result<ObjectInfo, Error> {
-    let SimulatorEnvironment { db, .. } = SimulatorEnvironment::new()?;
+pub fn export_object_info(
+    session: &mut SimulatorSession,
+    component_address: ComponentAddress,
+) -> Result<ObjectInfo, Error> {
+    let SimulatorEnvironment { db, .. } = session.env()?;
 
-    let system_reader = SystemDatabaseReader::new(&db);
+    let system_reader = SystemDatabaseReader::new(&*db);
     system_reader
         .get_object_info(component_address)
         .map_err(|_| Error::ComponentNotFound(component_address))
 }
 
 pub fn export_schema(
+    session: &mut SimulatorSession,
     node_id: &NodeId,
     schema_hash: SchemaHash,
 ) -> Result<VersionedScryptoSchema, Error> {
-    let SimulatorEnvironment { db, .. } = SimulatorEnvironment::new()?;
+    let SimulatorEnvironment { db, .. } = session.env()?;
 
-    let system_reader = SystemDatabaseReader::new(&db);
+    let system_reader = SystemDatabaseReader::new(&*db);
     let schema = system_reader
         .get_schema(node_id, &schema_hash)
         .map_err(|_| Error::SchemaNotFound(*node_id, schema_hash))?;
@@ -326,10 +435,11 @@ pub fn export_schema(
 }
 
 pub fn export_blueprint_interface(
+    session: &mut SimulatorSession,
     package_address: PackageAddress,
     blueprint_name: &str,
 ) -> Result<BlueprintInterface, Error> {
-    let interface = export_package_schema(package_address)?
+    let interface = export_package_schema(session, package_address)?
         .get(&BlueprintVersionKey::new_default(blueprint_name))
         .cloned()
         .ok_or(Error::BlueprintNotFound(
@@ -340,10 +450,13 @@ pub fn export_blueprint_interface(
     Ok(interface)
 }
 
-pub fn get_blueprint_id(component_address: ComponentAddress) -> Result<BlueprintId, Error> {
-    let SimulatorEnvironment { db, .. } = SimulatorEnvironment::new()?;
+pub fn get_blueprint_id(
+    session: &mut SimulatorSession,
+    component_address: ComponentAddress,
+) -> Result<BlueprintId, Error> {
+    let SimulatorEnvironment { db, .. } = session.env()?;
 
-    let system_reader = SystemDatabaseReader::new(&db);
+    let system_reader = SystemDatabaseReader::new(&*db);
     let object_info = system_reader
         .get_object_info(component_address)
         .expect("Unexpected");
@@ -351,12 +464,13 @@ pub fn get_blueprint_id(component_address: ComponentAddress) -> Result<Blueprint
 }
 
 pub fn db_upsert_timestamps(
+    session: &mut SimulatorSession,
     milli_timestamp: ProposerMilliTimestampSubstate,
     minute_timestamp: ProposerMinuteTimestampSubstate,
 ) -> Result<(), Error> {
-    let SimulatorEnvironment { mut db, .. } = SimulatorEnvironment::new()?;
+    let SimulatorEnvironment { db, .. } = session.env()?;
 
-    let mut writer = SystemDatabaseWriter::new(&mut db);
+    let mut writer = SystemDatabaseWriter::new(db);
 
     writer
         .write_typed_object_field(
@@ -383,10 +497,10 @@ pub fn db_upsert_timestamps(
     Ok(())
 }
 
-pub fn db_upsert_epoch(epoch: Epoch) -> Result<(), Error> {
-    let SimulatorEnvironment { mut db, .. } = SimulatorEnvironment::new()?;
+pub fn db_upsert_epoch(session: &mut SimulatorSession, epoch: Epoch) -> Result<(), Error> {
+    let SimulatorEnvironment { db, .. } = session.env()?;
 
-    let reader = SystemDatabaseReader::new(&db);
+    let reader = SystemDatabaseReader::new(&*db);
 
     let mut consensus_mgr_state = reader
         .read_typed_object_field::<ConsensusManagerStateFieldPayload>(
@@ -408,7 +522,7 @@ pub fn db_upsert_epoch(epoch: Epoch) -> Result<(), Error> {
 
     consensus_mgr_state.epoch = epoch;
 
-    let mut writer = SystemDatabaseWriter::new(&mut db);
+    let mut writer = SystemDatabaseWriter::new(db);
 
     writer
         .write_typed_object_field(
@@ -427,17 +541,21 @@ mod tests {
     use super::*;
 
     fn test_no_value() {
+        let mut session = SimulatorSession::default();
         let mut out = std::io::stdout();
-        let rtn = Reset {}.run(&mut out);
+        let rtn = Reset {}.run(&mut session, &mut out);
         assert!(rtn.is_ok(), "Reset failed with: {:?}", rtn);
         let new_account = NewAccount {
             network: None,
             manifest: None,
             trace: false,
         };
-        assert!(new_account.run(&mut out).is_ok());
-        let cmd = Show { address: None };
-        assert!(cmd.run(&mut out).is_ok());
+        assert!(new_account.run(&mut session, &mut out).is_ok());
+        let cmd = Show {
+            address: None,
+            ledger: LedgerOpenArgs::default(),
+        };
+        assert!(cmd.run(&mut session, &mut out).is_ok());
     }
 
     fn test_pre_process_manifest() {
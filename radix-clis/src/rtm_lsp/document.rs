@@ -0,0 +1,646 @@
+use super::reference::*;
+use crate::prelude::*;
+use lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, Documentation, Hover,
+    HoverContents, MarkupContent, MarkupKind, Range,
+};
+use radix_transactions::manifest::ast::{Instruction, ValueKind, ValueKindWithSpan};
+use radix_transactions::manifest::token::{Span, Token, TokenWithSpan};
+
+/// The value kinds which bind a name that can be referenced by later instructions.
+const NAMED_VALUE_KINDS: [ValueKind; 5] = [
+    ValueKind::Bucket,
+    ValueKind::Proof,
+    ValueKind::AddressReservation,
+    ValueKind::NamedAddress,
+    ValueKind::NamedIntent,
+];
+
+/// A reference to a named value, such as `Bucket("name")`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedValue {
+    pub value_kind: ValueKind,
+    pub name: String,
+    /// The span from the value kind to the closing parenthesis.
+    pub span: Span,
+}
+
+/// A manifest opened in the editor, along with the tokens it's made of.
+///
+/// Manifests are usually invalid while being edited, so lexing stops at the first error and all
+/// of the features besides the diagnostics work on the tokens before it. Spans of tokens are in
+/// chars, while the positions of the LSP are in UTF-16 code units, so all positions are converted
+/// through the text of the document.
+pub struct ManifestDocument {
+    text: String,
+    chars: Vec<char>,
+    /// The char index of the start of each line.
+    line_starts: Vec<usize>,
+    tokens: Vec<TokenWithSpan>,
+    named_values: Vec<NamedValue>,
+}
+
+impl ManifestDocument {
+    pub fn new(text: String) -> Self {
+        let chars: Vec<char> = text.chars().collect();
+        let line_starts = std::iter::once(0)
+            .chain(
+                chars
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| **c == '\n')
+                    .map(|(index, _)| index + 1),
+            )
+            .collect();
+
+        let mut tokens = Vec::new();
+        let mut lexer = lexer::Lexer::new(&text);
+        while let Ok(Some(token)) = lexer.next_token() {
+            tokens.push(token);
+        }
+
+        let named_values = tokens
+            .windows(4)
+            .filter_map(|window| match window {
+                [TokenWithSpan {
+                    token: Token::Ident(ident),
+                    span: start,
+                }, TokenWithSpan {
+                    token: Token::OpenParenthesis,
+                    ..
+                }, TokenWithSpan {
+                    token: Token::StringLiteral(name),
+                    ..
+                }, TokenWithSpan {
+                    token: Token::CloseParenthesis,
+                    span: end,
+                }] => Some(NamedValue {
+                    value_kind: named_value_kind(ident)?,
+                    name: name.clone(),
+                    span: Span {
+                        start: start.start,
+                        end: end.end,
+                    },
+                }),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            text,
+            chars,
+            line_starts,
+            tokens,
+            named_values,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Compiles and validates the manifest in the same way as `rtmc`.
+    pub fn diagnostics(
+        &self,
+        manifest_kind: ManifestKind,
+        network: &NetworkDefinition,
+    ) -> Vec<Diagnostic> {
+        let manifest =
+            match compile_any_manifest(&self.text, manifest_kind, network, MockBlobProvider::new())
+            {
+                Ok(manifest) => manifest,
+                Err(err) => {
+                    let details = compile_error_details(err);
+                    return vec![self.diagnostic(&details.span, details.title)];
+                }
+            };
+
+        let instruction_spans = self.instruction_spans();
+        let mut diagnostics = Vec::new();
+        if let Err(err) = manifest.validate(ValidationRuleset::all()) {
+            // Validation errors aren't located, so they're reported against the first instruction
+            if let Some(span) = instruction_spans.first() {
+                diagnostics.push(self.diagnostic(span, format!("invalid manifest: {:?}", err)));
+            }
+        }
+        if let Err(err) = validate_call_arguments_to_native_components(&manifest) {
+            if let Some(span) = instruction_spans.get(err.instruction_index) {
+                diagnostics
+                    .push(self.diagnostic(span, format!("invalid arguments: {:?}", err.cause)));
+            }
+        }
+        diagnostics
+    }
+
+    pub fn hover(
+        &self,
+        position: lsp_types::Position,
+        network: &NetworkDefinition,
+    ) -> Option<Hover> {
+        let index = self.char_index(position);
+        let (span, markdown) = if let Some(named_value) = self.named_value_at(index) {
+            (named_value.span, self.named_value_markdown(named_value))
+        } else {
+            let token_index = self.token_at(index)?;
+            let token = &self.tokens[token_index];
+            let markdown = match &token.token {
+                Token::Ident(ident) if self.is_instruction_position(token_index) => {
+                    instruction_markdown(instruction_reference(ident)?)
+                }
+                Token::Ident(ident) => value_markdown(ident, token.span)?,
+                Token::StringLiteral(value) => address_markdown(value, network)?,
+                literal => value_markdown(literal_ident(literal)?, token.span)?,
+            };
+            (token.span, markdown)
+        };
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: markdown,
+            }),
+            range: Some(self.range(&span)),
+        })
+    }
+
+    /// Completes the names of named values inside of a named value, such as `Bucket("`, and the
+    /// names of instructions at the start of an instruction.
+    pub fn completions(&self, position: lsp_types::Position) -> Vec<CompletionItem> {
+        let index = self.char_index(position);
+        let line_start = self.line_starts[self.line_idx(index)];
+        let line: String = self.chars[line_start..index].iter().collect();
+
+        if let Some((value_kind, is_quoted)) = named_value_context(&line) {
+            self.named_values
+                .iter()
+                .filter(|named_value| {
+                    named_value.value_kind == value_kind && named_value.span.end.full_index <= index
+                })
+                .filter(|named_value| self.declaration(named_value) == *named_value)
+                .map(|named_value| CompletionItem {
+                    label: named_value.name.clone(),
+                    kind: Some(CompletionItemKind::VARIABLE),
+                    detail: Some(self.declaration_description(named_value)),
+                    insert_text: (!is_quoted).then(|| format!("\"{}\"", named_value.name)),
+                    ..Default::default()
+                })
+                .collect()
+        } else if self.is_instruction_position_at(index) {
+            INSTRUCTIONS
+                .iter()
+                .map(|instruction| CompletionItem {
+                    label: instruction.ident.to_owned(),
+                    kind: Some(CompletionItemKind::KEYWORD),
+                    detail: Some(instruction.signature()),
+                    documentation: Some(Documentation::String(instruction.description.to_owned())),
+                    ..Default::default()
+                })
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Resolves a named value, such as `Bucket("name")`, to the named value which declares it.
+    pub fn definition(&self, position: lsp_types::Position) -> Option<Range> {
+        let named_value = self.named_value_at(self.char_index(position))?;
+        Some(self.range(&self.declaration(named_value).span))
+    }
+
+    fn diagnostic(&self, span: &Span, message: String) -> Diagnostic {
+        Diagnostic {
+            range: self.range(span),
+            severity: Some(DiagnosticSeverity::ERROR),
+            source: Some("rtm".to_owned()),
+            message,
+            ..Default::default()
+        }
+    }
+
+    /// The spans of the instructions of the compiled manifest, which excludes the
+    /// pseudo-instructions.
+    fn instruction_spans(&self) -> Vec<Span> {
+        let Ok(tokens) = lexer::tokenize(&self.text) else {
+            return Vec::new();
+        };
+        let Ok(instructions) = parser::Parser::new(tokens, parser::PARSER_MAX_DEPTH)
+            .and_then(|mut parser| parser.parse_manifest())
+        else {
+            return Vec::new();
+        };
+        instructions
+            .into_iter()
+            .filter(|instruction| {
+                !matches!(
+                    instruction.instruction,
+                    Instruction::UsePreallocatedAddress { .. } | Instruction::UseChild { .. }
+                )
+            })
+            .map(|instruction| instruction.span)
+            .collect()
+    }
+
+    /// Names can't be redefined, so a named value is declared by its first occurrence.
+    fn declaration<'a>(&'a self, named_value: &'a NamedValue) -> &'a NamedValue {
+        self.named_values
+            .iter()
+            .find(|declaration| {
+                declaration.value_kind == named_value.value_kind
+                    && declaration.name == named_value.name
+            })
+            .unwrap_or(named_value)
+    }
+
+    fn declaration_description(&self, named_value: &NamedValue) -> String {
+        let declaration = self.declaration(named_value);
+        match self.instruction_containing(declaration.span.start.full_index) {
+            Some(instruction) => format!(
+                "{} declared by {} on line {}",
+                declaration.value_kind,
+                instruction,
+                declaration.span.start.line_number()
+            ),
+            None => format!(
+                "{} declared on line {}",
+                declaration.value_kind,
+                declaration.span.start.line_number()
+            ),
+        }
+    }
+
+    fn named_value_markdown(&self, named_value: &NamedValue) -> String {
+        format!(
+            "```\n{}(\"{}\")\n```\n\n{}",
+            named_value.value_kind,
+            named_value.name,
+            self.declaration_description(named_value)
+        )
+    }
+
+    fn named_value_at(&self, index: usize) -> Option<&NamedValue> {
+        self.named_values.iter().find(|named_value| {
+            named_value.span.start.full_index <= index && index <= named_value.span.end.full_index
+        })
+    }
+
+    /// The token containing the char index, or ending at it.
+    fn token_at(&self, index: usize) -> Option<usize> {
+        self.tokens.iter().position(|token| {
+            token.span.start.full_index <= index && index <= token.span.end.full_index
+        })
+    }
+
+    fn is_instruction_position(&self, token_index: usize) -> bool {
+        token_index == 0 || self.tokens[token_index - 1].token == Token::Semicolon
+    }
+
+    /// Whether the word being typed at the char index is at the start of an instruction.
+    fn is_instruction_position_at(&self, index: usize) -> bool {
+        let word_length = self.chars[..index]
+            .iter()
+            .rev()
+            .take_while(|c| is_ident_char(**c))
+            .count();
+        self.tokens
+            .iter()
+            .take_while(|token| token.span.end.full_index <= index - word_length)
+            .last()
+            .is_none_or(|token| token.token == Token::Semicolon)
+    }
+
+    /// The name of the instruction which contains the char index.
+    fn instruction_containing(&self, index: usize) -> Option<&str> {
+        let mut instruction = None;
+        let mut is_instruction_position = true;
+        for token in self
+            .tokens
+            .iter()
+            .take_while(|token| token.span.start.full_index <= index)
+        {
+            match &token.token {
+                Token::Semicolon => {
+                    instruction = None;
+                    is_instruction_position = true;
+                }
+                Token::Ident(ident) if is_instruction_position => {
+                    instruction = Some(ident.as_str());
+                    is_instruction_position = false;
+                }
+                _ => is_instruction_position = false,
+            }
+        }
+        instruction
+    }
+
+    fn line_idx(&self, index: usize) -> usize {
+        self.line_starts
+            .partition_point(|line_start| *line_start <= index)
+            - 1
+    }
+
+    /// Converts a position of the LSP to a char index, clamped to the end of its line.
+    fn char_index(&self, position: lsp_types::Position) -> usize {
+        let Some(line_start) = self.line_starts.get(position.line as usize) else {
+            return self.chars.len();
+        };
+        let mut index = *line_start;
+        let mut character = 0;
+        while index < self.chars.len()
+            && self.chars[index] != '\n'
+            && character < position.character as usize
+        {
+            character += self.chars[index].len_utf16();
+            index += 1;
+        }
+        index
+    }
+
+    fn position(&self, index: usize) -> lsp_types::Position {
+        let index = index.min(self.chars.len());
+        let line_idx = self.line_idx(index);
+        let character: usize = self.chars[self.line_starts[line_idx]..index]
+            .iter()
+            .map(|c| c.len_utf16())
+            .sum();
+        lsp_types::Position::new(line_idx as u32, character as u32)
+    }
+
+    pub fn range(&self, span: &Span) -> Range {
+        Range::new(
+            self.position(span.start.full_index),
+            self.position(span.end.full_index),
+        )
+    }
+}
+
+fn named_value_kind(ident: &str) -> Option<ValueKind> {
+    ValueKind::from_ident(ident).filter(|value_kind| NAMED_VALUE_KINDS.contains(value_kind))
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Determines whether the end of the line is inside of a named value, either right after the
+/// opening parenthesis or inside of the name, and returns its value kind and whether the name is
+/// already quoted.
+fn named_value_context(line: &str) -> Option<(ValueKind, bool)> {
+    let opening = |s: &str| {
+        let s = s.trim_end().strip_suffix('(')?.trim_end();
+        let ident_start = s
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| is_ident_char(*c))
+            .last()?
+            .0;
+        named_value_kind(&s[ident_start..])
+    };
+
+    if let Some(value_kind) = opening(line) {
+        Some((value_kind, false))
+    } else {
+        let quote = line.rfind('"')?;
+        Some((opening(&line[..quote])?, true))
+    }
+}
+
+fn literal_ident(token: &Token) -> Option<&'static str> {
+    let ident = match token {
+        Token::BoolLiteral(_) => "Bool",
+        Token::I8Literal(_) => "I8",
+        Token::I16Literal(_) => "I16",
+        Token::I32Literal(_) => "I32",
+        Token::I64Literal(_) => "I64",
+        Token::I128Literal(_) => "I128",
+        Token::U8Literal(_) => "U8",
+        Token::U16Literal(_) => "U16",
+        Token::U32Literal(_) => "U32",
+        Token::U64Literal(_) => "U64",
+        Token::U128Literal(_) => "U128",
+        _ => return None,
+    };
+    Some(ident)
+}
+
+fn instruction_markdown(instruction: &InstructionReference) -> String {
+    format!(
+        "```\n{}\n```\n\n{}",
+        instruction.signature(),
+        instruction.description
+    )
+}
+
+fn value_markdown(ident: &str, span: Span) -> Option<String> {
+    let value = value_reference(ident)?;
+    let mut markdown = format!("```\n{}\n```\n\n{}", value.syntax, value.description);
+    if let Some(value_kind) = ValueKind::from_ident(ident) {
+        if let Ok(sbor_value_kind) = (ValueKindWithSpan { value_kind, span }).sbor_value_kind() {
+            markdown.push_str(&format!(
+                "\n\nEncoded with the SBOR value kind `{:?}`.",
+                sbor_value_kind
+            ));
+        }
+    }
+    Some(markdown)
+}
+
+fn address_markdown(address: &str, network: &NetworkDefinition) -> Option<String> {
+    let (hrp, entity_type, node_id) =
+        AddressBech32Decoder::validate_and_decode_ignore_hrp(address).ok()?;
+    let mut markdown = format!(
+        "**{}** address\n\nNode id: `{}`",
+        if entity_type.is_global() {
+            "Global"
+        } else {
+            "Internal"
+        },
+        hex::encode(node_id)
+    );
+    markdown.push_str(&format!("\n\nEntity type: `{:?}`", entity_type));
+    match AddressBech32Decoder::new(network).validate_and_decode(address) {
+        Ok(_) => markdown.push_str(&format!("\n\nNetwork: `{}`", network.logical_name)),
+        Err(_) => markdown.push_str(&format!(
+            "\n\nThe HRP `{}` is not valid for this entity type on the `{}` network.",
+            hrp, network.logical_name
+        )),
+    }
+    Some(markdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"CALL_METHOD
+    Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh")
+    "free"
+;
+TAKE_ALL_FROM_WORKTOP
+    Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3")
+    Bucket("xrd")
+;
+CREATE_PROOF_FROM_BUCKET_OF_ALL
+    Bucket("xrd")
+    Proof("proof")
+;
+DROP_PROOF
+    Proof("proof")
+;
+CALL_METHOD
+    Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh")
+    "lock_fee"
+    Decimal("10")
+;
+RETURN_TO_WORKTOP
+    Bucket("xrd")
+;
+"#;
+
+    fn hover_markdown(document: &ManifestDocument, line: u32, character: u32) -> String {
+        let hover = document
+            .hover(
+                lsp_types::Position::new(line, character),
+                &NetworkDefinition::simulator(),
+            )
+            .unwrap();
+        match hover.contents {
+            HoverContents::Markup(content) => content.value,
+            contents => panic!("Unexpected hover contents {:?}", contents),
+        }
+    }
+
+    #[test]
+    fn valid_manifest_has_no_diagnostics() {
+        let document = ManifestDocument::new(MANIFEST.to_owned());
+
+        let diagnostics = document.diagnostics(ManifestKind::V2, &NetworkDefinition::simulator());
+
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    fn undefined_bucket_is_reported_at_its_span() {
+        let document = ManifestDocument::new(MANIFEST.replace(
+            "RETURN_TO_WORKTOP\n    Bucket(\"xrd\")",
+            "RETURN_TO_WORKTOP\n    Bucket(\"xdr\")",
+        ));
+
+        let diagnostics = document.diagnostics(ManifestKind::V2, &NetworkDefinition::simulator());
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "undefined bucket 'xdr'");
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(
+                lsp_types::Position::new(21, 11),
+                lsp_types::Position::new(21, 16)
+            )
+        );
+    }
+
+    #[test]
+    fn native_call_arguments_are_validated() {
+        // Only calls to native components are validated, and the faucet isn't one
+        let consensus_manager = AddressBech32Encoder::for_simulator()
+            .encode(CONSENSUS_MANAGER.as_node_id().as_bytes())
+            .unwrap();
+        let document = ManifestDocument::new(MANIFEST.replace(
+            "component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh\")\n    \"lock_fee\"",
+            &format!("{consensus_manager}\")\n    \"get_current_epoch\""),
+        ));
+
+        let diagnostics = document.diagnostics(ManifestKind::V2, &NetworkDefinition::simulator());
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.starts_with("invalid arguments"));
+        assert_eq!(diagnostics[0].range.start, lsp_types::Position::new(15, 0));
+    }
+
+    #[test]
+    fn hover_shows_instruction_documentation() {
+        let document = ManifestDocument::new(MANIFEST.to_owned());
+
+        let markdown = hover_markdown(&document, 4, 3);
+
+        assert!(markdown.contains("TAKE_ALL_FROM_WORKTOP resource_address Bucket(\"name\");"));
+    }
+
+    #[test]
+    fn hover_shows_value_kinds() {
+        let document = ManifestDocument::new(MANIFEST.to_owned());
+
+        assert!(hover_markdown(&document, 18, 6).contains("`Custom(Decimal)`"));
+    }
+
+    #[test]
+    fn hover_resolves_addresses() {
+        let document = ManifestDocument::new(MANIFEST.to_owned());
+
+        let markdown = hover_markdown(&document, 5, 20);
+
+        assert!(markdown.contains("`GlobalFungibleResourceManager`"));
+        assert!(markdown.contains(&hex::encode(XRD.as_node_id().as_bytes())));
+        assert!(markdown.contains("Network: `simulator`"));
+    }
+
+    #[test]
+    fn hover_shows_declaration_of_named_values() {
+        let document = ManifestDocument::new(MANIFEST.to_owned());
+
+        let markdown = hover_markdown(&document, 13, 12);
+
+        assert!(markdown.contains("Proof declared by CREATE_PROOF_FROM_BUCKET_OF_ALL on line 11"));
+    }
+
+    #[test]
+    fn instruction_names_are_completed_at_the_start_of_an_instruction() {
+        let document = ManifestDocument::new(format!("{MANIFEST}TAKE_"));
+
+        let completions = document.completions(lsp_types::Position::new(23, 5));
+
+        assert_eq!(completions.len(), INSTRUCTIONS.len());
+        assert!(completions
+            .iter()
+            .any(|completion| completion.label == "TAKE_FROM_WORKTOP"));
+    }
+
+    #[test]
+    fn declared_names_are_completed_inside_of_named_values() {
+        let document = ManifestDocument::new(format!("{MANIFEST}BURN_RESOURCE Bucket(\""));
+
+        let completions = document.completions(lsp_types::Position::new(23, 22));
+
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].label, "xrd");
+        assert_eq!(completions[0].insert_text, None);
+        assert_eq!(
+            completions[0].detail.as_deref(),
+            Some("Bucket declared by TAKE_ALL_FROM_WORKTOP on line 7")
+        );
+    }
+
+    #[test]
+    fn names_are_quoted_when_completed_after_the_parenthesis() {
+        let document = ManifestDocument::new(format!("{MANIFEST}DROP_PROOF Proof("));
+
+        let completions = document.completions(lsp_types::Position::new(23, 17));
+
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].insert_text.as_deref(), Some("\"proof\""));
+    }
+
+    #[test]
+    fn named_values_resolve_to_their_declaration() {
+        let document = ManifestDocument::new(MANIFEST.to_owned());
+
+        let definition = document.definition(lsp_types::Position::new(21, 13));
+
+        assert_eq!(
+            definition,
+            Some(Range::new(
+                lsp_types::Position::new(6, 4),
+                lsp_types::Position::new(6, 17)
+            ))
+        );
+    }
+}
@@ -0,0 +1,90 @@
+mod document;
+mod reference;
+mod server;
+
+pub use document::*;
+pub use reference::*;
+pub use server::*;
+
+use crate::prelude::*;
+use lsp_server::{Connection, Message};
+
+/// Radix transaction manifest language server, communicating over stdio
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None, name = "rtm-lsp")]
+pub struct Args {
+    /// Network to Use [Simulator | Alphanet | Mainnet]
+    #[clap(short, long)]
+    network: Option<String>,
+
+    /// The manifest type [V1 | SystemV1 | V2 | SubintentV2], defaults to V2
+    #[clap(short, long)]
+    kind: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IoError(std::io::Error),
+    JsonError(serde_json::Error),
+    ParseNetworkError(ParseNetworkError),
+    ProtocolError(lsp_server::ProtocolError),
+    ConnectionClosed,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl From<Error> for String {
+    fn from(err: Error) -> String {
+        err.to_string()
+    }
+}
+
+pub fn run() -> Result<(), String> {
+    let args = Args::parse();
+
+    let network = match args.network {
+        Some(n) => NetworkDefinition::from_str(&n).map_err(Error::ParseNetworkError)?,
+        None => NetworkDefinition::simulator(),
+    };
+    let manifest_kind = ManifestKind::parse_or_latest(args.kind.as_deref())?;
+
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(server_capabilities()).map_err(Error::JsonError)?;
+    connection
+        .initialize(capabilities)
+        .map_err(Error::ProtocolError)?;
+
+    let mut server = ManifestLanguageServer::new(manifest_kind, network);
+    for message in &connection.receiver {
+        let response: Message = match message {
+            Message::Request(request) => {
+                if connection
+                    .handle_shutdown(&request)
+                    .map_err(Error::ProtocolError)?
+                {
+                    break;
+                }
+                server.handle_request(request).into()
+            }
+            Message::Notification(notification) => match server.handle_notification(notification) {
+                Some(notification) => notification.into(),
+                None => continue,
+            },
+            Message::Response(_) => continue,
+        };
+        connection
+            .sender
+            .send(response)
+            .map_err(|_| Error::ConnectionClosed)?;
+    }
+
+    // The IO threads only finish once the connection is dropped
+    drop(connection);
+    io_threads.join().map_err(Error::IoError)?;
+
+    Ok(())
+}
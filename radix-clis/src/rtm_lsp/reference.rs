@@ -0,0 +1,267 @@
+/// The documentation of a manifest instruction, shown on hover and completion.
+pub struct InstructionReference {
+    pub ident: &'static str,
+    pub arguments: &'static str,
+    pub description: &'static str,
+}
+
+impl InstructionReference {
+    pub fn signature(&self) -> String {
+        if self.arguments.is_empty() {
+            format!("{};", self.ident)
+        } else {
+            format!("{} {};", self.ident, self.arguments)
+        }
+    }
+}
+
+/// The documentation of a value kind or value alias, shown on hover.
+pub struct ValueReference {
+    pub ident: &'static str,
+    pub syntax: &'static str,
+    pub description: &'static str,
+}
+
+pub fn instruction_reference(ident: &str) -> Option<&'static InstructionReference> {
+    INSTRUCTIONS
+        .iter()
+        .find(|instruction| instruction.ident == ident)
+}
+
+pub fn value_reference(ident: &str) -> Option<&'static ValueReference> {
+    VALUES.iter().find(|value| value.ident == ident)
+}
+
+macro_rules! instructions {
+    ($($ident:literal ($($arguments:literal)?) => $description:literal,)*) => {
+        pub const INSTRUCTIONS: &[InstructionReference] = &[
+            $(
+                InstructionReference {
+                    ident: $ident,
+                    arguments: concat!("" $(, $arguments)?),
+                    description: $description,
+                },
+            )*
+        ];
+    };
+}
+
+instructions! {
+    // Pseudo-instructions
+    "USE_PREALLOCATED_ADDRESS" (
+        "package_address blueprint_name AddressReservation(\"name\") preallocated_address"
+    ) => "Binds an address reservation for an address preallocated by the transaction. Must come before all other instructions.",
+    "USE_CHILD" ("NamedIntent(\"name\") Intent(\"subintent_hash\")")
+        => "Declares a child subintent of this intent and binds a name to it. Must come before all other instructions.",
+
+    // Bucket Lifecycle
+    "TAKE_FROM_WORKTOP" ("resource_address amount Bucket(\"name\")")
+        => "Takes a bucket containing the given amount of resource from the worktop, and binds the given bucket name to that bucket.",
+    "TAKE_NON_FUNGIBLES_FROM_WORKTOP" ("resource_address Array<NonFungibleLocalId>(...) Bucket(\"name\")")
+        => "Takes a bucket containing the given non-fungibles from the worktop, and binds the given bucket name to that bucket.",
+    "TAKE_ALL_FROM_WORKTOP" ("resource_address Bucket(\"name\")")
+        => "Takes a bucket containing all of the given resource from the worktop, and binds the given bucket name to that bucket.",
+    "RETURN_TO_WORKTOP" ("Bucket(\"name\")") => "Returns a bucket to the worktop.",
+    "BURN_RESOURCE" ("Bucket(\"name\")") => "Burns the resources in a bucket.",
+
+    // Resource Assertions
+    "ASSERT_WORKTOP_CONTAINS_ANY" ("resource_address")
+        => "Asserts that the worktop contains a non-zero amount of the given resource.",
+    "ASSERT_WORKTOP_CONTAINS" ("resource_address amount")
+        => "Asserts that the worktop contains at least the given amount of the given resource.",
+    "ASSERT_WORKTOP_CONTAINS_NON_FUNGIBLES" ("resource_address Array<NonFungibleLocalId>(...)")
+        => "Asserts that the worktop contains at least the given non-fungibles.",
+    "ASSERT_WORKTOP_IS_EMPTY" ()
+        => "Asserts that the worktop is empty. An alias of ASSERT_WORKTOP_RESOURCES_ONLY with no constraints.",
+    "ASSERT_WORKTOP_RESOURCES_ONLY" ("constraints")
+        => "Asserts that the worktop contains only the given resources, and that each satisfies its constraint.",
+    "ASSERT_WORKTOP_RESOURCES_INCLUDE" ("constraints")
+        => "Asserts that the worktop contains the given resources, and that each satisfies its constraint.",
+    "ASSERT_NEXT_CALL_RETURNS_ONLY" ("constraints")
+        => "Asserts that the next invocation returns only the given resources, and that each satisfies its constraint.",
+    "ASSERT_NEXT_CALL_RETURNS_INCLUDE" ("constraints")
+        => "Asserts that the next invocation returns the given resources, and that each satisfies its constraint.",
+    "ASSERT_BUCKET_CONTENTS" ("Bucket(\"name\") constraint")
+        => "Asserts that the contents of a bucket satisfy the given constraint.",
+
+    // Proof Lifecycle
+    "CREATE_PROOF_FROM_BUCKET_OF_AMOUNT" ("Bucket(\"name\") amount Proof(\"name\")")
+        => "Creates a proof of the given amount from a bucket, and binds the given proof name to that proof.",
+    "CREATE_PROOF_FROM_BUCKET_OF_NON_FUNGIBLES" ("Bucket(\"name\") Array<NonFungibleLocalId>(...) Proof(\"name\")")
+        => "Creates a proof of the given non-fungibles from a bucket, and binds the given proof name to that proof.",
+    "CREATE_PROOF_FROM_BUCKET_OF_ALL" ("Bucket(\"name\") Proof(\"name\")")
+        => "Creates a proof of all the resources in a bucket, and binds the given proof name to that proof.",
+    "CREATE_PROOF_FROM_AUTH_ZONE_OF_AMOUNT" ("resource_address amount Proof(\"name\")")
+        => "Creates a proof of the given amount of the given resource from the auth zone, and binds the given proof name to that proof.",
+    "CREATE_PROOF_FROM_AUTH_ZONE_OF_NON_FUNGIBLES" ("resource_address Array<NonFungibleLocalId>(...) Proof(\"name\")")
+        => "Creates a proof of the given non-fungibles from the auth zone, and binds the given proof name to that proof.",
+    "CREATE_PROOF_FROM_AUTH_ZONE_OF_ALL" ("resource_address Proof(\"name\")")
+        => "Creates a proof of all of the given resource in the auth zone, and binds the given proof name to that proof.",
+    "CLONE_PROOF" ("Proof(\"name\") Proof(\"name\")")
+        => "Clones a proof, and binds the given proof name to the clone.",
+    "DROP_PROOF" ("Proof(\"name\")") => "Drops a proof.",
+    "PUSH_TO_AUTH_ZONE" ("Proof(\"name\")") => "Pushes a proof onto the auth zone.",
+    "POP_FROM_AUTH_ZONE" ("Proof(\"name\")")
+        => "Pops the most recent proof from the auth zone, and binds the given proof name to that proof.",
+    "DROP_AUTH_ZONE_PROOFS" () => "Drops all proofs in the auth zone.",
+    "DROP_AUTH_ZONE_REGULAR_PROOFS" () => "Drops all regular (non-signature) proofs in the auth zone.",
+    "DROP_AUTH_ZONE_SIGNATURE_PROOFS" () => "Drops all signature proofs in the auth zone.",
+    "DROP_NAMED_PROOFS" () => "Drops all named proofs.",
+    "DROP_ALL_PROOFS" () => "Drops all named proofs and all proofs in the auth zone.",
+
+    // Invocation
+    "CALL_FUNCTION" ("package_address blueprint_name function_name args...")
+        => "Calls a function on a blueprint.",
+    "CALL_METHOD" ("address method_name args...") => "Calls a method on the main module of a component.",
+    "CALL_ROYALTY_METHOD" ("address method_name args...") => "Calls a method on the royalty module of a component.",
+    "CALL_METADATA_METHOD" ("address method_name args...") => "Calls a method on the metadata module of an entity.",
+    "CALL_ROLE_ASSIGNMENT_METHOD" ("address method_name args...")
+        => "Calls a method on the role assignment module of an entity.",
+    "CALL_DIRECT_VAULT_METHOD" ("vault_id method_name args...") => "Calls a direct access method on a vault.",
+
+    // Address Allocation
+    "ALLOCATE_GLOBAL_ADDRESS" ("package_address blueprint_name AddressReservation(\"name\") NamedAddress(\"name\")")
+        => "Allocates a global address for a blueprint, and binds the given names to the address reservation and to the address.",
+
+    // Interaction with other intents
+    "YIELD_TO_PARENT" ("args...") => "Yields the given arguments to the parent intent, and resumes once the parent yields back.",
+    "YIELD_TO_CHILD" ("NamedIntent(\"name\") args...")
+        => "Yields the given arguments to a child subintent, and resumes once the child yields back.",
+    "VERIFY_PARENT" ("access_rule") => "Asserts that the parent intent satisfies the given access rule.",
+
+    // Call direct vault method aliases
+    "RECALL_FROM_VAULT" ("vault_id amount") => "Recalls the given amount of resource from a vault.",
+    "FREEZE_VAULT" ("vault_id flags") => "Freezes a vault.",
+    "UNFREEZE_VAULT" ("vault_id flags") => "Unfreezes a vault.",
+    "RECALL_NON_FUNGIBLES_FROM_VAULT" ("vault_id Array<NonFungibleLocalId>(...)")
+        => "Recalls the given non-fungibles from a vault.",
+
+    // Call function aliases
+    "PUBLISH_PACKAGE" ("definition code metadata") => "Publishes a package.",
+    "PUBLISH_PACKAGE_ADVANCED" ("owner_role definition code metadata address_reservation")
+        => "Publishes a package with an owner role and an optional address reservation.",
+    "CREATE_FUNGIBLE_RESOURCE" ("owner_role track_total_supply divisibility resource_roles metadata address_reservation")
+        => "Creates a fungible resource.",
+    "CREATE_FUNGIBLE_RESOURCE_WITH_INITIAL_SUPPLY" (
+        "owner_role track_total_supply divisibility initial_supply resource_roles metadata address_reservation"
+    ) => "Creates a fungible resource, and puts the initial supply on the worktop.",
+    "CREATE_NON_FUNGIBLE_RESOURCE" (
+        "owner_role id_type track_total_supply non_fungible_schema resource_roles metadata address_reservation"
+    ) => "Creates a non-fungible resource.",
+    "CREATE_NON_FUNGIBLE_RESOURCE_WITH_INITIAL_SUPPLY" (
+        "owner_role id_type track_total_supply non_fungible_schema entries resource_roles metadata address_reservation"
+    ) => "Creates a non-fungible resource, and puts the initial supply on the worktop.",
+    "CREATE_ACCESS_CONTROLLER" ("controlled_asset rule_set timed_recovery_delay_in_minutes address_reservation")
+        => "Creates an access controller which controls the given bucket.",
+    "CREATE_IDENTITY" () => "Creates an identity, and puts its owner badge on the worktop.",
+    "CREATE_IDENTITY_ADVANCED" ("owner_role") => "Creates an identity with the given owner role.",
+    "CREATE_ACCOUNT" () => "Creates an account, and puts its owner badge on the worktop.",
+    "CREATE_ACCOUNT_ADVANCED" ("owner_role address_reservation") => "Creates an account with the given owner role.",
+
+    // Call non-main-method aliases
+    "SET_METADATA" ("address key value") => "Sets a metadata entry of an entity.",
+    "REMOVE_METADATA" ("address key") => "Removes a metadata entry of an entity.",
+    "LOCK_METADATA" ("address key") => "Locks a metadata entry of an entity so it can no longer be changed.",
+    "SET_COMPONENT_ROYALTY" ("address method amount") => "Sets the royalty of a method of a component.",
+    "LOCK_COMPONENT_ROYALTY" ("address method") => "Locks the royalty of a method of a component so it can no longer be changed.",
+    "CLAIM_COMPONENT_ROYALTIES" ("address") => "Claims the royalties accumulated by a component, and puts them on the worktop.",
+    "SET_OWNER_ROLE" ("address rule") => "Sets the owner role of an entity.",
+    "LOCK_OWNER_ROLE" ("address") => "Locks the owner role of an entity so it can no longer be changed.",
+    "SET_ROLE" ("address module role_key rule") => "Sets a role of a module of an entity.",
+
+    // Call main-method aliases
+    "MINT_FUNGIBLE" ("address amount") => "Mints the given amount of a fungible resource, and puts it on the worktop.",
+    "MINT_NON_FUNGIBLE" ("address entries") => "Mints the given non-fungibles, and puts them on the worktop.",
+    "MINT_RUID_NON_FUNGIBLE" ("address entries")
+        => "Mints non-fungibles with random ids, and puts them on the worktop.",
+    "CLAIM_PACKAGE_ROYALTIES" ("address") => "Claims the royalties accumulated by a package, and puts them on the worktop.",
+    "CREATE_VALIDATOR" ("key fee_factor xrd_payment") => "Creates a validator, and puts its owner badge on the worktop.",
+}
+
+macro_rules! values {
+    ($($ident:literal $syntax:literal => $description:literal,)*) => {
+        pub const VALUES: &[ValueReference] = &[
+            $(
+                ValueReference {
+                    ident: $ident,
+                    syntax: $syntax,
+                    description: $description,
+                },
+            )*
+        ];
+    };
+}
+
+values! {
+    // Simple basic value kinds
+    "Bool" "true" => "A boolean.",
+    "I8" "1i8" => "A signed 8-bit integer.",
+    "I16" "1i16" => "A signed 16-bit integer.",
+    "I32" "1i32" => "A signed 32-bit integer.",
+    "I64" "1i64" => "A signed 64-bit integer.",
+    "I128" "1i128" => "A signed 128-bit integer.",
+    "U8" "1u8" => "An unsigned 8-bit integer.",
+    "U16" "1u16" => "An unsigned 16-bit integer.",
+    "U32" "1u32" => "An unsigned 32-bit integer.",
+    "U64" "1u64" => "An unsigned 64-bit integer.",
+    "U128" "1u128" => "An unsigned 128-bit integer.",
+    "String" "\"text\"" => "A UTF-8 string.",
+
+    // Composite basic value kinds
+    "Enum" "Enum<0u8>(fields...)" => "An enum variant with a discriminator and any number of fields.",
+    "Array" "Array<U8>(elements...)" => "An array of values of the same value kind.",
+    "Tuple" "Tuple(fields...)" => "A tuple of values of any value kind.",
+    "Map" "Map<String, U8>(\"key\" => 1u8)" => "A map with keys and values of fixed value kinds.",
+
+    // Value aliases
+    "Some" "Some(value)" => "An alias of `Enum<1u8>(value)`, the `Some` variant of an `Option`.",
+    "None" "None" => "An alias of `Enum<0u8>()`, the `None` variant of an `Option`.",
+    "Ok" "Ok(value)" => "An alias of `Enum<0u8>(value)`, the `Ok` variant of a `Result`.",
+    "Err" "Err(value)" => "An alias of `Enum<1u8>(value)`, the `Err` variant of a `Result`.",
+    "Bytes" "Bytes(\"hex\")" => "An alias of an `Array<U8>` written in hex.",
+    "NonFungibleGlobalId" "NonFungibleGlobalId(\"resource_address:local_id\")"
+        => "An alias of a `Tuple` of a resource address and a non-fungible local id.",
+
+    // Custom value kinds
+    "Address" "Address(\"address\")" => "A global or internal address in Bech32m.",
+    "NamedAddress" "NamedAddress(\"name\")" => "An address allocated earlier in the manifest by `ALLOCATE_GLOBAL_ADDRESS`.",
+    "Bucket" "Bucket(\"name\")" => "A bucket created earlier in the manifest.",
+    "Proof" "Proof(\"name\")" => "A proof created earlier in the manifest.",
+    "Expression" "Expression(\"ENTIRE_WORKTOP\")"
+        => "An expression which is resolved at runtime, either `ENTIRE_WORKTOP` or `ENTIRE_AUTH_ZONE`.",
+    "Blob" "Blob(\"hash\")" => "A reference to a blob attached to the transaction by its hash.",
+    "Decimal" "Decimal(\"1.5\")" => "A fixed-point decimal number with 18 decimal places.",
+    "PreciseDecimal" "PreciseDecimal(\"1.5\")" => "A fixed-point decimal number with 36 decimal places.",
+    "NonFungibleLocalId" "NonFungibleLocalId(\"#1#\")"
+        => "A non-fungible local id, either `<string>`, `#integer#`, `[bytes]` or `{ruid}`.",
+    "AddressReservation" "AddressReservation(\"name\")"
+        => "An address reservation created earlier in the manifest by `ALLOCATE_GLOBAL_ADDRESS`.",
+    "Intent" "Intent(\"subintent_hash\")" => "A subintent referenced by its hash.",
+    "NamedIntent" "NamedIntent(\"name\")" => "A child subintent declared earlier in the manifest by `USE_CHILD`.",
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use radix_transactions::manifest::ast::ValueKind;
+    use radix_transactions::manifest::parser::{InstructionIdent, ManifestValueIdent};
+
+    #[test]
+    fn all_referenced_idents_are_known_to_the_parser() {
+        for instruction in INSTRUCTIONS {
+            assert!(
+                InstructionIdent::from_ident(instruction.ident).is_some(),
+                "{} is not an instruction",
+                instruction.ident
+            );
+        }
+        for value in VALUES {
+            assert!(
+                ManifestValueIdent::from_ident(value.ident).is_some()
+                    || ValueKind::from_ident(value.ident).is_some(),
+                "{} is not a value",
+                value.ident
+            );
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use super::document::*;
+use crate::prelude::*;
+use lsp_server::{ErrorCode, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    Notification as LspNotification, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Request as LspRequest};
+use lsp_types::{
+    CompletionOptions, CompletionResponse, GotoDefinitionResponse, HoverProviderCapability,
+    Location, OneOf, PublishDiagnosticsParams, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, Uri,
+};
+
+pub fn server_capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["(".to_owned(), "\"".to_owned()]),
+            ..Default::default()
+        }),
+        definition_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    }
+}
+
+/// The state of the language server, which maps each message from the client to the messages to
+/// send back.
+pub struct ManifestLanguageServer {
+    manifest_kind: ManifestKind,
+    network: NetworkDefinition,
+    documents: IndexMap<Uri, ManifestDocument>,
+}
+
+impl ManifestLanguageServer {
+    pub fn new(manifest_kind: ManifestKind, network: NetworkDefinition) -> Self {
+        Self {
+            manifest_kind,
+            network,
+            documents: index_map_new(),
+        }
+    }
+
+    pub fn handle_request(&mut self, request: Request) -> Response {
+        match request.method.as_str() {
+            HoverRequest::METHOD => respond::<HoverRequest>(request, |params| {
+                let position = params.text_document_position_params;
+                self.documents
+                    .get(&position.text_document.uri)?
+                    .hover(position.position, &self.network)
+            }),
+            Completion::METHOD => respond::<Completion>(request, |params| {
+                let position = params.text_document_position;
+                let completions = self
+                    .documents
+                    .get(&position.text_document.uri)?
+                    .completions(position.position);
+                Some(CompletionResponse::Array(completions))
+            }),
+            GotoDefinition::METHOD => respond::<GotoDefinition>(request, |params| {
+                let position = params.text_document_position_params;
+                let range = self
+                    .documents
+                    .get(&position.text_document.uri)?
+                    .definition(position.position)?;
+                Some(GotoDefinitionResponse::Scalar(Location {
+                    uri: position.text_document.uri,
+                    range,
+                }))
+            }),
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request {}", request.method),
+            ),
+        }
+    }
+
+    /// Handles a notification from the client, returning the diagnostics to publish if a document
+    /// was changed.
+    pub fn handle_notification(&mut self, notification: Notification) -> Option<Notification> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = extract::<DidOpenTextDocument>(notification)?;
+                let document = params.text_document;
+                self.update(document.uri, document.text, document.version)
+            }
+            DidChangeTextDocument::METHOD => {
+                let mut params = extract::<DidChangeTextDocument>(notification)?;
+                // Documents are fully synced, so the last change holds the whole text
+                let change = params.content_changes.pop()?;
+                let document = params.text_document;
+                self.update(document.uri, change.text, document.version)
+            }
+            DidCloseTextDocument::METHOD => {
+                let uri = extract::<DidCloseTextDocument>(notification)?
+                    .text_document
+                    .uri;
+                self.documents.swap_remove(&uri);
+                Some(publish_diagnostics(PublishDiagnosticsParams::new(
+                    uri,
+                    Vec::new(),
+                    None,
+                )))
+            }
+            _ => None,
+        }
+    }
+
+    fn update(&mut self, uri: Uri, text: String, version: i32) -> Option<Notification> {
+        let document = ManifestDocument::new(text);
+        let diagnostics = document.diagnostics(self.manifest_kind, &self.network);
+        self.documents.insert(uri.clone(), document);
+        Some(publish_diagnostics(PublishDiagnosticsParams::new(
+            uri,
+            diagnostics,
+            Some(version),
+        )))
+    }
+}
+
+fn respond<R: LspRequest>(
+    request: Request,
+    handler: impl FnOnce(R::Params) -> R::Result,
+) -> Response {
+    let id = request.id.clone();
+    match request.extract::<R::Params>(R::METHOD) {
+        Ok((id, params)) => Response::new_ok(id, handler(params)),
+        Err(err) => Response::new_err(id, ErrorCode::InvalidParams as i32, err.to_string()),
+    }
+}
+
+fn extract<N: LspNotification>(notification: Notification) -> Option<N::Params> {
+    notification.extract(N::METHOD).ok()
+}
+
+fn publish_diagnostics(params: PublishDiagnosticsParams) -> Notification {
+    Notification::new(PublishDiagnostics::METHOD.to_owned(), params)
+}
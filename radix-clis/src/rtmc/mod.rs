@@ -23,6 +23,11 @@ pub struct Args {
     /// The manifest type [V1 | SystemV1 | V2 | SubintentV2], defaults to V2
     #[clap(short, long)]
     kind: Option<String>,
+
+    /// Path to a TOML or JSON file with the arguments for the `PARAMETER`s of the manifest.
+    /// Fragments in `INCLUDE` statements are resolved relative to the input file.
+    #[clap(short, long)]
+    parameters: Option<PathBuf>,
 }
 
 #[derive(Debug)]
@@ -30,6 +35,7 @@ pub enum Error {
     IoError(std::io::Error),
     EncodeError(sbor::EncodeError),
     ParseNetworkError(ParseNetworkError),
+    ParseParametersError(String),
     ManifestValidationError(ManifestValidationError),
     InstructionSchemaValidationError(radix_engine::utils::LocatedInstructionSchemaValidationError),
 }
@@ -62,10 +68,23 @@ pub fn run() -> Result<(), String> {
         }
     }
 
+    let arguments = match args.parameters {
+        Some(path) => read_template_arguments(&path)?,
+        None => TemplateArguments::default(),
+    };
+    let fragments = FragmentDirectory(
+        args.input
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    );
+
     let manifest_kind = ManifestKind::parse_or_latest(args.kind.as_deref())?;
-    let manifest = compile_any_manifest_with_pretty_error(
+    let manifest = compile_any_manifest_template_with_pretty_error(
         &content,
         manifest_kind,
+        &arguments,
+        &fragments,
         &network,
         BlobProvider::new_with_blobs(blobs),
         CompileErrorDiagnosticsStyle::TextTerminalColors,
@@ -86,3 +105,70 @@ pub fn run() -> Result<(), String> {
 
     Ok(())
 }
+
+/// Resolves the fragments of `INCLUDE` statements as paths relative to a directory.
+struct FragmentDirectory(PathBuf);
+
+impl IsTemplateFragmentProvider for FragmentDirectory {
+    fn get_fragment(&self, name: &str) -> Option<String> {
+        std::fs::read_to_string(self.0.join(name)).ok()
+    }
+}
+
+/// Reads the template arguments from a flat TOML or JSON object, depending on the file
+/// extension. Strings, numbers and booleans are accepted, and checked against the type of their
+/// parameter by the compiler. Integers beyond the range of TOML and JSON numbers, such as large
+/// `U128`s, can be given as strings.
+fn read_template_arguments(path: &Path) -> Result<TemplateArguments, Error> {
+    let content = std::fs::read_to_string(path).map_err(Error::IoError)?;
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension == "json");
+    let arguments = if is_json {
+        let serde_json::Value::Object(values) = serde_json::from_str(&content)
+            .map_err(|err| Error::ParseParametersError(err.to_string()))?
+        else {
+            return Err(Error::ParseParametersError(
+                "expected a JSON object".to_string(),
+            ));
+        };
+        values
+            .into_iter()
+            .map(|(name, value)| {
+                let argument = match value {
+                    serde_json::Value::String(value) => value,
+                    serde_json::Value::Number(value) => value.to_string(),
+                    serde_json::Value::Bool(value) => value.to_string(),
+                    _ => {
+                        return Err(Error::ParseParametersError(format!(
+                            "argument for parameter '${}' is not a string, number or boolean",
+                            name
+                        )))
+                    }
+                };
+                Ok((name, argument))
+            })
+            .collect::<Result<_, _>>()?
+    } else {
+        toml::from_str::<toml::Table>(&content)
+            .map_err(|err| Error::ParseParametersError(err.to_string()))?
+            .into_iter()
+            .map(|(name, value)| {
+                let argument = match value {
+                    toml::Value::String(value) => value,
+                    toml::Value::Integer(value) => value.to_string(),
+                    toml::Value::Float(value) => value.to_string(),
+                    toml::Value::Boolean(value) => value.to_string(),
+                    _ => {
+                        return Err(Error::ParseParametersError(format!(
+                            "argument for parameter '${}' is not a string, number or boolean",
+                            name
+                        )))
+                    }
+                };
+                Ok((name, argument))
+            })
+            .collect::<Result<_, _>>()?
+    };
+    Ok(arguments)
+}
@@ -16,6 +16,11 @@ pub struct Args {
     #[clap(short, long, action)]
     export_blobs: bool,
 
+    /// Write a summary of the account withdraws and deposits of the manifest, instead of the
+    /// decompiled manifest
+    #[clap(short, long, action)]
+    summary: bool,
+
     /// Input file
     #[clap(required = true)]
     input: PathBuf,
@@ -29,6 +34,7 @@ pub enum Error {
     ParseNetworkError(ParseNetworkError),
     ManifestValidationError(ManifestValidationError),
     InstructionSchemaValidationError(radix_engine::utils::LocatedInstructionSchemaValidationError),
+    StaticResourceMovementsError(static_resource_movements::StaticResourceMovementsError),
 }
 
 impl fmt::Display for Error {
@@ -62,9 +68,16 @@ pub fn run() -> Result<(), String> {
     validate_call_arguments_to_native_components(&manifest)
         .map_err(Error::InstructionSchemaValidationError)?;
 
-    let decompiled = decompile_any(&manifest, &network).map_err(Error::DecompileError)?;
+    let output = if args.summary {
+        static_resource_movements::ResourceMovementsSummary::from_manifest(&manifest)
+            .map_err(Error::StaticResourceMovementsError)?
+            .display(&AddressBech32Encoder::new(&network))
+            .to_string()
+    } else {
+        decompile_any(&manifest, &network).map_err(Error::DecompileError)?
+    };
 
-    write_ensuring_folder_exists(&args.output, &decompiled).map_err(Error::IoError)?;
+    write_ensuring_folder_exists(&args.output, &output).map_err(Error::IoError)?;
 
     if args.export_blobs {
         let directory = args.output.parent().unwrap();
@@ -0,0 +1,73 @@
+use crate::prelude::*;
+
+/// Radix transaction manifest formatter
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None, name = "rtmfmt")]
+pub struct Args {
+    /// Run in check mode, which lists the files that aren't formatted instead of formatting them
+    #[clap(short, long, action)]
+    check: bool,
+
+    /// Input files, which are formatted in place
+    #[clap(required = true)]
+    inputs: Vec<PathBuf>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IoError(std::io::Error),
+    CompileError(String),
+    NotFormatted(Vec<PathBuf>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::CompileError(diagnostics) => write!(f, "{}", diagnostics),
+            Error::NotFormatted(paths) => {
+                write!(f, "The following manifests are not formatted:")?;
+                for path in paths {
+                    write!(f, "\n{}", path.display())?;
+                }
+                Ok(())
+            }
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+impl From<Error> for String {
+    fn from(err: Error) -> String {
+        err.to_string()
+    }
+}
+
+pub fn run() -> Result<(), String> {
+    let args = Args::parse();
+
+    let mut unformatted = Vec::new();
+    for input in args.inputs {
+        let content = fs::read_to_string(&input).map_err(Error::IoError)?;
+        let formatted = format_manifest(&content).map_err(|err| {
+            Error::CompileError(compile_error_diagnostics(
+                &content,
+                err,
+                CompileErrorDiagnosticsStyle::TextTerminalColors,
+            ))
+        })?;
+        if formatted == content {
+            continue;
+        }
+        if args.check {
+            unformatted.push(input);
+        } else {
+            fs::write(&input, formatted).map_err(Error::IoError)?;
+        }
+    }
+
+    if unformatted.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::NotFormatted(unformatted).into())
+    }
+}
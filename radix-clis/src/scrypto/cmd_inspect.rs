@@ -0,0 +1,476 @@
+use crate::scrypto::Error;
+use crate::utils::{list_item_prefix, IdentifyLast};
+use clap::Parser;
+use colored::*;
+use radix_blueprint_schema_init::{Receiver, ReceiverInfo, TypeRef};
+use radix_common::prelude::*;
+use radix_engine::utils::extract_definition;
+use radix_engine::vm::wasm::{PrepareError, ScryptoV1WasmValidator, WasmModule, WasmSymbols};
+use radix_engine::vm::ScryptoVmVersion;
+use radix_engine_interface::blueprints::package::*;
+use radix_engine_interface::prelude::*;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Inspect a compiled Scrypto package without publishing it
+#[derive(Parser, Debug)]
+pub struct Inspect {
+    /// The path to the `.wasm` file of the package. The package definition is extracted from the
+    /// code and compared against the `.rpd` file next to it, if any.
+    path: PathBuf,
+
+    /// The number of largest functions to list. The default is 20.
+    #[clap(long)]
+    top_functions: Option<usize>,
+}
+
+impl Inspect {
+    pub fn run<O: Write>(&self, out: &mut O) -> Result<(), String> {
+        let code = fs::read(&self.path).map_err(Error::IOError)?;
+        self.inspect(&code, out)
+            .map_err(|err| Error::IOError(err).into())
+    }
+
+    fn inspect<O: Write>(&self, code: &[u8], out: &mut O) -> std::io::Result<()> {
+        let validator = ScryptoV1WasmValidator::new(ScryptoVmVersion::latest());
+
+        writeln!(out, "{}: {}", "Package".green().bold(), self.path.display())?;
+        writeln!(out, "{}: {} bytes", "Code size".green().bold(), code.len())?;
+
+        let definition = match extract_definition(code) {
+            Ok(definition) => {
+                self.compare_with_rpd(&definition, out)?;
+                Some(definition)
+            }
+            Err(err) => {
+                writeln!(
+                    out,
+                    "{}: {}",
+                    "Package definition".green().bold(),
+                    format!("failed to extract: {:?}", err).red()
+                )?;
+                None
+            }
+        };
+
+        if let Some(definition) = &definition {
+            writeln!(
+                out,
+                "{}: {}",
+                "Blueprints".green().bold(),
+                definition.blueprints.len()
+            )?;
+            for (last, (name, blueprint)) in definition.blueprints.iter().identify_last() {
+                writeln!(out, "{} {}", list_item_prefix(last), name.bold())?;
+                write_blueprint(blueprint, if last { "   " } else { "│  " }, out)?;
+            }
+        }
+
+        match WasmModule::init(code).and_then(|module| module.summary()) {
+            Ok(summary) => {
+                writeln!(
+                    out,
+                    "{}: {}",
+                    "Imports".green().bold(),
+                    summary.imports.len()
+                )?;
+                for (last, (module, name)) in summary.imports.iter().identify_last() {
+                    writeln!(out, "{} {}::{}", list_item_prefix(last), module, name)?;
+                }
+
+                writeln!(out, "{}:", "Limits".green().bold())?;
+                let (initial_pages, maximum_pages) = summary
+                    .memories
+                    .first()
+                    .map(|(initial, maximum)| (*initial, *maximum))
+                    .unwrap_or_default();
+                writeln!(
+                    out,
+                    "{} Memory pages: {} initial, {} maximum (limit {})",
+                    list_item_prefix(false),
+                    initial_pages,
+                    maximum_pages
+                        .map(|maximum| maximum.to_string())
+                        .unwrap_or("unbounded".to_string()),
+                    validator.max_memory_size_in_pages
+                )?;
+                writeln!(
+                    out,
+                    "{} Initial table size: {} (limit {})",
+                    list_item_prefix(false),
+                    summary
+                        .initial_table_sizes
+                        .first()
+                        .copied()
+                        .unwrap_or_default(),
+                    validator.max_initial_table_size
+                )?;
+                writeln!(
+                    out,
+                    "{} Functions: {} (limit {})",
+                    list_item_prefix(false),
+                    summary.num_local_functions,
+                    validator.max_number_of_functions
+                )?;
+                writeln!(
+                    out,
+                    "{} Globals: {} (limit {})",
+                    list_item_prefix(true),
+                    summary.num_local_globals,
+                    validator.max_number_of_globals
+                )?;
+
+                let symbols = WasmSymbols::parse(code);
+                let mut function_sizes: Vec<(u32, usize)> = summary
+                    .function_body_sizes
+                    .iter()
+                    .enumerate()
+                    .map(|(local_index, size)| (local_index as u32, *size))
+                    .collect();
+                function_sizes.sort_by(|a, b| b.1.cmp(&a.1));
+                function_sizes.truncate(self.top_functions.unwrap_or(20));
+                writeln!(
+                    out,
+                    "{}: {} largest of {}",
+                    "Function sizes".green().bold(),
+                    function_sizes.len(),
+                    summary.num_local_functions
+                )?;
+                for (last, (local_index, size)) in function_sizes.iter().identify_last() {
+                    let function_index = summary.num_imported_functions + local_index;
+                    let exports: Vec<&str> = summary
+                        .function_exports
+                        .iter()
+                        .filter(|(_, index)| *index == function_index)
+                        .map(|(name, _)| name.as_str())
+                        .collect();
+                    let name = symbols
+                        .get_function_name(function_index)
+                        .map(|name| name.to_string())
+                        .or_else(|| exports.first().map(|name| name.to_string()))
+                        .unwrap_or_else(|| format!("func[{}]", function_index));
+                    writeln!(
+                        out,
+                        "{} {:>8} bytes  {}",
+                        list_item_prefix(last),
+                        size,
+                        name
+                    )?;
+                }
+            }
+            Err(err) => {
+                writeln!(
+                    out,
+                    "{}: {}",
+                    "WASM".green().bold(),
+                    format!("failed to parse: {:?}", err).red()
+                )?;
+            }
+        }
+
+        let module = || WasmModule::init(code);
+        let mut checks: Vec<(&str, Result<(), PrepareError>)> = vec![
+            (
+                "enforce_no_start_function",
+                module().and_then(WasmModule::enforce_no_start_function),
+            ),
+            (
+                "enforce_import_constraints",
+                module().and_then(|m| m.enforce_import_constraints(validator.version)),
+            ),
+            (
+                "enforce_export_names",
+                module().and_then(WasmModule::enforce_export_names),
+            ),
+            (
+                "enforce_memory_limit",
+                module().and_then(|m| {
+                    m.enforce_memory_limit_and_inject_max(validator.max_memory_size_in_pages)
+                }),
+            ),
+            (
+                "enforce_table_limit",
+                module().and_then(|m| m.enforce_table_limit(validator.max_initial_table_size)),
+            ),
+            (
+                "enforce_br_table_limit",
+                module().and_then(|m| {
+                    m.enforce_br_table_limit(validator.max_number_of_br_table_targets)
+                }),
+            ),
+            (
+                "enforce_function_limit",
+                module().and_then(|m| {
+                    m.enforce_function_limit(
+                        validator.max_number_of_functions,
+                        validator.max_number_of_function_params,
+                        validator.max_number_of_function_locals,
+                    )
+                }),
+            ),
+            (
+                "enforce_global_limit",
+                module().and_then(|m| m.enforce_global_limit(validator.max_number_of_globals)),
+            ),
+        ]
+        .into_iter()
+        .map(|(name, result)| (name, result.map(|_| ())))
+        .collect();
+        if let Some(definition) = &definition {
+            checks.push((
+                "enforce_export_constraints",
+                module()
+                    .and_then(|m| m.enforce_export_constraints(definition.blueprints.values()))
+                    .map(|_| ()),
+            ));
+            checks.push((
+                "validate (instrumentation and instantiation)",
+                validator
+                    .validate(code, definition.blueprints.values())
+                    .map(|_| ()),
+            ));
+        }
+
+        writeln!(
+            out,
+            "{}: Scrypto VM {:?}",
+            "Validation".green().bold(),
+            validator.version
+        )?;
+        for (last, (name, result)) in checks.iter().identify_last() {
+            match result {
+                Ok(()) => writeln!(
+                    out,
+                    "{} {} {}",
+                    list_item_prefix(last),
+                    "PASS".green(),
+                    name
+                )?,
+                Err(err) => writeln!(
+                    out,
+                    "{} {} {}: {:?}",
+                    list_item_prefix(last),
+                    "FAIL".red(),
+                    name,
+                    err
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn compare_with_rpd<O: Write>(
+        &self,
+        definition: &PackageDefinition,
+        out: &mut O,
+    ) -> std::io::Result<()> {
+        let rpd_path = self.path.with_extension("rpd");
+        let Ok(rpd) = fs::read(&rpd_path) else {
+            return Ok(());
+        };
+        let rpd_definition = manifest_decode::<ManifestPackageDefinition>(&rpd)
+            .ok()
+            .and_then(|definition| definition.try_into_typed().ok());
+        let status = match rpd_definition {
+            Some(rpd_definition) if &rpd_definition == definition => "matches the code".green(),
+            Some(_) => "differs from the code".red(),
+            None => "could not be decoded".red(),
+        };
+        writeln!(
+            out,
+            "{}: {} {}",
+            "Package definition".green().bold(),
+            rpd_path.display(),
+            status
+        )
+    }
+}
+
+fn write_blueprint<O: Write>(
+    blueprint: &BlueprintDefinitionInit,
+    indent: &str,
+    out: &mut O,
+) -> std::io::Result<()> {
+    let schema = blueprint.schema.schema.v1();
+    let functions = &blueprint.schema.functions.functions;
+
+    writeln!(
+        out,
+        "{}{} Type: {:?}{}",
+        indent,
+        list_item_prefix(false),
+        blueprint.blueprint_type,
+        if blueprint.is_transient {
+            ", transient"
+        } else {
+            ""
+        }
+    )?;
+    if let MethodAuthTemplate::StaticRoleDefinition(StaticRoleDefinition {
+        roles: RoleSpecification::Normal(roles),
+        ..
+    }) = &blueprint.auth_config.method_auth
+    {
+        writeln!(
+            out,
+            "{}{} Roles: {}",
+            indent,
+            list_item_prefix(false),
+            roles
+                .keys()
+                .map(|role| role.key.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+    }
+    writeln!(
+        out,
+        "{}{} Functions: {}",
+        indent,
+        list_item_prefix(true),
+        functions.len()
+    )?;
+    for (last, (name, function)) in functions.iter().identify_last() {
+        let receiver = match &function.receiver {
+            None => "",
+            Some(ReceiverInfo {
+                receiver: Receiver::SelfRef,
+                ..
+            }) => "&self, ",
+            Some(ReceiverInfo {
+                receiver: Receiver::SelfRefMut,
+                ..
+            }) => "&mut self, ",
+        };
+        let params = match &function.input {
+            TypeRef::Static(type_id) => match schema.resolve_type_kind(*type_id) {
+                Some(TypeKind::Tuple { field_types }) => field_types
+                    .iter()
+                    .map(|type_id| type_name(schema, *type_id, 0))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                _ => type_name(schema, *type_id, 0),
+            },
+            TypeRef::Generic(index) => format!("G{}", index),
+        };
+        let output = match &function.output {
+            TypeRef::Static(type_id) => type_name(schema, *type_id, 0),
+            TypeRef::Generic(index) => format!("G{}", index),
+        };
+        let auth = match (&function.receiver, &blueprint.auth_config) {
+            (
+                None,
+                AuthConfig {
+                    function_auth: FunctionAuth::AllowAll,
+                    ..
+                },
+            ) => "public".to_string(),
+            (
+                None,
+                AuthConfig {
+                    function_auth: FunctionAuth::AccessRules(rules),
+                    ..
+                },
+            ) => rules
+                .get(name)
+                .map(|rule| format!("{:?}", rule))
+                .unwrap_or("none".to_string()),
+            (
+                None,
+                AuthConfig {
+                    function_auth: FunctionAuth::RootOnly,
+                    ..
+                },
+            ) => "root only".to_string(),
+            (
+                Some(_),
+                AuthConfig {
+                    method_auth: MethodAuthTemplate::AllowAll,
+                    ..
+                },
+            ) => "public".to_string(),
+            (
+                Some(_),
+                AuthConfig {
+                    method_auth: MethodAuthTemplate::StaticRoleDefinition(definition),
+                    ..
+                },
+            ) => match definition.methods.get(&MethodKey::new(name)) {
+                Some(MethodAccessibility::Public) => "public".to_string(),
+                Some(MethodAccessibility::RoleProtected(roles)) => format!(
+                    "roles: {}",
+                    roles
+                        .list
+                        .iter()
+                        .map(|role| role.key.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                Some(accessibility) => format!("{:?}", accessibility),
+                None => "none".to_string(),
+            },
+        };
+        let royalty = match &blueprint.royalty_config {
+            PackageRoyaltyConfig::Disabled => None,
+            PackageRoyaltyConfig::Enabled(royalties) => royalties.get(name),
+        };
+
+        writeln!(
+            out,
+            "{}   {} fn {}({}{}) -> {}",
+            indent,
+            list_item_prefix(last),
+            name,
+            receiver,
+            params,
+            output
+        )?;
+        writeln!(
+            out,
+            "{}   {}  export: {}, auth: {}{}",
+            indent,
+            if last { " " } else { "│" },
+            function.export,
+            auth,
+            royalty
+                .map(|royalty| format!(", royalty: {:?}", royalty))
+                .unwrap_or_default()
+        )?;
+    }
+
+    Ok(())
+}
+
+fn type_name(schema: &ScryptoSchema, type_id: LocalTypeId, depth: usize) -> String {
+    if let Some(name) = schema.resolve_type_name_from_metadata(type_id) {
+        return name.to_string();
+    }
+    if depth > 8 {
+        return "..".to_string();
+    }
+    match schema.resolve_type_kind(type_id) {
+        Some(TypeKind::Array { element_type }) => {
+            format!("Vec<{}>", type_name(schema, *element_type, depth + 1))
+        }
+        Some(TypeKind::Tuple { field_types }) => format!(
+            "({})",
+            field_types
+                .iter()
+                .map(|type_id| type_name(schema, *type_id, depth + 1))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Some(TypeKind::Map {
+            key_type,
+            value_type,
+        }) => format!(
+            "Map<{}, {}>",
+            type_name(schema, *key_type, depth + 1),
+            type_name(schema, *value_type, depth + 1)
+        ),
+        Some(kind) => format!("{:?}", kind.label()),
+        None => "?".to_string(),
+    }
+}
@@ -1,6 +1,7 @@
 mod cmd_build;
 mod cmd_coverage;
 mod cmd_fmt;
+mod cmd_inspect;
 mod cmd_new_package;
 mod cmd_test;
 mod error;
@@ -8,6 +9,7 @@ mod error;
 pub use cmd_build::*;
 pub use cmd_coverage::*;
 pub use cmd_fmt::*;
+pub use cmd_inspect::*;
 pub use cmd_new_package::*;
 pub use cmd_test::*;
 pub use error::*;
@@ -27,6 +29,7 @@ pub enum Command {
     Build(Build),
     Coverage(Coverage),
     Fmt(Fmt),
+    Inspect(Inspect),
     NewPackage(NewPackage),
     Test(Test),
 }
@@ -38,6 +41,7 @@ pub fn run() -> Result<(), String> {
         Command::Build(cmd) => cmd.run(),
         Command::Coverage(cmd) => cmd.run().map_err(|error| error.to_string()),
         Command::Fmt(cmd) => cmd.run(),
+        Command::Inspect(cmd) => cmd.run(&mut std::io::stdout()),
         Command::NewPackage(cmd) => cmd.run(),
         Command::Test(cmd) => cmd.run(),
     }
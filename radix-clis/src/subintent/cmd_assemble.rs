@@ -0,0 +1,71 @@
+use crate::prelude::*;
+use crate::subintent::*;
+use radix_transactions::validation::TransactionValidator;
+
+/// Build and notarize a transaction from a manifest and its signed children, checking that the
+/// whole transaction is valid
+#[derive(Parser, Debug)]
+pub struct Assemble {
+    /// The path to the transaction manifest
+    input: PathBuf,
+
+    /// The path to write the notarized transaction to
+    #[clap(short, long)]
+    output: PathBuf,
+
+    #[clap(flatten)]
+    intent: IntentArgs,
+
+    /// The notary's signature also counts as a signature of the transaction intent
+    #[clap(long)]
+    notary_is_signatory: bool,
+
+    #[clap(flatten)]
+    notary: SignerArgs,
+}
+
+impl Assemble {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        let network = self.intent.network()?;
+        let (manifest, children) = self
+            .intent
+            .compile_manifest::<TransactionManifestV2>(&self.input, &network)?;
+        let notary = &self.notary.signer()?;
+
+        let mut builder = TransactionV2Builder::new();
+        // The names are taken from the manifest, so only need to be unique
+        for (index, (_, child)) in children.into_iter().enumerate() {
+            builder = builder.add_signed_child(format!("child_{}", index), child);
+        }
+        let transaction = builder
+            .manifest(manifest)
+            .message(self.intent.message())
+            .intent_header(self.intent.intent_header(&network))
+            .transaction_header(TransactionHeaderV2 {
+                notary_public_key: notary.public_key(),
+                notary_is_signatory: self.notary_is_signatory,
+                tip_basis_points: 0,
+            })
+            .try_notarize(&notary)
+            .map_err(|error| Error::SignerError(format!("{:?}", error)))?
+            .build_minimal_no_validate();
+
+        let validated = transaction
+            .prepare_and_validate(&TransactionValidator::new_with_latest_config(&network))
+            .map_err(Error::TransactionValidationError)?;
+        write_ensuring_folder_exists(
+            &self.output,
+            transaction.to_raw().map_err(Error::EncodeError)?.to_vec(),
+        )
+        .map_err(Error::IoError)?;
+        writeln!(
+            out,
+            "Transaction intent hash: {}",
+            TransactionHashBech32Encoder::new(&network)
+                .encode(&validated.hashes().transaction_intent_hash)
+                .unwrap()
+        )
+        .map_err(Error::IoError)?;
+        Ok(())
+    }
+}
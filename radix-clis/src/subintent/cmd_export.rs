@@ -0,0 +1,47 @@
+use crate::prelude::*;
+use crate::subintent::*;
+
+/// Compile a subintent manifest into an unsigned partial transaction, to be sent to its signers
+#[derive(Parser, Debug)]
+pub struct Export {
+    /// The path to the subintent manifest
+    input: PathBuf,
+
+    /// The path to write the unsigned partial transaction to
+    #[clap(short, long)]
+    output: PathBuf,
+
+    #[clap(flatten)]
+    intent: IntentArgs,
+}
+
+impl Export {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        let network = self.intent.network()?;
+        let (manifest, children) = self
+            .intent
+            .compile_manifest::<SubintentManifestV2>(&self.input, &network)?;
+
+        let mut builder = PartialTransactionV2Builder::new();
+        // The names are taken from the manifest, so only need to be unique
+        for (index, (_, child)) in children.into_iter().enumerate() {
+            builder = builder.add_signed_child(format!("child_{}", index), child);
+        }
+        let detailed = builder
+            .manifest(manifest)
+            .message(self.intent.message())
+            .intent_header(self.intent.intent_header(&network))
+            .build();
+
+        write_signed_partial_transaction(&self.output, &detailed.partial_transaction)?;
+        writeln!(
+            out,
+            "Subintent hash: {}",
+            TransactionHashBech32Encoder::new(&network)
+                .encode(&detailed.root_subintent_hash)
+                .unwrap()
+        )
+        .map_err(Error::IoError)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use crate::prelude::*;
+use crate::subintent::*;
+
+/// Combine the signatures of copies of a partial transaction which were signed independently
+#[derive(Parser, Debug)]
+pub struct Merge {
+    /// The paths to the signed copies of the partial transaction
+    #[clap(required = true, multiple = true)]
+    inputs: Vec<PathBuf>,
+
+    /// The path to write the partial transaction with all signatures to
+    #[clap(short, long)]
+    output: PathBuf,
+}
+
+impl Merge {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        let signed_partial_transactions = self
+            .inputs
+            .iter()
+            .map(|path| read_signed_partial_transaction(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let merged = merge_partial_transaction_signatures(signed_partial_transactions)
+            .map_err(Error::CoordinationError)?;
+
+        write_signed_partial_transaction(&self.output, &merged)?;
+        writeln!(
+            out,
+            "Merged into {} signatures",
+            merged.root_subintent_signatures.signatures.len()
+        )
+        .map_err(Error::IoError)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use crate::prelude::*;
+use crate::subintent::*;
+
+/// Show what signing a partial transaction would agree to, and who has signed it
+#[derive(Parser, Debug)]
+pub struct Show {
+    /// The path to the partial transaction
+    input: PathBuf,
+
+    /// Network to Use [Simulator | Alphanet | Mainnet]
+    #[clap(short, long)]
+    network: Option<String>,
+}
+
+impl Show {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        let network = parse_network(self.network.as_deref())?;
+        let signed_partial_transaction = read_signed_partial_transaction(&self.input)?;
+        let summary = summarize_partial_transaction(&signed_partial_transaction, &network)
+            .map_err(Error::CoordinationError)?;
+        write!(out, "{}", summary).map_err(Error::IoError)?;
+        Ok(())
+    }
+}
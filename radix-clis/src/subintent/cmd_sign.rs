@@ -0,0 +1,32 @@
+use crate::prelude::*;
+use crate::subintent::*;
+
+/// Add a signature to the root subintent of a partial transaction
+#[derive(Parser, Debug)]
+pub struct Sign {
+    /// The path to the partial transaction
+    input: PathBuf,
+
+    /// The path to write the signed partial transaction to, defaults to the input
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    #[clap(flatten)]
+    signer: SignerArgs,
+}
+
+impl Sign {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        let mut signed_partial_transaction = read_signed_partial_transaction(&self.input)?;
+        let signer = self.signer.signer()?;
+        sign_partial_transaction(&mut signed_partial_transaction, &signer)
+            .map_err(Error::CoordinationError)?;
+
+        write_signed_partial_transaction(
+            self.output.as_ref().unwrap_or(&self.input),
+            &signed_partial_transaction,
+        )?;
+        writeln!(out, "Signed by: {:?}", signer.public_key()).map_err(Error::IoError)?;
+        Ok(())
+    }
+}
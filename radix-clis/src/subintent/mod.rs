@@ -0,0 +1,295 @@
+mod cmd_assemble;
+mod cmd_export;
+mod cmd_merge;
+mod cmd_show;
+mod cmd_sign;
+
+pub use cmd_assemble::*;
+pub use cmd_export::*;
+pub use cmd_merge::*;
+pub use cmd_show::*;
+pub use cmd_sign::*;
+
+use crate::prelude::*;
+use clap::Subcommand;
+use radix_transactions::errors::TransactionValidationError;
+use radix_transactions::signing::{ExternalSigner, ProcessConnection, TrySigner};
+use std::process;
+
+/// Coordinate the signing of subintents by multiple parties
+///
+/// The owner of a subintent exports it as an unsigned partial transaction, and sends a copy to
+/// each signer. Each signer inspects it with `show` and adds their signature with `sign`. The
+/// signed copies are combined with `merge`, and the result is used as a child of a transaction
+/// with `assemble`.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None, name = "subintent")]
+pub struct SubintentCli {
+    #[clap(subcommand)]
+    pub(crate) command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    Export(Export),
+    Show(Show),
+    Sign(Sign),
+    Merge(Merge),
+    Assemble(Assemble),
+}
+
+impl Command {
+    pub fn run<O: std::io::Write>(&self, out: &mut O) -> Result<(), String> {
+        match self {
+            Command::Export(cmd) => cmd.run(out),
+            Command::Show(cmd) => cmd.run(out),
+            Command::Sign(cmd) => cmd.run(out),
+            Command::Merge(cmd) => cmd.run(out),
+            Command::Assemble(cmd) => cmd.run(out),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IoError(std::io::Error),
+    EncodeError(sbor::EncodeError),
+    DecodeError(sbor::DecodeError),
+    ParseNetworkError(ParseNetworkError),
+    InvalidPrivateKey,
+    CompileError(String),
+    CoordinationError(PartialTransactionCoordinationError),
+    SignerError(String),
+    TransactionValidationError(TransactionValidationError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            // Compile errors are already pretty printed
+            Error::CompileError(error) => write!(f, "{}", error),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+impl From<Error> for String {
+    fn from(err: Error) -> String {
+        err.to_string()
+    }
+}
+
+impl From<PartialTransactionCoordinationError> for Error {
+    fn from(error: PartialTransactionCoordinationError) -> Self {
+        Error::CoordinationError(error)
+    }
+}
+
+pub fn run() -> Result<(), String> {
+    let cli = SubintentCli::parse();
+
+    let mut out = std::io::stdout();
+    cli.command.run(&mut out)
+}
+
+/// The header and message arguments shared by `export` and `assemble`.
+#[derive(clap::Args, Debug)]
+pub struct IntentArgs {
+    /// Network to Use [Simulator | Alphanet | Mainnet]
+    #[clap(short, long)]
+    network: Option<String>,
+
+    /// The first epoch in which the intent can be committed
+    #[clap(long)]
+    start_epoch: u64,
+
+    /// The epoch from which the intent can no longer be committed
+    #[clap(long)]
+    end_epoch: u64,
+
+    /// The unix time in seconds from which the intent can be committed
+    #[clap(long)]
+    min_timestamp: Option<i64>,
+
+    /// The unix time in seconds from which the intent can no longer be committed
+    #[clap(long)]
+    max_timestamp: Option<i64>,
+
+    /// Distinguishes otherwise identical intents, defaults to a random number
+    #[clap(long)]
+    discriminator: Option<u64>,
+
+    /// A plaintext message to attach to the intent
+    #[clap(short, long)]
+    message: Option<String>,
+
+    /// The paths to blobs
+    #[clap(short, long, multiple = true)]
+    blobs: Option<Vec<PathBuf>>,
+
+    /// The paths to signed partial transactions for the children used by the manifest
+    #[clap(short, long, multiple = true)]
+    children: Option<Vec<PathBuf>>,
+}
+
+impl IntentArgs {
+    pub fn network(&self) -> Result<NetworkDefinition, Error> {
+        parse_network(self.network.as_deref())
+    }
+
+    pub fn intent_header(&self, network: &NetworkDefinition) -> IntentHeaderV2 {
+        IntentHeaderV2 {
+            network_id: network.id,
+            start_epoch_inclusive: Epoch::of(self.start_epoch),
+            end_epoch_exclusive: Epoch::of(self.end_epoch),
+            min_proposer_timestamp_inclusive: self.min_timestamp.map(Instant::new),
+            max_proposer_timestamp_exclusive: self.max_timestamp.map(Instant::new),
+            intent_discriminator: self.discriminator.unwrap_or_else(rand::random),
+        }
+    }
+
+    pub fn message(&self) -> MessageV2 {
+        match &self.message {
+            Some(message) => MessageV2::Plaintext(PlaintextMessageV1::text(message)),
+            None => MessageV2::None,
+        }
+    }
+
+    /// Compiles the manifest at the given path, returning it with its children in the order it
+    /// uses them.
+    pub fn compile_manifest<M: BuildableManifest + ReadableManifest>(
+        &self,
+        path: &Path,
+        network: &NetworkDefinition,
+    ) -> Result<(M, Vec<(SubintentHash, SignedPartialTransactionV2)>), Error> {
+        let content = fs::read_to_string(path).map_err(Error::IoError)?;
+        let mut blobs = Vec::new();
+        for path in self.blobs.iter().flatten() {
+            blobs.push(fs::read(path).map_err(Error::IoError)?);
+        }
+        let manifest: M = compile_manifest_with_pretty_error(
+            &content,
+            network,
+            BlobProvider::new_with_blobs(blobs),
+            CompileErrorDiagnosticsStyle::TextTerminalColors,
+        )
+        .map_err(Error::CompileError)?;
+
+        let children = self
+            .children
+            .iter()
+            .flatten()
+            .map(|path| read_signed_partial_transaction(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let children = resolve_manifest_children(
+            &manifest.get_child_subintent_hashes().cloned().collect(),
+            children,
+        )?;
+        Ok((manifest, children))
+    }
+}
+
+/// The arguments to choose who signs, shared by `sign` and `assemble`.
+#[derive(clap::Args, Debug)]
+pub struct SignerArgs {
+    /// The private key to sign with, as Secp256k1 hex
+    #[clap(long, conflicts_with = "signer-command")]
+    private_key: Option<String>,
+
+    /// A program which signs over its standard input and output, such as a hardware wallet
+    /// bridge, so that the private key never has to be given to this tool
+    #[clap(long)]
+    signer_command: Option<String>,
+
+    /// An argument to pass to the signer command, which may be repeated
+    #[clap(long, requires = "signer-command", allow_hyphen_values = true)]
+    signer_arg: Vec<String>,
+}
+
+impl SignerArgs {
+    pub fn signer(&self) -> Result<CliSigner, Error> {
+        match (&self.private_key, &self.signer_command) {
+            (Some(private_key), _) => Secp256k1PrivateKey::from_hex(private_key)
+                .map(CliSigner::PrivateKey)
+                .map_err(|_| Error::InvalidPrivateKey),
+            (None, Some(signer_command)) => {
+                let mut command = process::Command::new(signer_command);
+                command.args(&self.signer_arg);
+                ExternalSigner::spawn(&mut command)
+                    .map(CliSigner::External)
+                    .map_err(|error| Error::SignerError(format!("{:?}", error)))
+            }
+            (None, None) => Err(Error::SignerError(
+                "either --private-key or --signer-command is required".to_string(),
+            )),
+        }
+    }
+}
+
+pub enum CliSigner {
+    PrivateKey(Secp256k1PrivateKey),
+    External(ExternalSigner<ProcessConnection>),
+}
+
+impl CliSigner {
+    pub fn public_key(&self) -> PublicKey {
+        match self {
+            CliSigner::PrivateKey(private_key) => private_key.public_key().into(),
+            CliSigner::External(signer) => signer.public_key(),
+        }
+    }
+}
+
+impl TrySigner for &CliSigner {
+    type Error = Error;
+
+    fn try_sign_without_public_key(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureV1, Self::Error> {
+        match self {
+            CliSigner::PrivateKey(private_key) => {
+                Ok(private_key.sign_without_public_key(message_hash))
+            }
+            CliSigner::External(signer) => signer
+                .try_sign_without_public_key(message_hash)
+                .map_err(|error| Error::SignerError(format!("{:?}", error))),
+        }
+    }
+
+    fn try_sign_with_public_key(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureWithPublicKeyV1, Self::Error> {
+        match self {
+            CliSigner::PrivateKey(private_key) => {
+                Ok(private_key.sign_with_public_key(message_hash))
+            }
+            CliSigner::External(signer) => signer
+                .try_sign_with_public_key(message_hash)
+                .map_err(|error| Error::SignerError(format!("{:?}", error))),
+        }
+    }
+}
+
+pub fn parse_network(network: Option<&str>) -> Result<NetworkDefinition, Error> {
+    match network {
+        Some(n) => NetworkDefinition::from_str(n).map_err(Error::ParseNetworkError),
+        None => Ok(NetworkDefinition::simulator()),
+    }
+}
+
+pub fn read_signed_partial_transaction(path: &Path) -> Result<SignedPartialTransactionV2, Error> {
+    let raw = RawSignedPartialTransaction::from_vec(fs::read(path).map_err(Error::IoError)?);
+    SignedPartialTransactionV2::from_raw(&raw).map_err(Error::DecodeError)
+}
+
+pub fn write_signed_partial_transaction(
+    path: &Path,
+    signed_partial_transaction: &SignedPartialTransactionV2,
+) -> Result<(), Error> {
+    let raw = signed_partial_transaction
+        .to_raw()
+        .map_err(Error::EncodeError)?;
+    write_ensuring_folder_exists(path, raw.to_vec()).map_err(Error::IoError)
+}
@@ -3,6 +3,7 @@ mod common_instructions;
 mod display;
 mod file;
 mod iter;
+mod node_id;
 mod resource_specifier;
 
 pub use cargo::*;
@@ -10,4 +11,5 @@ pub use common_instructions::*;
 pub use display::list_item_prefix;
 pub use file::*;
 pub use iter::{IdentifyLast, Iter};
+pub use node_id::*;
 pub use resource_specifier::*;
@@ -0,0 +1,12 @@
+use radix_common::prelude::*;
+
+/// Parses a node id given either as an address, of any network, or as hex.
+pub fn parse_node_id(node_id: &str) -> Option<NodeId> {
+    let bytes = match AddressBech32Decoder::validate_and_decode_ignore_hrp(node_id) {
+        Ok((_, _, bytes)) => bytes,
+        Err(_) => hex::decode(node_id).ok()?,
+    };
+    <[u8; NodeId::LENGTH]>::try_from(bytes.as_slice())
+        .ok()
+        .map(NodeId)
+}
@@ -7,6 +7,9 @@ cd "$(dirname "$0")/.."
 
 resim="cargo run --bin resim $@ --"
 
+# Index the events of the transactions, for `show-events`
+export ENABLE_EVENT_INDEX=1
+
 # Create test accounts and public keys
 $resim reset
 temp=`$resim new-account | awk '/Account component address:/ {print $NF}'`
@@ -38,6 +41,17 @@ if [[ ${ledger_state} != *"2023-01-27T13:01:00Z"* ]];then
     exit 1
 fi
 
+# Test - inspect the ledger without opening it for writing
+read_only_ledger_state=`$resim show-ledger --read-only`
+if [[ ${read_only_ledger_state} != *"858585"* || ${read_only_ledger_state} != *"2023-01-27T13:01:00Z"* ]];then
+    echo "Read-only ledger state differs!"
+    exit 1
+fi
+$resim show $account --secondary ./tests/out/resim_secondary
+db_inspect="cargo run --bin db-inspect $@ --"
+$db_inspect summary --simulator "${DATA_DIR:-$HOME/.scrypto}"
+$db_inspect list-substates --simulator "${DATA_DIR:-$HOME/.scrypto}" $account 0
+
 # Test - show account
 account_dump=`$resim show $account`
 if [[ ${account_dump} != *"XRD"* ]];then
@@ -60,6 +74,13 @@ $resim transfer "$non_fungible_global_id" $account2
 $resim mint 777 $token_address --proofs $minter_badge:1
 $resim transfer $token_address:111 $account2
 
+# Test - query the indexed events
+deposit_events=`$resim show-events --emitter $account2 --event-name DepositEvent --read-only`
+if [[ ${deposit_events} != *"Name: DepositEvent"* ]];then
+    echo "Deposit events not indexed!"
+    exit 1
+fi
+
 # Test - publish, call-function and call-method and non-fungibles
 owner_badge=`$resim new-simple-badge --name 'OwnerBadge' | awk '/NonFungibleGlobalId:/ {print $NF}'`
 package=`$resim publish ../examples/hello-world --owner-badge $owner_badge | awk '/Package:/ {print $NF}'`
@@ -72,6 +93,13 @@ $resim publish ../examples/hello-world/target/wasm32-unknown-unknown/release/hel
 # Test - export package definition
 $resim export-package-definition $package ../examples/hello-world/target/temp.rpd
 
+# Test - export package JSON schema
+$resim export-package-json-schema $package ../examples/hello-world/target/temp.schema.json
+$resim export-package-json-schema $package ../examples/hello-world/target/temp.openapi.json --open-api
+
+# Test - run a script
+$resim script tests/script.resim
+
 # Test - dump component state
 $resim show $package
 $resim show $component
@@ -128,3 +156,10 @@ token_address=`$resim new-token-mutable $non_fungible_global_id | awk '/Resource
 
 # Test - mint and transfer (Mintable that requires a `NonFungibleGlobalId`)
 $resim mint 777 $token_address --proofs "$non_fungible_global_id"
+
+# Test - save, reset and load a snapshot
+$resim snapshot save test-snapshot
+$resim reset
+$resim snapshot list | grep test-snapshot
+$resim snapshot load test-snapshot
+$resim show $token_address
@@ -11,3 +11,13 @@ rtmd="cargo run --bin rtmd -- $@ "
 # Decompile and recompile a subintent
 $rtmc --output ./tests/out/subintent.bin --kind subintentv2 ./tests/subintent.rtm
 $rtmd --output ./tests/out/subintent.rtm ./tests/out/subintent.bin
+
+# Formatting a decompiled manifest leaves it unchanged
+cargo run --bin rtmfmt -- --check ./tests/out/subintent.rtm
+
+# Compile a template with the arguments of its parameters
+$rtmc --output ./tests/out/template.bin --parameters ./tests/template.toml ./tests/template.rtm
+
+# Summarize the account withdraws and deposits of a manifest
+$rtmd --summary --output ./tests/out/template_summary.txt ./tests/out/template.bin
+grep -q "Withdraws from account_sim1" ./tests/out/template_summary.txt
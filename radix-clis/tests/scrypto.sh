@@ -27,6 +27,9 @@ $scrypto new-package hello-world --path $test_pkg --local
 # regenerate the Cargo.lock_template which is used.
 $scrypto build --path $test_pkg --locked
 
+# Inspect
+$scrypto inspect $test_pkg/target/wasm32-unknown-unknown/release/hello_world.wasm
+
 # Test
 $scrypto test --path $test_pkg --locked
 $scrypto test --path $test_pkg --locked -- test_hello --nocapture
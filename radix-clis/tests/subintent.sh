@@ -0,0 +1,28 @@
+#!/bin/bash
+
+set -x
+set -e
+
+cd "$(dirname "$0")/.."
+
+subintent="cargo run --bin subintent -- $@ "
+header="--start-epoch 0 --end-epoch 100"
+
+# Export an unsigned subintent, and sign copies of it independently
+hash=$($subintent export $header --discriminator 1 --message "Hello" --output ./tests/out/subintent_unsigned.bin ./tests/subintent.rtm | awk '{print $3}')
+$subintent sign --private-key 0000000000000000000000000000000000000000000000000000000000000001 --output ./tests/out/subintent_signed_1.bin ./tests/out/subintent_unsigned.bin
+$subintent sign --private-key 0000000000000000000000000000000000000000000000000000000000000002 --output ./tests/out/subintent_signed_2.bin ./tests/out/subintent_unsigned.bin
+
+# Merge the signatures, and show what was signed
+$subintent merge --output ./tests/out/subintent_signed.bin ./tests/out/subintent_signed_1.bin ./tests/out/subintent_signed_2.bin
+$subintent show ./tests/out/subintent_signed.bin
+
+# Use the signed subintent as the child of a transaction
+cat > ./tests/out/subintent_parent.rtm <<MANIFEST
+USE_CHILD NamedIntent("child") Intent("$hash");
+YIELD_TO_CHILD NamedIntent("child");
+MANIFEST
+$subintent assemble $header --children ./tests/out/subintent_signed.bin --private-key 0000000000000000000000000000000000000000000000000000000000000003 --notary-is-signatory --output ./tests/out/subintent_transaction.bin ./tests/out/subintent_parent.rtm
+
+# A transaction without its child is rejected
+! $subintent assemble $header --private-key 0000000000000000000000000000000000000000000000000000000000000003 --output ./tests/out/subintent_transaction.bin ./tests/out/subintent_parent.rtm
@@ -87,12 +87,119 @@ impl SerializableCustomExtension for ManifestCustomExtension {
     }
 }
 
+#[derive(Clone, Copy, Default)]
+pub struct ManifestValueDeserializationContext<'a> {
+    pub address_bech32_decoder: Option<&'a AddressBech32Decoder>,
+}
+
+impl<'a> ManifestValueDeserializationContext<'a> {
+    pub fn no_context() -> Self {
+        Self {
+            address_bech32_decoder: None,
+        }
+    }
+
+    pub fn with_optional_bech32(address_bech32_decoder: Option<&'a AddressBech32Decoder>) -> Self {
+        Self {
+            address_bech32_decoder,
+        }
+    }
+}
+
+impl<'a> From<&'a AddressBech32Decoder> for ManifestValueDeserializationContext<'a> {
+    fn from(val: &'a AddressBech32Decoder) -> Self {
+        ManifestValueDeserializationContext::with_optional_bech32(Some(val))
+    }
+}
+
+impl<'a> From<Option<&'a AddressBech32Decoder>> for ManifestValueDeserializationContext<'a> {
+    fn from(val: Option<&'a AddressBech32Decoder>) -> Self {
+        ManifestValueDeserializationContext::with_optional_bech32(val)
+    }
+}
+
+/// Note: buckets, proofs, address reservations and named addresses can only be deserialized from
+/// their numeric ids (i.e. not from the names which a [`ManifestValueDisplayContext`] may assign).
+impl DeserializableCustomExtension for ManifestCustomExtension {
+    type CustomValue = ManifestCustomValue;
+    type CustomDeserializationContext<'a> = ManifestValueDeserializationContext<'a>;
+
+    fn deserialize_custom_value(
+        context: &Self::CustomDeserializationContext<'_>,
+        custom_value_kind: Self::CustomValueKind,
+        value: &str,
+    ) -> Result<Self::CustomValue, String> {
+        let parse_id = |kind: &str| {
+            u32::from_str(value).map_err(|_| format!("`{}` is not a valid {} id", value, kind))
+        };
+        Ok(match custom_value_kind {
+            ManifestCustomValueKind::Address => {
+                if let Some(node_id) = NodeId::try_from_hex(value) {
+                    ManifestCustomValue::Address(ManifestAddress::Static(node_id))
+                } else if let Ok(id) = u32::from_str(value) {
+                    ManifestCustomValue::Address(ManifestAddress::Named(ManifestNamedAddress(id)))
+                } else if let Some(decoder) = context.address_bech32_decoder {
+                    let (_, bytes) = decoder.validate_and_decode(value).map_err(|error| {
+                        format!("`{}` is not a valid address: {:?}", value, error)
+                    })?;
+                    let node_id = bytes
+                        .try_into()
+                        .map(NodeId)
+                        .map_err(|_| format!("`{}` is not a valid address", value))?;
+                    ManifestCustomValue::Address(ManifestAddress::Static(node_id))
+                } else {
+                    return Err(format!(
+                        "Cannot decode address `{}` without knowing the network",
+                        value
+                    ));
+                }
+            }
+            ManifestCustomValueKind::Bucket => {
+                ManifestCustomValue::Bucket(ManifestBucket(parse_id("bucket")?))
+            }
+            ManifestCustomValueKind::Proof => {
+                ManifestCustomValue::Proof(ManifestProof(parse_id("proof")?))
+            }
+            ManifestCustomValueKind::AddressReservation => ManifestCustomValue::AddressReservation(
+                ManifestAddressReservation(parse_id("address reservation")?),
+            ),
+            ManifestCustomValueKind::Expression => ManifestCustomValue::Expression(match value {
+                "ENTIRE_WORKTOP" => ManifestExpression::EntireWorktop,
+                "ENTIRE_AUTH_ZONE" => ManifestExpression::EntireAuthZone,
+                _ => return Err(format!("`{}` is not a valid expression", value)),
+            }),
+            ManifestCustomValueKind::Blob => ManifestCustomValue::Blob(
+                hex::decode(value)
+                    .ok()
+                    .and_then(|bytes| ManifestBlobRef::try_from(bytes.as_slice()).ok())
+                    .ok_or_else(|| format!("`{}` is not a valid blob hash", value))?,
+            ),
+            ManifestCustomValueKind::Decimal => ManifestCustomValue::Decimal(from_decimal(
+                &Decimal::from_str(value)
+                    .map_err(|_| format!("`{}` is not a valid Decimal", value))?,
+            )),
+            ManifestCustomValueKind::PreciseDecimal => {
+                ManifestCustomValue::PreciseDecimal(from_precise_decimal(
+                    &PreciseDecimal::from_str(value)
+                        .map_err(|_| format!("`{}` is not a valid PreciseDecimal", value))?,
+                ))
+            }
+            ManifestCustomValueKind::NonFungibleLocalId => {
+                ManifestCustomValue::NonFungibleLocalId(from_non_fungible_local_id(
+                    NonFungibleLocalId::from_str(value)
+                        .map_err(|_| format!("`{}` is not a valid NonFungibleLocalId", value))?,
+                ))
+            }
+        })
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "serde")] // Ensures that VS Code runs this module with the features serde tag!
 mod tests {
     use super::*;
     use crate::address::test_addresses::*;
-    use crate::address::AddressBech32Encoder;
+    use crate::address::{AddressBech32Decoder, AddressBech32Encoder};
     use radix_rust::ContextualSerialize;
     use serde::Serialize;
     use serde_json::{json, to_string, to_value, Value as JsonValue};
@@ -276,6 +383,111 @@ mod tests {
         assert_programmatic_json_matches(&value, context, expected_programmatic);
     }
 
+    #[test]
+    #[cfg(feature = "serde")] // Workaround for VS Code "Run Test" feature
+    fn test_programmatic_json_deserialization() {
+        let encoder = AddressBech32Encoder::for_simulator();
+        let decoder = AddressBech32Decoder::for_simulator();
+        let value = (
+            ManifestCustomValue::Address(ManifestAddress::Static(*FUNGIBLE_RESOURCE.as_node_id())),
+            ManifestCustomValue::Address(ManifestAddress::Named(ManifestNamedAddress(1))),
+            ManifestCustomValue::Blob(ManifestBlobRef([7; 32])),
+            ManifestCustomValue::Bucket(ManifestBucket(2)),
+            ManifestCustomValue::Proof(ManifestProof(3)),
+            ManifestCustomValue::AddressReservation(ManifestAddressReservation(4)),
+            ManifestCustomValue::Expression(ManifestExpression::EntireAuthZone),
+            ManifestCustomValue::Decimal(from_decimal(&Decimal::ONE.checked_div(100).unwrap())),
+            ManifestCustomValue::PreciseDecimal(from_precise_decimal(&PreciseDecimal::ZERO)),
+            ManifestCustomValue::NonFungibleLocalId(from_non_fungible_local_id(
+                NonFungibleLocalId::string("hello").unwrap(),
+            )),
+        );
+        let payload = manifest_encode(&value).unwrap();
+
+        let json = to_value(
+            ManifestRawPayload::new_from_valid_slice(&payload).serializable(
+                SerializationParameters::Schemaless {
+                    mode: SerializationMode::Programmatic,
+                    custom_context: ManifestValueDisplayContext::with_optional_bech32(Some(
+                        &encoder,
+                    )),
+                    depth_limit: MANIFEST_SBOR_V1_MAX_DEPTH,
+                },
+            ),
+        )
+        .unwrap();
+        let deserialized_payload = deserialize_payload::<_, ManifestCustomExtension>(
+            json,
+            DeserializationParameters::Schemaless {
+                mode: SerializationMode::Programmatic,
+                custom_context: ManifestValueDeserializationContext::with_optional_bech32(Some(
+                    &decoder,
+                )),
+                depth_limit: MANIFEST_SBOR_V1_MAX_DEPTH,
+            },
+        )
+        .unwrap();
+        assert_eq!(deserialized_payload, payload);
+    }
+
+    #[derive(ScryptoDescribe)]
+    #[allow(dead_code)]
+    struct TestArguments {
+        resource: Reference,
+        amount: Decimal,
+        ids: Vec<NonFungibleLocalId>,
+        bucket: Own,
+        proofs: Vec<Own>,
+        blob: Vec<u8>,
+    }
+
+    #[test]
+    #[cfg(feature = "serde")] // Workaround for VS Code "Run Test" feature
+    fn test_natural_json_round_trips_with_schema() {
+        let encoder = AddressBech32Encoder::for_simulator();
+        let decoder = AddressBech32Decoder::for_simulator();
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<TestArguments, ScryptoCustomSchema>();
+        let payload = manifest_encode(&(
+            ManifestAddress::Static(*FUNGIBLE_RESOURCE.as_node_id()),
+            from_decimal(&Decimal::ONE.checked_div(100).unwrap()),
+            vec![from_non_fungible_local_id(NonFungibleLocalId::integer(123))],
+            ManifestBucket(2),
+            ManifestExpression::EntireAuthZone,
+            ManifestBlobRef([7; 32]),
+        ))
+        .unwrap();
+
+        let json = to_value(
+            ManifestRawPayload::new_from_valid_slice(&payload).serializable(
+                SerializationParameters::WithSchema {
+                    mode: SerializationMode::Natural,
+                    custom_context: ManifestValueDisplayContext::with_optional_bech32(Some(
+                        &encoder,
+                    )),
+                    schema: schema.v1(),
+                    type_id,
+                    depth_limit: MANIFEST_SBOR_V1_MAX_DEPTH,
+                },
+            ),
+        )
+        .unwrap();
+        let deserialized_payload = deserialize_payload::<_, ManifestCustomExtension>(
+            json,
+            DeserializationParameters::WithSchema {
+                mode: SerializationMode::Natural,
+                custom_context: ManifestValueDeserializationContext::with_optional_bech32(Some(
+                    &decoder,
+                )),
+                schema: schema.v1(),
+                type_id,
+                depth_limit: MANIFEST_SBOR_V1_MAX_DEPTH,
+            },
+        )
+        .unwrap();
+        assert_eq!(deserialized_payload, payload);
+    }
+
     fn assert_natural_json_matches<
         'a,
         T: ManifestEncode,
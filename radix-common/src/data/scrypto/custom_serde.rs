@@ -40,12 +40,103 @@ impl SerializableCustomExtension for ScryptoCustomExtension {
     }
 }
 
+#[derive(Clone, Copy, Default)]
+pub struct ScryptoValueDeserializationContext<'a> {
+    pub address_bech32_decoder: Option<&'a AddressBech32Decoder>,
+}
+
+impl<'a> ScryptoValueDeserializationContext<'a> {
+    pub fn no_context() -> Self {
+        Self {
+            address_bech32_decoder: None,
+        }
+    }
+
+    pub fn with_optional_bech32(address_bech32_decoder: Option<&'a AddressBech32Decoder>) -> Self {
+        Self {
+            address_bech32_decoder,
+        }
+    }
+}
+
+impl<'a> From<&'a AddressBech32Decoder> for ScryptoValueDeserializationContext<'a> {
+    fn from(val: &'a AddressBech32Decoder) -> Self {
+        ScryptoValueDeserializationContext::with_optional_bech32(Some(val))
+    }
+}
+
+impl<'a> From<Option<&'a AddressBech32Decoder>> for ScryptoValueDeserializationContext<'a> {
+    fn from(val: Option<&'a AddressBech32Decoder>) -> Self {
+        ScryptoValueDeserializationContext::with_optional_bech32(val)
+    }
+}
+
+impl DeserializableCustomExtension for ScryptoCustomExtension {
+    type CustomValue = ScryptoCustomValue;
+    type CustomDeserializationContext<'a> = ScryptoValueDeserializationContext<'a>;
+
+    fn deserialize_custom_value(
+        context: &Self::CustomDeserializationContext<'_>,
+        custom_value_kind: Self::CustomValueKind,
+        value: &str,
+    ) -> Result<Self::CustomValue, String> {
+        Ok(match custom_value_kind {
+            ScryptoCustomValueKind::Reference => ScryptoCustomValue::Reference(Reference(
+                parse_node_id(context.address_bech32_decoder, value)?,
+            )),
+            ScryptoCustomValueKind::Own => {
+                ScryptoCustomValue::Own(Own(parse_node_id(context.address_bech32_decoder, value)?))
+            }
+            ScryptoCustomValueKind::Decimal => ScryptoCustomValue::Decimal(
+                Decimal::from_str(value)
+                    .map_err(|_| format!("`{}` is not a valid Decimal", value))?,
+            ),
+            ScryptoCustomValueKind::PreciseDecimal => ScryptoCustomValue::PreciseDecimal(
+                PreciseDecimal::from_str(value)
+                    .map_err(|_| format!("`{}` is not a valid PreciseDecimal", value))?,
+            ),
+            ScryptoCustomValueKind::NonFungibleLocalId => ScryptoCustomValue::NonFungibleLocalId(
+                NonFungibleLocalId::from_str(value)
+                    .map_err(|_| format!("`{}` is not a valid NonFungibleLocalId", value))?,
+            ),
+        })
+    }
+}
+
+/// Parses a [`NodeId`] from either its Bech32m address (which requires a decoder), or its
+/// `NodeId(<hex>)` fallback representation (as output for non-addressable nodes).
+fn parse_node_id(
+    address_bech32_decoder: Option<&AddressBech32Decoder>,
+    value: &str,
+) -> Result<NodeId, String> {
+    if let Some(hex) = value
+        .strip_prefix("NodeId(")
+        .and_then(|value| value.strip_suffix(')'))
+    {
+        return NodeId::try_from_hex(hex)
+            .ok_or_else(|| format!("`{}` is not a valid NodeId", value));
+    }
+    let Some(decoder) = address_bech32_decoder else {
+        return Err(format!(
+            "Cannot decode address `{}` without knowing the network",
+            value
+        ));
+    };
+    let (_, bytes) = decoder
+        .validate_and_decode(value)
+        .map_err(|error| format!("`{}` is not a valid address: {:?}", value, error))?;
+    bytes
+        .try_into()
+        .map(NodeId)
+        .map_err(|_| format!("`{}` is not a valid address", value))
+}
+
 #[cfg(test)]
 #[cfg(feature = "serde")] // Ensures that VS Code runs this module with the features serde tag!
 mod tests {
     use super::*;
     use crate::address::test_addresses::*;
-    use crate::address::AddressBech32Encoder;
+    use crate::address::{AddressBech32Decoder, AddressBech32Encoder};
     use crate::data::scrypto::model::*;
     use crate::data::scrypto::{scrypto_encode, ScryptoValue};
     use crate::math::*;
@@ -230,6 +321,150 @@ mod tests {
         assert_programmatic_json_matches(&value, context, expected_programmatic);
     }
 
+    #[derive(ScryptoSbor, Debug, PartialEq, Eq)]
+    struct TestArguments {
+        resource: ResourceAddress,
+        amount: Decimal,
+        ids: Vec<NonFungibleLocalId>,
+        vault: Option<Own>,
+    }
+
+    #[test]
+    #[cfg(feature = "serde")] // Workaround for VS Code "Run Test" feature
+    fn test_natural_json_deserialization_with_schema() {
+        let encoder = AddressBech32Encoder::for_simulator();
+        let decoder = AddressBech32Decoder::for_simulator();
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<TestArguments, ScryptoCustomSchema>();
+        let value = TestArguments {
+            resource: FUNGIBLE_RESOURCE,
+            amount: Decimal::ONE.checked_div(100).unwrap(),
+            ids: vec![NonFungibleLocalId::integer(123)],
+            vault: Some(Own(FUNGIBLE_RESOURCE_NODE_ID)),
+        };
+        let payload = scrypto_encode(&value).unwrap();
+
+        // The output of the Natural serialization can be deserialized back...
+        let json = to_value(
+            ScryptoRawPayload::new_from_valid_slice(&payload).serializable(
+                SerializationParameters::WithSchema {
+                    mode: SerializationMode::Natural,
+                    custom_context: ScryptoValueDisplayContext::with_optional_bech32(Some(
+                        &encoder,
+                    )),
+                    schema: schema.v1(),
+                    type_id,
+                    depth_limit: SCRYPTO_SBOR_V1_MAX_DEPTH,
+                },
+            ),
+        )
+        .unwrap();
+        let parameters = || DeserializationParameters::WithSchema {
+            mode: SerializationMode::Natural,
+            custom_context: ScryptoValueDeserializationContext::with_optional_bech32(Some(
+                &decoder,
+            )),
+            schema: schema.v1(),
+            type_id,
+            depth_limit: SCRYPTO_SBOR_V1_MAX_DEPTH,
+        };
+        assert_eq!(
+            deserialize_payload::<_, ScryptoCustomExtension>(json, parameters()).unwrap(),
+            payload
+        );
+
+        // ...but the kind tags of custom values can also be omitted, if the schema is unambiguous
+        let hand_written_json = json!({
+            "resource": FUNGIBLE_RESOURCE_SIM_ADDRESS,
+            "amount": "0.01",
+            "ids": ["#123#"],
+            "vault": { "variant_name": "Some", "fields": [FUNGIBLE_RESOURCE_SIM_ADDRESS] },
+        });
+        assert_eq!(
+            deserialize_payload::<_, ScryptoCustomExtension>(hand_written_json, parameters())
+                .unwrap(),
+            payload
+        );
+
+        // An address of another network is rejected
+        let mainnet_address = AddressBech32Encoder::new(&NetworkDefinition::mainnet())
+            .encode(FUNGIBLE_RESOURCE_NODE_ID.as_bytes())
+            .unwrap();
+        let invalid_json = json!({
+            "resource": mainnet_address,
+            "amount": "0.01",
+            "ids": [],
+            "vault": { "variant_name": "None" },
+        });
+        assert!(
+            deserialize_payload::<_, ScryptoCustomExtension>(invalid_json, parameters()).is_err()
+        );
+    }
+
+    #[derive(ScryptoSbor, Debug, PartialEq, Eq)]
+    struct TestCustomValues {
+        reference: Reference,
+        own: Own,
+        decimal: Decimal,
+        precise_decimal: PreciseDecimal,
+        ids: Vec<NonFungibleLocalId>,
+    }
+
+    #[test]
+    #[cfg(feature = "serde")] // Workaround for VS Code "Run Test" feature
+    fn test_json_round_trips_all_custom_values() {
+        let encoder = AddressBech32Encoder::for_simulator();
+        let decoder = AddressBech32Decoder::for_simulator();
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<TestCustomValues, ScryptoCustomSchema>();
+        let payload = scrypto_encode(&TestCustomValues {
+            reference: Reference(FUNGIBLE_RESOURCE_NODE_ID),
+            own: Own(FUNGIBLE_RESOURCE_NODE_ID),
+            decimal: Decimal::ONE.checked_div(100).unwrap(),
+            precise_decimal: PreciseDecimal::MIN,
+            ids: vec![
+                NonFungibleLocalId::string("hello").unwrap(),
+                NonFungibleLocalId::integer(123),
+                NonFungibleLocalId::bytes(vec![0x23, 0x45]).unwrap(),
+                NonFungibleLocalId::ruid([0x11; 32]),
+            ],
+        })
+        .unwrap();
+
+        for mode in [SerializationMode::Programmatic, SerializationMode::Natural] {
+            for (encoder, decoder) in [(None, None), (Some(&encoder), Some(&decoder))] {
+                let json = to_value(
+                    ScryptoRawPayload::new_from_valid_slice(&payload).serializable(
+                        SerializationParameters::WithSchema {
+                            mode,
+                            custom_context: ScryptoValueDisplayContext::with_optional_bech32(
+                                encoder,
+                            ),
+                            schema: schema.v1(),
+                            type_id,
+                            depth_limit: SCRYPTO_SBOR_V1_MAX_DEPTH,
+                        },
+                    ),
+                )
+                .unwrap();
+                let deserialized_payload = deserialize_payload::<_, ScryptoCustomExtension>(
+                    json,
+                    DeserializationParameters::WithSchema {
+                        mode,
+                        custom_context: ScryptoValueDeserializationContext::with_optional_bech32(
+                            decoder,
+                        ),
+                        schema: schema.v1(),
+                        type_id,
+                        depth_limit: SCRYPTO_SBOR_V1_MAX_DEPTH,
+                    },
+                )
+                .unwrap();
+                assert_eq!(deserialized_payload, payload, "{:?}", mode);
+            }
+        }
+    }
+
     fn assert_natural_json_matches<
         'a,
         T: ScryptoEncode,
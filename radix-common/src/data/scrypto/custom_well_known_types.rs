@@ -492,6 +492,10 @@ create_well_known_lookup!(
                         4u8,
                         named_tuple("AnyOf", [RESOURCE_OR_NON_FUNGIBLE_LIST_TYPE])
                     ),
+                    (5u8, named_tuple("EpochAtLeast", [U64_TYPE])),
+                    (6u8, named_tuple("EpochBefore", [U64_TYPE])),
+                    (7u8, named_tuple("TimeAtLeast", [INSTANT_TYPE])),
+                    (8u8, named_tuple("TimeBefore", [INSTANT_TYPE])),
                 ],
             )
         ),
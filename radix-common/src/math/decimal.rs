@@ -10,6 +10,7 @@ use crate::data::scrypto::*;
 use crate::math::bnum_integer::*;
 use crate::math::rounding_mode::*;
 use crate::math::traits::*;
+use crate::math::transcendental;
 use crate::math::PreciseDecimal;
 use crate::well_known_scrypto_custom_type;
 use crate::*;
@@ -295,6 +296,62 @@ impl Decimal {
             Some(Decimal(nth_root))
         }
     }
+
+    /// Natural logarithm of a Decimal, rounded with the given mode.
+    ///
+    /// Returns `None` if the value is not positive. See [`Self::checked_pow`] for the accuracy of
+    /// the transcendental functions.
+    pub fn checked_ln(&self, mode: RoundingMode) -> Option<Self> {
+        let ln = transcendental::ln(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I192::try_from(ln).ok().map(Self)
+    }
+
+    /// Base-2 logarithm of a Decimal, rounded with the given mode.
+    ///
+    /// Returns `None` if the value is not positive. The result is exact for integer powers of two.
+    pub fn checked_log2(&self, mode: RoundingMode) -> Option<Self> {
+        let log2 = transcendental::log2(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I192::try_from(log2).ok().map(Self)
+    }
+
+    /// Base-10 logarithm of a Decimal, rounded with the given mode.
+    ///
+    /// Returns `None` if the value is not positive. The result is exact for integer powers of ten.
+    pub fn checked_log10(&self, mode: RoundingMode) -> Option<Self> {
+        let log10 = transcendental::log10(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I192::try_from(log10).ok().map(Self)
+    }
+
+    /// Exponential function (`e^x`) of a Decimal, rounded with the given mode.
+    ///
+    /// Returns `None` on overflow. Results too small to be represented are rounded like any other
+    /// value, i.e. to zero or to the smallest positive value, depending on the mode.
+    pub fn checked_exp(&self, mode: RoundingMode) -> Option<Self> {
+        let exp = transcendental::exp(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I192::try_from(exp).ok().map(Self)
+    }
+
+    /// Raises a Decimal to a real exponent, rounding the result with the given mode.
+    ///
+    /// Returns `None` on overflow, for a negative base with a non-integer exponent, and for a zero
+    /// base with a negative exponent. Integer exponents (up to 256 in magnitude) are computed
+    /// exactly, and then rounded once.
+    ///
+    /// All transcendental functions (`checked_ln`, `checked_log2`, `checked_log10`, `checked_exp`
+    /// and `checked_pow`) are computed with integer arithmetic only, so they are deterministic on
+    /// every platform. Intermediate results carry 20 more decimal places than the result, so the
+    /// returned value is the exact result rounded with the given mode, unless the exact result is
+    /// within `10^-20` units in the last place of a rounding boundary - in which case it may be
+    /// off by one unit in the last place.
+    pub fn checked_pow(&self, exp: Self, mode: RoundingMode) -> Option<Self> {
+        let pow = transcendental::pow(
+            &BigInt::from(self.0),
+            &BigInt::from(exp.0),
+            Self::SCALE,
+            mode,
+        )?;
+        I192::try_from(pow).ok().map(Self)
+    }
 }
 
 macro_rules! from_primitive_type {
@@ -1972,6 +2029,138 @@ mod tests {
         assert_eq!(root_0, None);
     }
 
+    #[test]
+    fn test_ln() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(test_dec!(1).checked_ln(mode).unwrap(), Decimal::ZERO);
+        assert_eq!(
+            test_dec!(2).checked_ln(mode).unwrap(),
+            test_dec!("0.693147180559945309")
+        );
+        assert_eq!(
+            test_dec!(2)
+                .checked_ln(RoundingMode::ToPositiveInfinity)
+                .unwrap(),
+            test_dec!("0.693147180559945310")
+        );
+        assert_eq!(
+            Decimal::ONE_ATTO.checked_ln(mode).unwrap(),
+            test_dec!("-41.446531673892822312")
+        );
+        assert_eq!(Decimal::ZERO.checked_ln(mode), None);
+        assert_eq!(test_dec!("-1").checked_ln(mode), None);
+    }
+
+    #[test]
+    fn test_log2_and_log10() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(
+            test_dec!(3).checked_log2(mode).unwrap(),
+            test_dec!("1.584962500721156181")
+        );
+        assert_eq!(
+            test_dec!(2).checked_log10(mode).unwrap(),
+            test_dec!("0.301029995663981195")
+        );
+
+        // Exact powers are exact in every rounding mode
+        assert_eq!(
+            test_dec!(1024)
+                .checked_log2(RoundingMode::ToNegativeInfinity)
+                .unwrap(),
+            test_dec!(10)
+        );
+        assert_eq!(
+            test_dec!("0.125")
+                .checked_log2(RoundingMode::ToPositiveInfinity)
+                .unwrap(),
+            test_dec!("-3")
+        );
+        assert_eq!(
+            Decimal::ONE_ATTO
+                .checked_log10(RoundingMode::AwayFromZero)
+                .unwrap(),
+            test_dec!("-18")
+        );
+        assert_eq!(Decimal::ZERO.checked_log10(mode), None);
+    }
+
+    #[test]
+    fn test_exp() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(Decimal::ZERO.checked_exp(mode).unwrap(), Decimal::ONE);
+        assert_eq!(
+            test_dec!(1).checked_exp(mode).unwrap(),
+            test_dec!("2.718281828459045235")
+        );
+        assert_eq!(
+            test_dec!("-1").checked_exp(mode).unwrap(),
+            test_dec!("0.367879441171442322")
+        );
+        assert_eq!(
+            test_dec!(90).checked_exp(mode).unwrap(),
+            test_dec!("1220403294317840802002710035136369753970.746421099767546244")
+        );
+        assert_eq!(test_dec!(91).checked_exp(mode), None);
+        assert_eq!(test_dec!("-100").checked_exp(mode).unwrap(), Decimal::ZERO);
+        assert_eq!(
+            test_dec!("-100")
+                .checked_exp(RoundingMode::ToPositiveInfinity)
+                .unwrap(),
+            Decimal::ONE_ATTO
+        );
+    }
+
+    #[test]
+    fn test_pow() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(
+            test_dec!(2).checked_pow(test_dec!("0.5"), mode).unwrap(),
+            test_dec!("1.414213562373095049")
+        );
+        assert_eq!(
+            test_dec!(2)
+                .checked_pow(test_dec!("0.5"), RoundingMode::ToZero)
+                .unwrap(),
+            test_dec!("1.414213562373095048")
+        );
+        assert_eq!(
+            test_dec!("1.05")
+                .checked_pow(test_dec!("2.5"), mode)
+                .unwrap(),
+            test_dec!("1.129726321947045722")
+        );
+        assert_eq!(
+            test_dec!(2).checked_pow(test_dec!(10), mode).unwrap(),
+            test_dec!(1024)
+        );
+        assert_eq!(
+            test_dec!(2).checked_pow(test_dec!("-2"), mode).unwrap(),
+            test_dec!("0.25")
+        );
+        assert_eq!(
+            test_dec!("-1.5").checked_pow(test_dec!(3), mode).unwrap(),
+            test_dec!("-3.375")
+        );
+        assert_eq!(
+            test_dec!(3)
+                .checked_pow(test_dec!("-1"), RoundingMode::ToPositiveInfinity)
+                .unwrap(),
+            test_dec!("0.333333333333333334")
+        );
+        assert_eq!(
+            Decimal::ZERO.checked_pow(Decimal::ZERO, mode).unwrap(),
+            Decimal::ONE
+        );
+        assert_eq!(
+            Decimal::ZERO.checked_pow(test_dec!("0.5"), mode).unwrap(),
+            Decimal::ZERO
+        );
+        assert_eq!(Decimal::ZERO.checked_pow(test_dec!("-1"), mode), None);
+        assert_eq!(test_dec!("-2").checked_pow(test_dec!("0.5"), mode), None);
+        assert_eq!(Decimal::MAX.checked_pow(test_dec!(2), mode), None);
+    }
+
     #[test]
     fn no_panic_with_18_decimal_places() {
         // Arrange
@@ -11,6 +11,7 @@ use crate::math::bnum_integer::*;
 use crate::math::decimal::*;
 use crate::math::rounding_mode::*;
 use crate::math::traits::*;
+use crate::math::transcendental;
 use crate::well_known_scrypto_custom_type;
 use crate::*;
 
@@ -306,6 +307,62 @@ impl PreciseDecimal {
             Some(Self(nth_root))
         }
     }
+
+    /// Natural logarithm of a PreciseDecimal, rounded with the given mode.
+    ///
+    /// Returns `None` if the value is not positive. See [`Self::checked_pow`] for the accuracy of
+    /// the transcendental functions.
+    pub fn checked_ln(&self, mode: RoundingMode) -> Option<Self> {
+        let ln = transcendental::ln(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I256::try_from(ln).ok().map(Self)
+    }
+
+    /// Base-2 logarithm of a PreciseDecimal, rounded with the given mode.
+    ///
+    /// Returns `None` if the value is not positive. The result is exact for integer powers of two.
+    pub fn checked_log2(&self, mode: RoundingMode) -> Option<Self> {
+        let log2 = transcendental::log2(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I256::try_from(log2).ok().map(Self)
+    }
+
+    /// Base-10 logarithm of a PreciseDecimal, rounded with the given mode.
+    ///
+    /// Returns `None` if the value is not positive. The result is exact for integer powers of ten.
+    pub fn checked_log10(&self, mode: RoundingMode) -> Option<Self> {
+        let log10 = transcendental::log10(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I256::try_from(log10).ok().map(Self)
+    }
+
+    /// Exponential function (`e^x`) of a PreciseDecimal, rounded with the given mode.
+    ///
+    /// Returns `None` on overflow. Results too small to be represented are rounded like any other
+    /// value, i.e. to zero or to the smallest positive value, depending on the mode.
+    pub fn checked_exp(&self, mode: RoundingMode) -> Option<Self> {
+        let exp = transcendental::exp(&BigInt::from(self.0), Self::SCALE, mode)?;
+        I256::try_from(exp).ok().map(Self)
+    }
+
+    /// Raises a PreciseDecimal to a real exponent, rounding the result with the given mode.
+    ///
+    /// Returns `None` on overflow, for a negative base with a non-integer exponent, and for a zero
+    /// base with a negative exponent. Integer exponents (up to 256 in magnitude) are computed
+    /// exactly, and then rounded once.
+    ///
+    /// All transcendental functions (`checked_ln`, `checked_log2`, `checked_log10`, `checked_exp`
+    /// and `checked_pow`) are computed with integer arithmetic only, so they are deterministic on
+    /// every platform. Intermediate results carry 20 more decimal places than the result, so the
+    /// returned value is the exact result rounded with the given mode, unless the exact result is
+    /// within `10^-20` units in the last place of a rounding boundary - in which case it may be
+    /// off by one unit in the last place.
+    pub fn checked_pow(&self, exp: Self, mode: RoundingMode) -> Option<Self> {
+        let pow = transcendental::pow(
+            &BigInt::from(self.0),
+            &BigInt::from(exp.0),
+            Self::SCALE,
+            mode,
+        )?;
+        I256::try_from(pow).ok().map(Self)
+    }
 }
 
 macro_rules! from_primitive_type {
@@ -2159,6 +2216,93 @@ mod tests {
         assert_eq!(root_0, None);
     }
 
+    #[test]
+    fn test_ln() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(
+            test_pdec!(1).checked_ln(mode).unwrap(),
+            PreciseDecimal::ZERO
+        );
+        assert_eq!(
+            test_pdec!(2).checked_ln(mode).unwrap(),
+            test_pdec!("0.693147180559945309417232121458176568")
+        );
+        assert_eq!(
+            test_pdec!(2)
+                .checked_ln(RoundingMode::ToPositiveInfinity)
+                .unwrap(),
+            test_pdec!("0.693147180559945309417232121458176569")
+        );
+        assert_eq!(
+            PreciseDecimal::ONE_ATTO.checked_ln(mode).unwrap(),
+            test_pdec!("-41.446531673892822312323846184318555737")
+        );
+        assert_eq!(PreciseDecimal::ZERO.checked_ln(mode), None);
+    }
+
+    #[test]
+    fn test_log2_and_log10() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(
+            test_pdec!(3).checked_log2(mode).unwrap(),
+            test_pdec!("1.584962500721156181453738943947816509")
+        );
+        assert_eq!(
+            test_pdec!(2).checked_log10(mode).unwrap(),
+            test_pdec!("0.301029995663981195213738894724493027")
+        );
+        assert_eq!(
+            test_pdec!("0.0001")
+                .checked_log10(RoundingMode::ToNegativeInfinity)
+                .unwrap(),
+            test_pdec!("-4")
+        );
+    }
+
+    #[test]
+    fn test_exp() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(
+            PreciseDecimal::ZERO.checked_exp(mode).unwrap(),
+            PreciseDecimal::ONE
+        );
+        assert_eq!(
+            test_pdec!(1).checked_exp(mode).unwrap(),
+            test_pdec!("2.718281828459045235360287471352662498")
+        );
+        assert_eq!(
+            test_pdec!("-1").checked_exp(mode).unwrap(),
+            test_pdec!("0.367879441171442321595523770161460867")
+        );
+        assert_eq!(
+            test_pdec!(90).checked_exp(mode).unwrap(),
+            test_pdec!(
+                "1220403294317840802002710035136369753970.746421099767546244343829824312727359"
+            )
+        );
+        assert_eq!(test_pdec!(95).checked_exp(mode), None);
+    }
+
+    #[test]
+    fn test_pow() {
+        let mode = RoundingMode::ToNearestMidpointToEven;
+        assert_eq!(
+            test_pdec!(2).checked_pow(test_pdec!("0.5"), mode).unwrap(),
+            test_pdec!("1.414213562373095048801688724209698079")
+        );
+        assert_eq!(
+            test_pdec!("1.05")
+                .checked_pow(test_pdec!("2.5"), mode)
+                .unwrap(),
+            test_pdec!("1.129726321947045721750119514527445982")
+        );
+        assert_eq!(
+            test_pdec!("-1.5").checked_pow(test_pdec!(3), mode).unwrap(),
+            test_pdec!("-3.375")
+        );
+        assert_eq!(test_pdec!("-2").checked_pow(test_pdec!("0.5"), mode), None);
+    }
+
     #[test]
     fn no_panic_with_36_decimal_places() {
         // Arrange
@@ -0,0 +1,315 @@
+//! Fixed-point implementations of the transcendental functions of [`Decimal`] and
+//! [`PreciseDecimal`].
+//!
+//! All functions take and return the underlying subunits of a fixed-scale decimal (i.e. a value
+//! `v` at scale `s` is passed as `v * 10^s`). Internally, they work on arbitrary-precision
+//! integers with [`GUARD_DIGITS`] more decimal places than the result needs (plus any digits
+//! which get amplified by the function), and only round once - with the requested
+//! [`RoundingMode`] - at the very end. Since only integer arithmetic is used, the results are
+//! fully deterministic across platforms (including WASM).
+//!
+//! [`Decimal`]: crate::math::Decimal
+//! [`PreciseDecimal`]: crate::math::PreciseDecimal
+
+use crate::internal_prelude::*;
+use crate::math::rounding_mode::*;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Pow, Signed, ToPrimitive, Zero};
+
+/// The number of additional decimal places used for intermediate results.
+///
+/// The accumulated error of the intermediate results stays far below `10^-GUARD_DIGITS` units in
+/// the last place of the result, so the final rounding is exact unless the exact result is closer
+/// than that to a rounding boundary.
+const GUARD_DIGITS: u32 = 20;
+
+/// An upper bound on the number of integer digits of any supported fixed-scale decimal.
+///
+/// It is used to size the working precision of `exp`, whose absolute error grows with the
+/// magnitude of its result.
+const MAX_INTEGER_DIGITS: u32 = 42;
+
+/// The largest integer exponent for which `pow` is computed exactly (by repeated multiplication),
+/// instead of via `exp(exponent * ln(base))`.
+const MAX_EXACT_POWER_EXPONENT: u32 = 256;
+
+/// Returns `ln(x)`, or `None` if `x` is not positive.
+pub(crate) fn ln(x: &BigInt, scale: u32, mode: RoundingMode) -> Option<BigInt> {
+    if !x.is_positive() {
+        return None;
+    }
+    let digits = scale + GUARD_DIGITS;
+    let ln = ln_fixed(&rescale_up(x, scale, digits), &pow10(digits));
+    Some(round_div(&ln, &pow10(GUARD_DIGITS), mode))
+}
+
+/// Returns `log2(x)`, or `None` if `x` is not positive.
+///
+/// The result is exact if `x` is an integer power of two.
+pub(crate) fn log2(x: &BigInt, scale: u32, mode: RoundingMode) -> Option<BigInt> {
+    log_base(x, scale, 2, mode)
+}
+
+/// Returns `log10(x)`, or `None` if `x` is not positive.
+///
+/// The result is exact if `x` is an integer power of ten.
+pub(crate) fn log10(x: &BigInt, scale: u32, mode: RoundingMode) -> Option<BigInt> {
+    log_base(x, scale, 10, mode)
+}
+
+/// Returns `e^x`, or `None` if the result is certainly out of the range of any supported type.
+///
+/// The caller is responsible for checking whether the result fits into its type.
+pub(crate) fn exp(x: &BigInt, scale: u32, mode: RoundingMode) -> Option<BigInt> {
+    let digits = exp_working_digits(scale, 0);
+    exp_with_rounding(&rescale_up(x, scale, digits), digits, scale, mode)
+}
+
+/// Returns `base^exponent`, or `None` if it is undefined (i.e. for a negative base with a
+/// non-integer exponent, or for a zero base with a negative exponent) or certainly out of the
+/// range of any supported type.
+///
+/// The result is exact for integer exponents up to [`MAX_EXACT_POWER_EXPONENT`] in magnitude.
+pub(crate) fn pow(
+    base: &BigInt,
+    exponent: &BigInt,
+    scale: u32,
+    mode: RoundingMode,
+) -> Option<BigInt> {
+    let one = pow10(scale);
+    let (integer_exponent, fractional_exponent) = exponent.div_rem(&one);
+    let is_integer_exponent = fractional_exponent.is_zero();
+
+    if base.is_zero() {
+        return match exponent.sign() {
+            num_bigint::Sign::Minus => None,
+            num_bigint::Sign::NoSign => Some(one),
+            num_bigint::Sign::Plus => Some(BigInt::zero()),
+        };
+    }
+    if base.is_negative() && !is_integer_exponent {
+        return None;
+    }
+
+    if is_integer_exponent {
+        if let Some(n) = integer_exponent
+            .abs()
+            .to_u32()
+            .filter(|n| *n <= MAX_EXACT_POWER_EXPONENT)
+        {
+            // base^n = base^n / one^(n-1) and base^(-n) = one^(n+1) / base^n, in subunits
+            let power = Pow::pow(base, n);
+            return Some(if integer_exponent.is_negative() {
+                round_div(&Pow::pow(&one, n + 1), &power, mode)
+            } else if n == 0 {
+                one
+            } else {
+                round_div(&power, &Pow::pow(&one, n - 1), mode)
+            });
+        }
+    }
+
+    // The absolute error of `exponent * ln(|base|)` is the error of the logarithm amplified by
+    // the magnitude of the exponent
+    let exponent_digits = integer_exponent.abs().to_string().len() as u32;
+    let digits = exp_working_digits(scale, exponent_digits);
+    let ln = ln_fixed(&rescale_up(&base.abs(), scale, digits), &pow10(digits));
+    let product = exponent * ln / pow10(scale);
+    let power = if base.is_negative() && integer_exponent.is_odd() {
+        // (-x)^n = -(x^n) for an odd n, which needs to be rounded as a negative value
+        -exp_with_rounding(&product, digits, scale, invert(mode))?
+    } else {
+        exp_with_rounding(&product, digits, scale, mode)?
+    };
+    Some(power)
+}
+
+fn log_base(x: &BigInt, scale: u32, base: u32, mode: RoundingMode) -> Option<BigInt> {
+    if !x.is_positive() {
+        return None;
+    }
+    let one = pow10(scale);
+    if let Some(exact) = exact_log(x, &one, base) {
+        return Some(BigInt::from(exact) * one);
+    }
+    let digits = scale + GUARD_DIGITS;
+    let working_one = pow10(digits);
+    let ln = ln_fixed(&rescale_up(x, scale, digits), &working_one);
+    let ln_base = match base {
+        2 => ln2(&working_one),
+        _ => ln10(&working_one),
+    };
+    Some(round_div(
+        &(ln * &working_one),
+        &(ln_base * pow10(GUARD_DIGITS)),
+        mode,
+    ))
+}
+
+/// Returns `n` if `x / one == base^n`.
+fn exact_log(x: &BigInt, one: &BigInt, base: u32) -> Option<i64> {
+    let (ratio, is_negative) = if x >= one {
+        let (ratio, remainder) = x.div_rem(one);
+        (remainder.is_zero().then_some(ratio)?, false)
+    } else {
+        let (ratio, remainder) = one.div_rem(x);
+        (remainder.is_zero().then_some(ratio)?, true)
+    };
+    let mut power = BigInt::one();
+    let mut n = 0i64;
+    while power < ratio {
+        power *= base;
+        n += 1;
+    }
+    (power == ratio).then_some(if is_negative { -n } else { n })
+}
+
+fn exp_working_digits(scale: u32, extra_digits: u32) -> u32 {
+    scale + GUARD_DIGITS + MAX_INTEGER_DIGITS + extra_digits
+}
+
+/// Computes `e^x` of a fixed-point `x` (with `digits` decimal places), and rounds it to `scale`.
+fn exp_with_rounding(x: &BigInt, digits: u32, scale: u32, mode: RoundingMode) -> Option<BigInt> {
+    let working_one = pow10(digits);
+
+    // Any larger result would not fit in `MAX_INTEGER_DIGITS`, since `e^97 > 10^42`
+    let max = BigInt::from(97) * &working_one;
+    if *x > max {
+        return None;
+    }
+    // Any smaller result is below the guard digits - it is still kept positive, so that it gets
+    // rounded correctly (e.g. to the smallest positive value when rounding up)
+    let min = -BigInt::from(3 * (scale + GUARD_DIGITS + 2)) * &working_one;
+    let x = if *x < min { &min } else { x };
+
+    let exp = exp_fixed(x, &working_one);
+    Some(round_div(&exp, &pow10(digits - scale), mode))
+}
+
+/// Computes `ln(x)` of a positive fixed-point `x` (where `one` represents `1`).
+fn ln_fixed(x: &BigInt, one: &BigInt) -> BigInt {
+    // Reduce to x = m * 2^k, with m in [1, 2)
+    let mut k = x.bits() as i64 - one.bits() as i64;
+    let two = one << 1;
+    let mut m = shift(x, -k);
+    while m >= two {
+        m >>= 1;
+        k += 1;
+    }
+    while m < *one {
+        m <<= 1;
+        k -= 1;
+    }
+
+    // ln(m) = 2 * atanh((m - 1) / (m + 1)), where the argument is in [0, 1/3)
+    let z = (&m - one) * one / (&m + one);
+    atanh(&z, one) * 2 + ln2(one) * k
+}
+
+/// Computes `e^x` of a fixed-point `x` (where `one` represents `1`).
+fn exp_fixed(x: &BigInt, one: &BigInt) -> BigInt {
+    // Reduce to x = k * ln(2) + r, with |r| <= ln(2) / 2
+    let ln2 = ln2(one);
+    let k = (x * 2u32 + &ln2).div_floor(&(&ln2 * 2u32));
+    let r = x - &k * &ln2;
+
+    // Taylor series of e^r
+    let mut sum = one.clone();
+    let mut term = one.clone();
+    let mut n = 1u32;
+    loop {
+        term = &term * &r / one / n;
+        if term.is_zero() {
+            break;
+        }
+        sum += &term;
+        n += 1;
+    }
+
+    shift(
+        &sum,
+        k.to_i64().expect("Exponent is bounded by the callers"),
+    )
+}
+
+/// Computes `atanh(z)` of a fixed-point `z` in `[0, 1)` (where `one` represents `1`).
+fn atanh(z: &BigInt, one: &BigInt) -> BigInt {
+    let z_squared = z * z / one;
+    let mut sum = z.clone();
+    let mut power = z.clone();
+    let mut n = 1u32;
+    loop {
+        power = &power * &z_squared / one;
+        let term = &power / (2 * n + 1);
+        if term.is_zero() {
+            break;
+        }
+        sum += term;
+        n += 1;
+    }
+    sum
+}
+
+fn ln2(one: &BigInt) -> BigInt {
+    atanh(&(one / 3), one) * 2
+}
+
+fn ln10(one: &BigInt) -> BigInt {
+    // ln(10) = 3 * ln(2) + ln(1.25), where ln(1.25) = 2 * atanh(1/9)
+    ln2(one) * 3 + atanh(&(one / 9), one) * 2
+}
+
+/// Multiplies `x` by `2^k`.
+fn shift(x: &BigInt, k: i64) -> BigInt {
+    if k >= 0 {
+        x << (k as usize)
+    } else {
+        x >> ((-k) as usize)
+    }
+}
+
+fn pow10(digits: u32) -> BigInt {
+    Pow::pow(BigInt::from(10), digits)
+}
+
+fn rescale_up(x: &BigInt, from_digits: u32, to_digits: u32) -> BigInt {
+    x * pow10(to_digits - from_digits)
+}
+
+/// Divides `numerator` by a non-zero `denominator`, rounding the quotient with the given mode.
+fn round_div(numerator: &BigInt, denominator: &BigInt, mode: RoundingMode) -> BigInt {
+    let (numerator, denominator) = if denominator.is_negative() {
+        (-numerator, -denominator)
+    } else {
+        (numerator.clone(), denominator.clone())
+    };
+    let (quotient, remainder) = numerator.div_mod_floor(&denominator);
+    if remainder.is_zero() {
+        return quotient;
+    }
+
+    let strategy = ResolvedRoundingStrategy::from_mode(mode, numerator.is_positive(), || {
+        (&remainder * 2u32).cmp(&denominator)
+    });
+    match strategy {
+        ResolvedRoundingStrategy::RoundUp => quotient + 1,
+        ResolvedRoundingStrategy::RoundDown => quotient,
+        ResolvedRoundingStrategy::RoundToEven => {
+            if quotient.is_even() {
+                quotient
+            } else {
+                quotient + 1
+            }
+        }
+    }
+}
+
+/// Returns the mode which rounds `-x` like the given mode rounds `x`.
+fn invert(mode: RoundingMode) -> RoundingMode {
+    match mode {
+        RoundingMode::ToPositiveInfinity => RoundingMode::ToNegativeInfinity,
+        RoundingMode::ToNegativeInfinity => RoundingMode::ToPositiveInfinity,
+        other => other,
+    }
+}
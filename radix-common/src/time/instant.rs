@@ -6,7 +6,10 @@ use sbor::*;
 /// Represents a Unix timestamp, capturing the seconds since the unix epoch.
 ///
 /// See also the [`UtcDateTime`](super::UtcDateTime) type which supports conversion to/from `Instant`.
-#[cfg_attr(feature = "fuzzing", derive(::arbitrary::Arbitrary))]
+#[cfg_attr(
+    feature = "fuzzing",
+    derive(::arbitrary::Arbitrary, ::serde::Serialize, ::serde::Deserialize)
+)]
 #[derive(
     Copy,
     Clone,
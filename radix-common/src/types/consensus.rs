@@ -10,6 +10,10 @@ use sbor::Sbor;
 pub type ValidatorIndex = u8;
 
 /// A type-safe consensus epoch number.
+#[cfg_attr(
+    feature = "fuzzing",
+    derive(::arbitrary::Arbitrary, ::serde::Serialize, ::serde::Deserialize)
+)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Sbor)]
 #[sbor(transparent)]
 pub struct Epoch(u64);
@@ -104,6 +104,14 @@ pub enum BasicRequirement {
     CountOf(u8, Vec<ResourceOrNonFungible>),
     AllOf(Vec<ResourceOrNonFungible>),
     AnyOf(Vec<ResourceOrNonFungible>),
+    /// Requires the current epoch to be at or after the given epoch.
+    EpochAtLeast(Epoch),
+    /// Requires the current epoch to be before the given epoch.
+    EpochBefore(Epoch),
+    /// Requires the current proposer timestamp, in seconds, to be at or after the given instant.
+    TimeAtLeast(Instant),
+    /// Requires the current proposer timestamp, in seconds, to be before the given instant.
+    TimeBefore(Instant),
 }
 
 impl Describe<ScryptoCustomTypeKind> for BasicRequirement {
@@ -260,6 +268,39 @@ where
     ))
 }
 
+/// A requirement for the current epoch to be at or after the given epoch.
+pub fn require_epoch_at_least(epoch: Epoch) -> CompositeRequirement {
+    CompositeRequirement::BasicRequirement(BasicRequirement::EpochAtLeast(epoch))
+}
+
+/// A requirement for the current epoch to be before the given epoch.
+pub fn require_epoch_before(epoch: Epoch) -> CompositeRequirement {
+    CompositeRequirement::BasicRequirement(BasicRequirement::EpochBefore(epoch))
+}
+
+/// A requirement for the current epoch to be within `[start_inclusive, end_exclusive)`.
+pub fn require_epoch_range(start_inclusive: Epoch, end_exclusive: Epoch) -> CompositeRequirement {
+    require_epoch_at_least(start_inclusive).and(require_epoch_before(end_exclusive))
+}
+
+/// A requirement for the current proposer timestamp to be at or after the given instant.
+pub fn require_time_at_least(instant: Instant) -> CompositeRequirement {
+    CompositeRequirement::BasicRequirement(BasicRequirement::TimeAtLeast(instant))
+}
+
+/// A requirement for the current proposer timestamp to be before the given instant.
+pub fn require_time_before(instant: Instant) -> CompositeRequirement {
+    CompositeRequirement::BasicRequirement(BasicRequirement::TimeBefore(instant))
+}
+
+/// A requirement for the current proposer timestamp to be within `[start_inclusive, end_exclusive)`.
+pub fn require_time_range(
+    start_inclusive: Instant,
+    end_exclusive: Instant,
+) -> CompositeRequirement {
+    require_time_at_least(start_inclusive).and(require_time_before(end_exclusive))
+}
+
 #[cfg_attr(
     feature = "fuzzing",
     derive(::arbitrary::Arbitrary, ::serde::Serialize, ::serde::Deserialize)
@@ -422,6 +463,55 @@ mod tests {
         .expect("Manifest access rule payload should match Manifest schema");
     }
 
+    #[test]
+    fn time_based_requirements_can_be_combined_with_proof_requirements() {
+        let rule = rule!(
+            require(XRD) && require_epoch_range(Epoch::of(10), Epoch::of(20))
+                || require_time_at_least(Instant::new(1_700_000_000))
+        );
+
+        assert_eq!(
+            rule,
+            AccessRule::Protected(CompositeRequirement::AnyOf(vec![
+                CompositeRequirement::AllOf(vec![
+                    require(XRD),
+                    CompositeRequirement::AllOf(vec![
+                        require_epoch_at_least(Epoch::of(10)),
+                        require_epoch_before(Epoch::of(20)),
+                    ]),
+                ]),
+                require_time_at_least(Instant::new(1_700_000_000)),
+            ]))
+        );
+    }
+
+    #[test]
+    fn time_based_access_rule_payloads_match_access_rule_schema() {
+        let rule = rule!(
+            require_epoch_range(Epoch::of(10), Epoch::of(20))
+                && require_time_range(Instant::new(0), Instant::new(1_700_000_000))
+        );
+
+        let (local_type_id, versioned_schema) =
+            generate_full_schema_from_single_type::<AccessRule, ScryptoCustomSchema>();
+        validate_payload_against_schema::<ScryptoCustomExtension, _>(
+            &scrypto_encode(&rule).unwrap(),
+            versioned_schema.v1(),
+            local_type_id,
+            &(),
+            SCRYPTO_SBOR_V1_MAX_DEPTH,
+        )
+        .expect("Scrypto access rule payload should match AccessRule schema");
+        validate_payload_against_schema::<ManifestCustomExtension, _>(
+            &manifest_encode(&ManifestAccessRule::from(rule)).unwrap(),
+            versioned_schema.v1(),
+            local_type_id,
+            &(),
+            MANIFEST_SBOR_V1_MAX_DEPTH,
+        )
+        .expect("Manifest access rule payload should match AccessRule schema");
+    }
+
     #[test]
     fn non_enums_cant_be_decoded_as_a_manifest_access_rule() {
         // Arrange
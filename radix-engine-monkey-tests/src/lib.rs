@@ -784,6 +784,9 @@ impl<T: TxnFuzzer> FuzzTest<T> {
                             panic!("Native panic: {:?} {:?} {:?}", export_name, input, error);
                         }
 
+                        if let TransactionOutcome::Failure(e) = &commit_result.outcome {
+                            eprintln!("DEBUGFAIL {:?} {:?}", fuzz_txn_intent, e);
+                        }
                         FuzzTxnResult::from_outcome(&commit_result.outcome, trivial)
                     }
                     TransactionResult::Reject(_) => FuzzTxnResult::Reject,
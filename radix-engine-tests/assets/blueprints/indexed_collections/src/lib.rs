@@ -0,0 +1,61 @@
+use scrypto::prelude::*;
+
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct Order {
+    pub owner: String,
+    pub quantity: u32,
+}
+
+#[blueprint]
+#[collections(
+    REGISTRY: IndexedStore<String, u32>,
+    ORDERS: SortedStore<u64, Order>,
+)]
+mod indexed_collections {
+    struct IndexedCollections {}
+
+    impl IndexedCollections {
+        pub fn new() -> Global<IndexedCollections> {
+            Self {}
+                .instantiate()
+                .prepare_to_globalize(OwnerRole::None)
+                .globalize()
+        }
+
+        pub fn register(&mut self, name: String, value: u32) {
+            REGISTRY.insert(name, value);
+        }
+
+        pub fn unregister(&mut self, name: String) -> Option<u32> {
+            REGISTRY.remove(&name)
+        }
+
+        pub fn registered_names(&self, limit: u32) -> Vec<String> {
+            REGISTRY.keys(limit)
+        }
+
+        pub fn registered_names_page(
+            &self,
+            cursor: IndexedStoreCursor,
+            limit: u32,
+        ) -> (Vec<String>, Option<IndexedStoreCursor>) {
+            REGISTRY.keys_page(cursor, limit)
+        }
+
+        pub fn drain_registry(&mut self, limit: u32) -> Vec<(String, u32)> {
+            REGISTRY.drain(limit)
+        }
+
+        pub fn place_order(&mut self, price: u16, id: u64, order: Order) {
+            ORDERS.insert(price, id, order);
+        }
+
+        pub fn cancel_order(&mut self, price: u16, id: u64) -> Option<Order> {
+            ORDERS.remove(price, &id)
+        }
+
+        pub fn best_orders(&self, count: u32) -> Vec<(u16, u64, Order)> {
+            ORDERS.first(count)
+        }
+    }
+}
@@ -1121,6 +1121,108 @@ fn static_analyzer_understands_when_non_of_the_input_resources_will_be_returned(
     );
 }
 
+#[test]
+fn resource_movements_summary_describes_withdraws_and_deposit_bounds() {
+    // Arrange
+    let account1 = account_address(1);
+    let account2 = account_address(2);
+    let resource_address = fungible_resource_address(1);
+    let manifest = ManifestBuilder::new_v2()
+        .lock_fee_and_withdraw(account1, 100, XRD, 10)
+        .call_method(component_address(1), "unknown_method", (1,))
+        .assert_worktop_contains(resource_address, 5)
+        .deposit_entire_worktop(account2)
+        .build();
+    let encoder = AddressBech32Encoder::for_simulator();
+
+    // Act
+    let summary = ResourceMovementsSummary::from_manifest(&manifest).unwrap();
+
+    // Assert
+    assert_eq!(
+        summary.account_withdraws.get(&account1),
+        Some(&NetWithdraws::empty().set_fungible(XRD, 10))
+    );
+    assert_eq!(
+        summary.account_deposits.get(&account2),
+        Some(
+            &NetDeposits::empty(UnspecifiedResources::some([ChangeSource::invocation_at(1)]))
+                .set(XRD, ResourceBounds::at_least_amount(10).unwrap())
+                .set(
+                    resource_address,
+                    ResourceBounds::at_least_amount(5).unwrap()
+                )
+        ),
+    );
+    assert_eq!(
+        summary.display(&encoder).to_string(),
+        format!(
+            "Withdraws from {}:\n  - 10 of {}\nDeposits into {}:\n  - at least 10 of {}\n  - at least 5 of {}\n  - possibly other, unspecified resources\n",
+            account1.display(&encoder),
+            XRD.display(&encoder),
+            account2.display(&encoder),
+            XRD.display(&encoder),
+            resource_address.display(&encoder),
+        )
+    );
+}
+
+#[test]
+fn resource_movements_summary_describes_non_fungible_transfers() {
+    // Arrange
+    let account1 = account_address(1);
+    let account2 = account_address(2);
+    let non_fungible_address = non_fungible_resource_address(1);
+    let manifest = ManifestBuilder::new_v2()
+        .lock_fee_and_withdraw_non_fungibles(
+            account1,
+            100,
+            non_fungible_address,
+            [
+                NonFungibleLocalId::integer(1),
+                NonFungibleLocalId::integer(2),
+            ],
+        )
+        .deposit_entire_worktop(account2)
+        .build();
+    let encoder = AddressBech32Encoder::for_simulator();
+
+    // Act
+    let summary = ResourceMovementsSummary::from_manifest(&manifest).unwrap();
+
+    // Assert
+    assert_eq!(
+        summary.display(&encoder).to_string(),
+        format!(
+            "Withdraws from {}:\n  - non-fungibles #1#, #2# of {}\nDeposits into {}:\n  - non-fungibles #1#, #2# of {}\n",
+            account1.display(&encoder),
+            non_fungible_address.display(&encoder),
+            account2.display(&encoder),
+            non_fungible_address.display(&encoder),
+        )
+    );
+}
+
+#[test]
+fn resource_movements_summary_of_a_subintent_without_account_interactions_is_empty() {
+    // Arrange
+    let manifest = ManifestBuilder::new_subintent_v2()
+        .yield_to_parent(())
+        .build();
+
+    // Act
+    let summary = ResourceMovementsSummary::from_manifest(&manifest).unwrap();
+
+    // Assert
+    assert!(summary.is_empty());
+    assert_eq!(
+        summary
+            .display(&AddressBech32Encoder::for_simulator())
+            .to_string(),
+        "No withdraws from or deposits into accounts\n"
+    );
+}
+
 fn account_address(id: u64) -> ComponentAddress {
     unsafe {
         ComponentAddress::new_unchecked(node_id(EntityType::GlobalPreallocatedEd25519Account, id).0)
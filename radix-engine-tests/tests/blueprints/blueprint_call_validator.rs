@@ -0,0 +1,172 @@
+use radix_engine::utils::{
+    validate_call_arguments_to_blueprints, BlueprintCallValidationError,
+    LocatedBlueprintCallValidationError,
+};
+use radix_engine_tests::common::*;
+use scrypto_test::prelude::*;
+
+#[test]
+fn validator_sees_valid_calls_to_published_blueprints_as_valid() {
+    // Arrange
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (package_address, component_address) = publish_and_create_component(&mut ledger);
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(
+            package_address,
+            "AuthListComponent",
+            "create_component",
+            manifest_args!(
+                1u8,
+                Vec::<NonFungibleGlobalId>::new(),
+                RoleAssignmentInit::new()
+            ),
+        )
+        .call_method(component_address, "update_count", manifest_args!(2u8))
+        .call_method(component_address, "get_secret", manifest_args!())
+        .build();
+
+    // Act
+    let validation_result = validate_call_arguments_to_blueprints(&manifest, ledger.substate_db());
+
+    // Assert
+    validation_result
+        .clone()
+        .unwrap_or_else(|_| panic!("Validation failed: {:?}", validation_result))
+}
+
+#[test]
+fn validator_reports_the_path_of_each_mismatching_argument() {
+    // Arrange
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (package_address, component_address) = publish_and_create_component(&mut ledger);
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(
+            package_address,
+            "AuthListComponent",
+            "create_component",
+            manifest_args!(1u8, vec![1u8], RoleAssignmentInit::new()),
+        )
+        .call_method(component_address, "update_count", manifest_args!(2u32))
+        .build();
+
+    // Act
+    let validation_result = validate_call_arguments_to_blueprints(&manifest, ledger.substate_db());
+
+    // Assert
+    let errors = validation_result.unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        &errors[0],
+        LocatedBlueprintCallValidationError {
+            instruction_index: 1,
+            cause: BlueprintCallValidationError::SchemaValidationError { path, .. },
+        } if path.contains("[1|auth]")
+    ));
+    assert!(matches!(
+        &errors[1],
+        LocatedBlueprintCallValidationError {
+            instruction_index: 2,
+            cause: BlueprintCallValidationError::SchemaValidationError { path, .. },
+        } if path.contains("[0|count]")
+    ));
+}
+
+#[test]
+fn validator_invalidates_calls_to_unknown_methods_on_a_published_blueprint() {
+    // Arrange
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (_, component_address) = publish_and_create_component(&mut ledger);
+    let manifest = ManifestBuilder::new()
+        .call_method(component_address, "my_made_up_method", manifest_args!())
+        .build();
+
+    // Act
+    let validation_result = validate_call_arguments_to_blueprints(&manifest, ledger.substate_db());
+
+    // Assert
+    assert!(matches!(
+        validation_result.unwrap_err().as_slice(),
+        [LocatedBlueprintCallValidationError {
+            instruction_index: 0,
+            cause: BlueprintCallValidationError::FunctionNotFound(..),
+        }]
+    ));
+}
+
+#[test]
+fn validator_invalidates_functions_called_as_methods() {
+    // Arrange
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (_, component_address) = publish_and_create_component(&mut ledger);
+    let manifest = ManifestBuilder::new()
+        .call_method(
+            component_address,
+            "create_component",
+            manifest_args!(
+                1u8,
+                Vec::<NonFungibleGlobalId>::new(),
+                RoleAssignmentInit::new()
+            ),
+        )
+        .build();
+
+    // Act
+    let validation_result = validate_call_arguments_to_blueprints(&manifest, ledger.substate_db());
+
+    // Assert
+    assert!(matches!(
+        validation_result.unwrap_err().as_slice(),
+        [LocatedBlueprintCallValidationError {
+            instruction_index: 0,
+            cause: BlueprintCallValidationError::InvalidReceiver(..),
+        }]
+    ));
+}
+
+#[test]
+fn validator_also_validates_calls_to_native_blueprints() {
+    // Arrange
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (_, _, account) = ledger.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .call_method(account, "withdraw", manifest_args!(XRD))
+        .build();
+
+    // Act
+    let validation_result = validate_call_arguments_to_blueprints(&manifest, ledger.substate_db());
+
+    // Assert
+    assert!(matches!(
+        validation_result.unwrap_err().as_slice(),
+        [LocatedBlueprintCallValidationError {
+            instruction_index: 0,
+            cause: BlueprintCallValidationError::SchemaValidationError { .. },
+        }]
+    ));
+}
+
+fn publish_and_create_component(
+    ledger: &mut DefaultLedgerSimulator,
+) -> (PackageAddress, ComponentAddress) {
+    let package_address = ledger.publish_package_simple(PackageLoader::get("component"));
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(
+            package_address,
+            "AuthListComponent",
+            "create_component",
+            manifest_args!(
+                1u8,
+                Vec::<NonFungibleGlobalId>::new(),
+                RoleAssignmentInit::new()
+            ),
+        )
+        .build();
+    let component_address = ledger
+        .execute_manifest(manifest, vec![])
+        .expect_commit_success()
+        .new_component_addresses()[0];
+    (package_address, component_address)
+}
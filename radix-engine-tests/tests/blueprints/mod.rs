@@ -6,6 +6,7 @@ mod account;
 mod account_authorized_depositors;
 mod account_deposit_modes;
 mod account_locker;
+mod blueprint_call_validator;
 mod clock;
 mod native_blueprint_call_validator;
 mod non_fungible;
@@ -0,0 +1,198 @@
+use radix_engine_tests::prelude::*;
+
+#[test]
+fn after_dugong_epoch_range_requirement_is_satisfied_only_within_the_range() {
+    // Arrange
+    let mut ledger = dugong_ledger();
+    let (account, resource_address) = create_resource_with_minter_rule(
+        &mut ledger,
+        rule!(require_epoch_range(Epoch::of(10), Epoch::of(20))),
+    );
+
+    // Act & Assert
+    ledger.set_current_epoch(Epoch::of(9));
+    mint(&mut ledger, account, resource_address).expect_specific_failure(is_auth_error);
+
+    ledger.set_current_epoch(Epoch::of(10));
+    mint(&mut ledger, account, resource_address).expect_commit_success();
+
+    ledger.set_current_epoch(Epoch::of(19));
+    mint(&mut ledger, account, resource_address).expect_commit_success();
+
+    ledger.set_current_epoch(Epoch::of(20));
+    mint(&mut ledger, account, resource_address).expect_specific_failure(is_auth_error);
+}
+
+#[test]
+fn after_dugong_time_requirement_uses_the_proposer_timestamp() {
+    // Arrange
+    let mut ledger = dugong_ledger();
+    let unlock_time = Instant::new(1_700_000_000);
+    let (account, resource_address) =
+        create_resource_with_minter_rule(&mut ledger, rule!(require_time_at_least(unlock_time)));
+
+    // Act & Assert
+    ledger
+        .advance_to_round_at_timestamp(
+            Round::of(1),
+            unlock_time.seconds_since_unix_epoch * 1000 - 1,
+        )
+        .expect_commit_success();
+    mint(&mut ledger, account, resource_address).expect_specific_failure(is_auth_error);
+
+    ledger
+        .advance_to_round_at_timestamp(Round::of(2), unlock_time.seconds_since_unix_epoch * 1000)
+        .expect_commit_success();
+    mint(&mut ledger, account, resource_address).expect_commit_success();
+}
+
+#[test]
+fn after_dugong_time_requirements_can_be_combined_with_proof_requirements() {
+    // Arrange
+    let mut ledger = dugong_ledger();
+    let (public_key, _, account) = ledger.new_allocated_account();
+    let badge = ledger.create_fungible_resource(dec!(1), 0, account);
+    let (_, resource_address) = create_resource_with_minter_rule(
+        &mut ledger,
+        rule!(require(badge) && require_epoch_before(Epoch::of(100))),
+    );
+    ledger.set_current_epoch(Epoch::of(50));
+
+    // Act & Assert
+    mint(&mut ledger, account, resource_address).expect_specific_failure(is_auth_error);
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_amount(account, badge, dec!(1))
+        .mint_fungible(resource_address, dec!(1))
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    ledger
+        .execute_manifest(manifest, [NonFungibleGlobalId::from_public_key(public_key)])
+        .expect_commit_success();
+}
+
+#[test]
+fn before_dugong_resources_with_time_based_requirements_cannot_be_created() {
+    // Arrange
+    let mut ledger = LedgerSimulatorBuilder::new()
+        .with_custom_protocol(|builder| builder.from_bootstrap_to(ProtocolVersion::CuttlefishPart2))
+        .build();
+
+    // Act
+    let receipt = create_resource(&mut ledger, rule!(require_epoch_at_least(Epoch::zero())));
+
+    // Assert
+    receipt.expect_specific_failure(is_unknown_requirement_error);
+}
+
+#[test]
+fn before_dugong_roles_cannot_be_set_to_time_based_requirements() {
+    // Arrange
+    let mut ledger = LedgerSimulatorBuilder::new()
+        .with_custom_protocol(|builder| builder.from_bootstrap_to(ProtocolVersion::CuttlefishPart2))
+        .build();
+    let (public_key, _, account) = ledger.new_allocated_account();
+    let owner_badge = ledger.create_fungible_resource(dec!(1), 0, account);
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_fungible_resource(
+            OwnerRole::Updatable(rule!(require(owner_badge))),
+            true,
+            18,
+            FungibleResourceRoles::default(),
+            metadata!(),
+            None,
+        )
+        .build();
+    let resource_address = ledger
+        .execute_manifest(manifest, [])
+        .expect_commit_success()
+        .new_resource_addresses()[0];
+
+    // Act
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_amount(account, owner_badge, dec!(1))
+        .set_owner_role(
+            resource_address,
+            rule!(require_time_before(Instant::new(0))),
+        )
+        .build();
+    let receipt =
+        ledger.execute_manifest(manifest, [NonFungibleGlobalId::from_public_key(public_key)]);
+
+    // Assert
+    receipt.expect_specific_failure(is_unknown_requirement_error);
+}
+
+fn is_unknown_requirement_error(error: &RuntimeError) -> bool {
+    matches!(
+        error,
+        RuntimeError::ApplicationError(ApplicationError::InputDecodeError(
+            DecodeError::UnknownDiscriminator(..)
+        ))
+    )
+}
+
+fn dugong_ledger() -> DefaultLedgerSimulator {
+    LedgerSimulatorBuilder::new()
+        .with_custom_protocol(|builder| {
+            builder
+                .configure_dugong(|mut dugong_settings| {
+                    dugong_settings.system_logic_updates =
+                        UpdateSetting::Enabled(Default::default());
+                    dugong_settings
+                })
+                .from_bootstrap_to(ProtocolVersion::Dugong)
+        })
+        .build()
+}
+
+fn create_resource_with_minter_rule<E: NativeVmExtension, D: TestDatabase>(
+    ledger: &mut LedgerSimulator<E, D>,
+    minter_rule: AccessRule,
+) -> (ComponentAddress, ResourceAddress) {
+    let (_, _, account) = ledger.new_allocated_account();
+    let resource_address = create_resource(ledger, minter_rule)
+        .expect_commit_success()
+        .new_resource_addresses()[0];
+    (account, resource_address)
+}
+
+fn create_resource<E: NativeVmExtension, D: TestDatabase>(
+    ledger: &mut LedgerSimulator<E, D>,
+    minter_rule: AccessRule,
+) -> TransactionReceipt {
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_fungible_resource(
+            OwnerRole::None,
+            true,
+            18,
+            FungibleResourceRoles {
+                mint_roles: mint_roles! {
+                    minter => minter_rule;
+                    minter_updater => rule!(deny_all);
+                },
+                ..Default::default()
+            },
+            metadata!(),
+            None,
+        )
+        .build();
+    ledger.execute_manifest(manifest, [])
+}
+
+fn mint<E: NativeVmExtension, D: TestDatabase>(
+    ledger: &mut LedgerSimulator<E, D>,
+    account: ComponentAddress,
+    resource_address: ResourceAddress,
+) -> TransactionReceipt {
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .mint_fungible(resource_address, dec!(1))
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    ledger.execute_manifest(manifest, [])
+}
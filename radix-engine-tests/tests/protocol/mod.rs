@@ -5,6 +5,7 @@ mod always_visible_global_nodes;
 mod consensus_manager;
 mod cuttlefish_transaction_changes;
 mod dugong_no_auth_changes;
+mod dugong_time_based_access_rules;
 mod metadata;
 mod protocol_updates;
 mod transaction;
@@ -9,6 +9,7 @@ use radix_engine::blueprints::package::PackageError;
 use radix_engine::blueprints::{account, resource::*};
 use radix_engine::errors::{ApplicationError, RuntimeError, SystemError};
 use radix_engine::object_modules::metadata::SetMetadataEvent;
+use radix_engine::system::system_db_reader::SystemDatabaseReader;
 use radix_engine::system::system_type_checker::TypeCheckError;
 use radix_engine::updates::BabylonSettings;
 use radix_engine_interface::api::ModuleId;
@@ -2741,3 +2742,66 @@ fn event_replacements_occur_as_expected() {
         }
     );
 }
+
+#[test]
+fn event_index_can_be_queried_by_emitter_blueprint_and_version() {
+    // Arrange
+    let mut ledger = LedgerSimulatorBuilder::new().with_event_index().build();
+    let (public_key, _, account) = ledger.new_allocated_account();
+    let (_, _, other_account) = ledger.new_allocated_account();
+    let first_version = ledger.collected_events().len() as u64 + 1;
+
+    // Act
+    for amount in [10, 20] {
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .withdraw_from_account(account, XRD, amount)
+            .try_deposit_entire_worktop_or_abort(other_account, None)
+            .build();
+        ledger
+            .execute_manifest(
+                manifest,
+                vec![NonFungibleGlobalId::from_public_key(public_key)],
+            )
+            .expect_commit_success();
+    }
+
+    // Assert
+    let vault = ledger.get_component_vaults(account, XRD)[0];
+    let withdrawals = ledger.query_events(
+        &EventQuery::new()
+            .method_emitter(vault, ModuleId::Main)
+            .event_name("WithdrawEvent"),
+    );
+    assert_eq!(
+        withdrawals
+            .iter()
+            .map(|event| (
+                event.version,
+                event.decode::<fungible_vault::WithdrawEvent>().unwrap()
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (first_version, fungible_vault::WithdrawEvent::new(10.into())),
+            (
+                first_version + 1,
+                fungible_vault::WithdrawEvent::new(20.into())
+            ),
+        ]
+    );
+
+    let vault_withdrawals = ledger.query_events(
+        &EventQuery::new()
+            .blueprint(BlueprintId::new(
+                &RESOURCE_PACKAGE,
+                FUNGIBLE_VAULT_BLUEPRINT,
+            ))
+            .event_name("WithdrawEvent")
+            .versions(first_version + 1..),
+    );
+    assert_eq!(vault_withdrawals, withdrawals[1..].to_vec());
+
+    let reader = SystemDatabaseReader::new(ledger.substate_db());
+    let display = withdrawals[0].to_annotated_string(&reader, None).unwrap();
+    assert!(display.contains("WithdrawEvent"), "{}", display);
+}
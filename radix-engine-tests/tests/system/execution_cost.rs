@@ -2,7 +2,10 @@
 
 use radix_common::data::scrypto::*;
 use radix_common::prelude::*;
+use radix_engine::system::system_modules::costing::ExecutionCostBreakdownItem;
+use radix_engine::transaction::TransactionReceipt;
 use radix_engine::updates::*;
+use radix_engine_tests::common::*;
 use radix_transactions::manifest::decompile;
 use radix_transactions::prelude::*;
 use sbor::representations::SerializationParameters;
@@ -63,6 +66,78 @@ fn executing_transactions_with_debug_information_outputs_the_detailed_cost_break
     );
 }
 
+#[test]
+fn wasm_profiling_attributes_wasm_execution_cost_to_functions() {
+    // Arrange
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let package_address = ledger.publish_package_simple(PackageLoader::get("logger"));
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(
+            package_address,
+            "Logger",
+            "emit_log",
+            manifest_args!("Hello".to_owned()),
+        )
+        .build();
+
+    // Act
+    let receipt_with_profiling = ledger.execute_manifest_with_execution_config(
+        manifest.clone(),
+        vec![],
+        ExecutionConfig::for_debug_transaction().with_wasm_profiling(true),
+    );
+    let receipt_without_profiling = ledger.execute_manifest_with_execution_config(
+        manifest,
+        vec![],
+        ExecutionConfig::for_debug_transaction(),
+    );
+
+    // Assert
+    receipt_with_profiling.expect_commit_success();
+    receipt_without_profiling.expect_commit_success();
+    assert_eq!(
+        receipt_with_profiling.fee_summary,
+        receipt_without_profiling.fee_summary
+    );
+
+    let run_wasm_code_entries = |receipt: &TransactionReceipt| {
+        receipt
+            .debug_information
+            .as_ref()
+            .unwrap()
+            .detailed_execution_cost_breakdown
+            .iter()
+            .filter_map(|entry| match &entry.item {
+                ExecutionCostBreakdownItem::Execution {
+                    simple_name,
+                    cost_units,
+                    ..
+                } if simple_name.starts_with("RunWasmCode::Logger_emit_log") => {
+                    Some((simple_name.clone(), *cost_units))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let profiled = run_wasm_code_entries(&receipt_with_profiling);
+    let unprofiled = run_wasm_code_entries(&receipt_without_profiling);
+    assert!(!profiled.is_empty());
+    assert!(profiled
+        .iter()
+        .all(|(simple_name, _)| simple_name.starts_with("RunWasmCode::Logger_emit_log::")));
+    assert_eq!(
+        profiled
+            .iter()
+            .map(|(_, cost_units)| *cost_units)
+            .sum::<u32>(),
+        unprofiled
+            .iter()
+            .map(|(_, cost_units)| *cost_units)
+            .sum::<u32>()
+    );
+}
+
 #[test]
 fn assert_flamegraph_of_faucet_lock_fee_method() {
     run_faucet_lock_fee(AlignerExecutionMode::Assert);
@@ -0,0 +1,240 @@
+use radix_common::prelude::*;
+use radix_engine::blueprints::package::PackageError;
+use radix_engine::errors::{ApplicationError, RuntimeError};
+use radix_engine::updates::*;
+use radix_engine_tests::common::*;
+use scrypto_test::prelude::*;
+
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+struct Order {
+    owner: String,
+    quantity: u32,
+}
+
+/// Mirrors `IndexedStoreCursor`, which only has a Scrypto encoding.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, PartialEq, Eq)]
+struct Cursor {
+    offset: u32,
+}
+
+fn ledger_with_actor_index_collections() -> DefaultLedgerSimulator {
+    LedgerSimulatorBuilder::new()
+        .with_custom_protocol(|builder| {
+            builder
+                .configure_dugong(|mut dugong_settings| {
+                    dugong_settings.vm_boot_to_enable_actor_index_collections =
+                        UpdateSetting::Enabled(NoSettings);
+                    dugong_settings
+                })
+                .from_bootstrap_to(ProtocolVersion::Dugong)
+        })
+        .build()
+}
+
+fn instantiate(ledger: &mut DefaultLedgerSimulator) -> ComponentAddress {
+    let package_address = ledger.publish_package_simple(PackageLoader::get("indexed_collections"));
+    let receipt = ledger.execute_manifest(
+        ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_function(
+                package_address,
+                "IndexedCollections",
+                "new",
+                manifest_args!(),
+            )
+            .build(),
+        vec![],
+    );
+    receipt.expect_commit_success().new_component_addresses()[0]
+}
+
+fn call(
+    ledger: &mut DefaultLedgerSimulator,
+    component_address: ComponentAddress,
+    method_name: &str,
+    args: impl ResolvableArguments,
+) -> TransactionReceipt {
+    ledger.execute_manifest(
+        ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_method(component_address, method_name, args)
+            .build(),
+        vec![],
+    )
+}
+
+#[test]
+fn publishing_blueprint_with_collections_before_vm_boot_update_should_fail() {
+    // Arrange
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+
+    // Act
+    let receipt = ledger.try_publish_package(PackageLoader::get("indexed_collections"));
+
+    // Assert
+    receipt.expect_specific_failure(|e| {
+        matches!(
+            e,
+            RuntimeError::ApplicationError(ApplicationError::PackageError(
+                PackageError::InvalidWasm(..)
+            ))
+        )
+    });
+}
+
+#[test]
+fn indexed_store_supports_insert_scan_remove_and_drain() {
+    // Arrange
+    let mut ledger = ledger_with_actor_index_collections();
+    let component_address = instantiate(&mut ledger);
+    for (name, value) in [("a", 1u32), ("b", 2u32), ("c", 3u32)] {
+        call(&mut ledger, component_address, "register", (name, value)).expect_commit_success();
+    }
+
+    // Act
+    let removed: Option<u32> = call(&mut ledger, component_address, "unregister", ("b",))
+        .expect_commit_success()
+        .output(1);
+    let names: Vec<String> = call(&mut ledger, component_address, "registered_names", (10u32,))
+        .expect_commit_success()
+        .output(1);
+    let drained: Vec<(String, u32)> =
+        call(&mut ledger, component_address, "drain_registry", (10u32,))
+            .expect_commit_success()
+            .output(1);
+    let names_after_drain: Vec<String> =
+        call(&mut ledger, component_address, "registered_names", (10u32,))
+            .expect_commit_success()
+            .output(1);
+
+    // Assert
+    assert_eq!(removed, Some(2));
+    assert_eq!(
+        names.into_iter().collect::<BTreeSet<_>>(),
+        btreeset!["a".to_string(), "c".to_string()]
+    );
+    assert_eq!(
+        drained.into_iter().collect::<BTreeMap<_, _>>(),
+        btreemap!["a".to_string() => 1, "c".to_string() => 3]
+    );
+    assert!(names_after_drain.is_empty());
+}
+
+#[test]
+fn indexed_store_keys_can_be_paged_through() {
+    // Arrange
+    let mut ledger = ledger_with_actor_index_collections();
+    let component_address = instantiate(&mut ledger);
+    for (name, value) in [
+        ("a", 1u32),
+        ("b", 2u32),
+        ("c", 3u32),
+        ("d", 4u32),
+        ("e", 5u32),
+    ] {
+        call(&mut ledger, component_address, "register", (name, value)).expect_commit_success();
+    }
+
+    // Act
+    let mut pages = Vec::new();
+    let mut cursor = Some(Cursor { offset: 0 });
+    while let Some(current) = cursor {
+        let (names, next): (Vec<String>, Option<Cursor>) = call(
+            &mut ledger,
+            component_address,
+            "registered_names_page",
+            (current, 2u32),
+        )
+        .expect_commit_success()
+        .output(1);
+        pages.push(names);
+        cursor = next;
+    }
+
+    // Assert
+    assert_eq!(
+        pages.iter().map(|page| page.len()).collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+    assert_eq!(
+        pages.into_iter().flatten().collect::<BTreeSet<_>>(),
+        btreeset![
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "d".to_string(),
+            "e".to_string()
+        ]
+    );
+}
+
+#[test]
+fn sorted_store_returns_entries_ordered_by_sort_prefix() {
+    // Arrange
+    let mut ledger = ledger_with_actor_index_collections();
+    let component_address = instantiate(&mut ledger);
+    let order = |owner: &str, quantity: u32| Order {
+        owner: owner.to_string(),
+        quantity,
+    };
+    for (price, id, order) in [
+        (300u16, 1u64, order("alice", 10)),
+        (100u16, 2u64, order("bob", 20)),
+        (200u16, 3u64, order("carol", 30)),
+        (100u16, 4u64, order("dave", 40)),
+    ] {
+        call(
+            &mut ledger,
+            component_address,
+            "place_order",
+            (price, id, order),
+        )
+        .expect_commit_success();
+    }
+
+    // Act
+    let cancelled: Option<Order> = call(
+        &mut ledger,
+        component_address,
+        "cancel_order",
+        (100u16, 2u64),
+    )
+    .expect_commit_success()
+    .output(1);
+    let best_orders: Vec<(u16, u64, Order)> =
+        call(&mut ledger, component_address, "best_orders", (2u32,))
+            .expect_commit_success()
+            .output(1);
+
+    // Assert
+    assert_eq!(cancelled, Some(order("bob", 20)));
+    assert_eq!(
+        best_orders,
+        vec![(100, 4, order("dave", 40)), (200, 3, order("carol", 30))]
+    );
+}
+
+#[test]
+fn collections_are_part_of_the_blueprint_state_schema() {
+    // Arrange
+    let mut ledger = ledger_with_actor_index_collections();
+    let package_address = ledger.publish_package_simple(PackageLoader::get("indexed_collections"));
+
+    // Act
+    let definition = ledger.get_package_blueprint_definitions(&package_address);
+
+    // Assert
+    let blueprint = definition
+        .get(&BlueprintVersionKey::new_default("IndexedCollections"))
+        .unwrap();
+    let partition_types = (0..blueprint.interface.state.collections.len() as u8)
+        .map(|index| blueprint.interface.state.get_partition(index).unwrap().1)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        partition_types,
+        vec![
+            BlueprintPartitionType::IndexCollection,
+            BlueprintPartitionType::SortedIndexCollection,
+        ]
+    );
+}
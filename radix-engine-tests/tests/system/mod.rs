@@ -34,6 +34,7 @@ mod faucet;
 mod fee;
 mod fee_reserve_states;
 mod identity;
+mod indexed_collections;
 mod instructions;
 mod invalid_stored_values;
 mod kv_store;
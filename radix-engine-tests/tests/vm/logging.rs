@@ -1,6 +1,7 @@
 use radix_engine::{
     errors::{ApplicationError, RuntimeError},
     transaction::TransactionReceipt,
+    vm::wasm::WasmSourceLocation,
 };
 use radix_engine_interface::prelude::*;
 use radix_engine_interface::types::Level;
@@ -86,6 +87,66 @@ fn test_rust_panic() {
     }
 }
 
+#[test]
+fn test_rust_panic_is_located_by_wasm_backtrace() {
+    // Act
+    let receipt = call("rust_panic", "Hey");
+
+    // Assert
+    let wasm_backtrace = receipt
+        .debug_information
+        .as_ref()
+        .and_then(|debug_information| debug_information.wasm_backtrace.as_ref())
+        .expect("Expected a wasm backtrace");
+    assert!(!wasm_backtrace.frames.is_empty());
+    assert!(wasm_backtrace.frames.iter().all(|frame| {
+        frame.export_name == "Logger_rust_panic" && frame.function_index.is_some()
+    }));
+    assert_eq!(
+        wasm_backtrace.panic_location,
+        Some(WasmSourceLocation {
+            file: "logger/src/lib.rs".to_string(),
+            line: 15,
+            column: Some(13),
+        })
+    );
+}
+
+#[test]
+fn test_wasm_backtrace_does_not_affect_fees() {
+    // Arrange
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let package_address = ledger.publish_package_simple(PackageLoader::get("logger"));
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_function(
+            package_address,
+            "Logger",
+            "rust_panic",
+            manifest_args!("Hey".to_owned()),
+        )
+        .build();
+
+    // Act
+    let receipt_with_backtrace = ledger.execute_manifest_with_execution_config(
+        manifest.clone(),
+        vec![],
+        ExecutionConfig::for_test_transaction(),
+    );
+    let receipt_without_backtrace = ledger.execute_manifest_with_execution_config(
+        manifest,
+        vec![],
+        ExecutionConfig::for_test_transaction().with_wasm_backtraces(false),
+    );
+
+    // Assert
+    assert!(receipt_without_backtrace.debug_information.is_none());
+    assert_eq!(
+        receipt_with_backtrace.fee_summary,
+        receipt_without_backtrace.fee_summary
+    );
+}
+
 #[test]
 fn test_scrypto_panic() {
     // Arrange
@@ -668,6 +668,24 @@ fn validate_names(definition: &PackageDefinition) -> Result<(), PackageError> {
     Ok(())
 }
 
+fn verify_function_auth_is_supported(
+    definition: &PackageDefinition,
+    system_version: SystemVersion,
+) -> Result<(), RuntimeError> {
+    for definition_init in definition.blueprints.values() {
+        if let FunctionAuth::AccessRules(functions) = &definition_init.auth_config.function_auth {
+            for access_rule in functions.values() {
+                RoleAssignmentNativePackage::verify_access_rule_is_supported(
+                    access_rule,
+                    system_version,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 const SECURIFY_OWNER_ROLE: &str = "securify_owner";
 
 struct SecurifiedPackage;
@@ -949,6 +967,7 @@ impl PackageNativePackage {
         export_name: &str,
         input: &IndexedScryptoValue,
         version: PackageV1MinorVersion,
+        system_version: SystemVersion,
         api: &mut Y,
         vm_api: &V,
     ) -> Result<IndexedScryptoValue, RuntimeError> {
@@ -962,6 +981,7 @@ impl PackageNativePackage {
                 let input: PackagePublishNativeInput = input.as_typed().map_err(|e| {
                     RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
                 })?;
+                verify_function_auth_is_supported(&input.definition, system_version)?;
 
                 let rtn = Self::publish_native(
                     input.package_address,
@@ -979,6 +999,7 @@ impl PackageNativePackage {
                 let input: PackagePublishWasmInput = input.as_typed().map_err(|e| {
                     RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
                 })?;
+                verify_function_auth_is_supported(&input.definition, system_version)?;
 
                 let rtn = Self::publish_wasm(
                     input.code,
@@ -995,6 +1016,7 @@ impl PackageNativePackage {
                 let input: PackagePublishWasmAdvancedInput = input.as_typed().map_err(|e| {
                     RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
                 })?;
+                verify_function_auth_is_supported(&input.definition, system_version)?;
 
                 let rtn = Self::publish_wasm_advanced(
                     input.package_address,
@@ -158,6 +158,8 @@ impl RoleAssignmentNativePackage {
                 let input: RoleAssignmentSetInput = input.as_typed().map_err(|e| {
                     RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
                 })?;
+                let system_version = api.system().versioned_system_logic;
+                Self::verify_access_rule_is_supported(&input.rule, system_version)?;
                 let role_list = Self::resolve_update_role_method_permission(
                     global_address.as_node_id(),
                     input.module,
@@ -198,6 +200,7 @@ impl RoleAssignmentNativePackage {
     pub fn invoke_export<Y: SystemApi<RuntimeError>>(
         export_name: &str,
         input: &IndexedScryptoValue,
+        system_version: SystemVersion,
         api: &mut Y,
     ) -> Result<IndexedScryptoValue, RuntimeError> {
         match export_name {
@@ -205,6 +208,12 @@ impl RoleAssignmentNativePackage {
                 let input: RoleAssignmentCreateInput = input.as_typed().map_err(|e| {
                     RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
                 })?;
+                Self::verify_access_rule_is_supported(&input.owner_role.rule, system_version)?;
+                for role_init in input.roles.values() {
+                    for access_rule in role_init.data.values().flatten() {
+                        Self::verify_access_rule_is_supported(access_rule, system_version)?;
+                    }
+                }
 
                 let rtn = Self::create(input.owner_role, input.roles, api)?;
                 Ok(IndexedScryptoValue::from_typed(&rtn))
@@ -213,6 +222,7 @@ impl RoleAssignmentNativePackage {
                 let input: RoleAssignmentSetOwnerInput = input.as_typed().map_err(|e| {
                     RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e))
                 })?;
+                Self::verify_access_rule_is_supported(&input.rule, system_version)?;
 
                 let rtn = Self::set_owner_role(input.rule, api)?;
                 Ok(IndexedScryptoValue::from_typed(&rtn))
@@ -289,6 +299,44 @@ impl RoleAssignmentNativePackage {
         access_rule.dfs_traverse_nodes(&mut AccessRuleVerifier(0))
     }
 
+    /// Checks that an access rule only uses requirements which exist in the given system version.
+    ///
+    /// The epoch and time based requirements were introduced with [`SystemVersion::V4`]. Before
+    /// that, they are rejected the same way as any other unknown [`BasicRequirement`] variant,
+    /// so that they can't be stored on a ledger which can't evaluate them.
+    pub fn verify_access_rule_is_supported(
+        access_rule: &AccessRule,
+        system_version: SystemVersion,
+    ) -> Result<(), RuntimeError> {
+        pub struct SupportedRequirementsVerifier;
+        impl AccessRuleVisitor for SupportedRequirementsVerifier {
+            type Error = DecodeError;
+            fn visit(
+                &mut self,
+                node: &CompositeRequirement,
+                _depth: usize,
+            ) -> Result<(), Self::Error> {
+                let discriminator = match node {
+                    CompositeRequirement::BasicRequirement(BasicRequirement::EpochAtLeast(..)) => 5,
+                    CompositeRequirement::BasicRequirement(BasicRequirement::EpochBefore(..)) => 6,
+                    CompositeRequirement::BasicRequirement(BasicRequirement::TimeAtLeast(..)) => 7,
+                    CompositeRequirement::BasicRequirement(BasicRequirement::TimeBefore(..)) => 8,
+                    _ => return Ok(()),
+                };
+
+                Err(DecodeError::UnknownDiscriminator(discriminator))
+            }
+        }
+
+        if system_version.supports_time_based_requirements() {
+            return Ok(());
+        }
+
+        access_rule
+            .dfs_traverse_nodes(&mut SupportedRequirementsVerifier)
+            .map_err(|e| RuntimeError::ApplicationError(ApplicationError::InputDecodeError(e)))
+    }
+
     fn resolve_update_owner_role_method_permission<Y: SystemBasedKernelApi>(
         receiver: &NodeId,
         api: &mut SystemService<Y>,
@@ -34,6 +34,7 @@ use crate::system::system_type_checker::{BlueprintTypeTarget, KVStoreTypeTarget}
 use crate::system::transaction::multithread_intent_processor::MultiThreadIntentProcessor;
 use crate::track::*;
 use crate::transaction::*;
+use crate::vm::wasm::{WasmBacktrace, WasmSymbols};
 use radix_blueprint_schema_init::RefTypes;
 use radix_engine_interface::api::field_api::LockFlags;
 use radix_engine_interface::api::SystemObjectApi;
@@ -258,6 +259,10 @@ impl SystemVersion {
     pub fn assert_access_rule_is_noop_when_auth_module_disabled(self) -> bool {
         self >= SystemVersion::V4
     }
+
+    pub fn supports_time_based_requirements(self) -> bool {
+        self >= SystemVersion::V4
+    }
 }
 
 #[derive(Clone, Default)]
@@ -372,6 +377,8 @@ pub struct SystemSelfInit {
     pub enable_cost_breakdown: bool,
     pub execution_trace: Option<usize>,
     pub enable_debug_information: bool,
+    pub enable_wasm_backtraces: bool,
+    pub enable_wasm_profiling: bool,
 
     // Configuration
     pub system_parameters: SystemParameters,
@@ -389,6 +396,8 @@ impl SystemSelfInit {
             enable_kernel_trace: execution_config.enable_kernel_trace,
             enable_cost_breakdown: execution_config.enable_cost_breakdown,
             enable_debug_information: execution_config.enable_debug_information,
+            enable_wasm_backtraces: execution_config.enable_wasm_backtraces,
+            enable_wasm_profiling: execution_config.enable_wasm_profiling,
             execution_trace: execution_config.execution_trace,
             system_overrides: execution_config.system_overrides,
             system_logic_version,
@@ -405,6 +414,11 @@ pub struct System<V: SystemCallbackObject> {
     pub auth_cache: NonIterMap<CanonicalBlueprintId, AuthConfig>,
     pub modules: SystemModuleMixer,
     pub finalization: SystemFinalization,
+    /// The backtrace of a failed Scrypto invocation, captured if wasm backtraces are enabled.
+    pub wasm_backtrace: Option<WasmBacktrace>,
+    /// The code of the Scrypto exports whose execution cost was attributed to WASM functions, if
+    /// wasm profiling is enabled.
+    pub profiled_wasm_exports: IndexMap<(PackageAddress, String), CodeHash>,
 }
 
 pub trait HasModules {
@@ -445,6 +459,8 @@ impl<V: SystemCallbackObject> System<V> {
             modules,
             finalization,
             versioned_system_logic,
+            wasm_backtrace: None,
+            profiled_wasm_exports: index_map_new(),
         }
     }
 
@@ -1357,6 +1373,29 @@ impl<V: SystemCallbackObject> System<V> {
         )
     }
 
+    fn resolve_wasm_symbols<S: SubstateDatabase>(&mut self, substate_db: &S) {
+        let reader = SystemDatabaseReader::new(substate_db);
+
+        if let Some(wasm_backtrace) = &mut self.wasm_backtrace {
+            wasm_backtrace.symbolize(&reader);
+        }
+
+        let mut symbols = index_map_new::<(PackageAddress, CodeHash), Option<WasmSymbols>>();
+        let profiled_wasm_exports = &self.profiled_wasm_exports;
+        if let Some(costing) = self.modules.costing_mut() {
+            costing.resolve_wasm_function_names(|package_address, export_name, function_index| {
+                let code_hash =
+                    profiled_wasm_exports.get(&(*package_address, export_name.to_string()))?;
+                symbols
+                    .entry((*package_address, *code_hash))
+                    .or_insert_with(|| WasmSymbols::load(&reader, package_address, code_hash))
+                    .as_ref()?
+                    .get_local_function_name(function_index)
+                    .map(|name| name.to_string())
+            });
+        }
+    }
+
     #[cfg_attr(feature = "alloc", allow(unused_variables))]
     fn create_receipt_internal(
         print_execution_summary: bool,
@@ -1379,6 +1418,7 @@ impl<V: SystemCallbackObject> System<V> {
 
         let debug_information = detailed_cost_breakdown.map(|b| TransactionDebugInformation {
             detailed_execution_cost_breakdown: b.detailed_execution_cost_breakdown,
+            wasm_backtrace: None,
         });
 
         let receipt = TransactionReceipt {
@@ -1420,6 +1460,12 @@ impl<V: SystemCallbackObject> System<V> {
             if init_input.execution_trace.is_some() {
                 enabled_modules |= EnabledModules::EXECUTION_TRACE;
             }
+            if init_input.enable_wasm_backtraces {
+                enabled_modules |= EnabledModules::WASM_BACKTRACE;
+            }
+            if init_input.enable_wasm_profiling {
+                enabled_modules |= EnabledModules::WASM_PROFILING;
+            }
 
             enabled_modules
         };
@@ -1827,7 +1873,13 @@ impl<V: SystemCallbackObject> KernelTransactionExecutor for System<V> {
             &mut self.modules.costing_mut_even_if_disabled().fee_reserve,
         );
 
-        match result_type {
+        // The code symbols are resolved from the pre-transaction state, rather than during the
+        // execution, so that debugging does not affect it
+        if self.wasm_backtrace.is_some() || !self.profiled_wasm_exports.is_empty() {
+            self.resolve_wasm_symbols(track.substate_db());
+        }
+
+        let mut receipt = match result_type {
             TransactionResultType::Reject(reason) => {
                 Self::create_rejection_receipt(reason, self.modules)
             }
@@ -1837,7 +1889,16 @@ impl<V: SystemCallbackObject> KernelTransactionExecutor for System<V> {
             TransactionResultType::Commit(outcome) => {
                 Self::create_commit_receipt(outcome, track, self.modules, self.finalization)
             }
+        };
+
+        if let Some(wasm_backtrace) = self.wasm_backtrace {
+            receipt
+                .debug_information
+                .get_or_insert_with(Default::default)
+                .wasm_backtrace = Some(wasm_backtrace);
         }
+
+        receipt
     }
 }
 
@@ -1,3 +1,7 @@
+use crate::blueprints::consensus_manager::{
+    ConsensusManagerField, ConsensusManagerProposerMilliTimestampFieldPayload,
+    ConsensusManagerStateFieldPayload,
+};
 use crate::blueprints::resource::AuthZone;
 use crate::errors::RuntimeError;
 use crate::internal_prelude::*;
@@ -5,6 +9,7 @@ use crate::kernel::kernel_api::KernelSubstateApi;
 use crate::object_modules::role_assignment::{
     RoleAssignmentAccessRuleEntryPayload, RoleAssignmentOwnerFieldPayload,
 };
+use crate::system::system_callback::SystemBasedKernelInternalApi;
 use crate::system::system_modules::auth::{
     AuthorityListAuthorizationResult, AuthorizationCheckResult,
 };
@@ -226,7 +231,7 @@ impl Authorization {
     }
 
     pub fn verify_proof_rule<
-        Y: SystemObjectApi<RuntimeError> + KernelSubstateApi<L>,
+        Y: SystemObjectApi<RuntimeError> + KernelSubstateApi<L> + SystemBasedKernelInternalApi,
         L: Default,
     >(
         auth_zone: &NodeId,
@@ -282,10 +287,98 @@ impl Authorization {
                 }
                 Ok(false)
             }
+            BasicRequirement::EpochAtLeast(epoch) => {
+                Ok(Self::current_epoch_if_supported(api)?.is_some_and(|current| current >= *epoch))
+            }
+            BasicRequirement::EpochBefore(epoch) => {
+                Ok(Self::current_epoch_if_supported(api)?.is_some_and(|current| current < *epoch))
+            }
+            BasicRequirement::TimeAtLeast(instant) => Ok(
+                Self::current_time_if_supported(api)?.is_some_and(|current| current >= *instant)
+            ),
+            BasicRequirement::TimeBefore(instant) => {
+                Ok(Self::current_time_if_supported(api)?.is_some_and(|current| current < *instant))
+            }
         }
     }
 
-    pub fn verify_auth_rule<Y: SystemObjectApi<RuntimeError> + KernelSubstateApi<L>, L: Default>(
+    /// Reads the current epoch from the consensus manager.
+    ///
+    /// Returns `None` if the system logic predates time-based requirements, in which case they
+    /// are never satisfied.
+    fn current_epoch_if_supported<
+        Y: KernelSubstateApi<L> + SystemBasedKernelInternalApi,
+        L: Default,
+    >(
+        api: &mut Y,
+    ) -> Result<Option<Epoch>, RuntimeError> {
+        if !Self::should_evaluate_time_based_requirements(api) {
+            return Ok(None);
+        }
+
+        let handle = api.kernel_open_substate(
+            CONSENSUS_MANAGER.as_node_id(),
+            MAIN_BASE_PARTITION,
+            &ConsensusManagerField::State.into(),
+            LockFlags::read_only(),
+            L::default(),
+        )?;
+        let substate: FieldSubstate<ConsensusManagerStateFieldPayload> =
+            api.kernel_read_substate(handle)?.as_typed().unwrap();
+        api.kernel_close_substate(handle)?;
+
+        Ok(Some(
+            substate
+                .into_payload()
+                .fully_update_and_into_latest_version()
+                .epoch,
+        ))
+    }
+
+    /// Reads the current proposer timestamp, rounded down to seconds, from the consensus manager.
+    ///
+    /// Returns `None` if the system logic predates time-based requirements, in which case they
+    /// are never satisfied.
+    fn current_time_if_supported<
+        Y: KernelSubstateApi<L> + SystemBasedKernelInternalApi,
+        L: Default,
+    >(
+        api: &mut Y,
+    ) -> Result<Option<Instant>, RuntimeError> {
+        if !Self::should_evaluate_time_based_requirements(api) {
+            return Ok(None);
+        }
+
+        let handle = api.kernel_open_substate(
+            CONSENSUS_MANAGER.as_node_id(),
+            MAIN_BASE_PARTITION,
+            &ConsensusManagerField::ProposerMilliTimestamp.into(),
+            LockFlags::read_only(),
+            L::default(),
+        )?;
+        let substate: FieldSubstate<ConsensusManagerProposerMilliTimestampFieldPayload> =
+            api.kernel_read_substate(handle)?.as_typed().unwrap();
+        api.kernel_close_substate(handle)?;
+
+        let epoch_milli = substate
+            .into_payload()
+            .fully_update_and_into_latest_version()
+            .epoch_milli;
+        Ok(Some(Instant::new(epoch_milli.div_euclid(1000))))
+    }
+
+    fn should_evaluate_time_based_requirements<Y: SystemBasedKernelInternalApi>(
+        api: &mut Y,
+    ) -> bool {
+        api.kernel_get_system()
+            .versioned_system_logic
+            .supports_time_based_requirements()
+    }
+
+    pub fn verify_auth_rule<
+        Y: SystemObjectApi<RuntimeError> + KernelSubstateApi<L> + SystemBasedKernelInternalApi,
+        L: Default,
+    >(
         auth_zone: &NodeId,
         requirement_rule: &CompositeRequirement,
         api: &mut Y,
@@ -321,7 +414,7 @@ impl Authorization {
     }
 
     pub fn check_authorization_against_role_key_internal<
-        Y: SystemObjectApi<RuntimeError> + KernelSubstateApi<L>,
+        Y: SystemObjectApi<RuntimeError> + KernelSubstateApi<L> + SystemBasedKernelInternalApi,
         L: Default,
     >(
         auth_zone: &NodeId,
@@ -378,7 +471,7 @@ impl Authorization {
     }
 
     pub fn check_authorization_against_access_rule<
-        Y: SystemObjectApi<RuntimeError> + KernelSubstateApi<L>,
+        Y: SystemObjectApi<RuntimeError> + KernelSubstateApi<L> + SystemBasedKernelInternalApi,
         L: Default,
     >(
         api: &mut Y,
@@ -402,7 +495,7 @@ impl Authorization {
     }
 
     pub fn check_authorization_against_role_list<
-        Y: SystemObjectApi<RuntimeError> + KernelSubstateApi<L>,
+        Y: SystemObjectApi<RuntimeError> + KernelSubstateApi<L> + SystemBasedKernelInternalApi,
         L: Default,
     >(
         auth_zone: &NodeId,
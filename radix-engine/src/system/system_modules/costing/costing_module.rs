@@ -328,6 +328,154 @@ impl CostingModule {
         Ok(())
     }
 
+    /// Replaces the `RunWasmCode` entries of the detailed cost breakdown which were recorded since
+    /// `start_index` outside of nested invocations by one `RunWasmCode` entry per WASM function,
+    /// distributing their cost units proportionally to the WASM execution units consumed by each
+    /// function.
+    ///
+    /// The functions are named `func[<local index>]`, until resolved with
+    /// [`Self::resolve_wasm_function_names`].
+    ///
+    /// The fee reserve and the aggregated cost breakdown are not affected.
+    pub fn attribute_wasm_execution_cost(
+        &mut self,
+        start_index: usize,
+        function_wasm_execution_units: IndexMap<u32, u64>,
+    ) {
+        let Some(detailed_cost_breakdown) = &mut self.detailed_cost_breakdown else {
+            return;
+        };
+        let entries = &mut detailed_cost_breakdown.detailed_execution_cost_breakdown;
+        let total_wasm_execution_units = function_wasm_execution_units
+            .values()
+            .map(|units| *units as u128)
+            .sum::<u128>();
+        if start_index > entries.len() || total_wasm_execution_units == 0 {
+            return;
+        }
+
+        let mut nesting = 0usize;
+        let mut run_wasm_code = None;
+        let mut total_cost_units = 0u128;
+        let mut retained = Vec::new();
+        for entry in entries.drain(start_index..) {
+            match &entry.item {
+                ExecutionCostBreakdownItem::Invocation { .. } => nesting += 1,
+                ExecutionCostBreakdownItem::InvocationComplete => {
+                    nesting = nesting.saturating_sub(1)
+                }
+                ExecutionCostBreakdownItem::Execution {
+                    item:
+                        owned::ExecutionCostingEntryOwned::RunWasmCode {
+                            package_address,
+                            export_name,
+                            ..
+                        },
+                    cost_units,
+                    ..
+                } if nesting == 0 => {
+                    total_cost_units += *cost_units as u128;
+                    run_wasm_code.get_or_insert((
+                        entry.depth,
+                        *package_address,
+                        export_name.clone(),
+                    ));
+                    continue;
+                }
+                _ => {}
+            }
+            retained.push(entry);
+        }
+        entries.extend(retained);
+
+        let Some((depth, package_address, export_name)) = run_wasm_code else {
+            return;
+        };
+
+        // Largest remainder rounding, so that the attributed cost units add up to the charged ones
+        let mut shares: Vec<(u32, u64, u128, u128)> = function_wasm_execution_units
+            .into_iter()
+            .map(|(function_index, wasm_execution_units)| {
+                let product = total_cost_units * wasm_execution_units as u128;
+                (
+                    function_index,
+                    wasm_execution_units,
+                    product / total_wasm_execution_units,
+                    product % total_wasm_execution_units,
+                )
+            })
+            .collect();
+        let attributed = shares.iter().map(|share| share.2).sum::<u128>();
+        let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+        by_remainder.sort_by(|a, b| shares[*b].3.cmp(&shares[*a].3));
+        for i in by_remainder
+            .into_iter()
+            .take((total_cost_units - attributed) as usize)
+        {
+            shares[i].2 += 1;
+        }
+
+        for (function_index, wasm_execution_units, cost_units, _) in shares {
+            if cost_units == 0 {
+                continue;
+            }
+            entries.push(DetailedExecutionCostBreakdownEntry {
+                depth,
+                item: ExecutionCostBreakdownItem::Execution {
+                    simple_name: format!("RunWasmCode::{}::func[{}]", export_name, function_index),
+                    item: owned::ExecutionCostingEntryOwned::RunWasmCode {
+                        package_address,
+                        export_name: export_name.clone(),
+                        wasm_execution_units: wasm_execution_units.try_into().unwrap_or(u32::MAX),
+                    },
+                    cost_units: cost_units as u32,
+                },
+            });
+        }
+    }
+
+    /// Renames the `func[<local index>]` functions of the entries added by
+    /// [`Self::attribute_wasm_execution_cost`], given the name of a local function of an export's
+    /// code (if known).
+    pub fn resolve_wasm_function_names(
+        &mut self,
+        mut get_function_name: impl FnMut(&PackageAddress, &str, u32) -> Option<String>,
+    ) {
+        let Some(detailed_cost_breakdown) = &mut self.detailed_cost_breakdown else {
+            return;
+        };
+        for entry in &mut detailed_cost_breakdown.detailed_execution_cost_breakdown {
+            let ExecutionCostBreakdownItem::Execution {
+                simple_name,
+                item:
+                    owned::ExecutionCostingEntryOwned::RunWasmCode {
+                        package_address,
+                        export_name,
+                        ..
+                    },
+                ..
+            } = &mut entry.item
+            else {
+                continue;
+            };
+            let prefix = format!("RunWasmCode::{}::", export_name);
+            let Some(function_index) = simple_name
+                .strip_prefix(&prefix)
+                .and_then(|function| function.strip_prefix("func["))
+                .and_then(|function| function.strip_suffix(']'))
+                .and_then(|function_index| function_index.parse().ok())
+            else {
+                continue;
+            };
+            if let Some(function_name) =
+                get_function_name(package_address, export_name, function_index)
+            {
+                // `;` separates the frames of the cost breakdown flamegraph
+                *simple_name = format!("{}{}", prefix, function_name.replace(';', ":"));
+            }
+        }
+    }
+
     pub fn lock_fee(
         &mut self,
         vault_id: NodeId,
@@ -36,6 +36,12 @@ bitflags! {
 
         // Execution trace, for preview only
         const EXECUTION_TRACE = 0x01 << 6;
+
+        // Wasm backtraces, for preview and testing only
+        const WASM_BACKTRACE = 0x01 << 7;
+
+        // Wasm profiling, for debugging only
+        const WASM_PROFILING = 0x01 << 8;
     }
 }
 
@@ -154,6 +160,18 @@ impl SystemModuleMixer {
             .contains(EnabledModules::EXECUTION_TRACE)
     }
 
+    #[inline]
+    pub fn is_wasm_backtrace_enabled(&self) -> bool {
+        self.enabled_modules
+            .contains(EnabledModules::WASM_BACKTRACE)
+    }
+
+    #[inline]
+    pub fn is_wasm_profiling_enabled(&self) -> bool {
+        self.enabled_modules
+            .contains(EnabledModules::WASM_PROFILING)
+    }
+
     #[inline]
     pub fn is_auth_enabled(&self) -> bool {
         self.enabled_modules.contains(EnabledModules::AUTH)
@@ -115,6 +115,11 @@ impl<'s, S: SubstateDatabase, M: DatabaseKeyMapper> MappedTrack<'s, S, M> {
         }
     }
 
+    /// Returns the underlying database, i.e. the state before the transaction.
+    pub fn substate_db(&self) -> &'s S {
+        self.substate_db
+    }
+
     // TODO cleanup interface to avoid redundant information
     fn get_substate_from_db<E, F: FnMut(IOAccess) -> Result<(), E>>(
         substate_db: &'s S,
@@ -185,6 +185,10 @@ pub struct ExecutionConfig {
     pub enable_cost_breakdown: bool,
     pub execution_trace: Option<usize>,
     pub enable_debug_information: bool,
+    pub enable_wasm_backtraces: bool,
+    /// Attributes the WASM execution cost of Scrypto invocations to individual WASM functions in
+    /// the detailed execution cost breakdown, which requires `enable_debug_information`.
+    pub enable_wasm_profiling: bool,
 
     pub system_overrides: Option<SystemOverrides>,
 }
@@ -199,6 +203,8 @@ impl ExecutionConfig {
             execution_trace: None,
             system_overrides: None,
             enable_debug_information: false,
+            enable_wasm_backtraces: false,
+            enable_wasm_profiling: false,
         }
     }
 
@@ -270,6 +276,7 @@ impl ExecutionConfig {
     pub fn for_test_transaction() -> Self {
         Self {
             enable_cost_breakdown: true,
+            enable_wasm_backtraces: true,
             ..Self::default_with_network(NetworkDefinition::simulator())
         }
     }
@@ -285,6 +292,7 @@ impl ExecutionConfig {
         Self {
             enable_cost_breakdown: true,
             execution_trace: Some(MAX_EXECUTION_TRACE_DEPTH),
+            enable_wasm_backtraces: true,
             ..Self::default_with_network(network_definition)
         }
     }
@@ -293,6 +301,7 @@ impl ExecutionConfig {
         Self {
             enable_cost_breakdown: true,
             execution_trace: Some(MAX_EXECUTION_TRACE_DEPTH),
+            enable_wasm_backtraces: true,
             system_overrides: Some(SystemOverrides {
                 disable_auth: true,
                 network_definition: Some(network_definition),
@@ -316,6 +325,16 @@ impl ExecutionConfig {
         self.enable_cost_breakdown = enabled;
         self
     }
+
+    pub fn with_wasm_backtraces(mut self, enabled: bool) -> Self {
+        self.enable_wasm_backtraces = enabled;
+        self
+    }
+
+    pub fn with_wasm_profiling(mut self, enabled: bool) -> Self {
+        self.enable_wasm_profiling = enabled;
+        self
+    }
 }
 
 pub fn execute_transaction<V: VmInitialize>(
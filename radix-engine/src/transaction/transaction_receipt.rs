@@ -8,6 +8,7 @@ use crate::system::system_modules::costing::*;
 use crate::system::system_modules::execution_trace::*;
 use crate::system::system_substate_schemas::*;
 use crate::transaction::SystemStructure;
+use crate::vm::wasm::WasmBacktrace;
 use colored::*;
 use radix_engine_interface::blueprints::transaction_processor::InstructionOutput;
 use radix_transactions::prelude::*;
@@ -370,11 +371,19 @@ pub struct ResourcesUsage {
 ///
 /// This is intentionally not SBOR codable since we never want this data to be persisted or
 /// transmitted over the wire.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Default)]
 pub struct TransactionDebugInformation {
     /* Costing Breakdown */
     /// A detailed trace of where execution cost units were consumed.
+    /// Available if `ExecutionConfig::enable_debug_information` is enabled
     pub detailed_execution_cost_breakdown: Vec<DetailedExecutionCostBreakdownEntry>,
+
+    /* Failure */
+    /// The WASM functions of the Scrypto invocations which led to the transaction failure,
+    /// innermost first.
+    /// Available if `ExecutionConfig::enable_wasm_backtraces` is enabled and a Scrypto
+    /// invocation failed
+    pub wasm_backtrace: Option<WasmBacktrace>,
 }
 
 impl TransactionExecutionTrace {
@@ -564,15 +573,26 @@ impl TransactionReceipt {
     pub fn expect_commit(&self, success: bool) -> &CommitResult {
         let c = self.expect_commit_ignore_outcome();
         if c.outcome.is_success() != success {
+            let wasm_backtrace = match self
+                .debug_information
+                .as_ref()
+                .and_then(|debug_information| debug_information.wasm_backtrace.as_ref())
+            {
+                Some(wasm_backtrace) => {
+                    format!("\nWasm backtrace:\n{}", wasm_backtrace.display(NO_NETWORK))
+                }
+                None => String::new(),
+            };
             panic!(
-                "Expected {} but was {}: {:?}",
+                "Expected {} but was {}: {:?}{}",
                 if success { "success" } else { "failure" },
                 if c.outcome.is_success() {
                     "success"
                 } else {
                     "failure"
                 },
-                c.outcome
+                c.outcome,
+                wasm_backtrace
             )
         }
         c
@@ -964,6 +984,29 @@ impl<'a> ContextualDisplay<TransactionReceiptDisplayContext<'a>> for Transaction
             context.display_result(result),
         )?;
 
+        if let Some(wasm_backtrace) = self
+            .debug_information
+            .as_ref()
+            .and_then(|debug_information| debug_information.wasm_backtrace.as_ref())
+        {
+            context.format_top_level_title_with_detail(
+                f,
+                "Wasm Backtrace",
+                wasm_backtrace.frames.len(),
+            )?;
+            if let Some(panic_location) = &wasm_backtrace.panic_location {
+                write!(f, "\nPanicked at {}", panic_location)?;
+            }
+            for (i, frame) in wasm_backtrace.frames.iter().enumerate() {
+                write!(
+                    f,
+                    "\n{} {}",
+                    prefix!(i, wasm_backtrace.frames),
+                    frame.display(address_display_context)
+                )?;
+            }
+        }
+
         context.format_top_level_title_with_detail(
             f,
             "Transaction Cost",
@@ -5,6 +5,7 @@ use crate::{internal_prelude::*, system::system_callback::SystemBoot};
 pub struct DugongSettings {
     pub native_entity_metadata_updates: UpdateSetting<NoSettings>,
     pub system_logic_updates: UpdateSetting<NoSettings>,
+    pub vm_boot_to_enable_actor_index_collections: UpdateSetting<NoSettings>,
 }
 
 impl UpdateSettings for DugongSettings {
@@ -27,6 +28,7 @@ impl UpdateSettings for DugongSettings {
         Self {
             native_entity_metadata_updates: UpdateSetting::Disabled,
             system_logic_updates: UpdateSetting::Disabled,
+            vm_boot_to_enable_actor_index_collections: UpdateSetting::Disabled,
         }
     }
 
@@ -57,6 +59,7 @@ fn generate_main_batch(
     DugongSettings {
         native_entity_metadata_updates,
         system_logic_updates,
+        vm_boot_to_enable_actor_index_collections,
     }: &DugongSettings,
 ) -> ProtocolUpdateBatch {
     let mut batch = ProtocolUpdateBatch::empty();
@@ -75,6 +78,13 @@ fn generate_main_batch(
         );
     }
 
+    if let UpdateSetting::Enabled(NoSettings) = &vm_boot_to_enable_actor_index_collections {
+        batch.mut_add_flash(
+            "dugong-vm-boot-to-enable-actor-index-collections",
+            generate_vm_boot_to_enable_actor_index_collections(),
+        );
+    }
+
     batch
 }
 
@@ -125,3 +135,14 @@ fn generate_system_logic_v4_updates(store: &dyn SubstateDatabase) -> StateUpdate
         SystemBoot::dugong_for_previous_parameters(existing_system_boot.into_parameters()),
     )
 }
+
+fn generate_vm_boot_to_enable_actor_index_collections() -> StateUpdates {
+    StateUpdates::empty().set_substate(
+        TRANSACTION_TRACKER,
+        BOOT_LOADER_PARTITION,
+        BootLoaderField::VmBoot,
+        VmBoot::V1 {
+            scrypto_version: ScryptoVmVersion::actor_index_collections().into(),
+        },
+    )
+}
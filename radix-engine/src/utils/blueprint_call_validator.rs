@@ -0,0 +1,165 @@
+use crate::internal_prelude::*;
+use crate::system::system_db_reader::{SystemDatabaseReader, SystemReaderError};
+use crate::system::system_type_checker::{BlueprintTypeTarget, SchemaValidationMeta};
+use radix_common::data::manifest::*;
+use radix_engine_interface::api::ModuleId;
+use radix_engine_interface::blueprints::package::*;
+use radix_substate_store_interface::interface::SubstateDatabase;
+use radix_transactions::manifest::*;
+
+/// Validates the arguments of every invocation in the manifest against the input schema of the
+/// invoked function or method, as resolved from the given substate database.
+///
+/// Unlike [`validate_call_arguments_to_native_components`], this covers any published blueprint,
+/// but requires the invoked packages and components to exist in the database. Invocations of
+/// addresses which are only allocated by the manifest itself can't be resolved, and are skipped.
+///
+/// All invalid invocations are reported, rather than just the first.
+///
+/// [`validate_call_arguments_to_native_components`]: super::validate_call_arguments_to_native_components
+pub fn validate_call_arguments_to_blueprints<S: SubstateDatabase + ?Sized>(
+    manifest: &impl ReadableManifest,
+    substate_db: &S,
+) -> Result<(), Vec<LocatedBlueprintCallValidationError>> {
+    let reader = SystemDatabaseReader::new(substate_db);
+    let errors: Vec<_> = manifest
+        .iter_instruction_effects()
+        .enumerate()
+        .filter_map(|(instruction_index, effect)| {
+            validate_instruction_call_arguments_to_blueprints(&reader, effect)
+                .err()
+                .map(|cause| LocatedBlueprintCallValidationError {
+                    instruction_index,
+                    cause,
+                })
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn validate_instruction_call_arguments_to_blueprints<S: SubstateDatabase + ?Sized>(
+    reader: &SystemDatabaseReader<S>,
+    instruction_effect: ManifestInstructionEffect,
+) -> Result<(), BlueprintCallValidationError> {
+    // The ref types of the receiver which the invoked function must have, or `None` for functions
+    let (target, function, args, expected_ref_types) = match instruction_effect {
+        ManifestInstructionEffect::Invocation {
+            kind:
+                InvocationKind::Function {
+                    address: ManifestPackageAddress::Static(address),
+                    blueprint,
+                    function,
+                },
+            args,
+        } => {
+            let target = BlueprintTypeTarget {
+                blueprint_info: BlueprintInfo {
+                    blueprint_id: BlueprintId::new(address, blueprint),
+                    blueprint_version: BlueprintVersion::default(),
+                    outer_obj_info: OuterObjectInfo::None,
+                    features: indexset!(),
+                    generic_substitutions: vec![],
+                },
+                meta: SchemaValidationMeta::Blueprint,
+            };
+            (target, function, args, None)
+        }
+        ManifestInstructionEffect::Invocation {
+            kind:
+                InvocationKind::Method {
+                    address: ManifestGlobalAddress::Static(address),
+                    module_id,
+                    method,
+                },
+            args,
+        } => {
+            let target = reader
+                .get_blueprint_type_target(address.as_node_id(), module_id)
+                .map_err(BlueprintCallValidationError::SystemReaderError)?;
+            (target, method, args, Some(RefTypes::NORMAL))
+        }
+        ManifestInstructionEffect::Invocation {
+            kind: InvocationKind::DirectMethod { address, method },
+            args,
+        } => {
+            let target = reader
+                .get_blueprint_type_target(address.as_node_id(), ModuleId::Main)
+                .map_err(BlueprintCallValidationError::SystemReaderError)?;
+            (target, method, args, Some(RefTypes::DIRECT_ACCESS))
+        }
+        _ => return Ok(()),
+    };
+
+    let blueprint_id = &target.blueprint_info.blueprint_id;
+    let definition = reader
+        .get_blueprint_definition(blueprint_id)
+        .map_err(BlueprintCallValidationError::SystemReaderError)?;
+    let function_schema = definition
+        .interface
+        .functions
+        .get(function)
+        .ok_or_else(|| {
+            BlueprintCallValidationError::FunctionNotFound(
+                blueprint_id.clone(),
+                function.to_owned(),
+            )
+        })?;
+    let ref_types = function_schema
+        .receiver
+        .as_ref()
+        .map(|receiver| receiver.ref_types);
+    if ref_types != expected_ref_types {
+        return Err(BlueprintCallValidationError::InvalidReceiver(
+            blueprint_id.clone(),
+            function.to_owned(),
+        ));
+    }
+
+    let payload_schema = reader
+        .get_blueprint_payload_schema(
+            &target,
+            &BlueprintPayloadIdentifier::Function(function.to_owned(), InputOrOutput::Input),
+        )
+        .map_err(BlueprintCallValidationError::SystemReaderError)?;
+    let schema = payload_schema.schema.v1();
+    validate_payload_against_schema::<ManifestCustomExtension, _>(
+        &manifest_encode(args).unwrap(),
+        schema,
+        payload_schema.type_id,
+        &(),
+        MANIFEST_SBOR_V1_MAX_DEPTH,
+    )
+    .map_err(
+        |error| BlueprintCallValidationError::SchemaValidationError {
+            path: error.location.path_to_string(schema),
+            message: error.error.to_string(),
+        },
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocatedBlueprintCallValidationError {
+    pub instruction_index: usize,
+    pub cause: BlueprintCallValidationError,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlueprintCallValidationError {
+    /// The invoked entity, its blueprint or the schema of the function couldn't be read.
+    SystemReaderError(SystemReaderError),
+    FunctionNotFound(BlueprintId, String),
+    /// A function was invoked as a method, a method as a function, or a direct access method as a
+    /// regular method (or vice versa).
+    InvalidReceiver(BlueprintId, String),
+    /// The arguments don't match the input schema. The path locates the mismatching value within
+    /// the arguments tuple, such as `AuthListComponent_update_count_Input.[0|count]`.
+    SchemaValidationError {
+        path: String,
+        message: String,
+    },
+}
@@ -1,3 +1,4 @@
+mod blueprint_call_validator;
 #[cfg(feature = "test_utils")]
 mod costing_formatting;
 #[cfg(feature = "coverage")]
@@ -9,6 +10,7 @@ mod native_blueprint_call_validator;
 mod package_extractor;
 mod panics;
 
+pub use blueprint_call_validator::*;
 #[cfg(feature = "test_utils")]
 pub use costing_formatting::*;
 #[cfg(feature = "coverage")]
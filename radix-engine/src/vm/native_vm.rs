@@ -127,20 +127,28 @@ impl<I: VmInvoke> VmInvoke for NativeVmInstance<I> {
                 )?;
 
                 match code_id {
-                    NativeCodeId::PackageCode1 => PackageNativePackage::invoke_export(
-                        export_name,
-                        input,
-                        PackageV1MinorVersion::Zero,
-                        api,
-                        vm_api,
-                    ),
-                    NativeCodeId::PackageCode2 => PackageNativePackage::invoke_export(
-                        export_name,
-                        input,
-                        PackageV1MinorVersion::One,
-                        api,
-                        vm_api,
-                    ),
+                    NativeCodeId::PackageCode1 => {
+                        let system_version = api.kernel_get_system().versioned_system_logic;
+                        PackageNativePackage::invoke_export(
+                            export_name,
+                            input,
+                            PackageV1MinorVersion::Zero,
+                            system_version,
+                            api,
+                            vm_api,
+                        )
+                    }
+                    NativeCodeId::PackageCode2 => {
+                        let system_version = api.kernel_get_system().versioned_system_logic;
+                        PackageNativePackage::invoke_export(
+                            export_name,
+                            input,
+                            PackageV1MinorVersion::One,
+                            system_version,
+                            api,
+                            vm_api,
+                        )
+                    }
                     NativeCodeId::ResourceCode1 => {
                         ResourceNativePackage::invoke_export(export_name, input, api)
                     }
@@ -207,7 +215,13 @@ impl<I: VmInvoke> VmInvoke for NativeVmInstance<I> {
                         RoyaltyNativePackage::invoke_export(export_name, input, api)
                     }
                     NativeCodeId::RoleAssignmentCode1 => {
-                        RoleAssignmentNativePackage::invoke_export(export_name, input, api)
+                        let system_version = api.kernel_get_system().versioned_system_logic;
+                        RoleAssignmentNativePackage::invoke_export(
+                            export_name,
+                            input,
+                            system_version,
+                            api,
+                        )
                     }
                     NativeCodeId::RoleAssignmentCode2 => {
                         RoleAssignmentBottlenoseExtension::invoke_export(export_name, input, api)
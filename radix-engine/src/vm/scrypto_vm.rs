@@ -7,10 +7,13 @@ use crate::vm::VmApi;
 use radix_engine_interface::api::SystemApi;
 use radix_engine_interface::blueprints::package::CodeHash;
 use radix_engine_profiling_derive::trace_resources;
+use sbor::rust::sync::Arc;
 
 pub struct ScryptoVm<W: WasmEngine> {
     pub wasm_engine: W,
     pub wasm_validator_config: WasmValidatorConfigV1,
+    /// An optional cache of the code instrumented when packages are published.
+    pub instrumented_code_cache: Option<Arc<dyn InstrumentedCodeCache>>,
 }
 
 impl<W: WasmEngine + Default> Default for ScryptoVm<W> {
@@ -18,11 +21,22 @@ impl<W: WasmEngine + Default> Default for ScryptoVm<W> {
         Self {
             wasm_engine: W::default(),
             wasm_validator_config: WasmValidatorConfigV1::new(),
+            instrumented_code_cache: None,
         }
     }
 }
 
 impl<W: WasmEngine> ScryptoVm<W> {
+    pub fn with_instrumented_code_cache(
+        self,
+        instrumented_code_cache: impl InstrumentedCodeCache + 'static,
+    ) -> Self {
+        Self {
+            instrumented_code_cache: Some(Arc::new(instrumented_code_cache)),
+            ..self
+        }
+    }
+
     pub fn create_instance(
         &self,
         package_address: &PackageAddress,
@@ -34,6 +48,23 @@ impl<W: WasmEngine> ScryptoVm<W> {
             package_address: *package_address,
         }
     }
+
+    /// Creates an instance which tracks the WASM execution units consumed by each function of the
+    /// code and the WASM call stack, see [`ScryptoVmInstance::take_execution_profile`] and
+    /// [`ScryptoVmInstance::take_call_stack`].
+    pub fn create_profiling_instance(
+        &self,
+        package_address: &PackageAddress,
+        code_hash: CodeHash,
+        instrumented_code: &[u8],
+    ) -> ScryptoVmInstance<W::WasmInstance> {
+        ScryptoVmInstance {
+            instance: self
+                .wasm_engine
+                .instantiate_with_profiling(code_hash, instrumented_code),
+            package_address: *package_address,
+        }
+    }
 }
 
 pub struct ScryptoVmInstance<I: WasmInstance> {
@@ -41,6 +72,19 @@ pub struct ScryptoVmInstance<I: WasmInstance> {
     package_address: PackageAddress,
 }
 
+impl<I: WasmInstance> ScryptoVmInstance<I> {
+    /// Takes the WASM execution units consumed by each function so far, by local function index.
+    pub fn take_execution_profile(&mut self) -> WasmExecutionProfile {
+        self.instance.take_execution_profile()
+    }
+
+    /// Takes the local indices of the functions which were active when the last invocation
+    /// returned (or trapped), outermost first.
+    pub fn take_call_stack(&mut self) -> Vec<u32> {
+        self.instance.take_call_stack()
+    }
+}
+
 impl<I: WasmInstance> VmInvoke for ScryptoVmInstance<I> {
     #[trace_resources(log=self.package_address.is_native_package(), log=self.package_address.to_hex(), log=export_name)]
     fn invoke<Y: SystemApi<RuntimeError>, V: VmApi>(
@@ -5,7 +5,10 @@ use crate::kernel::kernel_api::{KernelNodeApi, KernelSubstateApi};
 use crate::system::system_callback::*;
 use crate::system::system_callback_api::SystemCallbackObject;
 use crate::system::system_substates::KeyValueEntrySubstate;
-use crate::vm::wasm::{ScryptoV1WasmValidator, WasmEngine};
+use crate::vm::wasm::{
+    InstrumentedCodeCache, ScryptoV1WasmValidator, WasmEngine, WasmExecutionProfile,
+    WasmSourceLocation,
+};
 use crate::vm::{NativeVm, NativeVmExtension, ScryptoVm};
 use radix_engine_interface::api::field_api::LockFlags;
 use radix_engine_interface::api::SystemApi;
@@ -54,6 +57,11 @@ impl VmBoot {
 
 pub trait VmApi {
     fn get_scrypto_version(&self) -> ScryptoVmVersion;
+
+    /// Returns the cache to use when instrumenting the code of published packages, if any.
+    fn get_instrumented_code_cache(&self) -> Option<&dyn InstrumentedCodeCache> {
+        None
+    }
 }
 
 impl VmApi for VmBoot {
@@ -65,6 +73,24 @@ impl VmApi for VmBoot {
     }
 }
 
+/// The [`VmApi`] of a running [`Vm`], i.e. its [`VmBoot`] together with its Scrypto VM's
+/// instrumented code cache.
+#[derive(Clone)]
+pub struct VmBootWithCache<'g> {
+    pub vm_boot: VmBoot,
+    pub instrumented_code_cache: Option<&'g dyn InstrumentedCodeCache>,
+}
+
+impl<'g> VmApi for VmBootWithCache<'g> {
+    fn get_scrypto_version(&self) -> ScryptoVmVersion {
+        self.vm_boot.get_scrypto_version()
+    }
+
+    fn get_instrumented_code_cache(&self) -> Option<&dyn InstrumentedCodeCache> {
+        self.instrumented_code_cache
+    }
+}
+
 /// This trait is intended to encapsulate the data and types required to
 /// initalize the VMs in the engine.
 ///
@@ -201,37 +227,24 @@ impl<'g, W: WasmEngine + 'g, E: NativeVmExtension> SystemCallbackObject for Vm<'
                 .unwrap_or_else(|| panic!("Vm type not found: {:?}", export))
         };
 
-        let vm_api = api.kernel_get_system().callback.vm_boot.clone();
+        let vm_api = {
+            let vm: &Self = &api.kernel_get_system().callback;
+            let scrypto_vm: &'g ScryptoVm<W> = vm.scrypto_vm;
+            VmBootWithCache {
+                vm_boot: vm.vm_boot.clone(),
+                instrumented_code_cache: scrypto_vm.instrumented_code_cache.as_deref(),
+            }
+        };
 
         let output = match vm_type.fully_update_and_into_latest_version().vm_type {
             VmType::Native => {
-                let original_code = {
-                    let handle = api.kernel_open_substate_with_default(
-                        address.as_node_id(),
-                        MAIN_BASE_PARTITION
-                            .at_offset(PACKAGE_ORIGINAL_CODE_PARTITION_OFFSET)
-                            .unwrap(),
-                        &SubstateKey::Map(scrypto_encode(&export.code_hash).unwrap()),
-                        LockFlags::read_only(),
-                        Some(|| {
-                            let kv_entry = KeyValueEntrySubstate::<()>::default();
-                            IndexedScryptoValue::from_typed(&kv_entry)
-                        }),
-                        SystemLockData::default(),
-                    )?;
-                    let original_code = api.kernel_read_substate(handle)?;
-                    let original_code: PackageCodeOriginalCodeEntrySubstate =
-                        original_code.as_typed().unwrap();
-                    api.kernel_close_substate(handle)?;
-                    original_code
-                        .into_value()
-                        .unwrap_or_else(|| panic!("Original code not found: {:?}", export))
-                };
+                let original_code = Self::load_original_code(address, &export, api)?;
 
-                let mut vm_instance = api.kernel_get_system().callback.native_vm.create_instance(
-                    address,
-                    &original_code.fully_update_and_into_latest_version().code,
-                )?;
+                let mut vm_instance = api
+                    .kernel_get_system()
+                    .callback
+                    .native_vm
+                    .create_instance(address, &original_code)?;
                 let output =
                     { vm_instance.invoke(export.export_name.as_str(), input, api, &vm_api)? };
 
@@ -262,23 +275,65 @@ impl<'g, W: WasmEngine + 'g, E: NativeVmExtension> SystemCallbackObject for Vm<'
                         .fully_update_and_into_latest_version()
                 };
 
+                // Profiling is only of use if there is a detailed cost breakdown to attribute to
+                let profiling_start_index =
+                    if api.kernel_get_system().modules.is_wasm_profiling_enabled() {
+                        api.kernel_get_system()
+                            .modules
+                            .costing()
+                            .and_then(|costing| costing.detailed_cost_breakdown.as_ref())
+                            .map(|breakdown| breakdown.detailed_execution_cost_breakdown.len())
+                    } else {
+                        None
+                    };
+
+                let is_wasm_backtrace_enabled =
+                    api.kernel_get_system().modules.is_wasm_backtrace_enabled();
+
                 let mut scrypto_vm_instance = {
-                    api.kernel_get_system().callback.scrypto_vm.create_instance(
-                        address,
-                        export.code_hash,
-                        &instrumented_code.instrumented_code,
-                    )
+                    let scrypto_vm = api.kernel_get_system().callback.scrypto_vm;
+                    if profiling_start_index.is_some() || is_wasm_backtrace_enabled {
+                        scrypto_vm.create_profiling_instance(
+                            address,
+                            export.code_hash,
+                            &instrumented_code.instrumented_code,
+                        )
+                    } else {
+                        scrypto_vm.create_instance(
+                            address,
+                            export.code_hash,
+                            &instrumented_code.instrumented_code,
+                        )
+                    }
                 };
 
                 api.consume_cost_units(ClientCostingEntry::PrepareWasmCode {
                     size: instrumented_code.instrumented_code.len(),
                 })?;
 
-                let output = {
-                    scrypto_vm_instance.invoke(export.export_name.as_str(), input, api, &vm_api)?
-                };
+                let output =
+                    scrypto_vm_instance.invoke(export.export_name.as_str(), input, api, &vm_api);
 
-                output
+                if let Some(start_index) = profiling_start_index {
+                    let execution_profile = scrypto_vm_instance.take_execution_profile();
+                    Self::attribute_wasm_execution_cost(
+                        address,
+                        &export,
+                        start_index,
+                        execution_profile,
+                        vm_api.get_scrypto_version(),
+                        api,
+                    );
+                }
+
+                if let Err(error) = &output {
+                    if is_wasm_backtrace_enabled {
+                        let call_stack = scrypto_vm_instance.take_call_stack();
+                        Self::record_wasm_backtrace(address, &export, call_stack, error, api);
+                    }
+                }
+
+                output?
             }
         };
 
@@ -286,6 +341,96 @@ impl<'g, W: WasmEngine + 'g, E: NativeVmExtension> SystemCallbackObject for Vm<'
     }
 }
 
+impl<'g, W: WasmEngine + 'g, E: NativeVmExtension> Vm<'g, W, E> {
+    fn load_original_code<Y: KernelSubstateApi<SystemLockData>>(
+        address: &PackageAddress,
+        export: &PackageExport,
+        api: &mut Y,
+    ) -> Result<Vec<u8>, RuntimeError> {
+        let handle = api.kernel_open_substate_with_default(
+            address.as_node_id(),
+            MAIN_BASE_PARTITION
+                .at_offset(PACKAGE_ORIGINAL_CODE_PARTITION_OFFSET)
+                .unwrap(),
+            &SubstateKey::Map(scrypto_encode(&export.code_hash).unwrap()),
+            LockFlags::read_only(),
+            Some(|| {
+                let kv_entry = KeyValueEntrySubstate::<()>::default();
+                IndexedScryptoValue::from_typed(&kv_entry)
+            }),
+            SystemLockData::default(),
+        )?;
+        let original_code = api.kernel_read_substate(handle)?;
+        let original_code: PackageCodeOriginalCodeEntrySubstate = original_code.as_typed().unwrap();
+        api.kernel_close_substate(handle)?;
+        Ok(original_code
+            .into_value()
+            .unwrap_or_else(|| panic!("Original code not found: {:?}", export))
+            .fully_update_and_into_latest_version()
+            .code)
+    }
+
+    /// Attributes the WASM execution cost of a Scrypto invocation to the functions of its code,
+    /// in the detailed cost breakdown.
+    ///
+    /// The functions are named by their local index, and only resolved to the names in the
+    /// original code once the execution is finalized, so that profiling does not affect it.
+    fn attribute_wasm_execution_cost<Y: SystemBasedKernelInternalApi<SystemCallback = Self>>(
+        address: &PackageAddress,
+        export: &PackageExport,
+        start_index: usize,
+        execution_profile: WasmExecutionProfile,
+        scrypto_vm_version: ScryptoVmVersion,
+        api: &mut Y,
+    ) {
+        let wasm_execution_units_base = scrypto_vm_version.wasm_execution_units_base();
+
+        let mut function_wasm_execution_units = index_map_new::<u32, u64>();
+        for (local_function_index, metering) in execution_profile {
+            let units = function_wasm_execution_units
+                .entry(local_function_index)
+                .or_default();
+            *units = units.saturating_add(metering.wasm_execution_units(wasm_execution_units_base));
+        }
+
+        let system = api.kernel_get_system();
+        system
+            .profiled_wasm_exports
+            .insert((*address, export.export_name.clone()), export.code_hash);
+        if let Some(costing) = system.modules.costing_mut() {
+            costing.attribute_wasm_execution_cost(start_index, function_wasm_execution_units);
+        }
+    }
+
+    /// Records the call stack of a failed invocation of a Scrypto export in the transaction's
+    /// wasm backtrace.
+    fn record_wasm_backtrace<Y: SystemBasedKernelInternalApi<SystemCallback = Self>>(
+        address: &PackageAddress,
+        export: &PackageExport,
+        call_stack: Vec<u32>,
+        error: &RuntimeError,
+        api: &mut Y,
+    ) {
+        let wasm_backtrace = api
+            .kernel_get_system()
+            .wasm_backtrace
+            .get_or_insert_with(Default::default);
+        // Only the innermost invocation sees the panic, as the panic message is then propagated
+        // through the outer invocations unchanged
+        if wasm_backtrace.frames.is_empty() {
+            if let RuntimeError::ApplicationError(ApplicationError::PanicMessage(message)) = error {
+                wasm_backtrace.panic_location = WasmSourceLocation::from_panic_message(message);
+            }
+        }
+        wasm_backtrace.push_invocation_frames(
+            *address,
+            export.code_hash,
+            &export.export_name,
+            call_stack,
+        );
+    }
+}
+
 pub trait VmInvoke {
     // TODO: Remove KernelNodeAPI + KernelSubstateAPI from api, unify with VmApi
     fn invoke<
@@ -318,14 +463,20 @@ impl VmPackageValidation {
                 let version = vm_api.get_scrypto_version();
 
                 // Validate WASM
-                let instrumented_code = ScryptoV1WasmValidator::new(version)
-                    .validate(code, definition.blueprints.values())
-                    .map_err(|e| {
-                        RuntimeError::ApplicationError(ApplicationError::PackageError(
-                            PackageError::InvalidWasm(e),
-                        ))
-                    })?
-                    .0;
+                let validator = ScryptoV1WasmValidator::new(version);
+                let instrumented_code = match vm_api.get_instrumented_code_cache() {
+                    Some(cache) => {
+                        validator.validate_with_cache(code, definition.blueprints.values(), cache)
+                    }
+                    None => validator
+                        .validate(code, definition.blueprints.values())
+                        .map(|(instrumented_code, _)| instrumented_code),
+                }
+                .map_err(|e| {
+                    RuntimeError::ApplicationError(ApplicationError::PackageError(
+                        PackageError::InvalidWasm(e),
+                    ))
+                })?;
 
                 for BlueprintDefinitionInit {
                     is_transient,
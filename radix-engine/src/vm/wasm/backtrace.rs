@@ -0,0 +1,333 @@
+use crate::blueprints::package::*;
+use crate::internal_prelude::*;
+use crate::system::system_db_reader::SystemDatabaseReader;
+use radix_engine_interface::blueprints::package::CodeHash;
+use wasmparser::{ExternalKind, Name, NameSectionReader, Parser, Payload, TypeRef};
+
+/// A backtrace of the WASM functions which led to a transaction failure, innermost first.
+///
+/// The call stack of a failed Scrypto invocation is tracked by a shadow stack in the code prepared
+/// for profiling (see [`inject_function_profiling`]), and its frames are followed by the frames of
+/// the invocation which called it. The function names are resolved from the original code of the
+/// packages after the execution, see [`Self::symbolize`].
+///
+/// [`inject_function_profiling`]: crate::vm::wasm::inject_function_profiling
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WasmBacktrace {
+    /// The location of the panic, as reported by the Scrypto panic hook.
+    pub panic_location: Option<WasmSourceLocation>,
+    pub frames: Vec<WasmBacktraceFrame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmBacktraceFrame {
+    pub package_address: PackageAddress,
+    pub code_hash: CodeHash,
+    /// The export invoked by the Scrypto invocation this frame belongs to.
+    pub export_name: String,
+    /// The local (i.e. non-imported) index of the function, if the call stack was tracked.
+    pub function_index: Option<u32>,
+    /// The name of the function, from the original code's name section.
+    pub function_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmSourceLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: Option<u32>,
+}
+
+impl WasmSourceLocation {
+    /// Parses the location appended to a panic message by the Scrypto panic hook, i.e. the
+    /// `file:line:column` in `<message> @ file:line:column`.
+    pub fn from_panic_message(message: &str) -> Option<Self> {
+        let (_, location) = message.rsplit_once(" @ ")?;
+        let mut parts = location.rsplitn(3, ':');
+        let column = parts.next()?.parse().ok()?;
+        let line = parts.next()?.parse().ok()?;
+        let file = parts.next()?;
+        Some(Self {
+            file: file.to_string(),
+            line,
+            column: Some(column),
+        })
+    }
+}
+
+impl WasmBacktrace {
+    /// Appends the frames of a failed Scrypto invocation, given its call stack (outermost first).
+    ///
+    /// Without a tracked call stack, a single frame for the invoked export is appended.
+    pub fn push_invocation_frames(
+        &mut self,
+        package_address: PackageAddress,
+        code_hash: CodeHash,
+        export_name: &str,
+        call_stack: Vec<u32>,
+    ) {
+        let frame = |function_index| WasmBacktraceFrame {
+            package_address,
+            code_hash,
+            export_name: export_name.to_string(),
+            function_index,
+            function_name: None,
+        };
+        if call_stack.is_empty() {
+            self.frames.push(frame(None));
+        } else {
+            self.frames
+                .extend(call_stack.into_iter().rev().map(|index| frame(Some(index))));
+        }
+    }
+
+    /// Resolves the function names of the frames, from the original code of their packages.
+    ///
+    /// Code which is not in the database (e.g. published by the failed transaction itself) is
+    /// left unsymbolized.
+    pub fn symbolize<S: SubstateDatabase + ?Sized>(&mut self, reader: &SystemDatabaseReader<S>) {
+        let mut symbols = index_map_new::<(PackageAddress, CodeHash), Option<WasmSymbols>>();
+        for frame in &mut self.frames {
+            let Some(frame_symbols) = symbols
+                .entry((frame.package_address, frame.code_hash))
+                .or_insert_with(|| {
+                    WasmSymbols::load(reader, &frame.package_address, &frame.code_hash)
+                })
+            else {
+                continue;
+            };
+            frame.function_name = match frame.function_index {
+                Some(index) => frame_symbols.get_local_function_name(index),
+                None => frame_symbols
+                    .get_export_function_index(&frame.export_name)
+                    .and_then(|index| frame_symbols.get_function_name(index)),
+            }
+            .map(|name| name.to_string());
+        }
+    }
+}
+
+impl fmt::Display for WasmSourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ContextualDisplay<AddressDisplayContext<'a>> for WasmBacktrace {
+    type Error = fmt::Error;
+
+    fn contextual_format(
+        &self,
+        f: &mut fmt::Formatter,
+        context: &AddressDisplayContext<'a>,
+    ) -> Result<(), Self::Error> {
+        if let Some(panic_location) = &self.panic_location {
+            write!(f, "panicked at {}", panic_location)?;
+        }
+        for (i, frame) in self.frames.iter().enumerate() {
+            if i > 0 || self.panic_location.is_some() {
+                writeln!(f)?;
+            }
+            write!(f, "{:>4}: {}", i, frame.display(*context))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ContextualDisplay<AddressDisplayContext<'a>> for WasmBacktraceFrame {
+    type Error = fmt::Error;
+
+    fn contextual_format(
+        &self,
+        f: &mut fmt::Formatter,
+        context: &AddressDisplayContext<'a>,
+    ) -> Result<(), Self::Error> {
+        write!(
+            f,
+            "{}::{}",
+            self.package_address.display(*context),
+            self.export_name
+        )?;
+        match (&self.function_name, self.function_index) {
+            (Some(function_name), _) if function_name != &self.export_name => {
+                write!(f, " in {}", function_name)?;
+            }
+            (None, Some(function_index)) => {
+                write!(f, " in func[{}]", function_index)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// The symbols of a Scrypto module, resolved from its (original, i.e. not instrumented) code.
+///
+/// Function names are read from the optional `name` custom section; a malformed section is ignored,
+/// as symbolization is best-effort.
+#[derive(Debug, Clone, Default)]
+pub struct WasmSymbols {
+    num_imported_functions: u32,
+    exports: IndexMap<String, u32>,
+    function_names: IndexMap<u32, String>,
+}
+
+impl WasmSymbols {
+    /// Loads the symbols of the original code of the given package, if it is in the database.
+    pub fn load<S: SubstateDatabase + ?Sized>(
+        reader: &SystemDatabaseReader<S>,
+        package_address: &PackageAddress,
+        code_hash: &CodeHash,
+    ) -> Option<Self> {
+        let original_code = reader
+            .fetch_substate::<PackageCodeOriginalCodeEntrySubstate>(
+                package_address.as_node_id(),
+                MAIN_BASE_PARTITION
+                    .at_offset(PACKAGE_ORIGINAL_CODE_PARTITION_OFFSET)
+                    .unwrap(),
+                &SubstateKey::Map(scrypto_encode(code_hash).unwrap()),
+            )?
+            .into_value()?
+            .fully_update_and_into_latest_version()
+            .code;
+        Some(Self::parse(&original_code))
+    }
+
+    pub fn parse(code: &[u8]) -> Self {
+        let mut symbols = Self::default();
+        // A malformed module is symbolized up to the first error
+        let _ = symbols.parse_payloads(code);
+        symbols
+    }
+
+    fn parse_payloads(&mut self, code: &[u8]) -> Result<(), wasmparser::BinaryReaderError> {
+        for payload in Parser::new(0).parse_all(code) {
+            match payload? {
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        if let TypeRef::Func(_) = import?.ty {
+                            self.num_imported_functions += 1;
+                        }
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export?;
+                        if export.kind == ExternalKind::Func {
+                            self.exports.insert(export.name.to_string(), export.index);
+                        }
+                    }
+                }
+                Payload::CustomSection(reader) if reader.name() == "name" => {
+                    for name in NameSectionReader::new(reader.data(), reader.data_offset()) {
+                        if let Name::Function(function_names) = name? {
+                            for naming in function_names {
+                                let naming = naming?;
+                                self.function_names
+                                    .insert(naming.index, naming.name.to_string());
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_export_function_index(&self, export_name: &str) -> Option<u32> {
+        self.exports.get(export_name).copied()
+    }
+
+    pub fn get_function_name(&self, function_index: u32) -> Option<&str> {
+        self.function_names
+            .get(&function_index)
+            .map(|name| name.as_str())
+    }
+
+    /// Returns the name of the given local (i.e. non-imported) function.
+    pub fn get_local_function_name(&self, local_function_index: u32) -> Option<&str> {
+        self.get_function_name(
+            self.num_imported_functions
+                .checked_add(local_function_index)?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_panic_location() {
+        assert_eq!(
+            WasmSourceLocation::from_panic_message("Not enough tokens @ src/lib.rs:25:13"),
+            Some(WasmSourceLocation {
+                file: "src/lib.rs".to_string(),
+                line: 25,
+                column: Some(13),
+            })
+        );
+        assert_eq!(
+            WasmSourceLocation::from_panic_message("Panic @ <unknown>"),
+            None
+        );
+        assert_eq!(WasmSourceLocation::from_panic_message("No location"), None);
+    }
+
+    #[test]
+    fn test_resolve_function_names_using_name_section() {
+        let code = wat::parse_str(
+            r#"
+            (module
+                (import "env" "consume_wasm_execution_units" (func $gas (param i64)))
+                (func $helper)
+                (func $hello_free_token (param i64) (result i64)
+                    (i64.const 0)
+                )
+                (export "Hello_free_token" (func $hello_free_token))
+            )
+            "#,
+        )
+        .unwrap();
+        let symbols = WasmSymbols::parse(&code);
+
+        assert_eq!(
+            symbols.get_export_function_index("Hello_free_token"),
+            Some(2)
+        );
+        assert_eq!(symbols.get_function_name(2), Some("hello_free_token"));
+        assert_eq!(symbols.get_local_function_name(0), Some("helper"));
+        assert_eq!(symbols.get_local_function_name(2), None);
+        assert_eq!(symbols.get_export_function_index("Hello_unknown"), None);
+    }
+
+    #[test]
+    fn test_push_invocation_frames_innermost_first() {
+        let code_hash = CodeHash(Hash([1; Hash::LENGTH]));
+        let mut backtrace = WasmBacktrace::default();
+        backtrace.push_invocation_frames(PACKAGE_PACKAGE, code_hash, "Inner_f", vec![3, 5]);
+        backtrace.push_invocation_frames(PACKAGE_PACKAGE, code_hash, "Outer_f", vec![]);
+
+        let frame = |export_name: &str, function_index| WasmBacktraceFrame {
+            package_address: PACKAGE_PACKAGE,
+            code_hash,
+            export_name: export_name.to_string(),
+            function_index,
+            function_name: None,
+        };
+        assert_eq!(
+            backtrace.frames,
+            vec![
+                frame("Inner_f", Some(5)),
+                frame("Inner_f", Some(3)),
+                frame("Outer_f", None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use crate::internal_prelude::*;
+use crate::vm::wasm::WasmValidatorConfigV1;
+use crate::vm::ScryptoVmVersion;
+use radix_engine_interface::blueprints::package::{BlueprintDefinitionInit, CodeHash};
+
+/// The key of a validated and instrumented WASM code in an [`InstrumentedCodeCache`].
+///
+/// Apart from the original code, the key captures everything which affects the validation and
+/// instrumentation result: the blueprint definitions (i.e. the export constraints), the
+/// instrumenter's config version and the Scrypto VM version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstrumentedCodeCacheKey {
+    pub code_hash: CodeHash,
+    pub blueprints_hash: Hash,
+    pub validator_config_version: u8,
+    pub scrypto_vm_version: ScryptoVmVersion,
+}
+
+impl InstrumentedCodeCacheKey {
+    pub fn new<'a>(
+        code: &[u8],
+        blueprints: impl Iterator<Item = &'a BlueprintDefinitionInit>,
+        validator_config: &WasmValidatorConfigV1,
+        scrypto_vm_version: ScryptoVmVersion,
+    ) -> Self {
+        Self {
+            code_hash: CodeHash::from_hash(hash(code)),
+            blueprints_hash: hash(scrypto_encode(&blueprints.collect::<Vec<_>>()).unwrap()),
+            validator_config_version: validator_config.version(),
+            scrypto_vm_version,
+        }
+    }
+
+    /// Returns a single hash identifying the entire key.
+    pub fn to_hash(&self) -> Hash {
+        let mut bytes = Vec::with_capacity(2 * Hash::LENGTH + 2);
+        bytes.extend_from_slice(self.code_hash.as_slice());
+        bytes.extend_from_slice(self.blueprints_hash.as_slice());
+        bytes.push(self.validator_config_version);
+        bytes.push(self.scrypto_vm_version as u8);
+        hash(bytes)
+    }
+}
+
+/// A cache of validated and instrumented WASM code, which allows to skip the (costly) validation
+/// and instrumentation of the same package code - e.g. across separate `resim` invocations, or
+/// replay restarts.
+///
+/// A cache hit never skips the validation of the original code (see
+/// [`ScryptoV1WasmValidator::validate_with_cache()`](crate::vm::wasm::ScryptoV1WasmValidator::validate_with_cache)),
+/// and the cached code is re-checked to be an instantiable module. Its instrumentation is trusted
+/// though, i.e. the cache's backing storage must not be writable by untrusted parties.
+///
+/// Note: the compiled `WasmiModule`s are not persisted, as wasmi offers no serialization of its
+/// modules - and with lazy translation, compiling the cached code on instantiation is cheap.
+pub trait InstrumentedCodeCache: Send + Sync {
+    fn get(&self, key: &InstrumentedCodeCacheKey) -> Option<Vec<u8>>;
+
+    fn put(&self, key: &InstrumentedCodeCacheKey, instrumented_code: &[u8]);
+}
+
+#[cfg(not(feature = "alloc"))]
+pub use persistent::*;
+
+#[cfg(not(feature = "alloc"))]
+mod persistent {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    /// An [`InstrumentedCodeCache`] backed by a directory, with a file per entry.
+    ///
+    /// The entries are grouped into sub-directories by the instrumenter's config version and the
+    /// Scrypto VM version, so that a cache directory can be shared by different engine versions
+    /// (and a stale group can simply be deleted).
+    ///
+    /// Each entry starts with the hash of its key and the hash of its code, which are verified on
+    /// every read - a corrupted (e.g. partially written) entry is deleted and reported as a miss.
+    /// Failures to access the directory are never fatal, as the code can always be re-instrumented.
+    #[derive(Debug, Clone)]
+    pub struct PersistentInstrumentedCodeCache {
+        directory: PathBuf,
+    }
+
+    impl PersistentInstrumentedCodeCache {
+        pub fn new(directory: impl Into<PathBuf>) -> Self {
+            Self {
+                directory: directory.into(),
+            }
+        }
+
+        pub fn directory(&self) -> &Path {
+            &self.directory
+        }
+
+        pub(super) fn entry_path(&self, key: &InstrumentedCodeCacheKey) -> PathBuf {
+            self.directory
+                .join(format!(
+                    "validator-v{}-scrypto-v{}",
+                    key.validator_config_version,
+                    u64::from(key.scrypto_vm_version)
+                ))
+                .join(format!("{}.wasm", key.to_hash()))
+        }
+
+        fn decode_entry(key: &InstrumentedCodeCacheKey, bytes: &[u8]) -> Option<Vec<u8>> {
+            if bytes.len() < 2 * Hash::LENGTH {
+                return None;
+            }
+            let (key_hash, rest) = bytes.split_at(Hash::LENGTH);
+            let (code_hash, instrumented_code) = rest.split_at(Hash::LENGTH);
+            if key_hash != key.to_hash().as_slice()
+                || code_hash != hash(instrumented_code).as_slice()
+            {
+                return None;
+            }
+            Some(instrumented_code.to_vec())
+        }
+    }
+
+    impl InstrumentedCodeCache for PersistentInstrumentedCodeCache {
+        fn get(&self, key: &InstrumentedCodeCacheKey) -> Option<Vec<u8>> {
+            let path = self.entry_path(key);
+            let bytes = std::fs::read(&path).ok()?;
+            let instrumented_code = Self::decode_entry(key, &bytes);
+            if instrumented_code.is_none() {
+                let _ = std::fs::remove_file(&path);
+            }
+            instrumented_code
+        }
+
+        fn put(&self, key: &InstrumentedCodeCacheKey, instrumented_code: &[u8]) {
+            let path = self.entry_path(key);
+            let Some(parent) = path.parent() else {
+                return;
+            };
+            if std::fs::create_dir_all(parent).is_err() {
+                return;
+            }
+            let mut bytes = Vec::with_capacity(2 * Hash::LENGTH + instrumented_code.len());
+            bytes.extend_from_slice(key.to_hash().as_slice());
+            bytes.extend_from_slice(hash(instrumented_code).as_slice());
+            bytes.extend_from_slice(instrumented_code);
+            // Write to a temporary file first, so that concurrent readers never see a partial entry
+            let Ok(mut temp_file) = tempfile::NamedTempFile::new_in(parent) else {
+                return;
+            };
+            if std::io::Write::write_all(&mut temp_file, &bytes).is_ok() {
+                let _ = temp_file.persist(&path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    fn test_key(code: &[u8]) -> InstrumentedCodeCacheKey {
+        InstrumentedCodeCacheKey::new(
+            code,
+            [BlueprintDefinitionInit::default()].iter(),
+            &WasmValidatorConfigV1::new(),
+            ScryptoVmVersion::latest(),
+        )
+    }
+
+    #[test]
+    fn test_persistent_cache_round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = PersistentInstrumentedCodeCache::new(directory.path());
+        let key = test_key(&[1, 2, 3]);
+
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, &[4, 5, 6]);
+        assert_eq!(cache.get(&key), Some(vec![4, 5, 6]));
+        assert_eq!(
+            PersistentInstrumentedCodeCache::new(directory.path()).get(&key),
+            Some(vec![4, 5, 6])
+        );
+        assert_eq!(cache.get(&test_key(&[1, 2])), None);
+        assert_eq!(
+            cache.get(&InstrumentedCodeCacheKey {
+                scrypto_vm_version: ScryptoVmVersion::babylon_genesis(),
+                ..key
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_persistent_cache_discards_corrupted_entry() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = PersistentInstrumentedCodeCache::new(directory.path());
+        let key = test_key(&[1, 2, 3]);
+        cache.put(&key, &[4, 5, 6]);
+
+        let path = cache.entry_path(&key);
+        let mut bytes = std::fs::read(&path).unwrap();
+        *bytes.last_mut().unwrap() = 7;
+        std::fs::write(&path, bytes).unwrap();
+
+        assert_eq!(cache.get(&key), None);
+        assert!(!path.exists());
+    }
+}
@@ -1,6 +1,9 @@
+mod backtrace;
 mod constants;
 mod errors;
+mod instrumented_code_cache;
 mod prepare;
+mod profiling;
 mod traits;
 mod wasm_validator;
 mod wasm_validator_config;
@@ -8,9 +11,12 @@ mod wasmi;
 mod weights;
 
 pub use self::wasmi::*;
+pub use backtrace::*;
 pub use constants::*;
 pub use errors::*;
+pub use instrumented_code_cache::*;
 pub use prepare::*;
+pub use profiling::*;
 pub use traits::*;
 pub use wasm_validator::*;
 pub use wasm_validator_config::*;
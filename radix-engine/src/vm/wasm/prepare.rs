@@ -20,6 +20,24 @@ pub struct WasmModule {
     module: ModuleInfo,
 }
 
+/// An overview of the imports, definitions and code size of a module, for inspection tools.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmModuleSummary {
+    /// The imports, as `(module, name)`.
+    pub imports: Vec<(String, String)>,
+    pub num_imported_functions: u32,
+    pub num_local_functions: u32,
+    pub num_local_globals: u32,
+    /// The initial and maximum number of pages of each memory.
+    pub memories: Vec<(u64, Option<u64>)>,
+    /// The initial size of each table.
+    pub initial_table_sizes: Vec<u32>,
+    /// The body size in bytes of each local function.
+    pub function_body_sizes: Vec<usize>,
+    /// The exported functions, as `(name, function index)`.
+    pub function_exports: Vec<(String, u32)>,
+}
+
 impl WasmModule {
     pub fn init(code: &[u8]) -> Result<Self, PrepareError> {
         // deserialize
@@ -742,15 +760,11 @@ impl WasmModule {
                     }
                     // Crypto Utils v1 begin
                     CRYPTO_UTILS_BLS12381_V1_VERIFY_FUNCTION_NAME => {
-                        if version < ScryptoVmVersion::crypto_utils_v1() {
-                            return Err(PrepareError::InvalidImport(
-                                InvalidImport::ProtocolVersionMismatch {
-                                    name: entry.name.to_string(),
-                                    current_version: version.into(),
-                                    expected_version: ScryptoVmVersion::crypto_utils_v1().into(),
-                                },
-                            ));
-                        }
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::crypto_utils_v1(),
+                        )?;
 
                         if let TypeRef::Func(type_index) = entry.ty {
                             if Self::function_type_matches(
@@ -774,15 +788,11 @@ impl WasmModule {
                         }
                     }
                     CRYPTO_UTILS_BLS12381_V1_AGGREGATE_VERIFY_FUNCTION_NAME => {
-                        if version < ScryptoVmVersion::crypto_utils_v1() {
-                            return Err(PrepareError::InvalidImport(
-                                InvalidImport::ProtocolVersionMismatch {
-                                    name: entry.name.to_string(),
-                                    current_version: version.into(),
-                                    expected_version: ScryptoVmVersion::crypto_utils_v1().into(),
-                                },
-                            ));
-                        }
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::crypto_utils_v1(),
+                        )?;
 
                         if let TypeRef::Func(type_index) = entry.ty {
                             if Self::function_type_matches(
@@ -799,15 +809,11 @@ impl WasmModule {
                         }
                     }
                     CRYPTO_UTILS_BLS12381_V1_FAST_AGGREGATE_VERIFY_FUNCTION_NAME => {
-                        if version < ScryptoVmVersion::crypto_utils_v1() {
-                            return Err(PrepareError::InvalidImport(
-                                InvalidImport::ProtocolVersionMismatch {
-                                    name: entry.name.to_string(),
-                                    current_version: version.into(),
-                                    expected_version: ScryptoVmVersion::crypto_utils_v1().into(),
-                                },
-                            ));
-                        }
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::crypto_utils_v1(),
+                        )?;
 
                         if let TypeRef::Func(type_index) = entry.ty {
                             if Self::function_type_matches(
@@ -831,15 +837,11 @@ impl WasmModule {
                         }
                     }
                     CRYPTO_UTILS_BLS12381_G2_SIGNATURE_AGGREGATE_FUNCTION_NAME => {
-                        if version < ScryptoVmVersion::crypto_utils_v1() {
-                            return Err(PrepareError::InvalidImport(
-                                InvalidImport::ProtocolVersionMismatch {
-                                    name: entry.name.to_string(),
-                                    current_version: version.into(),
-                                    expected_version: ScryptoVmVersion::crypto_utils_v1().into(),
-                                },
-                            ));
-                        }
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::crypto_utils_v1(),
+                        )?;
 
                         if let TypeRef::Func(type_index) = entry.ty {
                             if Self::function_type_matches(
@@ -856,15 +858,11 @@ impl WasmModule {
                         }
                     }
                     CRYPTO_UTILS_KECCAK256_HASH_FUNCTION_NAME => {
-                        if version < ScryptoVmVersion::crypto_utils_v1() {
-                            return Err(PrepareError::InvalidImport(
-                                InvalidImport::ProtocolVersionMismatch {
-                                    name: entry.name.to_string(),
-                                    current_version: version.into(),
-                                    expected_version: ScryptoVmVersion::crypto_utils_v1().into(),
-                                },
-                            ));
-                        }
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::crypto_utils_v1(),
+                        )?;
 
                         if let TypeRef::Func(type_index) = entry.ty {
                             if Self::function_type_matches(
@@ -883,15 +881,11 @@ impl WasmModule {
                     // Crypto Utils v1 end
                     // Crypto Utils v2 begin
                     CRYPTO_UTILS_BLAKE2B_256_HASH_FUNCTION_NAME => {
-                        if version < ScryptoVmVersion::crypto_utils_v2() {
-                            return Err(PrepareError::InvalidImport(
-                                InvalidImport::ProtocolVersionMismatch {
-                                    name: entry.name.to_string(),
-                                    current_version: version.into(),
-                                    expected_version: ScryptoVmVersion::crypto_utils_v2().into(),
-                                },
-                            ));
-                        }
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::crypto_utils_v2(),
+                        )?;
 
                         if let TypeRef::Func(type_index) = entry.ty {
                             if Self::function_type_matches(
@@ -909,15 +903,11 @@ impl WasmModule {
                     }
                     CRYPTO_UTILS_ED25519_VERIFY_FUNCTION_NAME
                     | CRYPTO_UTILS_SECP256K1_ECDSA_VERIFY_FUNCTION_NAME => {
-                        if version < ScryptoVmVersion::crypto_utils_v2() {
-                            return Err(PrepareError::InvalidImport(
-                                InvalidImport::ProtocolVersionMismatch {
-                                    name: entry.name.to_string(),
-                                    current_version: version.into(),
-                                    expected_version: ScryptoVmVersion::crypto_utils_v2().into(),
-                                },
-                            ));
-                        }
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::crypto_utils_v2(),
+                        )?;
 
                         if let TypeRef::Func(type_index) = entry.ty {
                             if Self::function_type_matches(
@@ -941,15 +931,33 @@ impl WasmModule {
                         }
                     }
                     CRYPTO_UTILS_SECP256K1_ECDSA_VERIFY_AND_KEY_RECOVER_FUNCTION_NAME => {
-                        if version < ScryptoVmVersion::crypto_utils_v2() {
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::crypto_utils_v2(),
+                        )?;
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![ValType::I32, ValType::I32, ValType::I32, ValType::I32],
+                                vec![ValType::I64],
+                            ) {
+                                continue;
+                            }
                             return Err(PrepareError::InvalidImport(
-                                InvalidImport::ProtocolVersionMismatch {
-                                    name: entry.name.to_string(),
-                                    current_version: version.into(),
-                                    expected_version: ScryptoVmVersion::crypto_utils_v2().into(),
-                                },
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
                             ));
                         }
+                    }
+                    CRYPTO_UTILS_SECP256K1_ECDSA_VERIFY_AND_KEY_RECOVER_UNCOMPRESSED_FUNCTION_NAME =>
+                    {
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::crypto_utils_v2(),
+                        )?;
 
                         if let TypeRef::Func(type_index) = entry.ty {
                             if Self::function_type_matches(
@@ -965,17 +973,42 @@ impl WasmModule {
                             ));
                         }
                     }
-                    CRYPTO_UTILS_SECP256K1_ECDSA_VERIFY_AND_KEY_RECOVER_UNCOMPRESSED_FUNCTION_NAME =>
-                    {
-                        if version < ScryptoVmVersion::crypto_utils_v2() {
+                    // Crypto Utils v2 end
+                    // Actor index collections begin
+                    ACTOR_INDEX_INSERT_FUNCTION_NAME => {
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::actor_index_collections(),
+                        )?;
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                ],
+                                vec![],
+                            ) {
+                                continue;
+                            }
                             return Err(PrepareError::InvalidImport(
-                                InvalidImport::ProtocolVersionMismatch {
-                                    name: entry.name.to_string(),
-                                    current_version: version.into(),
-                                    expected_version: ScryptoVmVersion::crypto_utils_v2().into(),
-                                },
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
                             ));
                         }
+                    }
+                    ACTOR_INDEX_REMOVE_FUNCTION_NAME => {
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::actor_index_collections(),
+                        )?;
 
                         if let TypeRef::Func(type_index) = entry.ty {
                             if Self::function_type_matches(
@@ -991,7 +1024,126 @@ impl WasmModule {
                             ));
                         }
                     }
-                    // Crypto Utils v2 end
+                    ACTOR_INDEX_SCAN_KEYS_FUNCTION_NAME => {
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::actor_index_collections(),
+                        )?;
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![ValType::I32, ValType::I32, ValType::I32],
+                                vec![ValType::I64],
+                            ) {
+                                continue;
+                            }
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    ACTOR_INDEX_DRAIN_FUNCTION_NAME => {
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::actor_index_collections(),
+                        )?;
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![ValType::I32, ValType::I32, ValType::I32],
+                                vec![ValType::I64],
+                            ) {
+                                continue;
+                            }
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    ACTOR_SORTED_INDEX_INSERT_FUNCTION_NAME => {
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::actor_index_collections(),
+                        )?;
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                ],
+                                vec![],
+                            ) {
+                                continue;
+                            }
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    ACTOR_SORTED_INDEX_REMOVE_FUNCTION_NAME => {
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::actor_index_collections(),
+                        )?;
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                    ValType::I32,
+                                ],
+                                vec![ValType::I64],
+                            ) {
+                                continue;
+                            }
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    ACTOR_SORTED_INDEX_SCAN_FUNCTION_NAME => {
+                        Self::check_import_version(
+                            entry.name,
+                            version,
+                            ScryptoVmVersion::actor_index_collections(),
+                        )?;
+
+                        if let TypeRef::Func(type_index) = entry.ty {
+                            if Self::function_type_matches(
+                                &self.module,
+                                type_index,
+                                vec![ValType::I32, ValType::I32, ValType::I32],
+                                vec![ValType::I64],
+                            ) {
+                                continue;
+                            }
+                            return Err(PrepareError::InvalidImport(
+                                InvalidImport::InvalidFunctionType(entry.name.to_string()),
+                            ));
+                        }
+                    }
+                    // Actor index collections end
                     _ => {}
                 };
             }
@@ -1307,6 +1459,65 @@ impl WasmModule {
         Ok(self)
     }
 
+    pub fn summary(&self) -> Result<WasmModuleSummary, PrepareError> {
+        let imports = self
+            .module
+            .import_section()
+            .map_err(|err| PrepareError::ModuleInfoError(err.to_string()))?
+            .unwrap_or(vec![]);
+        let num_imported_functions = imports
+            .iter()
+            .filter(|entry| matches!(entry.ty, TypeRef::Func(_)))
+            .count() as u32;
+        let memories = self
+            .module
+            .memory_section()
+            .map_err(|err| PrepareError::ModuleInfoError(err.to_string()))?
+            .unwrap_or(vec![])
+            .iter()
+            .map(|memory| (memory.initial, memory.maximum))
+            .collect();
+        let initial_table_sizes = self
+            .module
+            .table_section()
+            .map_err(|err| PrepareError::ModuleInfoError(err.to_string()))?
+            .unwrap_or(vec![])
+            .iter()
+            .map(|table| table.ty.initial)
+            .collect();
+        let function_body_sizes = self
+            .module
+            .code_section()
+            .map_err(|err| PrepareError::ModuleInfoError(err.to_string()))?
+            .unwrap_or(vec![])
+            .iter()
+            .map(|body| body.range().len())
+            .collect();
+        let function_exports = self
+            .module
+            .export_section()
+            .map_err(|err| PrepareError::ModuleInfoError(err.to_string()))?
+            .unwrap_or(vec![])
+            .iter()
+            .filter(|export| export.kind == ExternalKind::Func)
+            .map(|export| (export.name.to_string(), export.index))
+            .collect();
+
+        Ok(WasmModuleSummary {
+            imports: imports
+                .iter()
+                .map(|entry| (entry.module.to_string(), entry.name.to_string()))
+                .collect(),
+            num_imported_functions,
+            num_local_functions: self.module.num_local_functions(),
+            num_local_globals: self.module.num_local_globals(),
+            memories,
+            initial_table_sizes,
+            function_body_sizes,
+            function_exports,
+        })
+    }
+
     pub fn to_bytes(self) -> Result<(Vec<u8>, Vec<String>), PrepareError> {
         let mut function_exports = vec![];
 
@@ -1337,6 +1548,24 @@ impl WasmModule {
         }
     }
 
+    /// Checks that an imported function is available in the given Scrypto VM version.
+    fn check_import_version(
+        name: &str,
+        version: ScryptoVmVersion,
+        expected_version: ScryptoVmVersion,
+    ) -> Result<(), PrepareError> {
+        if version < expected_version {
+            return Err(PrepareError::InvalidImport(
+                InvalidImport::ProtocolVersionMismatch {
+                    name: name.to_string(),
+                    current_version: version.into(),
+                    expected_version: expected_version.into(),
+                },
+            ));
+        }
+        Ok(())
+    }
+
     fn function_type_matches(
         module: &ModuleInfo,
         type_index: u32,
@@ -1580,6 +1809,19 @@ mod tests {
                     CRYPTO_UTILS_SECP256K1_ECDSA_VERIFY_AND_KEY_RECOVER_UNCOMPRESSED_FUNCTION_NAME,
                 ],
             ),
+            (
+                ScryptoVmVersion::V1_2,
+                ScryptoVmVersion::actor_index_collections(),
+                vec![
+                    ACTOR_INDEX_INSERT_FUNCTION_NAME,
+                    ACTOR_INDEX_REMOVE_FUNCTION_NAME,
+                    ACTOR_INDEX_SCAN_KEYS_FUNCTION_NAME,
+                    ACTOR_INDEX_DRAIN_FUNCTION_NAME,
+                    ACTOR_SORTED_INDEX_INSERT_FUNCTION_NAME,
+                    ACTOR_SORTED_INDEX_REMOVE_FUNCTION_NAME,
+                    ACTOR_SORTED_INDEX_SCAN_FUNCTION_NAME,
+                ],
+            ),
         ] {
             for name in names {
                 assert_invalid_wasm!(
@@ -1842,6 +2084,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_summary() {
+        let code = wat2wasm!(
+            r#"
+            (module
+                (import "env" "buffer_consume" (func $buffer_consume (param i32 i32)))
+                (global $g (mut i32) (i32.const 0))
+                (table 3 funcref)
+                (memory 1 4)
+                (func $f (export "Test_f") (result i64)
+                    (i64.const 1)
+                )
+                (func $g
+                    (call $buffer_consume (i32.const 0) (i32.const 0))
+                )
+                (export "memory" (memory 0))
+            )
+            "#
+        );
+
+        let summary = WasmModule::init(&code).unwrap().summary().unwrap();
+
+        assert_eq!(
+            summary.imports,
+            vec![("env".to_string(), "buffer_consume".to_string())]
+        );
+        assert_eq!(summary.num_imported_functions, 1);
+        assert_eq!(summary.num_local_functions, 2);
+        assert_eq!(summary.num_local_globals, 1);
+        assert_eq!(summary.memories, vec![(1, Some(4))]);
+        assert_eq!(summary.initial_table_sizes, vec![3]);
+        assert_eq!(summary.function_body_sizes.len(), 2);
+        assert_eq!(summary.function_exports, vec![("Test_f".to_string(), 1)]);
+    }
+
     #[cfg(feature = "radix_engine_tests")]
     #[test]
     fn test_contains_sign_ext_ops() {
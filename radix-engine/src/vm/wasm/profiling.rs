@@ -0,0 +1,287 @@
+extern crate radix_wasm_instrument as wasm_instrument;
+
+use crate::internal_prelude::*;
+use crate::vm::wasm::errors::*;
+use wasm_encoder::{CodeSection, ConstExpr, ExportKind, Function, Instruction, SectionId};
+use wasm_instrument::utils::module_info::{copy_locals, ModuleInfo};
+use wasm_instrument::utils::translator::{DefaultTranslator, Translator};
+use wasmparser::{FunctionBody, GlobalType, Operator, ValType};
+
+/// The name of the exported global which holds the local index of the currently executing function,
+/// in code prepared for profiling.
+pub const EXPORT_PROFILER_FUNCTION_GLOBAL: &str = "__profiler_function";
+
+/// The name of the exported global which holds the depth of the WASM call stack (i.e. the number
+/// of active non-imported functions), in code prepared for profiling.
+pub const EXPORT_PROFILER_DEPTH_GLOBAL: &str = "__profiler_depth";
+
+/// The metering of a single function, as recorded by the metering host function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WasmFunctionMetering {
+    /// The sum of the units passed to the metering host function.
+    pub metered_units: u64,
+    /// The number of calls to the metering host function.
+    pub metering_calls: u64,
+}
+
+impl WasmFunctionMetering {
+    /// Returns the WASM execution units consumed by the function, given the units which are
+    /// consumed on every call to the metering host function.
+    pub fn wasm_execution_units(&self, wasm_execution_units_base: u32) -> u64 {
+        self.metered_units
+            .saturating_add(self.metering_calls * wasm_execution_units_base as u64)
+    }
+}
+
+/// The metering of an invocation, by local (i.e. non-imported) function index.
+pub type WasmExecutionProfile = IndexMap<u32, WasmFunctionMetering>;
+
+/// Prepares instrumented code for profiling.
+///
+/// Two mutable `i32` globals are added and exported:
+/// - [`EXPORT_PROFILER_FUNCTION_GLOBAL`], which every function sets to its local index on entry, and
+///   again after each call to a non-imported function returns, so that the metering host function
+///   can attribute the units it is charged to the calling function.
+/// - [`EXPORT_PROFILER_DEPTH_GLOBAL`], which every function increments on entry, and decrements
+///   again after each call to a non-imported function returns, so that the metering host function
+///   can maintain a shadow call stack, e.g. for backtraces.
+pub fn inject_function_profiling(code: &[u8]) -> Result<Vec<u8>, PrepareError> {
+    let mut module = ModuleInfo::new(code).map_err(|_| PrepareError::DeserializationError)?;
+
+    let function_global_index = module.num_globals();
+    let depth_global_index = function_global_index + 1;
+    for initial_value in [-1, 0] {
+        module
+            .add_global(
+                GlobalType {
+                    content_type: ValType::I32,
+                    mutable: true,
+                },
+                &ConstExpr::i32_const(initial_value),
+            )
+            .map_err(|err| PrepareError::ModuleInfoError(err.to_string()))?;
+    }
+    module
+        .add_exports(&[
+            (
+                EXPORT_PROFILER_FUNCTION_GLOBAL.to_string(),
+                ExportKind::Global,
+                function_global_index,
+            ),
+            (
+                EXPORT_PROFILER_DEPTH_GLOBAL.to_string(),
+                ExportKind::Global,
+                depth_global_index,
+            ),
+        ])
+        .map_err(|err| PrepareError::ModuleInfoError(err.to_string()))?;
+
+    let num_imported_functions = module.num_imported_functions();
+    let code_section = match module
+        .code_section()
+        .map_err(|err| PrepareError::ModuleInfoError(err.to_string()))?
+    {
+        Some(bodies) => {
+            let mut code_section = CodeSection::new();
+            for (local_function_index, body) in bodies.into_iter().enumerate() {
+                code_section.function(&instrument_function_body(
+                    body,
+                    local_function_index as u32,
+                    num_imported_functions,
+                    function_global_index,
+                    depth_global_index,
+                )?);
+            }
+            Some(code_section)
+        }
+        None => None,
+    };
+    if let Some(code_section) = code_section {
+        module
+            .replace_section(SectionId::Code.into(), &code_section)
+            .map_err(|err| PrepareError::ModuleInfoError(err.to_string()))?;
+    }
+
+    Ok(module.bytes())
+}
+
+fn instrument_function_body(
+    body: FunctionBody,
+    local_function_index: u32,
+    num_imported_functions: u32,
+    function_global_index: u32,
+    depth_global_index: u32,
+) -> Result<Function, PrepareError> {
+    let set_function = [
+        Instruction::I32Const(local_function_index as i32),
+        Instruction::GlobalSet(function_global_index),
+    ];
+    let add_depth = |delta: i32| {
+        [
+            Instruction::GlobalGet(depth_global_index),
+            Instruction::I32Const(delta),
+            Instruction::I32Add,
+            Instruction::GlobalSet(depth_global_index),
+        ]
+    };
+
+    let mut function = Function::new(
+        copy_locals(&body).map_err(|err| PrepareError::ModuleInfoError(err.to_string()))?,
+    );
+    add_depth(1)
+        .iter()
+        .chain(set_function.iter())
+        .for_each(|instruction| {
+            function.instruction(instruction);
+        });
+    for operator in body
+        .get_operators_reader()
+        .map_err(|_| PrepareError::DeserializationError)?
+    {
+        let operator = operator.map_err(|_| PrepareError::DeserializationError)?;
+        let returns_to_caller = match operator {
+            Operator::Call { function_index } => function_index >= num_imported_functions,
+            Operator::CallIndirect { .. } => true,
+            _ => false,
+        };
+        function.instruction(
+            &DefaultTranslator
+                .translate_op(&operator)
+                .map_err(|err| PrepareError::ModuleInfoError(format!("{:?}", err)))?,
+        );
+        // The callee does not decrement the depth itself, as it may return from many places
+        if returns_to_caller {
+            add_depth(-1)
+                .iter()
+                .chain(set_function.iter())
+                .for_each(|instruction| {
+                    function.instruction(instruction);
+                });
+        }
+    }
+    Ok(function)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmparser::{ExternalKind, Parser, Payload, Validator};
+
+    fn function_operators(code: &[u8]) -> Vec<Vec<String>> {
+        let mut functions = Vec::new();
+        for payload in Parser::new(0).parse_all(code) {
+            if let Payload::CodeSectionEntry(body) = payload.unwrap() {
+                let mut reader = body.get_operators_reader().unwrap();
+                let mut operators = Vec::new();
+                while !reader.eof() {
+                    operators.push(format!("{:?}", reader.read().unwrap()));
+                }
+                functions.push(operators);
+            }
+        }
+        functions
+    }
+
+    #[test]
+    fn test_inject_function_profiling() {
+        let code = wat::parse_str(
+            r#"
+            (module
+                (import "env" "consume_wasm_execution_units" (func $gas (param i64)))
+                (global $g (mut i32) (i32.const 0))
+                (func $a (export "a")
+                    (call $gas (i64.const 1))
+                    (call $b)
+                )
+                (func $b)
+                (memory 1)
+            )
+            "#,
+        )
+        .unwrap();
+
+        let code = inject_function_profiling(&code).unwrap();
+
+        Validator::new().validate_all(&code).unwrap();
+        let mut exports = Vec::new();
+        for payload in Parser::new(0).parse_all(&code) {
+            if let Payload::ExportSection(reader) = payload.unwrap() {
+                for e in reader {
+                    let e = e.unwrap();
+                    if e.kind == ExternalKind::Global {
+                        exports.push((e.name.to_string(), e.index));
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            exports,
+            vec![
+                (EXPORT_PROFILER_FUNCTION_GLOBAL.to_string(), 1),
+                (EXPORT_PROFILER_DEPTH_GLOBAL.to_string(), 2),
+            ]
+        );
+        assert_eq!(
+            function_operators(&code),
+            vec![
+                vec![
+                    "GlobalGet { global_index: 2 }",
+                    "I32Const { value: 1 }",
+                    "I32Add",
+                    "GlobalSet { global_index: 2 }",
+                    "I32Const { value: 0 }",
+                    "GlobalSet { global_index: 1 }",
+                    "I64Const { value: 1 }",
+                    "Call { function_index: 0 }",
+                    "Call { function_index: 2 }",
+                    "GlobalGet { global_index: 2 }",
+                    "I32Const { value: -1 }",
+                    "I32Add",
+                    "GlobalSet { global_index: 2 }",
+                    "I32Const { value: 0 }",
+                    "GlobalSet { global_index: 1 }",
+                    "End",
+                ],
+                vec![
+                    "GlobalGet { global_index: 2 }",
+                    "I32Const { value: 1 }",
+                    "I32Add",
+                    "GlobalSet { global_index: 2 }",
+                    "I32Const { value: 1 }",
+                    "GlobalSet { global_index: 1 }",
+                    "End"
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inject_function_profiling_adds_missing_sections() {
+        let code = wat::parse_str(
+            r#"
+            (module
+                (func $a)
+                (data (i32.const 0) "x")
+                (memory 1)
+            )
+            "#,
+        )
+        .unwrap();
+
+        let code = inject_function_profiling(&code).unwrap();
+
+        Validator::new().validate_all(&code).unwrap();
+        assert_eq!(
+            function_operators(&code),
+            vec![vec![
+                "GlobalGet { global_index: 1 }",
+                "I32Const { value: 1 }",
+                "I32Add",
+                "GlobalSet { global_index: 1 }",
+                "I32Const { value: 0 }",
+                "GlobalSet { global_index: 0 }",
+                "End"
+            ]]
+        );
+    }
+}
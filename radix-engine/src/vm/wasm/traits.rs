@@ -1,6 +1,7 @@
 use crate::errors::InvokeError;
 use crate::internal_prelude::*;
 use crate::vm::wasm::errors::*;
+use crate::vm::wasm::profiling::*;
 use radix_engine_interface::api::actor_api::EventFlags;
 use radix_engine_interface::api::ActorRefHandle;
 use radix_engine_interface::blueprints::package::CodeHash;
@@ -184,6 +185,59 @@ pub trait WasmRuntime {
         event_flags: EventFlags,
     ) -> Result<(), InvokeError<WasmRuntimeError>>;
 
+    fn actor_index_insert(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), InvokeError<WasmRuntimeError>>;
+
+    fn actor_index_remove(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn actor_index_scan_keys(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn actor_index_drain(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn actor_sorted_index_insert(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        sort_prefix: u16,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), InvokeError<WasmRuntimeError>>;
+
+    fn actor_sorted_index_remove(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        sort_prefix: u16,
+        key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
+    fn actor_sorted_index_scan(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        count: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>>;
+
     fn sys_log(
         &mut self,
         level: Vec<u8>,
@@ -278,6 +332,18 @@ pub trait WasmInstance {
         args: Vec<Buffer>,
         runtime: &mut Box<dyn WasmRuntime + 'r>,
     ) -> Result<Vec<u8>, InvokeError<WasmRuntimeError>>;
+
+    /// Takes the WASM execution units consumed by each function so far, if this instance was
+    /// created for profiling.
+    fn take_execution_profile(&mut self) -> WasmExecutionProfile {
+        WasmExecutionProfile::default()
+    }
+
+    /// Takes the local indices of the functions which were active when the last invocation
+    /// returned (or trapped), outermost first, if this instance was created for profiling.
+    fn take_call_stack(&mut self) -> Vec<u32> {
+        Vec::new()
+    }
 }
 
 /// A Scrypto WASM engine validates, instruments and runs Scrypto modules.
@@ -288,4 +354,16 @@ pub trait WasmEngine {
     ///
     /// The code must have been validated and instrumented!
     fn instantiate(&self, code_hash: CodeHash, instrumented_code: &[u8]) -> Self::WasmInstance;
+
+    /// Instantiate a Scrypto module which tracks the WASM execution units consumed by each function,
+    /// and the WASM call stack.
+    ///
+    /// Engines without profiling support instantiate the module as is.
+    fn instantiate_with_profiling(
+        &self,
+        code_hash: CodeHash,
+        instrumented_code: &[u8],
+    ) -> Self::WasmInstance {
+        self.instantiate(code_hash, instrumented_code)
+    }
 }
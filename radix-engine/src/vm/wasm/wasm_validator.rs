@@ -41,6 +41,57 @@ impl ScryptoV1WasmValidator {
         code: &[u8],
         blueprints: I,
     ) -> Result<(Vec<u8>, Vec<String>), PrepareError> {
+        self.enforce_constraints(code, blueprints)?
+            .inject_instruction_metering(&self.instrumenter_config)?
+            .inject_stack_metering(self.instrumenter_config.max_stack_size())?
+            .ensure_instantiatable()?
+            .ensure_compilable()?
+            .to_bytes()
+    }
+
+    /// Validates and instruments the given code, like [`Self::validate()`], but looks up the
+    /// instrumented code in the given cache (and populates it on a miss).
+    ///
+    /// A cache hit only skips the instrumentation: the original code is always validated, and the
+    /// cached code is re-checked to be a valid and instantiable module before it is used.
+    pub fn validate_with_cache<'a, I: Iterator<Item = &'a BlueprintDefinitionInit> + Clone>(
+        &self,
+        code: &[u8],
+        blueprints: I,
+        cache: &dyn InstrumentedCodeCache,
+    ) -> Result<Vec<u8>, PrepareError> {
+        let module = self.enforce_constraints(code, blueprints.clone())?;
+
+        let key = InstrumentedCodeCacheKey::new(
+            code,
+            blueprints,
+            &self.instrumenter_config,
+            self.version,
+        );
+        if let Some(instrumented_code) = cache.get(&key) {
+            let is_valid = WasmModule::init(&instrumented_code)
+                .and_then(|module| module.ensure_instantiatable())
+                .is_ok();
+            if is_valid {
+                return Ok(instrumented_code);
+            }
+        }
+
+        let (instrumented_code, _) = module
+            .inject_instruction_metering(&self.instrumenter_config)?
+            .inject_stack_metering(self.instrumenter_config.max_stack_size())?
+            .ensure_instantiatable()?
+            .ensure_compilable()?
+            .to_bytes()?;
+        cache.put(&key, &instrumented_code);
+        Ok(instrumented_code)
+    }
+
+    fn enforce_constraints<'a, I: Iterator<Item = &'a BlueprintDefinitionInit>>(
+        &self,
+        code: &[u8],
+        blueprints: I,
+    ) -> Result<WasmModule, PrepareError> {
         WasmModule::init(code)?
             .enforce_no_start_function()?
             .enforce_import_constraints(self.version)?
@@ -54,12 +105,7 @@ impl ScryptoV1WasmValidator {
                 self.max_number_of_function_locals,
             )?
             .enforce_global_limit(self.max_number_of_globals)?
-            .enforce_export_constraints(blueprints)?
-            .inject_instruction_metering(&self.instrumenter_config)?
-            .inject_stack_metering(self.instrumenter_config.max_stack_size())?
-            .ensure_instantiatable()?
-            .ensure_compilable()?
-            .to_bytes()
+            .enforce_export_constraints(blueprints)
     }
 }
 
@@ -167,4 +213,74 @@ mod tests {
 "#
         )
     }
+
+    #[test]
+    fn test_validate_with_cache_never_trusts_cache_blindly() {
+        use crate::vm::wasm::{InstrumentedCodeCache, InstrumentedCodeCacheKey};
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct TestCache(Mutex<Vec<(InstrumentedCodeCacheKey, Vec<u8>)>>);
+
+        impl InstrumentedCodeCache for TestCache {
+            fn get(&self, key: &InstrumentedCodeCacheKey) -> Option<Vec<u8>> {
+                let entries = self.0.lock().unwrap();
+                entries
+                    .iter()
+                    .find(|(k, _)| k == key)
+                    .map(|(_, v)| v.clone())
+            }
+
+            fn put(&self, key: &InstrumentedCodeCacheKey, instrumented_code: &[u8]) {
+                let mut entries = self.0.lock().unwrap();
+                entries.retain(|(k, _)| k != key);
+                entries.push((*key, instrumented_code.to_vec()));
+            }
+        }
+
+        let validator = ScryptoV1WasmValidator::new(ScryptoVmVersion::latest());
+        let definition = PackageDefinition::new_single_function_test_definition("Test", "f");
+        let valid_code = wat::parse_str(
+            r#"(module (memory $0 1) (export "memory" (memory $0))
+                (func $Test_f (param $0 i64) (result i64) (i64.const 0))
+                (export "Test_f" (func $Test_f)))"#,
+        )
+        .unwrap();
+        let invalid_code = wat::parse_str(
+            r#"(module (memory $0 1) (export "memory" (memory $0))
+                (func $Test_f (param $0 i64) (result i64) (i64.const 0)))"#,
+        )
+        .unwrap();
+        let (expected_code, _) = validator
+            .validate(&valid_code, definition.blueprints.values())
+            .unwrap();
+
+        // A corrupted entry is replaced with freshly instrumented code
+        let cache = TestCache::default();
+        for code in [&valid_code, &invalid_code] {
+            let key = InstrumentedCodeCacheKey::new(
+                code,
+                definition.blueprints.values(),
+                &validator.instrumenter_config,
+                validator.version,
+            );
+            cache.put(&key, &[0, 1, 2]);
+        }
+        assert_eq!(
+            validator.validate_with_cache(&valid_code, definition.blueprints.values(), &cache),
+            Ok(expected_code.clone())
+        );
+
+        // A (seemingly valid) entry does not make code missing the blueprint export pass
+        let key = InstrumentedCodeCacheKey::new(
+            &invalid_code,
+            definition.blueprints.values(),
+            &validator.instrumenter_config,
+            validator.version,
+        );
+        cache.put(&key, &expected_code);
+        assert!(validator
+            .validate_with_cache(&invalid_code, definition.blueprints.values(), &cache)
+            .is_err());
+    }
 }
@@ -4,6 +4,7 @@ use crate::internal_prelude::*;
 use crate::utils::save_coverage_data;
 use crate::vm::wasm::constants::*;
 use crate::vm::wasm::errors::*;
+use crate::vm::wasm::profiling::*;
 use crate::vm::wasm::traits::*;
 use crate::vm::wasm::WasmEngine;
 use radix_engine_interface::api::actor_api::EventFlags;
@@ -13,6 +14,7 @@ use sbor::rust::mem::MaybeUninit;
 use sbor::rust::sync::Arc;
 use wasmi::core::HostError;
 use wasmi::errors::InstantiationError;
+use wasmi::Global;
 use wasmi::*;
 
 type HostState = WasmiInstanceEnv;
@@ -37,12 +39,21 @@ pub struct WasmiInstance {
 /// This is to construct a `Store<WasmiInstanceEnv>`
 pub struct WasmiInstanceEnv {
     runtime_ptr: MaybeUninit<*mut Box<dyn WasmRuntime>>,
+    profiler_global: Option<Global>,
+    profiler_depth_global: Option<Global>,
+    execution_profile: WasmExecutionProfile,
+    /// The local indices of the active functions, outermost first, as of the last metering.
+    call_stack: Vec<Option<u32>>,
 }
 
 impl WasmiInstanceEnv {
     pub fn new() -> Self {
         Self {
             runtime_ptr: MaybeUninit::uninit(),
+            profiler_global: None,
+            profiler_depth_global: None,
+            execution_profile: WasmExecutionProfile::default(),
+            call_stack: Vec::new(),
         }
     }
 }
@@ -566,11 +577,52 @@ fn get_blueprint_name(caller: Caller<'_, HostState>) -> Result<u64, InvokeError<
     runtime.actor_get_blueprint_name().map(|buffer| buffer.0)
 }
 
+/// Records the given function as the active one at the given depth of the call stack.
+///
+/// Functions which were not metered since their entry (e.g. the thunks added by stack metering)
+/// are unknown.
+fn update_call_stack(call_stack: &mut Vec<Option<u32>>, depth: i32, function_index: u32) {
+    let Ok(depth) = usize::try_from(depth) else {
+        return;
+    };
+    if depth == 0 {
+        return;
+    }
+    call_stack.resize(depth - 1, None);
+    call_stack.push(Some(function_index));
+}
+
 #[inline]
 fn consume_wasm_execution_units(
-    caller: Caller<'_, HostState>,
+    mut caller: Caller<'_, HostState>,
     n: u64,
 ) -> Result<(), InvokeError<WasmRuntimeError>> {
+    if let Some(profiler_global) = caller.data().profiler_global {
+        if let Val::I32(function_index) = profiler_global.get(caller.as_context()) {
+            if function_index >= 0 {
+                let metering = caller
+                    .data_mut()
+                    .execution_profile
+                    .entry(function_index as u32)
+                    .or_default();
+                metering.metered_units += n;
+                metering.metering_calls += 1;
+
+                let depth = caller
+                    .data()
+                    .profiler_depth_global
+                    .map(|global| global.get(caller.as_context()));
+                if let Some(Val::I32(depth)) = depth {
+                    update_call_stack(
+                        &mut caller.data_mut().call_stack,
+                        depth,
+                        function_index as u32,
+                    );
+                }
+            }
+        }
+    }
+
     let runtime: &mut Box<dyn WasmRuntime> =
         unsafe { &mut *caller.data().runtime_ptr.assume_init() };
 
@@ -609,6 +661,129 @@ fn emit_event(
     runtime.actor_emit_event(event_name, event_data, event_flags)
 }
 
+fn actor_index_insert(
+    mut caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    key_ptr: u32,
+    key_len: u32,
+    value_ptr: u32,
+    value_len: u32,
+) -> Result<(), InvokeError<WasmRuntimeError>> {
+    let runtime = grab_runtime!(caller);
+    let memory = grab_memory!(caller);
+
+    let key = read_memory(caller.as_context_mut(), memory, key_ptr, key_len)?;
+    let value = read_memory(caller.as_context_mut(), memory, value_ptr, value_len)?;
+
+    runtime.actor_index_insert(object_handle, collection_index as u8, key, value)
+}
+
+fn actor_index_remove(
+    mut caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    key_ptr: u32,
+    key_len: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let runtime = grab_runtime!(caller);
+    let memory = grab_memory!(caller);
+
+    let key = read_memory(caller.as_context_mut(), memory, key_ptr, key_len)?;
+
+    runtime
+        .actor_index_remove(object_handle, collection_index as u8, key)
+        .map(|buffer| buffer.0)
+}
+
+fn actor_index_scan_keys(
+    caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    limit: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let runtime = grab_runtime!(caller);
+
+    runtime
+        .actor_index_scan_keys(object_handle, collection_index as u8, limit)
+        .map(|buffer| buffer.0)
+}
+
+fn actor_index_drain(
+    caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    limit: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let runtime = grab_runtime!(caller);
+
+    runtime
+        .actor_index_drain(object_handle, collection_index as u8, limit)
+        .map(|buffer| buffer.0)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn actor_sorted_index_insert(
+    mut caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    sort_prefix: u32,
+    key_ptr: u32,
+    key_len: u32,
+    value_ptr: u32,
+    value_len: u32,
+) -> Result<(), InvokeError<WasmRuntimeError>> {
+    let runtime = grab_runtime!(caller);
+    let memory = grab_memory!(caller);
+
+    let key = read_memory(caller.as_context_mut(), memory, key_ptr, key_len)?;
+    let value = read_memory(caller.as_context_mut(), memory, value_ptr, value_len)?;
+
+    runtime.actor_sorted_index_insert(
+        object_handle,
+        collection_index as u8,
+        sort_prefix as u16,
+        key,
+        value,
+    )
+}
+
+fn actor_sorted_index_remove(
+    mut caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    sort_prefix: u32,
+    key_ptr: u32,
+    key_len: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let runtime = grab_runtime!(caller);
+    let memory = grab_memory!(caller);
+
+    let key = read_memory(caller.as_context_mut(), memory, key_ptr, key_len)?;
+
+    runtime
+        .actor_sorted_index_remove(
+            object_handle,
+            collection_index as u8,
+            sort_prefix as u16,
+            key,
+        )
+        .map(|buffer| buffer.0)
+}
+
+fn actor_sorted_index_scan(
+    caller: Caller<'_, HostState>,
+    object_handle: u32,
+    collection_index: u32,
+    count: u32,
+) -> Result<u64, InvokeError<WasmRuntimeError>> {
+    let runtime = grab_runtime!(caller);
+
+    runtime
+        .actor_sorted_index_scan(object_handle, collection_index as u8, count)
+        .map(|buffer| buffer.0)
+}
+
 fn get_transaction_hash(
     caller: Caller<'_, HostState>,
 ) -> Result<u64, InvokeError<WasmRuntimeError>> {
@@ -1423,6 +1598,124 @@ impl WasmiModule {
             },
         );
 
+        let host_actor_index_insert = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             key_ptr: u32,
+             key_len: u32,
+             value_ptr: u32,
+             value_len: u32|
+             -> Result<(), Error> {
+                actor_index_insert(
+                    caller,
+                    object_handle,
+                    collection_index,
+                    key_ptr,
+                    key_len,
+                    value_ptr,
+                    value_len,
+                )
+                .map_err(Error::host)
+            },
+        );
+
+        let host_actor_index_remove = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             key_ptr: u32,
+             key_len: u32|
+             -> Result<u64, Error> {
+                actor_index_remove(caller, object_handle, collection_index, key_ptr, key_len)
+                    .map_err(Error::host)
+            },
+        );
+
+        let host_actor_index_scan_keys = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             limit: u32|
+             -> Result<u64, Error> {
+                actor_index_scan_keys(caller, object_handle, collection_index, limit)
+                    .map_err(Error::host)
+            },
+        );
+
+        let host_actor_index_drain = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             limit: u32|
+             -> Result<u64, Error> {
+                actor_index_drain(caller, object_handle, collection_index, limit)
+                    .map_err(Error::host)
+            },
+        );
+
+        let host_actor_sorted_index_insert = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             sort_prefix: u32,
+             key_ptr: u32,
+             key_len: u32,
+             value_ptr: u32,
+             value_len: u32|
+             -> Result<(), Error> {
+                actor_sorted_index_insert(
+                    caller,
+                    object_handle,
+                    collection_index,
+                    sort_prefix,
+                    key_ptr,
+                    key_len,
+                    value_ptr,
+                    value_len,
+                )
+                .map_err(Error::host)
+            },
+        );
+
+        let host_actor_sorted_index_remove = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             sort_prefix: u32,
+             key_ptr: u32,
+             key_len: u32|
+             -> Result<u64, Error> {
+                actor_sorted_index_remove(
+                    caller,
+                    object_handle,
+                    collection_index,
+                    sort_prefix,
+                    key_ptr,
+                    key_len,
+                )
+                .map_err(Error::host)
+            },
+        );
+
+        let host_actor_sorted_index_scan = Func::wrap(
+            store.as_context_mut(),
+            |caller: Caller<'_, HostState>,
+             object_handle: u32,
+             collection_index: u32,
+             count: u32|
+             -> Result<u64, Error> {
+                actor_sorted_index_scan(caller, object_handle, collection_index, count)
+                    .map_err(Error::host)
+            },
+        );
+
         let host_emit_log = Func::wrap(
             store.as_context_mut(),
             |caller: Caller<'_, HostState>,
@@ -1785,6 +2078,41 @@ impl WasmiModule {
             host_consume_wasm_execution_units
         );
         linker_define!(linker, ACTOR_EMIT_EVENT_FUNCTION_NAME, host_emit_event);
+        linker_define!(
+            linker,
+            ACTOR_INDEX_INSERT_FUNCTION_NAME,
+            host_actor_index_insert
+        );
+        linker_define!(
+            linker,
+            ACTOR_INDEX_REMOVE_FUNCTION_NAME,
+            host_actor_index_remove
+        );
+        linker_define!(
+            linker,
+            ACTOR_INDEX_SCAN_KEYS_FUNCTION_NAME,
+            host_actor_index_scan_keys
+        );
+        linker_define!(
+            linker,
+            ACTOR_INDEX_DRAIN_FUNCTION_NAME,
+            host_actor_index_drain
+        );
+        linker_define!(
+            linker,
+            ACTOR_SORTED_INDEX_INSERT_FUNCTION_NAME,
+            host_actor_sorted_index_insert
+        );
+        linker_define!(
+            linker,
+            ACTOR_SORTED_INDEX_REMOVE_FUNCTION_NAME,
+            host_actor_sorted_index_remove
+        );
+        linker_define!(
+            linker,
+            ACTOR_SORTED_INDEX_SCAN_FUNCTION_NAME,
+            host_actor_sorted_index_scan
+        );
         linker_define!(linker, SYS_LOG_FUNCTION_NAME, host_emit_log);
         linker_define!(linker, SYS_PANIC_FUNCTION_NAME, host_panic);
         linker_define!(
@@ -2052,6 +2380,32 @@ impl WasmInstance for WasmiInstance {
 
         result
     }
+
+    fn take_execution_profile(&mut self) -> WasmExecutionProfile {
+        mem::take(&mut self.store.data_mut().execution_profile)
+    }
+
+    fn take_call_stack(&mut self) -> Vec<u32> {
+        // The globals are left as they were at a trap, which locates the innermost function
+        // more precisely than the last metering
+        let host_state = self.store.data();
+        let globals = host_state
+            .profiler_global
+            .zip(host_state.profiler_depth_global)
+            .map(|(function_global, depth_global)| {
+                (
+                    function_global.get(self.store.as_context()),
+                    depth_global.get(self.store.as_context()),
+                )
+            });
+        let mut call_stack = mem::take(&mut self.store.data_mut().call_stack);
+        if let Some((Val::I32(function_index), Val::I32(depth))) = globals {
+            if function_index >= 0 {
+                update_call_stack(&mut call_stack, depth, function_index as u32);
+            }
+        }
+        call_stack.into_iter().flatten().collect()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -2068,6 +2422,11 @@ pub struct WasmiEngine {
     #[cfg(feature = "fuzzing")]
     #[allow(dead_code)]
     modules_cache: usize,
+    // Modules prepared for profiling, which differ from the modules above
+    #[cfg(all(not(feature = "fuzzing"), not(feature = "moka")))]
+    profiled_modules_cache: RefCell<lru::LruCache<CodeHash, Arc<WasmiModule>>>,
+    #[cfg(all(not(feature = "fuzzing"), feature = "moka"))]
+    profiled_modules_cache: moka::sync::Cache<CodeHash, Arc<WasmiModule>>,
 }
 
 impl Default for WasmiEngine {
@@ -2095,7 +2454,21 @@ impl WasmiEngine {
         #[cfg(feature = "fuzzing")]
         let modules_cache = options.max_cache_size;
 
-        Self { modules_cache }
+        #[cfg(all(not(feature = "fuzzing"), not(feature = "moka")))]
+        let profiled_modules_cache = RefCell::new(lru::LruCache::new(
+            sbor::rust::num::NonZeroUsize::new(options.max_cache_size).unwrap(),
+        ));
+        #[cfg(all(not(feature = "fuzzing"), feature = "moka"))]
+        let profiled_modules_cache = moka::sync::Cache::builder()
+            .weigher(|_key: &CodeHash, _value: &Arc<WasmiModule>| -> u32 { 1u32 })
+            .max_capacity(options.max_cache_size as u64)
+            .build();
+
+        Self {
+            modules_cache,
+            #[cfg(not(feature = "fuzzing"))]
+            profiled_modules_cache,
+        }
     }
 }
 
@@ -2133,6 +2506,60 @@ impl WasmEngine for WasmiEngine {
 
         instance
     }
+
+    fn instantiate_with_profiling(
+        &self,
+        code_hash: CodeHash,
+        instrumented_code: &[u8],
+    ) -> WasmiInstance {
+        #[cfg(all(not(feature = "fuzzing"), not(feature = "moka")))]
+        let cached_module = self
+            .profiled_modules_cache
+            .borrow_mut()
+            .get(&code_hash)
+            .cloned();
+        #[cfg(all(not(feature = "fuzzing"), feature = "moka"))]
+        let cached_module = self.profiled_modules_cache.get(&code_hash);
+        #[cfg(feature = "fuzzing")]
+        let cached_module: Option<Arc<WasmiModule>> = None;
+
+        let module = match cached_module {
+            Some(module) => module,
+            None => {
+                let Ok(profiled_code) = inject_function_profiling(instrumented_code) else {
+                    return self.instantiate(code_hash, instrumented_code);
+                };
+                let module =
+                    Arc::new(WasmiModule::new(&profiled_code).expect("Failed to compile module"));
+
+                #[cfg(all(not(feature = "fuzzing"), not(feature = "moka")))]
+                self.profiled_modules_cache
+                    .borrow_mut()
+                    .put(code_hash, module.clone());
+                #[cfg(all(not(feature = "fuzzing"), feature = "moka"))]
+                self.profiled_modules_cache
+                    .insert(code_hash, module.clone());
+
+                module
+            }
+        };
+
+        let mut instance = module.instantiate_unchecked();
+        let [profiler_global, profiler_depth_global] = [
+            EXPORT_PROFILER_FUNCTION_GLOBAL,
+            EXPORT_PROFILER_DEPTH_GLOBAL,
+        ]
+        .map(|name| {
+            instance
+                .instance
+                .get_export(instance.store.as_context(), name)
+                .and_then(Extern::into_global)
+        });
+        let host_state = instance.store.data_mut();
+        host_state.profiler_global = profiler_global;
+        host_state.profiler_depth_global = profiler_depth_global;
+        instance
+    }
 }
 
 // Below tests verify WASM "mutable-global" feature, which allows importing/exporting mutable globals.
@@ -2243,4 +2670,20 @@ mod tests {
         };
         assert_eq!(val, 11100);
     }
+
+    #[test]
+    fn test_update_call_stack() {
+        let mut call_stack = Vec::new();
+        update_call_stack(&mut call_stack, 1, 4);
+        update_call_stack(&mut call_stack, 2, 7);
+        assert_eq!(call_stack, vec![Some(4), Some(7)]);
+
+        // Returned to the caller, which calls another function (not metered on entry)
+        update_call_stack(&mut call_stack, 1, 4);
+        update_call_stack(&mut call_stack, 3, 9);
+        assert_eq!(call_stack, vec![Some(4), None, Some(9)]);
+
+        update_call_stack(&mut call_stack, 0, 1);
+        assert_eq!(call_stack, vec![Some(4), None, Some(9)]);
+    }
 }
@@ -243,6 +243,73 @@ impl<'a> WasmRuntime for NoOpWasmRuntime<'a> {
         Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
     }
 
+    fn actor_index_insert(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn actor_index_remove(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn actor_index_scan_keys(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn actor_index_drain(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn actor_sorted_index_insert(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        sort_prefix: u16,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn actor_sorted_index_remove(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        sort_prefix: u16,
+        key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
+    fn actor_sorted_index_scan(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        count: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        Err(InvokeError::SelfError(WasmRuntimeError::NotImplemented))
+    }
+
     fn sys_log(
         &mut self,
         level: Vec<u8>,
@@ -31,15 +31,7 @@ impl<'y, Y: SystemApi<RuntimeError>> ScryptoRuntime<'y, Y> {
         export_name: String,
         scrypto_vm_version: ScryptoVmVersion,
     ) -> Self {
-        let wasm_execution_units_base = if scrypto_vm_version < ScryptoVmVersion::cuttlefish() {
-            0
-        } else {
-            // Add 28,000 base units to make sure the we do not undercharge for WASM execution,
-            // which might lead to system exploitation.
-            // This is especially important in corner-cases such as `costing::spin_loop_v2` benchmark.
-            // less frequently.
-            28000
-        };
+        let wasm_execution_units_base = scrypto_vm_version.wasm_execution_units_base();
 
         ScryptoRuntime {
             api,
@@ -97,7 +89,7 @@ impl<'y, Y: SystemApi<RuntimeError>> WasmRuntime for ScryptoRuntime<'y, Y> {
             // Practically speaking, there is little gain of keeping multiple buffers open before
             // [multi-value](https://github.com/WebAssembly/multi-value/blob/master/proposals/multi-value/Overview.md) is supported and used.
             // We reduce it to `4` so that the amount of memory that a transaction can consume is reduced, which is beneficial for parallel execution.
-            ScryptoVmVersion::V1_2 => 4,
+            ScryptoVmVersion::V1_2 | ScryptoVmVersion::V1_3 => 4,
         };
         if self.buffers.len() >= max_number_of_buffers {
             return Err(InvokeError::SelfError(WasmRuntimeError::TooManyBuffers));
@@ -489,6 +481,98 @@ impl<'y, Y: SystemApi<RuntimeError>> WasmRuntime for ScryptoRuntime<'y, Y> {
         Ok(())
     }
 
+    fn actor_index_insert(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), InvokeError<WasmRuntimeError>> {
+        self.api
+            .actor_index_insert(object_handle, collection_index, key, value)?;
+        Ok(())
+    }
+
+    fn actor_index_remove(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let removed = self
+            .api
+            .actor_index_remove(object_handle, collection_index, key)?;
+        self.allocate_buffer(scrypto_encode(&removed).expect("Failed to encode removed entry"))
+    }
+
+    fn actor_index_scan_keys(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let keys = self
+            .api
+            .actor_index_scan_keys(object_handle, collection_index, limit)?;
+        self.allocate_buffer(scrypto_encode(&keys).expect("Failed to encode scanned keys"))
+    }
+
+    fn actor_index_drain(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        limit: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let entries = self
+            .api
+            .actor_index_drain(object_handle, collection_index, limit)?;
+        self.allocate_buffer(scrypto_encode(&entries).expect("Failed to encode drained entries"))
+    }
+
+    fn actor_sorted_index_insert(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        sort_prefix: u16,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) -> Result<(), InvokeError<WasmRuntimeError>> {
+        self.api.actor_sorted_index_insert(
+            object_handle,
+            collection_index,
+            (sort_prefix.to_be_bytes(), key),
+            value,
+        )?;
+        Ok(())
+    }
+
+    fn actor_sorted_index_remove(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        sort_prefix: u16,
+        key: Vec<u8>,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let removed = self.api.actor_sorted_index_remove(
+            object_handle,
+            collection_index,
+            &(sort_prefix.to_be_bytes(), key),
+        )?;
+        self.allocate_buffer(scrypto_encode(&removed).expect("Failed to encode removed entry"))
+    }
+
+    fn actor_sorted_index_scan(
+        &mut self,
+        object_handle: u32,
+        collection_index: u8,
+        count: u32,
+    ) -> Result<Buffer, InvokeError<WasmRuntimeError>> {
+        let entries = self
+            .api
+            .actor_sorted_index_scan(object_handle, collection_index, count)?;
+        self.allocate_buffer(scrypto_encode(&entries).expect("Failed to encode scanned entries"))
+    }
+
     fn sys_log(
         &mut self,
         level: Vec<u8>,
@@ -1,3 +1,4 @@
+use core::ops::Bound;
 use radix_common::prelude::*;
 use radix_substate_store_interface::interface::*;
 
@@ -88,3 +89,36 @@ impl ListableSubstateDatabase for InMemorySubstateDatabase {
         Box::new(partition_iter)
     }
 }
+
+/// An [`EventIndexStore`] keeping its entries in memory.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct InMemoryEventIndexStore {
+    entries: BTreeMap<DbEventIndexKey, DbEventIndexValue>,
+}
+
+impl InMemoryEventIndexStore {
+    pub fn standard() -> Self {
+        Self::default()
+    }
+}
+
+impl EventIndexStore for InMemoryEventIndexStore {
+    fn get_event_index_entry(&self, key: &[u8]) -> Option<DbEventIndexValue> {
+        self.entries.get(key).cloned()
+    }
+
+    fn list_event_index_entries_from(
+        &self,
+        from_key: &[u8],
+    ) -> Box<dyn Iterator<Item = (DbEventIndexKey, DbEventIndexValue)> + '_> {
+        Box::new(
+            self.entries
+                .range::<[u8], _>((Bound::Included(from_key), Bound::Unbounded))
+                .map(|(key, value)| (key.clone(), value.clone())),
+        )
+    }
+
+    fn put_event_index_entries(&mut self, entries: Vec<(DbEventIndexKey, DbEventIndexValue)>) {
+        self.entries.extend(entries);
+    }
+}
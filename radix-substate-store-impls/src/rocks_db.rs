@@ -3,15 +3,124 @@ use radix_common::constants::MAX_SUBSTATE_KEY_SIZE;
 use radix_common::prelude::*;
 use radix_rust::copy_u8_array;
 use radix_substate_store_interface::interface::*;
+pub use rocksdb::Error as RocksDBError;
 pub use rocksdb::{BlockBasedOptions, LogLevel, Options};
 use rocksdb::{
     ColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode, Direction, IteratorMode,
-    SingleThreaded, DB,
+    SingleThreaded, WriteBatch, DB,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// The way in which a RocksDB-backed substate store opens its database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RocksDBOpenMode {
+    /// Exclusive read-write access, creating the database and its column families if missing.
+    ReadWrite,
+    /// A read-only view of the database as it was when opened. This doesn't take the database
+    /// lock, so it can be used while another process has the database open for writing, but it
+    /// doesn't see any writes made after it was opened.
+    ReadOnly,
+    /// A RocksDB secondary instance, which keeps its own info logs under the given path. Like
+    /// [`RocksDBOpenMode::ReadOnly`] it can be used alongside a writing process, and it can also
+    /// follow that process' writes by catching up with the primary.
+    Secondary { secondary_path: PathBuf },
+}
+
+impl RocksDBOpenMode {
+    pub fn is_writable(&self) -> bool {
+        matches!(self, RocksDBOpenMode::ReadWrite)
+    }
+}
+
+/// Opens the database at the given path in the given mode, with the given column families, which
+/// must already exist unless the database is opened for writing.
+pub(crate) fn open_db_with_column_families(
+    options: &Options,
+    root: &Path,
+    mode: &RocksDBOpenMode,
+    column_families: &[&str],
+) -> Result<DB, RocksDBError> {
+    // A database which is not opened for writing cannot create the column families added by a
+    // newer version of the store, so it only opens the existing ones.
+    let existing_column_families = if mode.is_writable() {
+        None
+    } else {
+        Some(DB::list_cf(options, root)?)
+    };
+    let descriptors = column_families
+        .iter()
+        .filter(|name| {
+            existing_column_families
+                .as_ref()
+                .map_or(true, |existing| existing.iter().any(|cf| cf == *name))
+        })
+        .map(|name| ColumnFamilyDescriptor::new(*name, Options::default()))
+        .collect::<Vec<_>>();
+    match mode {
+        RocksDBOpenMode::ReadWrite => {
+            let mut options = options.clone();
+            options.create_if_missing(true);
+            options.create_missing_column_families(true);
+            DB::open_cf_descriptors(&options, root, descriptors)
+        }
+        RocksDBOpenMode::ReadOnly => {
+            DB::open_cf_descriptors_read_only(options, root, descriptors, false)
+        }
+        RocksDBOpenMode::Secondary { secondary_path } => {
+            // A secondary instance has to keep all files open, as the primary may delete them
+            // during compaction.
+            let mut options = options.clone();
+            options.set_max_open_files(-1);
+            DB::open_cf_descriptors_as_secondary(
+                &options,
+                root,
+                secondary_path.as_path(),
+                descriptors,
+            )
+        }
+    }
+}
+
+/// The column family holding the entries of an [`EventIndexStore`].
+pub(crate) const EVENT_INDEX_CF: &str = "event_index";
+
+pub(crate) fn get_event_index_entry(db: &DB, key: &[u8]) -> Option<DbEventIndexValue> {
+    // The column family is missing from a database written before it was added, and opened
+    // without writing
+    db.cf_handle(EVENT_INDEX_CF)
+        .and_then(|cf| db.get_cf(cf, key).expect("IO Error"))
+}
+
+pub(crate) fn list_event_index_entries_from<'a>(
+    db: &'a DB,
+    from_key: &[u8],
+) -> Box<dyn Iterator<Item = (DbEventIndexKey, DbEventIndexValue)> + 'a> {
+    let Some(cf) = db.cf_handle(EVENT_INDEX_CF) else {
+        return Box::new(core::iter::empty());
+    };
+    Box::new(
+        db.iterator_cf(cf, IteratorMode::From(from_key, Direction::Forward))
+            .map(|kv| {
+                let (key, value) = kv.expect("IO Error");
+                (key.to_vec(), value.to_vec())
+            }),
+    )
+}
+
+pub(crate) fn put_event_index_entries(
+    db: &DB,
+    batch: &mut WriteBatch,
+    entries: Vec<(DbEventIndexKey, DbEventIndexValue)>,
+) {
+    let cf = db.cf_handle(EVENT_INDEX_CF).unwrap();
+    for (key, value) in entries {
+        batch.put_cf(cf, key, value);
+    }
+}
 
 pub struct RocksdbSubstateStore {
     db: DBWithThreadMode<SingleThreaded>,
+    open_mode: RocksDBOpenMode,
 }
 
 impl RocksdbSubstateStore {
@@ -21,20 +130,50 @@ impl RocksdbSubstateStore {
     pub fn standard(root: PathBuf) -> Self {
         Self::with_options(&Options::default(), root)
     }
+
     pub fn with_options(options: &Options, root: PathBuf) -> Self {
-        let mut options = options.clone();
-        options.create_if_missing(true);
-        options.create_missing_column_families(true);
-        let db = DB::open_cf_descriptors(
-            &options,
-            root.as_path(),
-            vec![ColumnFamilyDescriptor::new(
-                Self::THE_ONLY_CF,
-                Options::default(),
-            )],
+        Self::with_options_and_mode(options, root, RocksDBOpenMode::ReadWrite).unwrap()
+    }
+
+    /// Opens an existing database read-only, see [`RocksDBOpenMode::ReadOnly`].
+    pub fn read_only(root: PathBuf) -> Result<Self, RocksDBError> {
+        Self::with_options_and_mode(&Options::default(), root, RocksDBOpenMode::ReadOnly)
+    }
+
+    /// Opens an existing database as a secondary instance, see [`RocksDBOpenMode::Secondary`].
+    pub fn secondary(root: PathBuf, secondary_path: PathBuf) -> Result<Self, RocksDBError> {
+        Self::with_options_and_mode(
+            &Options::default(),
+            root,
+            RocksDBOpenMode::Secondary { secondary_path },
         )
-        .unwrap();
-        Self { db }
+    }
+
+    pub fn with_options_and_mode(
+        options: &Options,
+        root: PathBuf,
+        open_mode: RocksDBOpenMode,
+    ) -> Result<Self, RocksDBError> {
+        let db = open_db_with_column_families(
+            options,
+            root.as_path(),
+            &open_mode,
+            &[Self::THE_ONLY_CF, EVENT_INDEX_CF],
+        )?;
+        Ok(Self { db, open_mode })
+    }
+
+    pub fn open_mode(&self) -> &RocksDBOpenMode {
+        &self.open_mode
+    }
+
+    /// Makes a secondary instance see the writes made by the primary since it was opened or last
+    /// caught up. This is a no-op for a read-write store, and fails for a read-only one.
+    pub fn try_catch_up_with_primary(&self) -> Result<(), RocksDBError> {
+        match self.open_mode {
+            RocksDBOpenMode::ReadWrite => Ok(()),
+            _ => self.db.try_catch_up_with_primary(),
+        }
     }
 
     fn cf(&self) -> &ColumnFamily {
@@ -79,8 +218,13 @@ impl SubstateDatabase for RocksdbSubstateStore {
     }
 }
 
-impl CommittableSubstateDatabase for RocksdbSubstateStore {
-    fn commit(&mut self, database_updates: &DatabaseUpdates) {
+impl RocksdbSubstateStore {
+    fn commit_in_batch(&mut self, database_updates: &DatabaseUpdates, mut batch: WriteBatch) {
+        assert!(
+            self.open_mode.is_writable(),
+            "Cannot commit to a database opened in {:?} mode",
+            self.open_mode
+        );
         for (node_key, node_updates) in &database_updates.node_updates {
             for (partition_num, partition_updates) in &node_updates.partition_updates {
                 let partition_key = DbPartitionKey {
@@ -93,11 +237,10 @@ impl CommittableSubstateDatabase for RocksdbSubstateStore {
                             let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
                             match update {
                                 DatabaseUpdate::Set(value_bytes) => {
-                                    self.db.put_cf(self.cf(), key_bytes, value_bytes)
+                                    batch.put_cf(self.cf(), key_bytes, value_bytes)
                                 }
-                                DatabaseUpdate::Delete => self.db.delete_cf(self.cf(), key_bytes),
+                                DatabaseUpdate::Delete => batch.delete_cf(self.cf(), key_bytes),
                             }
-                            .expect("IO error");
                         }
                     }
                     PartitionDatabaseUpdates::Reset {
@@ -105,26 +248,29 @@ impl CommittableSubstateDatabase for RocksdbSubstateStore {
                     } => {
                         // Note: a plain `delete_range()` is missing from rocksdb's API, and
                         // (at the moment of writing) this is the only reason of having CF.
-                        self.db
-                            .delete_range_cf(
-                                self.cf(),
-                                encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![])),
-                                encode_to_rocksdb_bytes(
-                                    &partition_key,
-                                    &DbSortKey(vec![u8::MAX; 2 * MAX_SUBSTATE_KEY_SIZE]),
-                                ),
-                            )
-                            .expect("IO error");
+                        batch.delete_range_cf(
+                            self.cf(),
+                            encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![])),
+                            encode_to_rocksdb_bytes(
+                                &partition_key,
+                                &DbSortKey(vec![u8::MAX; 2 * MAX_SUBSTATE_KEY_SIZE]),
+                            ),
+                        );
                         for (sort_key, value_bytes) in new_substate_values {
                             let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
-                            self.db
-                                .put_cf(self.cf(), key_bytes, value_bytes)
-                                .expect("IO error");
+                            batch.put_cf(self.cf(), key_bytes, value_bytes);
                         }
                     }
                 }
             }
         }
+        self.db.write(batch).expect("IO error");
+    }
+}
+
+impl CommittableSubstateDatabase for RocksdbSubstateStore {
+    fn commit(&mut self, database_updates: &DatabaseUpdates) {
+        self.commit_in_batch(database_updates, WriteBatch::default());
     }
 }
 
@@ -145,6 +291,42 @@ impl ListableSubstateDatabase for RocksdbSubstateStore {
     }
 }
 
+impl EventIndexStore for RocksdbSubstateStore {
+    fn get_event_index_entry(&self, key: &[u8]) -> Option<DbEventIndexValue> {
+        get_event_index_entry(&self.db, key)
+    }
+
+    fn list_event_index_entries_from(
+        &self,
+        from_key: &[u8],
+    ) -> Box<dyn Iterator<Item = (DbEventIndexKey, DbEventIndexValue)> + '_> {
+        list_event_index_entries_from(&self.db, from_key)
+    }
+
+    fn put_event_index_entries(&mut self, entries: Vec<(DbEventIndexKey, DbEventIndexValue)>) {
+        assert!(
+            self.open_mode.is_writable(),
+            "Cannot write to a database opened in {:?} mode",
+            self.open_mode
+        );
+        let mut batch = WriteBatch::default();
+        put_event_index_entries(&self.db, &mut batch, entries);
+        self.db.write(batch).expect("IO Error");
+    }
+}
+
+impl CommittableSubstateDatabaseWithEventIndex for RocksdbSubstateStore {
+    fn commit_with_event_index_entries(
+        &mut self,
+        database_updates: &DatabaseUpdates,
+        event_index_entries: Vec<(DbEventIndexKey, DbEventIndexValue)>,
+    ) {
+        let mut batch = WriteBatch::default();
+        put_event_index_entries(&self.db, &mut batch, event_index_entries);
+        self.commit_in_batch(database_updates, batch);
+    }
+}
+
 pub fn encode_to_rocksdb_bytes(partition_key: &DbPartitionKey, sort_key: &DbSortKey) -> Vec<u8> {
     let mut buffer = Vec::new();
     buffer.extend(
@@ -224,4 +406,56 @@ mod tests {
         });
         assert_eq!(db.list_partition_keys().count(), 8);
     }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn test_read_only_and_secondary_instances() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let secondary_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let partition_key = DbPartitionKey {
+            node_key: vec![1],
+            partition_num: 0,
+        };
+        let set = |value: u8| {
+            DatabaseUpdates::from_delta_maps(indexmap! {
+                partition_key.clone() => indexmap! {
+                    DbSortKey(vec![2]) => DatabaseUpdate::Set(vec![value]),
+                },
+            })
+        };
+
+        let mut db = RocksdbSubstateStore::standard(root.clone());
+        db.commit(&set(1));
+
+        // Both can be opened while the primary holds the database
+        let read_only = RocksdbSubstateStore::read_only(root.clone()).unwrap();
+        let secondary =
+            RocksdbSubstateStore::secondary(root.clone(), secondary_dir.path().to_path_buf())
+                .unwrap();
+        let get = |db: &RocksdbSubstateStore| {
+            db.get_raw_substate_by_db_key(&partition_key, &DbSortKey(vec![2]))
+        };
+        assert_eq!(get(&read_only), Some(vec![1]));
+        assert_eq!(get(&secondary), Some(vec![1]));
+
+        // Only the secondary can follow the primary's writes
+        db.commit(&set(2));
+        secondary.try_catch_up_with_primary().unwrap();
+        assert_eq!(get(&read_only), Some(vec![1]));
+        assert_eq!(get(&secondary), Some(vec![2]));
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    #[should_panic]
+    fn test_commit_to_read_only_database_panics() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        drop(RocksdbSubstateStore::standard(
+            temp_dir.path().to_path_buf(),
+        ));
+
+        let mut db = RocksdbSubstateStore::read_only(temp_dir.path().to_path_buf()).unwrap();
+        db.commit(&DatabaseUpdates::default());
+    }
 }
@@ -1,3 +1,7 @@
+use crate::state_tree::entity_tier::EntityTier;
+use crate::state_tree::list_substate_hashes_at_retained_version;
+use crate::state_tree::substate_proof::*;
+use crate::state_tree::substate_tier::SubstateSummary;
 use crate::state_tree::tree_store::*;
 use itertools::Itertools;
 use radix_common::constants::MAX_SUBSTATE_KEY_SIZE;
@@ -5,23 +9,51 @@ use radix_common::prelude::*;
 use radix_substate_store_interface::interface::*;
 pub use rocksdb::{BlockBasedOptions, LogLevel, Options};
 use rocksdb::{
-    ColumnFamily, ColumnFamilyDescriptor, DBWithThreadMode, Direction, IteratorMode,
-    SingleThreaded, WriteBatch, DB,
+    ColumnFamily, DBWithThreadMode, Direction, IteratorMode, SingleThreaded, WriteBatch,
 };
 use std::path::PathBuf;
 
 mod state_tree;
-use crate::rocks_db::{decode_from_rocksdb_bytes, encode_to_rocksdb_bytes};
+use crate::rocks_db::{
+    decode_from_rocksdb_bytes, encode_to_rocksdb_bytes, get_event_index_entry,
+    list_event_index_entries_from, open_db_with_column_families, put_event_index_entries,
+    EVENT_INDEX_CF,
+};
+pub use crate::rocks_db::{RocksDBError, RocksDBOpenMode};
 pub use state_tree::*;
 
 const META_CF: &str = "meta";
 const SUBSTATES_CF: &str = "substates";
 const MERKLE_NODES_CF: &str = "merkle_nodes";
 const STALE_MERKLE_TREE_PARTS_CF: &str = "stale_merkle_tree_parts";
+const HISTORICAL_SUBSTATE_VALUES_CF: &str = "historical_substate_values";
+const STALE_HISTORICAL_SUBSTATE_VALUES_CF: &str = "stale_historical_substate_values";
+
+const ALL_CFS: [&str; 7] = [
+    META_CF,
+    SUBSTATES_CF,
+    MERKLE_NODES_CF,
+    STALE_MERKLE_TREE_PARTS_CF,
+    HISTORICAL_SUBSTATE_VALUES_CF,
+    STALE_HISTORICAL_SUBSTATE_VALUES_CF,
+    EVENT_INDEX_CF,
+];
 
+// The `META_CF` key of the `Metadata` is empty - this is the key of the first retained version.
+const FIRST_RETAINED_VERSION_KEY: [u8; 1] = [1];
+
+/// A RocksDB-backed substate store which also maintains a Merkle tree of the substates.
+///
+/// Unless the [`StateTreeRetention`] only keeps the current version, the store also keeps a
+/// "stale index" (the parts of the tree which are no longer needed from a specific version
+/// onwards), which drives their garbage collection. If enabled with
+/// [`Self::with_historical_values()`], the upserted Substate values are recorded (and indexed)
+/// too, which allows reading the Substates as of any retained historical version.
 pub struct RocksDBWithMerkleTreeSubstateStore {
     db: DBWithThreadMode<SingleThreaded>,
-    pruning_enabled: bool,
+    retention: StateTreeRetention,
+    keep_historical_values: bool,
+    open_mode: RocksDBOpenMode,
 }
 
 impl RocksDBWithMerkleTreeSubstateStore {
@@ -35,33 +67,99 @@ impl RocksDBWithMerkleTreeSubstateStore {
     }
 
     pub fn standard(root: PathBuf) -> Self {
-        let mut options = Options::default();
-        options.create_if_missing(true);
-        options.create_missing_column_families(true);
-        Self::with_options(&options, root, true)
+        Self::with_options(&Options::default(), root, true)
     }
 
+    /// Note: with pruning disabled, the stale tree parts are only indexed (and deleted by an
+    /// explicit [`Self::garbage_collect()`]), and the historical Substate values are not recorded -
+    /// see [`Self::with_options_and_retention()`] and [`Self::with_historical_values()`] for
+    /// configuring these explicitly.
     pub fn with_options(options: &Options, root: PathBuf, pruning_enabled: bool) -> Self {
-        let db = DB::open_cf_descriptors(
-            options,
-            root.as_path(),
-            [
-                META_CF,
-                SUBSTATES_CF,
-                MERKLE_NODES_CF,
-                STALE_MERKLE_TREE_PARTS_CF,
-            ]
-            .into_iter()
-            .map(|name| ColumnFamilyDescriptor::new(name, Options::default()))
-            .collect::<Vec<_>>(),
+        let retention = if pruning_enabled {
+            StateTreeRetention::CURRENT_VERSION_ONLY
+        } else {
+            StateTreeRetention::AllVersions
+        };
+        Self::with_options_and_retention(options, root, retention)
+    }
+
+    pub fn with_options_and_retention(
+        options: &Options,
+        root: PathBuf,
+        retention: StateTreeRetention,
+    ) -> Self {
+        Self::with_options_and_mode(options, root, retention, RocksDBOpenMode::ReadWrite).unwrap()
+    }
+
+    /// Opens an existing database read-only, see [`RocksDBOpenMode::ReadOnly`].
+    pub fn read_only(root: PathBuf) -> Result<Self, RocksDBError> {
+        Self::with_options_and_mode(
+            &Options::default(),
+            root,
+            StateTreeRetention::CURRENT_VERSION_ONLY,
+            RocksDBOpenMode::ReadOnly,
         )
-        .unwrap();
-        Self {
+    }
+
+    /// Opens an existing database as a secondary instance, see [`RocksDBOpenMode::Secondary`].
+    pub fn secondary(root: PathBuf, secondary_path: PathBuf) -> Result<Self, RocksDBError> {
+        Self::with_options_and_mode(
+            &Options::default(),
+            root,
+            StateTreeRetention::CURRENT_VERSION_ONLY,
+            RocksDBOpenMode::Secondary { secondary_path },
+        )
+    }
+
+    /// Note: the `retention` only affects commits, so it is irrelevant unless the store is opened
+    /// for writing.
+    pub fn with_options_and_mode(
+        options: &Options,
+        root: PathBuf,
+        retention: StateTreeRetention,
+        open_mode: RocksDBOpenMode,
+    ) -> Result<Self, RocksDBError> {
+        let db = open_db_with_column_families(options, root.as_path(), &open_mode, &ALL_CFS)?;
+        Ok(Self {
             db,
-            pruning_enabled,
+            retention,
+            keep_historical_values: false,
+            open_mode,
+        })
+    }
+
+    /// Makes the commits record the upserted Substate values, so that they can be read as of any
+    /// retained version (see [`Self::get_substate_value_at_version()`]).
+    ///
+    /// Note: the values are only recorded if the [`StateTreeRetention`] keeps any past versions.
+    pub fn with_historical_values(self) -> Self {
+        Self {
+            keep_historical_values: true,
+            ..self
+        }
+    }
+
+    pub fn open_mode(&self) -> &RocksDBOpenMode {
+        &self.open_mode
+    }
+
+    /// Makes a secondary instance see the writes made by the primary since it was opened or last
+    /// caught up. This is a no-op for a read-write store, and fails for a read-only one.
+    pub fn try_catch_up_with_primary(&self) -> Result<(), RocksDBError> {
+        match self.open_mode {
+            RocksDBOpenMode::ReadWrite => Ok(()),
+            _ => self.db.try_catch_up_with_primary(),
         }
     }
 
+    fn assert_writable(&self) {
+        assert!(
+            self.open_mode.is_writable(),
+            "Cannot write to a database opened in {:?} mode",
+            self.open_mode
+        );
+    }
+
     fn cf(&self, cf: &str) -> &ColumnFamily {
         self.db.cf_handle(cf).unwrap()
     }
@@ -90,13 +188,291 @@ impl RocksDBWithMerkleTreeSubstateStore {
             .unwrap_or(Hash([0u8; Hash::LENGTH]))
     }
 
+    /// Returns the current value of the given Substate (if it exists), together with a proof of
+    /// its inclusion (or exclusion) under the [`Self::get_current_root_hash()`].
+    pub fn get_substate_with_proof(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> (Option<DbSubstateValue>, SubstateProof) {
+        let (_, proof) = get_substate_proof_at_version(
+            self,
+            self.get_current_version(),
+            partition_key,
+            sort_key,
+        )
+        .expect("current state tree root must exist");
+        let value = self.get_raw_substate_by_db_key(partition_key, sort_key);
+        (value, proof)
+    }
+
+    /// Returns a proof of the given Substate's inclusion (or exclusion) at the given historical
+    /// state version.
+    ///
+    /// Note: `None` is returned for any version which is not retained by the store's
+    /// [`StateTreeRetention`] (see [`Self::is_version_retained()`]).
+    pub fn get_substate_proof_at_version(
+        &self,
+        version: Version,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Option<SubstateProof> {
+        get_substate_proof_at_version(self, version, partition_key, sort_key)
+            .map(|(_, proof)| proof)
+    }
+
+    /// Returns the version of the oldest tree which was not (even partially) garbage-collected.
+    ///
+    /// Note: if the store only keeps the current version, this is only updated by an explicit
+    /// [`Self::garbage_collect()`] - see [`Self::is_version_retained()`].
+    pub fn get_first_retained_version(&self) -> Version {
+        self.db
+            .get_cf(self.cf(META_CF), FIRST_RETAINED_VERSION_KEY)
+            .unwrap()
+            .map(|bytes| Version::from_be_bytes(copy_u8_array(&bytes)))
+            .unwrap_or(0)
+    }
+
+    /// Returns true if the tree of the given state version is fully available.
+    pub fn is_version_retained(&self, version: Version) -> bool {
+        let current_version = self.get_current_version();
+        if version > current_version {
+            return false;
+        }
+        if version == current_version {
+            return true;
+        }
+        if version < self.get_first_retained_version() {
+            return false;
+        }
+        // A tree pruned on commit has no stale index - the previous versions are only available
+        // until their root node is deleted, which also happens on most commits.
+        let root_node_key = StoredTreeNodeKey::unprefixed(TreeNodeKey::new_empty_path(version));
+        version == 0 || self.get_node(&root_node_key).is_some()
+    }
+
+    /// Returns the value hashes of all Substates at the given state version, or `None` if that
+    /// version is not retained (see [`Self::is_version_retained()`]).
+    pub fn list_substate_hashes_at_version(
+        &self,
+        version: Version,
+    ) -> Option<IndexMap<DbPartitionKey, IndexMap<DbSortKey, Hash>>> {
+        if !self.is_version_retained(version) {
+            return None;
+        }
+        list_substate_hashes_at_retained_version(self, version)
+    }
+
+    /// Returns the value of the given Substate at the given state version: `Some(None)` if the
+    /// Substate did not exist at that version, or `None` if the version is not retained, or the
+    /// value was not recorded (i.e. it was written while the store only kept the current version,
+    /// and it has changed since).
+    pub fn get_substate_value_at_version(
+        &self,
+        version: Version,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Option<Option<DbSubstateValue>> {
+        if !self.is_version_retained(version) {
+            return None;
+        }
+        if version == 0 {
+            return Some(None);
+        }
+        EntityTier::new(self, Some(version))
+            .get_entity_partition_tier(partition_key.node_key.clone())
+            .get_partition_substate_tier(partition_key.partition_num)
+            .get_substate_summary(sort_key)
+            .map_or(Some(None), |summary| {
+                self.get_substate_value_of_summary(partition_key, &summary)
+                    .map(Some)
+            })
+    }
+
+    /// Returns the values of all Substates of the given entity at the given state version, or
+    /// `None` if that version is not retained (see [`Self::is_version_retained()`]). A value is
+    /// `None` if it was not recorded (see [`Self::get_substate_value_at_version()`]).
+    pub fn list_entity_substate_values_at_version(
+        &self,
+        version: Version,
+        node_key: &DbNodeKey,
+    ) -> Option<Vec<(DbPartitionKey, DbSortKey, Option<DbSubstateValue>)>> {
+        if !self.is_version_retained(version) {
+            return None;
+        }
+        if version == 0 {
+            return Some(vec![]);
+        }
+        let partition_tier =
+            EntityTier::new(self, Some(version)).get_entity_partition_tier(node_key.clone());
+        let mut values = Vec::new();
+        for substate_tier in partition_tier.iter_partition_substate_tiers_from(None) {
+            let partition_key = substate_tier.partition_key().clone();
+            for summary in substate_tier.iter_substate_summaries_from(None) {
+                let value = self.get_substate_value_of_summary(&partition_key, &summary);
+                values.push((partition_key.clone(), summary.sort_key, value));
+            }
+        }
+        Some(values)
+    }
+
+    /// Returns the value which the given Substate had at the version of the given summary (i.e.
+    /// a historical value, or the current one if it is unchanged since), if it is available.
+    fn get_substate_value_of_summary(
+        &self,
+        partition_key: &DbPartitionKey,
+        summary: &SubstateSummary,
+    ) -> Option<DbSubstateValue> {
+        let historical_value = self
+            .db
+            .get_cf(
+                self.cf(HISTORICAL_SUBSTATE_VALUES_CF),
+                encode_historical_value_key(
+                    &encode_to_rocksdb_bytes(partition_key, &summary.sort_key),
+                    summary.upsert_version,
+                ),
+            )
+            .unwrap();
+        if historical_value.is_some() {
+            return historical_value;
+        }
+        // The value may be unchanged since, even if it was not recorded
+        self.get_raw_substate_by_db_key(partition_key, &summary.sort_key)
+            .filter(|current_value| hash(current_value) == summary.value_hash)
+    }
+
+    /// Deletes all tree parts and historical Substate values which are not needed by the given
+    /// state version onwards, and returns the number of deleted tree nodes.
+    ///
+    /// This is done automatically on commit if the [`StateTreeRetention`] keeps a number of recent
+    /// versions - but it can also be run explicitly, e.g. periodically by a background job, if it
+    /// keeps all versions.
+    pub fn garbage_collect(&mut self, first_retained_version: Version) -> usize {
+        self.assert_writable();
+        let first_retained_version = first_retained_version.min(self.get_current_version());
+        let mut batch = WriteBatch::default();
+        let mut deleted_node_count = 0;
+
+        let stale_tree_parts = self
+            .db
+            .iterator_cf(self.cf(STALE_MERKLE_TREE_PARTS_CF), IteratorMode::Start)
+            .map(|kv| kv.unwrap())
+            .take_while(|(key, _)| {
+                Version::from_be_bytes(copy_u8_array(key)) <= first_retained_version
+            });
+        for (key, value) in stale_tree_parts {
+            for part in scrypto_decode::<Vec<StaleTreePart>>(&value).unwrap() {
+                deleted_node_count += self.delete_stale_tree_part(&mut batch, part);
+            }
+            batch.delete_cf(self.cf(STALE_MERKLE_TREE_PARTS_CF), key);
+        }
+
+        let stale_values = self
+            .db
+            .iterator_cf(
+                self.cf(STALE_HISTORICAL_SUBSTATE_VALUES_CF),
+                IteratorMode::Start,
+            )
+            .map(|kv| kv.unwrap())
+            .take_while(|(key, _)| {
+                Version::from_be_bytes(copy_u8_array(key)) <= first_retained_version
+            });
+        for (key, value) in stale_values {
+            let stale_since_version = Version::from_be_bytes(copy_u8_array(&key));
+            for substate_key_bytes in scrypto_decode::<Vec<Vec<u8>>>(&value).unwrap() {
+                // Only the value upserted at the stale-since version (if any) remains needed
+                let superseded_values = self
+                    .db
+                    .iterator_cf(
+                        self.cf(HISTORICAL_SUBSTATE_VALUES_CF),
+                        IteratorMode::From(
+                            &encode_historical_value_key(&substate_key_bytes, 0),
+                            Direction::Forward,
+                        ),
+                    )
+                    .map(|kv| kv.unwrap().0)
+                    .take_while(|historical_key| {
+                        historical_key.as_ref()
+                            < encode_historical_value_key(&substate_key_bytes, stale_since_version)
+                                .as_slice()
+                    })
+                    .filter(|historical_key| historical_key.len() == substate_key_bytes.len() + 8);
+                for historical_key in superseded_values {
+                    batch.delete_cf(self.cf(HISTORICAL_SUBSTATE_VALUES_CF), historical_key);
+                }
+            }
+            batch.delete_cf(self.cf(STALE_HISTORICAL_SUBSTATE_VALUES_CF), key);
+        }
+
+        if first_retained_version > self.get_first_retained_version() {
+            batch.put_cf(
+                self.cf(META_CF),
+                FIRST_RETAINED_VERSION_KEY,
+                first_retained_version.to_be_bytes(),
+            );
+        }
+        self.db.write(batch).unwrap();
+        deleted_node_count
+    }
+
+    /// Deletes the given stale tree part (within the given batch), and returns the number of
+    /// deleted nodes.
+    fn delete_stale_tree_part(&self, batch: &mut WriteBatch, part: StaleTreePart) -> usize {
+        match part {
+            StaleTreePart::Node(node_key) => {
+                batch.delete_cf(self.cf(MERKLE_NODES_CF), encode_key(&node_key));
+                1
+            }
+            StaleTreePart::Subtree(node_key) => {
+                let mut deleted_node_count = 0;
+                let mut queue = VecDeque::new();
+                queue.push_back(node_key);
+
+                while let Some(node_key) = queue.pop_front() {
+                    if let Some(bytes) = self
+                        .db
+                        .get_cf(self.cf(MERKLE_NODES_CF), encode_key(&node_key))
+                        .unwrap()
+                    {
+                        batch.delete_cf(self.cf(MERKLE_NODES_CF), encode_key(&node_key));
+                        deleted_node_count += 1;
+                        let value: VersionedTreeNode = scrypto_decode(&bytes).unwrap();
+                        match value.fully_update_and_into_latest_version() {
+                            TreeNodeV1::Internal(x) => {
+                                for child in x.children {
+                                    queue.push_back(
+                                        node_key.gen_child_node_key(child.version, child.nibble),
+                                    )
+                                }
+                            }
+                            TreeNodeV1::Leaf(_) => {}
+                            TreeNodeV1::Null => {}
+                        }
+                    }
+                }
+                deleted_node_count
+            }
+        }
+    }
+
     pub fn overwrite_metadata(&mut self, meta: &Metadata) {
+        self.assert_writable();
         self.db
             .put_cf(self.cf(META_CF), [], scrypto_encode(meta).unwrap())
             .unwrap();
     }
 }
 
+/// Encodes the key of a historical Substate value: the Substate's RocksDB key followed by the
+/// big-endian version at which the value was upserted (so that all values of a Substate are stored
+/// contiguously, in version order).
+fn encode_historical_value_key(substate_key_bytes: &[u8], version: Version) -> Vec<u8> {
+    let mut key = Vec::with_capacity(substate_key_bytes.len() + 8);
+    key.extend_from_slice(substate_key_bytes);
+    key.extend_from_slice(&version.to_be_bytes());
+    key
+}
+
 impl SubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
     fn get_raw_substate_by_db_key(
         &self,
@@ -136,8 +512,10 @@ impl SubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
     }
 }
 
-impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
-    fn commit(&mut self, database_updates: &DatabaseUpdates) {
+impl RocksDBWithMerkleTreeSubstateStore {
+    fn commit_in_batch(&mut self, database_updates: &DatabaseUpdates, mut batch: WriteBatch) {
+        self.assert_writable();
+
         // read required info about current database state (here I fake it a bit)
         let metadata = self
             .db
@@ -151,8 +529,14 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
         let parent_state_version = metadata.current_state_version;
         let next_state_version = parent_state_version + 1;
 
-        // prepare a batch write (we use the same approach in the actual Node)
-        let mut batch = WriteBatch::default();
+        // all changes go into a single batch write (we use the same approach in the actual Node),
+        // which may already hold entries to commit atomically with them, e.g. of the event index
+
+        // unless only the current version is kept, index the stale tree parts - and if enabled,
+        // record the upserted values, and index the ones they supersede as stale
+        let index_stale_tree_parts = !self.retention.is_current_version_only();
+        let keep_history = index_stale_tree_parts && self.keep_historical_values;
+        let mut touched_substate_keys = Vec::new();
 
         // put regular substate changes
         for (node_key, node_updates) in &database_updates.node_updates {
@@ -165,38 +549,60 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
                     PartitionDatabaseUpdates::Delta { substate_updates } => {
                         for (sort_key, update) in substate_updates {
                             let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
+                            if keep_history {
+                                if let DatabaseUpdate::Set(value_bytes) = update {
+                                    batch.put_cf(
+                                        self.cf(HISTORICAL_SUBSTATE_VALUES_CF),
+                                        encode_historical_value_key(&key_bytes, next_state_version),
+                                        value_bytes,
+                                    );
+                                }
+                                touched_substate_keys.push(key_bytes.clone());
+                            }
                             match update {
                                 DatabaseUpdate::Set(value_bytes) => {
-                                    self.db
-                                        .put_cf(self.cf(SUBSTATES_CF), key_bytes, value_bytes)
+                                    batch.put_cf(self.cf(SUBSTATES_CF), key_bytes, value_bytes)
                                 }
                                 DatabaseUpdate::Delete => {
-                                    self.db.delete_cf(self.cf(SUBSTATES_CF), key_bytes)
+                                    batch.delete_cf(self.cf(SUBSTATES_CF), key_bytes)
                                 }
                             }
-                            .expect("IO error");
                         }
                     }
                     PartitionDatabaseUpdates::Reset {
                         new_substate_values,
                     } => {
+                        if keep_history {
+                            touched_substate_keys.extend(
+                                self.list_raw_values_from_db_key(&partition_key, None).map(
+                                    |(sort_key, _)| {
+                                        encode_to_rocksdb_bytes(&partition_key, &sort_key)
+                                    },
+                                ),
+                            );
+                            for (sort_key, value_bytes) in new_substate_values {
+                                let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
+                                batch.put_cf(
+                                    self.cf(HISTORICAL_SUBSTATE_VALUES_CF),
+                                    encode_historical_value_key(&key_bytes, next_state_version),
+                                    value_bytes,
+                                );
+                                touched_substate_keys.push(key_bytes);
+                            }
+                        }
                         // Note: a plain `delete_range()` is missing from rocksdb's API, and
                         // (at the moment of writing) this is the only reason of having CF.
-                        self.db
-                            .delete_range_cf(
-                                self.cf(SUBSTATES_CF),
-                                encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![])),
-                                encode_to_rocksdb_bytes(
-                                    &partition_key,
-                                    &DbSortKey(vec![u8::MAX; 2 * MAX_SUBSTATE_KEY_SIZE]),
-                                ),
-                            )
-                            .expect("IO error");
+                        batch.delete_range_cf(
+                            self.cf(SUBSTATES_CF),
+                            encode_to_rocksdb_bytes(&partition_key, &DbSortKey(vec![])),
+                            encode_to_rocksdb_bytes(
+                                &partition_key,
+                                &DbSortKey(vec![u8::MAX; 2 * MAX_SUBSTATE_KEY_SIZE]),
+                            ),
+                        );
                         for (sort_key, value_bytes) in new_substate_values {
                             let key_bytes = encode_to_rocksdb_bytes(&partition_key, sort_key);
-                            self.db
-                                .put_cf(self.cf(SUBSTATES_CF), key_bytes, value_bytes)
-                                .expect("IO error");
+                            batch.put_cf(self.cf(SUBSTATES_CF), key_bytes, value_bytes);
                         }
                     }
                 }
@@ -213,14 +619,21 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
                 scrypto_encode(&VersionedTreeNode::from_latest_version(node)).unwrap(),
             );
         }
-        if !self.pruning_enabled {
-            // If pruning is not enabled, we store the stale nodes in DB.
+        if index_stale_tree_parts {
+            // If older versions are kept, we index the stale parts for a later garbage collection.
             batch.put_cf(
                 self.cf(STALE_MERKLE_TREE_PARTS_CF),
                 next_state_version.to_be_bytes(),
                 scrypto_encode(&state_tree_diff.stale_tree_parts).unwrap(),
             );
         }
+        if keep_history {
+            batch.put_cf(
+                self.cf(STALE_HISTORICAL_SUBSTATE_VALUES_CF),
+                next_state_version.to_be_bytes(),
+                scrypto_encode(&touched_substate_keys).unwrap(),
+            );
+        }
 
         // update the metadata
         batch.put_cf(
@@ -236,51 +649,28 @@ impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
         // flush the batch
         self.db.write(batch).unwrap();
 
-        if self.pruning_enabled {
-            for part in state_tree_diff.stale_tree_parts.take() {
-                match part {
-                    StaleTreePart::Node(node_key) => {
-                        self.db
-                            .delete_cf(self.cf(MERKLE_NODES_CF), encode_key(&node_key))
-                            .unwrap();
-                    }
-                    StaleTreePart::Subtree(node_key) => {
-                        let mut queue = VecDeque::new();
-                        queue.push_back(node_key);
-
-                        while let Some(node_key) = queue.pop_front() {
-                            if let Some(bytes) = self
-                                .db
-                                .get_cf(self.cf(MERKLE_NODES_CF), encode_key(&node_key))
-                                .unwrap()
-                            {
-                                self.db
-                                    .delete_cf(self.cf(MERKLE_NODES_CF), encode_key(&node_key))
-                                    .unwrap();
-                                let value: VersionedTreeNode = scrypto_decode(&bytes).unwrap();
-                                match value.fully_update_and_into_latest_version() {
-                                    TreeNodeV1::Internal(x) => {
-                                        for child in x.children {
-                                            queue.push_back(
-                                                node_key.gen_child_node_key(
-                                                    child.version,
-                                                    child.nibble,
-                                                ),
-                                            )
-                                        }
-                                    }
-                                    TreeNodeV1::Leaf(_) => {}
-                                    TreeNodeV1::Null => {}
-                                }
-                            }
-                        }
-                    }
+        match self.retention {
+            StateTreeRetention::AllVersions => {}
+            retention if retention.is_current_version_only() => {
+                let mut batch = WriteBatch::default();
+                for part in state_tree_diff.stale_tree_parts.take() {
+                    self.delete_stale_tree_part(&mut batch, part);
                 }
+                self.db.write(batch).unwrap();
+            }
+            retention => {
+                self.garbage_collect(retention.first_retained_version(next_state_version));
             }
         }
     }
 }
 
+impl CommittableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
+    fn commit(&mut self, database_updates: &DatabaseUpdates) {
+        self.commit_in_batch(database_updates, WriteBatch::default());
+    }
+}
+
 impl ListableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
     fn list_partition_keys(&self) -> Box<dyn Iterator<Item = DbPartitionKey> + '_> {
         Box::new(
@@ -298,6 +688,38 @@ impl ListableSubstateDatabase for RocksDBWithMerkleTreeSubstateStore {
     }
 }
 
+impl EventIndexStore for RocksDBWithMerkleTreeSubstateStore {
+    fn get_event_index_entry(&self, key: &[u8]) -> Option<DbEventIndexValue> {
+        get_event_index_entry(&self.db, key)
+    }
+
+    fn list_event_index_entries_from(
+        &self,
+        from_key: &[u8],
+    ) -> Box<dyn Iterator<Item = (DbEventIndexKey, DbEventIndexValue)> + '_> {
+        list_event_index_entries_from(&self.db, from_key)
+    }
+
+    fn put_event_index_entries(&mut self, entries: Vec<(DbEventIndexKey, DbEventIndexValue)>) {
+        self.assert_writable();
+        let mut batch = WriteBatch::default();
+        put_event_index_entries(&self.db, &mut batch, entries);
+        self.db.write(batch).unwrap();
+    }
+}
+
+impl CommittableSubstateDatabaseWithEventIndex for RocksDBWithMerkleTreeSubstateStore {
+    fn commit_with_event_index_entries(
+        &mut self,
+        database_updates: &DatabaseUpdates,
+        event_index_entries: Vec<(DbEventIndexKey, DbEventIndexValue)>,
+    ) {
+        let mut batch = WriteBatch::default();
+        put_event_index_entries(&self.db, &mut batch, event_index_entries);
+        self.commit_in_batch(database_updates, batch);
+    }
+}
+
 impl ReadableTreeStore for RocksDBWithMerkleTreeSubstateStore {
     fn get_node(&self, key: &StoredTreeNodeKey) -> Option<TreeNode> {
         self.db
@@ -368,4 +790,295 @@ mod tests {
         });
         assert_eq!(db.list_partition_keys().count(), 8);
     }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn test_substate_proofs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut db = RocksDBWithMerkleTreeSubstateStore::with_options(
+            &{
+                let mut options = Options::default();
+                options.create_if_missing(true);
+                options.create_missing_column_families(true);
+                options
+            },
+            temp_dir.into_path(),
+            false,
+        );
+        let partition_key = DbPartitionKey {
+            node_key: vec![1, 2, 3],
+            partition_num: 64,
+        };
+        let sort_key = DbSortKey(vec![5]);
+
+        db.commit(&DatabaseUpdates::from_delta_maps(indexmap! {
+            partition_key.clone() => indexmap! {
+                sort_key.clone() => DatabaseUpdate::Set(vec![6]),
+            },
+        }));
+        let root_hash_v1 = db.get_current_root_hash();
+        db.commit(&DatabaseUpdates::from_delta_maps(indexmap! {
+            partition_key.clone() => indexmap! {
+                sort_key.clone() => DatabaseUpdate::Delete,
+            },
+        }));
+
+        let (value, proof) = db.get_substate_with_proof(&partition_key, &sort_key);
+        assert_eq!(value, None);
+        assert_eq!(
+            verify_substate_proof(
+                &proof,
+                db.get_current_root_hash(),
+                &partition_key,
+                &sort_key,
+                None
+            ),
+            Ok(())
+        );
+
+        let historical_proof = db
+            .get_substate_proof_at_version(1, &partition_key, &sort_key)
+            .unwrap();
+        assert_eq!(
+            verify_substate_proof(
+                &historical_proof,
+                root_hash_v1,
+                &partition_key,
+                &sort_key,
+                Some(&vec![6])
+            ),
+            Ok(())
+        );
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn test_secondary_instance_follows_primary() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let secondary_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        let partition_key = DbPartitionKey {
+            node_key: vec![1, 2, 3],
+            partition_num: 64,
+        };
+        let sort_key = DbSortKey(vec![5]);
+
+        let mut db = RocksDBWithMerkleTreeSubstateStore::standard(root.clone());
+        db.commit(&DatabaseUpdates::from_delta_maps(indexmap! {
+            partition_key.clone() => indexmap! {
+                sort_key.clone() => DatabaseUpdate::Set(vec![6]),
+            },
+        }));
+        let secondary = RocksDBWithMerkleTreeSubstateStore::secondary(
+            root.clone(),
+            secondary_dir.path().to_path_buf(),
+        )
+        .unwrap();
+        assert_eq!(secondary.get_current_version(), 1);
+
+        db.commit(&DatabaseUpdates::from_delta_maps(indexmap! {
+            partition_key.clone() => indexmap! {
+                sort_key.clone() => DatabaseUpdate::Set(vec![7]),
+            },
+        }));
+        assert_eq!(secondary.get_current_version(), 1);
+        secondary.try_catch_up_with_primary().unwrap();
+        assert_eq!(secondary.get_current_version(), 2);
+        assert_eq!(
+            secondary.get_current_root_hash(),
+            db.get_current_root_hash()
+        );
+        assert_eq!(
+            secondary.get_raw_substate_by_db_key(&partition_key, &sort_key),
+            Some(vec![7])
+        );
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn test_retention_window_and_historical_values() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut db = RocksDBWithMerkleTreeSubstateStore::with_options_and_retention(
+            &Options::default(),
+            temp_dir.into_path(),
+            StateTreeRetention::RecentVersions(2),
+        )
+        .with_historical_values();
+        let partition_key = DbPartitionKey {
+            node_key: vec![1, 2, 3],
+            partition_num: 64,
+        };
+        let sort_key = DbSortKey(vec![5]);
+
+        for value in [6, 7, 8] {
+            db.commit(&DatabaseUpdates::from_delta_maps(indexmap! {
+                partition_key.clone() => indexmap! {
+                    sort_key.clone() => DatabaseUpdate::Set(vec![value]),
+                },
+            }));
+        }
+
+        assert_eq!(db.get_first_retained_version(), 2);
+        assert!(!db.is_version_retained(1));
+        assert_eq!(db.list_substate_hashes_at_version(1), None);
+        assert_eq!(
+            db.get_substate_value_at_version(1, &partition_key, &sort_key),
+            None
+        );
+        assert_eq!(
+            db.get_substate_value_at_version(2, &partition_key, &sort_key),
+            Some(Some(vec![7]))
+        );
+        assert_eq!(
+            db.get_substate_value_at_version(3, &partition_key, &sort_key),
+            Some(Some(vec![8]))
+        );
+        assert_eq!(
+            db.list_substate_hashes_at_version(2),
+            Some(indexmap! {
+                partition_key.clone() => indexmap! {
+                    sort_key.clone() => hash(vec![7]),
+                },
+            })
+        );
+        assert_eq!(
+            db.list_entity_substate_values_at_version(2, &partition_key.node_key),
+            Some(vec![(
+                partition_key.clone(),
+                sort_key.clone(),
+                Some(vec![7])
+            )])
+        );
+        assert_eq!(
+            db.list_entity_substate_values_at_version(1, &partition_key.node_key),
+            None
+        );
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn test_explicit_garbage_collection() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut db = RocksDBWithMerkleTreeSubstateStore::with_options_and_retention(
+            &Options::default(),
+            temp_dir.into_path(),
+            StateTreeRetention::AllVersions,
+        )
+        .with_historical_values();
+        let partition_key = DbPartitionKey {
+            node_key: vec![1, 2, 3],
+            partition_num: 64,
+        };
+        let sort_key = DbSortKey(vec![5]);
+
+        db.commit(&DatabaseUpdates::from_delta_maps(indexmap! {
+            partition_key.clone() => indexmap! {
+                sort_key.clone() => DatabaseUpdate::Set(vec![6]),
+            },
+        }));
+        db.commit(&DatabaseUpdates::from_delta_maps(indexmap! {
+            partition_key.clone() => indexmap! {
+                sort_key.clone() => DatabaseUpdate::Delete,
+            },
+        }));
+        db.commit(&DatabaseUpdates::from_delta_maps(indexmap! {
+            partition_key.clone() => indexmap! {
+                DbSortKey(vec![9]) => DatabaseUpdate::Set(vec![10]),
+            },
+        }));
+
+        assert_eq!(
+            db.get_substate_value_at_version(1, &partition_key, &sort_key),
+            Some(Some(vec![6]))
+        );
+        assert_eq!(
+            db.get_substate_value_at_version(2, &partition_key, &sort_key),
+            Some(None)
+        );
+
+        assert!(db.garbage_collect(2) > 0);
+        assert_eq!(db.get_first_retained_version(), 2);
+        assert_eq!(
+            db.get_substate_value_at_version(1, &partition_key, &sort_key),
+            None
+        );
+        assert_eq!(
+            db.get_substate_value_at_version(2, &partition_key, &sort_key),
+            Some(None)
+        );
+        assert_eq!(
+            db.get_substate_value_at_version(3, &partition_key, &DbSortKey(vec![9])),
+            Some(Some(vec![10]))
+        );
+        assert_eq!(db.list_substate_hashes_at_version(2), Some(indexmap! {}));
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn test_disabled_pruning_keeps_trees_without_values() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut db = RocksDBWithMerkleTreeSubstateStore::with_options(
+            &Options::default(),
+            temp_dir.into_path(),
+            false,
+        );
+        let partition_key = DbPartitionKey {
+            node_key: vec![1, 2, 3],
+            partition_num: 64,
+        };
+        let sort_key = DbSortKey(vec![5]);
+
+        for value in [6, 7] {
+            db.commit(&DatabaseUpdates::from_delta_maps(indexmap! {
+                partition_key.clone() => indexmap! {
+                    sort_key.clone() => DatabaseUpdate::Set(vec![value]),
+                },
+            }));
+        }
+
+        assert_eq!(
+            db.list_substate_hashes_at_version(1),
+            Some(indexmap! {
+                partition_key.clone() => indexmap! {
+                    sort_key.clone() => hash(vec![6]),
+                },
+            })
+        );
+        assert_eq!(
+            db.get_substate_value_at_version(1, &partition_key, &sort_key),
+            None
+        );
+        assert_eq!(
+            db.get_substate_value_at_version(2, &partition_key, &sort_key),
+            Some(Some(vec![7]))
+        );
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    #[test]
+    fn test_commit_with_event_index_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut db = RocksDBWithMerkleTreeSubstateStore::standard(temp_dir.into_path());
+        let partition_key = DbPartitionKey {
+            node_key: vec![1, 2, 3],
+            partition_num: 64,
+        };
+        let sort_key = DbSortKey(vec![5]);
+
+        db.commit_with_event_index_entries(
+            &DatabaseUpdates::from_delta_maps(indexmap! {
+                partition_key.clone() => indexmap! {
+                    sort_key.clone() => DatabaseUpdate::Set(vec![6]),
+                },
+            }),
+            vec![(vec![1, 2], vec![3])],
+        );
+
+        assert_eq!(db.get_current_version(), 1);
+        assert_eq!(
+            db.get_raw_substate_by_db_key(&partition_key, &sort_key),
+            Some(vec![6])
+        );
+        assert_eq!(db.get_event_index_entry(&[1, 2]), Some(vec![3]));
+    }
 }
@@ -43,7 +43,7 @@ impl<'s, S> WriteableTreeStore for CollectingTreeStore<'s, S> {
         // intentionally empty
     }
 
-    fn record_stale_tree_part(&self, part: StaleTreePart) {
+    fn record_stale_tree_part(&self, _stale_since_version: Version, part: StaleTreePart) {
         self.diff.stale_tree_parts.borrow_mut().push(part);
     }
 }
@@ -90,9 +90,11 @@ impl<'s, S: WriteableTreeStore> WriteableTier for EntityTier<'s, S> {
             .insert_node(self.stored_node_key(local_key), node);
     }
 
-    fn record_stale_local_node(&self, local_key: &TreeNodeKey) {
-        self.base_store
-            .record_stale_tree_part(StaleTreePart::Node(self.stored_node_key(local_key)))
+    fn record_stale_local_node(&self, local_key: &TreeNodeKey, stale_since_version: Version) {
+        self.base_store.record_stale_tree_part(
+            stale_since_version,
+            StaleTreePart::Node(self.stored_node_key(local_key)),
+        )
     }
 
     fn set_root_version(&mut self, new_version: Option<Version>) {
@@ -3,6 +3,7 @@ pub mod tree_store;
 
 pub mod entity_tier;
 pub mod partition_tier;
+pub mod substate_proof;
 pub mod substate_tier;
 pub mod tier_framework;
 
@@ -13,9 +14,9 @@ use radix_substate_store_interface::interface::*;
 use tree_store::*;
 use types::*;
 
-// The sources copied from Aptos (the `jellyfish` and `types` modules) contain support for
-// generating proofs, which we plan to use in near future. Hence, we do not delete that code, but
-// suppress warnings.
+// The sources copied from Aptos (the `jellyfish` and `types` modules) contain more proof-related
+// features (e.g. range proofs) than we currently use (see `substate_proof`). Hence, we do not
+// delete that code, but suppress warnings.
 
 #[allow(dead_code)]
 mod jellyfish;
@@ -48,6 +49,10 @@ pub fn put_at_next_version<S: TreeStore>(
         .unwrap_or(SPARSE_MERKLE_PLACEHOLDER_HASH)
 }
 
+/// Lists the value hashes of all Substates in the tree of the given state version.
+///
+/// Note: the tree of that version is assumed to be fully available - see
+/// [`list_substate_hashes_at_retained_version()`] for a variant which checks it.
 pub fn list_substate_hashes_at_version<S: ReadableTreeStore>(
     tree_store: &S,
     root_state_version: Version,
@@ -68,3 +73,23 @@ pub fn list_substate_hashes_at_version<S: ReadableTreeStore>(
         })
         .collect()
 }
+
+/// Lists the value hashes of all Substates in the tree of the given state version, or returns
+/// `None` if that version's root node is not available (e.g. it was already pruned).
+///
+/// Note: this cannot detect a tree which was only partially garbage-collected - the callers are
+/// expected to track the first retained version.
+pub fn list_substate_hashes_at_retained_version<S: ReadableTreeStore>(
+    tree_store: &S,
+    root_state_version: Version,
+) -> Option<IndexMap<DbPartitionKey, IndexMap<DbSortKey, Hash>>> {
+    if root_state_version > 0 {
+        let root_node_key =
+            StoredTreeNodeKey::unprefixed(TreeNodeKey::new_empty_path(root_state_version));
+        tree_store.get_node(&root_node_key)?;
+    }
+    Some(list_substate_hashes_at_version(
+        tree_store,
+        root_state_version,
+    ))
+}
@@ -0,0 +1,279 @@
+use super::entity_tier::EntityTier;
+use super::partition_tier::PartitionTier;
+use super::substate_tier::SubstateTier;
+use super::tier_framework::*;
+use super::tree_store::*;
+use super::types::*;
+use radix_common::prelude::*;
+use radix_substate_store_interface::interface::*;
+
+// Re-exports
+pub use super::types::{LeafKey, SparseMerkleLeafNode, SparseMerkleProof};
+
+/// A composite Merkle proof of a single Substate's inclusion (or exclusion) in the "3-Tier JMT",
+/// at a specific state version.
+///
+/// It consists of a regular [`SparseMerkleProof`] for each tier which had to be visited in order to
+/// locate the Substate. A lower-tier proof is only present if the higher tier proves the existence
+/// of the corresponding nested tree (whose root hash is then the `value_hash` of the higher tier's
+/// proven leaf):
+/// * An absent entity is proven by the `entity_tier_proof` alone,
+/// * An absent partition (of an existing entity) is proven by the `entity_tier_proof` and the
+///   `partition_tier_proof`,
+/// * A present or absent Substate (within an existing partition) is proven by all three proofs.
+///
+/// The proof can be checked without any access to the state - see [`verify_substate_proof()`].
+#[derive(Clone, Debug, PartialEq, Eq, Sbor)]
+pub struct SubstateProof {
+    pub entity_tier_proof: SparseMerkleProof,
+    pub partition_tier_proof: Option<SparseMerkleProof>,
+    pub substate_tier_proof: Option<SparseMerkleProof>,
+}
+
+impl SubstateProof {
+    /// Creates a proof of any Substate's absence in an empty state (i.e. the one represented by
+    /// the [`SPARSE_MERKLE_PLACEHOLDER_HASH`] root).
+    pub fn of_empty_state() -> Self {
+        Self {
+            entity_tier_proof: SparseMerkleProof::new(None, vec![]),
+            partition_tier_proof: None,
+            substate_tier_proof: None,
+        }
+    }
+}
+
+/// The tier of the "3-Tier JMT" at which a [`SubstateProof`] verification failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProofTier {
+    Entity,
+    Partition,
+    Substate,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubstateProofVerificationError {
+    /// A tier's proof has more siblings than there are bits in the proven key.
+    TooManySiblings { tier: ProofTier },
+    /// A tier's proof is an exclusion proof based on some other leaf, but that leaf is not located
+    /// on the path of the proven key (i.e. it cannot prove anything about it).
+    UnrelatedExclusionLeaf { tier: ProofTier },
+    /// A tier's proof leads to a different root hash than expected (i.e. the root hash of the
+    /// entire state, or the value hash of the higher tier's proven leaf).
+    RootHashMismatch {
+        tier: ProofTier,
+        expected: Hash,
+        actual: Hash,
+    },
+    /// A tier's proof is missing, even though the higher tier proves the existence of its tree.
+    MissingTierProof { tier: ProofTier },
+    /// A tier's proof is present, even though the higher tier proves the absence of its tree.
+    UnexpectedTierProof { tier: ProofTier },
+    /// The proof is valid, but it proves a different Substate value (or presence) than expected.
+    SubstateValueMismatch {
+        expected_value_hash: Option<Hash>,
+        proven_value_hash: Option<Hash>,
+    },
+}
+
+/// Returns a proof of the given Substate's inclusion (or exclusion) at the given state version of
+/// the "3-Tier JMT" persisted within the given `TreeStore`, together with the Substate's value hash
+/// (if the Substate exists).
+///
+/// Returns `None` if the tree's root at the requested version is not available (i.e. either it was
+/// never created, or it has been already pruned).
+/// Version `0` denotes the initial empty state (not backed by any tree node).
+pub fn get_substate_proof_at_version<S: ReadableTreeStore>(
+    tree_store: &S,
+    root_state_version: Version,
+    partition_key: &DbPartitionKey,
+    sort_key: &DbSortKey,
+) -> Option<(Option<Hash>, SubstateProof)> {
+    if root_state_version == 0 {
+        return Some((None, SubstateProof::of_empty_state()));
+    }
+    let root_node_key =
+        StoredTreeNodeKey::unprefixed(TreeNodeKey::new_empty_path(root_state_version));
+    tree_store.get_node(&root_node_key)?;
+
+    let entity_tier = EntityTier::new(tree_store, Some(root_state_version));
+    let (entity_leaf, entity_tier_proof) =
+        entity_tier.get_persisted_leaf_with_proof(&partition_key.node_key);
+    let Some((_, entity_root_version)) = entity_leaf else {
+        return Some((
+            None,
+            SubstateProof {
+                entity_tier_proof,
+                partition_tier_proof: None,
+                substate_tier_proof: None,
+            },
+        ));
+    };
+
+    let partition_tier = PartitionTier::new(
+        tree_store,
+        Some(entity_root_version),
+        partition_key.node_key.clone(),
+    );
+    let (partition_leaf, partition_tier_proof) =
+        partition_tier.get_persisted_leaf_with_proof(&partition_key.partition_num);
+    let Some((_, partition_root_version)) = partition_leaf else {
+        return Some((
+            None,
+            SubstateProof {
+                entity_tier_proof,
+                partition_tier_proof: Some(partition_tier_proof),
+                substate_tier_proof: None,
+            },
+        ));
+    };
+
+    let substate_tier = SubstateTier::new(
+        tree_store,
+        Some(partition_root_version),
+        partition_key.node_key.clone(),
+        partition_key.partition_num,
+    );
+    let (substate_leaf, substate_tier_proof) =
+        substate_tier.get_persisted_leaf_with_proof(sort_key);
+    Some((
+        substate_leaf.map(|(value_hash, _)| value_hash),
+        SubstateProof {
+            entity_tier_proof,
+            partition_tier_proof: Some(partition_tier_proof),
+            substate_tier_proof: Some(substate_tier_proof),
+        },
+    ))
+}
+
+/// Verifies that the given proof shows the given Substate to have the `expected_value` (where
+/// `None` means "the Substate does not exist") in the state represented by the `root_hash`.
+///
+/// This function does not need any access to the state, and thus is suitable for light clients
+/// which only trust the root hash (e.g. from a ledger proof).
+pub fn verify_substate_proof(
+    proof: &SubstateProof,
+    root_hash: Hash,
+    partition_key: &DbPartitionKey,
+    sort_key: &DbSortKey,
+    expected_value: Option<&DbSubstateValue>,
+) -> Result<(), SubstateProofVerificationError> {
+    let proven_value_hash = verify_composite_proof(proof, root_hash, partition_key, sort_key)?;
+    let expected_value_hash = expected_value.map(hash);
+    if proven_value_hash != expected_value_hash {
+        return Err(SubstateProofVerificationError::SubstateValueMismatch {
+            expected_value_hash,
+            proven_value_hash,
+        });
+    }
+    Ok(())
+}
+
+/// Walks down the tiers of the given composite proof and returns the proven value hash of the
+/// Substate (or `None` if its absence was proven).
+fn verify_composite_proof(
+    proof: &SubstateProof,
+    root_hash: Hash,
+    partition_key: &DbPartitionKey,
+    sort_key: &DbSortKey,
+) -> Result<Option<Hash>, SubstateProofVerificationError> {
+    let entity_root_hash = verify_tier_proof(
+        ProofTier::Entity,
+        &proof.entity_tier_proof,
+        root_hash,
+        &EntityTier::<()>::to_leaf_key(&partition_key.node_key),
+    )?;
+    let Some(partition_root_hash) = verify_nested_tier_proof(
+        ProofTier::Partition,
+        proof.partition_tier_proof.as_ref(),
+        entity_root_hash,
+        &PartitionTier::<()>::to_leaf_key(&partition_key.partition_num),
+    )?
+    else {
+        return match proof.substate_tier_proof {
+            Some(_) => Err(SubstateProofVerificationError::UnexpectedTierProof {
+                tier: ProofTier::Substate,
+            }),
+            None => Ok(None),
+        };
+    };
+    verify_nested_tier_proof(
+        ProofTier::Substate,
+        proof.substate_tier_proof.as_ref(),
+        Some(partition_root_hash),
+        &SubstateTier::<()>::to_leaf_key(sort_key),
+    )
+}
+
+fn verify_nested_tier_proof(
+    tier: ProofTier,
+    proof: Option<&SparseMerkleProof>,
+    tier_root_hash: Option<Hash>,
+    key: &LeafKey,
+) -> Result<Option<Hash>, SubstateProofVerificationError> {
+    match (tier_root_hash, proof) {
+        (Some(tier_root_hash), Some(proof)) => verify_tier_proof(tier, proof, tier_root_hash, key),
+        (Some(_), None) => Err(SubstateProofVerificationError::MissingTierProof { tier }),
+        (None, Some(_)) => Err(SubstateProofVerificationError::UnexpectedTierProof { tier }),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Verifies a single tier's proof against the given root hash, and returns the value hash of the
+/// leaf under the given key (or `None` if its absence was proven).
+///
+/// The verification follows the regular Sparse Merkle Tree approach (just like the original Aptos'
+/// `SparseMerkleProof::verify()`), adjusted to our variable-length [`LeafKey`]s.
+fn verify_tier_proof(
+    tier: ProofTier,
+    proof: &SparseMerkleProof,
+    expected_root_hash: Hash,
+    key: &LeafKey,
+) -> Result<Option<Hash>, SubstateProofVerificationError> {
+    let siblings = proof.siblings();
+    let key_bit_count = key.bytes.len() * 8;
+    if siblings.len() > key_bit_count {
+        return Err(SubstateProofVerificationError::TooManySiblings { tier });
+    }
+
+    let proven_value_hash = match proof.leaf() {
+        Some(leaf) if leaf.key() == key => Some(*leaf.value_hash()),
+        Some(leaf) => {
+            // An exclusion proof based on another leaf must show that this leaf occupies the
+            // entire subtree on the proven key's path.
+            let common_prefix_bit_count = key
+                .iter_bits()
+                .zip(leaf.key().iter_bits())
+                .take_while(|(bit, leaf_bit)| bit == leaf_bit)
+                .count();
+            if common_prefix_bit_count < siblings.len() {
+                return Err(SubstateProofVerificationError::UnrelatedExclusionLeaf { tier });
+            }
+            None
+        }
+        None => None,
+    };
+
+    let leaf_hash = proof
+        .leaf()
+        .map(|leaf| leaf.hash())
+        .unwrap_or(SPARSE_MERKLE_PLACEHOLDER_HASH);
+    let actual_root_hash = siblings
+        .iter()
+        .zip(key.iter_bits().rev().skip(key_bit_count - siblings.len()))
+        .fold(leaf_hash, |hash, (sibling_hash, bit)| {
+            if bit {
+                SparseMerkleInternalNode::new(*sibling_hash, hash).hash()
+            } else {
+                SparseMerkleInternalNode::new(hash, *sibling_hash).hash()
+            }
+        });
+    if actual_root_hash != expected_root_hash {
+        return Err(SubstateProofVerificationError::RootHashMismatch {
+            tier,
+            expected: expected_root_hash,
+            actual: actual_root_hash,
+        });
+    }
+
+    Ok(proven_value_hash)
+}
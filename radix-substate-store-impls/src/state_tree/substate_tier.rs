@@ -82,9 +82,11 @@ impl<'s, S: WriteableTreeStore> WriteableTier for SubstateTier<'s, S> {
             .insert_node(self.stored_node_key(local_key), node);
     }
 
-    fn record_stale_local_node(&self, local_key: &TreeNodeKey) {
-        self.base_store
-            .record_stale_tree_part(StaleTreePart::Node(self.stored_node_key(local_key)))
+    fn record_stale_local_node(&self, local_key: &TreeNodeKey, stale_since_version: Version) {
+        self.base_store.record_stale_tree_part(
+            stale_since_version,
+            StaleTreePart::Node(self.stored_node_key(local_key)),
+        )
     }
 
     fn set_root_version(&mut self, new_version: Option<Version>) {
@@ -167,12 +169,14 @@ impl<'s, S: ReadableTreeStore + WriteableTreeStore> SubstateTier<'s, S> {
                 // * Setting this tier's root version to None, so that when we generate an update batch, it's
                 //   on an empty tree
                 if let Some(substate_root_version) = self.root_version {
-                    self.base_store
-                        .record_stale_tree_part(StaleTreePart::Subtree(
+                    self.base_store.record_stale_tree_part(
+                        next_version,
+                        StaleTreePart::Subtree(
                             self.stored_node_key(&TreeNodeKey::new_empty_path(
                                 substate_root_version,
                             )),
-                        ));
+                        ),
+                    );
                 }
                 self.set_root_version(None);
 
@@ -1,4 +1,5 @@
 use super::jellyfish::JellyfishMerkleTree;
+use super::substate_proof::*;
 use super::tier_framework::{StateTreeTier, TIER_SEPARATOR};
 use super::tree_store::*;
 use super::types::*;
@@ -375,7 +376,7 @@ fn records_stale_tree_node_keys() {
         .stale_part_buffer
         .borrow()
         .iter()
-        .map(|stale_part| {
+        .map(|(_, stale_part)| {
             let StaleTreePart::Node(key) = stale_part else {
                 panic!("expected only single node removals");
             };
@@ -445,7 +446,13 @@ fn records_stale_subtree_root_key_when_partition_removed() {
     ]);
     tester.reset_partition(from_seed(4), 7, vec![]);
     assert_eq!(
-        tester.tree_store.stale_part_buffer.borrow().to_vec(),
+        tester
+            .tree_store
+            .stale_part_buffer
+            .borrow()
+            .iter()
+            .map(|(_, stale_part)| stale_part.clone())
+            .collect::<Vec<_>>(),
         vec![
             // The entire subtree starting at the root of substate-tier JMT of partition `4:7`:
             StaleTreePart::Subtree(StoredTreeNodeKey::new(
@@ -863,6 +870,256 @@ fn lists_historical_substate_summaries() {
     );
 }
 
+#[test]
+fn keeps_recent_versions_according_to_retention() {
+    let mut tester = StateTreeTester::new(
+        TypedInMemoryTreeStore::new().with_retention(StateTreeRetention::RecentVersions(2)),
+    );
+    tester.put_substate_changes(vec![change(1, 6, 2, Some(30))]);
+    tester.put_substate_changes(vec![change(1, 6, 2, Some(40))]);
+    tester.put_substate_changes(vec![change(1, 6, 2, Some(50))]);
+
+    assert_eq!(tester.tree_store.list_substate_hashes_at_version(1), None);
+    assert_eq!(
+        tester.tree_store.list_substate_hashes_at_version(2),
+        Some(indexmap! {
+            partition_key(from_seed(1), 6) => indexmap! {
+                DbSortKey(from_seed(2)) => hash(from_seed(40)),
+            },
+        })
+    );
+    assert_eq!(
+        tester.tree_store.list_substate_hashes_at_version(3),
+        Some(indexmap! {
+            partition_key(from_seed(1), 6) => indexmap! {
+                DbSortKey(from_seed(2)) => hash(from_seed(50)),
+            },
+        })
+    );
+}
+
+#[test]
+fn garbage_collects_explicitly_when_keeping_all_versions() {
+    let mut tester = StateTreeTester::new_empty();
+    tester.put_substate_changes(vec![change(1, 6, 2, Some(30))]);
+    tester.put_substate_changes(vec![change(1, 6, 2, Some(40))]);
+    tester.put_substate_changes(vec![change(1, 6, 2, Some(50))]);
+    assert!(tester
+        .tree_store
+        .list_substate_hashes_at_version(1)
+        .is_some());
+
+    let deleted_count = tester.tree_store.garbage_collect(3);
+
+    assert!(deleted_count > 0);
+    assert!(tester.tree_store.stale_part_buffer.borrow().is_empty());
+    assert_eq!(tester.tree_store.list_substate_hashes_at_version(2), None);
+    assert!(tester
+        .tree_store
+        .list_substate_hashes_at_version(3)
+        .is_some());
+}
+
+#[test]
+fn substate_proof_verifies_inclusion() {
+    let mut tester = StateTreeTester::new_empty();
+    let root_hash = tester
+        .put_substate_changes(vec![
+            change(1, 9, 6, Some(196)),
+            change(2, 3, 4, Some(234)),
+            change(2, 3, 5, Some(235)),
+            change(2, 4, 7, Some(237)),
+        ])
+        .unwrap();
+
+    let (value_hash, proof) = tester
+        .tree_store
+        .get_substate_proof_at_version(1, &partition_key(from_seed(2), 3), &DbSortKey(from_seed(5)))
+        .unwrap();
+    assert_eq!(value_hash, Some(hash(from_seed(235))));
+    assert_eq!(
+        verify_substate_proof(
+            &proof,
+            root_hash,
+            &partition_key(from_seed(2), 3),
+            &DbSortKey(from_seed(5)),
+            Some(&from_seed(235)),
+        ),
+        Ok(())
+    );
+
+    // The same proof does not prove any other value, nor the absence:
+    assert!(matches!(
+        verify_substate_proof(
+            &proof,
+            root_hash,
+            &partition_key(from_seed(2), 3),
+            &DbSortKey(from_seed(5)),
+            Some(&from_seed(236)),
+        ),
+        Err(SubstateProofVerificationError::SubstateValueMismatch { .. })
+    ));
+    assert!(matches!(
+        verify_substate_proof(
+            &proof,
+            root_hash,
+            &partition_key(from_seed(2), 3),
+            &DbSortKey(from_seed(5)),
+            None,
+        ),
+        Err(SubstateProofVerificationError::SubstateValueMismatch { .. })
+    ));
+
+    // It also cannot be used for a different key:
+    assert!(verify_substate_proof(
+        &proof,
+        root_hash,
+        &partition_key(from_seed(2), 3),
+        &DbSortKey(from_seed(4)),
+        Some(&from_seed(235)),
+    )
+    .is_err());
+}
+
+#[test]
+fn substate_proof_verifies_exclusion_at_each_tier() {
+    let mut tester = StateTreeTester::new_empty();
+    let root_hash = tester
+        .put_substate_changes(vec![
+            change(1, 9, 6, Some(196)),
+            change(2, 3, 4, Some(234)),
+            change(2, 3, 5, Some(235)),
+            change(2, 4, 7, Some(237)),
+        ])
+        .unwrap();
+
+    let cases = vec![
+        (
+            partition_key(from_seed(3), 3),
+            DbSortKey(from_seed(5)),
+            false,
+            false,
+        ),
+        (
+            partition_key(from_seed(2), 5),
+            DbSortKey(from_seed(5)),
+            true,
+            false,
+        ),
+        (
+            partition_key(from_seed(2), 3),
+            DbSortKey(from_seed(6)),
+            true,
+            true,
+        ),
+    ];
+    for (partition_key, sort_key, has_partition_proof, has_substate_proof) in cases {
+        let (value_hash, proof) = tester
+            .tree_store
+            .get_substate_proof_at_version(1, &partition_key, &sort_key)
+            .unwrap();
+        assert_eq!(value_hash, None);
+        assert_eq!(proof.partition_tier_proof.is_some(), has_partition_proof);
+        assert_eq!(proof.substate_tier_proof.is_some(), has_substate_proof);
+        assert_eq!(
+            verify_substate_proof(&proof, root_hash, &partition_key, &sort_key, None),
+            Ok(())
+        );
+        assert!(matches!(
+            verify_substate_proof(
+                &proof,
+                root_hash,
+                &partition_key,
+                &sort_key,
+                Some(&from_seed(235))
+            ),
+            Err(SubstateProofVerificationError::SubstateValueMismatch { .. })
+        ));
+    }
+}
+
+#[test]
+fn substate_proof_fails_for_different_root_hash() {
+    let mut tester = StateTreeTester::new_empty();
+    let root_hash_v1 = tester
+        .put_substate_changes(vec![change(1, 9, 6, Some(196)), change(2, 3, 4, Some(234))])
+        .unwrap();
+    let root_hash_v2 = tester
+        .put_substate_changes(vec![change(2, 3, 4, Some(134))])
+        .unwrap();
+
+    let (_, proof_v2) = tester
+        .tree_store
+        .get_substate_proof_at_version(2, &partition_key(from_seed(2), 3), &DbSortKey(from_seed(4)))
+        .unwrap();
+    assert_eq!(
+        verify_substate_proof(
+            &proof_v2,
+            root_hash_v2,
+            &partition_key(from_seed(2), 3),
+            &DbSortKey(from_seed(4)),
+            Some(&from_seed(134)),
+        ),
+        Ok(())
+    );
+    assert!(matches!(
+        verify_substate_proof(
+            &proof_v2,
+            root_hash_v1,
+            &partition_key(from_seed(2), 3),
+            &DbSortKey(from_seed(4)),
+            Some(&from_seed(134)),
+        ),
+        Err(SubstateProofVerificationError::RootHashMismatch {
+            tier: ProofTier::Entity,
+            ..
+        })
+    ));
+
+    // The historical version is still provable (since this store does not prune):
+    let (_, proof_v1) = tester
+        .tree_store
+        .get_substate_proof_at_version(1, &partition_key(from_seed(2), 3), &DbSortKey(from_seed(4)))
+        .unwrap();
+    assert_eq!(
+        verify_substate_proof(
+            &proof_v1,
+            root_hash_v1,
+            &partition_key(from_seed(2), 3),
+            &DbSortKey(from_seed(4)),
+            Some(&from_seed(234)),
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn substate_proof_of_empty_and_unknown_state() {
+    let tester = StateTreeTester::new_empty();
+    let (value_hash, proof) = tester
+        .tree_store
+        .get_substate_proof_at_version(0, &partition_key(from_seed(2), 3), &DbSortKey(from_seed(4)))
+        .unwrap();
+    assert_eq!(value_hash, None);
+    assert_eq!(
+        verify_substate_proof(
+            &proof,
+            SPARSE_MERKLE_PLACEHOLDER_HASH,
+            &partition_key(from_seed(2), 3),
+            &DbSortKey(from_seed(4)),
+            None,
+        ),
+        Ok(())
+    );
+
+    let unknown_version_proof = tester.tree_store.get_substate_proof_at_version(
+        1,
+        &partition_key(from_seed(2), 3),
+        &DbSortKey(from_seed(4)),
+    );
+    assert_eq!(unknown_version_proof, None);
+}
+
 type SingleSubstateChange = (DbSubstateKey, DatabaseUpdate);
 
 fn change(
@@ -998,7 +1255,7 @@ impl StateTreeTester<TypedInMemoryTreeStore> {
         let binding = self.tree_store.stale_part_buffer.borrow().clone();
         let stale_node_keys = binding
             .into_iter()
-            .flat_map(|stale_part| match stale_part {
+            .flat_map(|(_, stale_part)| match stale_part {
                 StaleTreePart::Node(key) => vec![key],
                 StaleTreePart::Subtree(key) => JellyfishMerkleTree::new(&self.tree_store)
                     .get_all_nodes_referenced(TreeNodeKey::new(
@@ -48,6 +48,27 @@ pub trait ReadableTier: StateTreeTier {
         let (leaf_node_data, _proof) = self.jmt().get_with_proof(&leaf_key, root_version).unwrap();
         leaf_node_data.map(|(_hash, payload, _version)| payload)
     }
+
+    /// Gets the leaf's value hash and payload (if it exists), together with a proof of its
+    /// inclusion (or exclusion) under this tier's root.
+    /// An empty tier (i.e. without any root version) yields a trivial proof, matching the
+    /// [`SPARSE_MERKLE_PLACEHOLDER_HASH`].
+    fn get_persisted_leaf_with_proof(
+        &self,
+        key: &Self::TypedLeafKey,
+    ) -> (Option<(Hash, Self::Payload)>, SparseMerkleProof) {
+        let Some(root_version) = self.root_version() else {
+            return (None, SparseMerkleProof::new(None, vec![]));
+        };
+
+        let leaf_key = Self::to_leaf_key(key);
+
+        let (leaf_node_data, proof) = self.jmt().get_with_proof(&leaf_key, root_version).unwrap();
+        (
+            leaf_node_data.map(|(value_hash, payload, _version)| (value_hash, payload)),
+            proof,
+        )
+    }
 }
 
 pub struct TierLeaf<T: StateTreeTier> {
@@ -180,8 +201,8 @@ pub trait WriteableTier: StateTreeTier {
     fn insert_local_node(&self, local_key: &TreeNodeKey, node: Self::StoredNode);
 
     /// Marks the given tree part for a future removal by an arbitrary external pruning
-    /// process.
-    fn record_stale_local_node(&self, local_key: &TreeNodeKey);
+    /// process, as no longer needed by the trees of the given version onwards.
+    fn record_stale_local_node(&self, local_key: &TreeNodeKey, stale_since_version: Version);
 
     /// Sets the root version of the TierView
     fn set_root_version(&mut self, new_version: Option<Version>);
@@ -233,7 +254,7 @@ pub trait RwTier: ReadableTier + WriteableTier {
             self.insert_local_node(key, Self::StoredNode::from_jmt_node(node, key));
         }
         for stale_node in update_batch.stale_node_index_batch.iter().flatten() {
-            self.record_stale_local_node(&stale_node.node_key);
+            self.record_stale_local_node(&stale_node.node_key, stale_node.stale_since_version);
         }
 
         self.set_root_version(Some(*new_version));
@@ -1,10 +1,12 @@
+use super::substate_proof::*;
 use super::tier_framework::StoredNode;
 // Re-exports
 pub use super::types::{Nibble, NibblePath, TreeNodeKey, Version};
-use super::{Node, StorageError, TreeReader};
+use super::{list_substate_hashes_at_retained_version, Node, StorageError, TreeReader};
 
 use radix_common::prelude::*;
 use radix_substate_store_interface::interface::*;
+use sbor::rust::cell::Cell;
 use sbor::rust::cell::Ref;
 use sbor::rust::cell::RefCell;
 
@@ -164,8 +166,9 @@ pub trait WriteableTreeStore {
     );
 
     /// Marks the given tree part for a (potential) future removal by an arbitrary external pruning
-    /// process.
-    fn record_stale_tree_part(&self, global_tree_part: StaleTreePart);
+    /// process, as no longer needed by the trees of the given version onwards (but still needed by
+    /// the trees of previous versions).
+    fn record_stale_tree_part(&self, stale_since_version: Version, global_tree_part: StaleTreePart);
 }
 
 /// A Substate value associated with a tree leaf (see [`WriteableTreeStore#associate_substate()`]).
@@ -186,14 +189,51 @@ pub enum AssociatedSubstateValue<'v> {
 pub trait TreeStore: ReadableTreeStore + WriteableTreeStore {}
 impl<S: ReadableTreeStore + WriteableTreeStore> TreeStore for S {}
 
+/// A policy of how long the tree parts which became stale are kept, i.e. which historical
+/// versions of the tree remain readable.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StateTreeRetention {
+    /// Keeps the trees of the given number of most recent versions (including the current one),
+    /// garbage-collecting older tree parts as new versions are put.
+    ///
+    /// A value of `1` (or `0`) keeps only the current tree, deleting stale parts immediately.
+    RecentVersions(u64),
+    /// Keeps the trees of all versions, unless garbage-collected explicitly.
+    AllVersions,
+}
+
+impl StateTreeRetention {
+    /// Keeps only the current tree (i.e. the pruning behavior).
+    pub const CURRENT_VERSION_ONLY: Self = Self::RecentVersions(1);
+
+    /// Returns the first version whose tree must still be readable when the given version is the
+    /// current one.
+    pub fn first_retained_version(&self, current_version: Version) -> Version {
+        match self {
+            Self::RecentVersions(count) => (current_version + 1).saturating_sub((*count).max(1)),
+            Self::AllVersions => 0,
+        }
+    }
+
+    /// Returns true if stale tree parts are deleted as soon as they are recorded.
+    pub fn is_current_version_only(&self) -> bool {
+        matches!(self, Self::RecentVersions(count) if *count <= 1)
+    }
+}
+
 /// A `TreeStore` based on memory object copies (i.e. no serialization).
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct TypedInMemoryTreeStore {
     pub tree_nodes: RefCell<HashMap<StoredTreeNodeKey, TreeNode>>,
-    pub stale_part_buffer: RefCell<Vec<StaleTreePart>>,
+    /// The recorded tree parts which were not garbage-collected yet, together with the version
+    /// they became stale at (i.e. ordered by that version, so that they are collected from the
+    /// front).
+    pub stale_part_buffer: RefCell<VecDeque<(Version, StaleTreePart)>>,
     pub associated_substates:
         RefCell<HashMap<StoredTreeNodeKey, (DbSubstateKey, Option<DbSubstateValue>)>>,
-    pub pruning_enabled: bool,
+    pub retention: StateTreeRetention,
+    /// The version of the oldest tree which was not (even partially) garbage-collected.
+    pub first_retained_version: Cell<Version>,
     pub store_associated_substates: bool,
 }
 
@@ -202,18 +242,20 @@ impl TypedInMemoryTreeStore {
     pub fn new() -> Self {
         Self {
             tree_nodes: RefCell::new(hash_map_new()),
-            stale_part_buffer: RefCell::new(Vec::new()),
+            stale_part_buffer: RefCell::new(VecDeque::new()),
             associated_substates: RefCell::new(hash_map_new()),
-            pruning_enabled: false,
+            retention: StateTreeRetention::AllVersions,
+            first_retained_version: Cell::new(0),
             store_associated_substates: false,
         }
     }
 
     pub fn with_pruning_enabled(self) -> Self {
-        Self {
-            pruning_enabled: true,
-            ..self
-        }
+        self.with_retention(StateTreeRetention::CURRENT_VERSION_ONLY)
+    }
+
+    pub fn with_retention(self, retention: StateTreeRetention) -> Self {
+        Self { retention, ..self }
     }
 
     pub fn storing_associated_substates(self) -> Self {
@@ -222,6 +264,93 @@ impl TypedInMemoryTreeStore {
             ..self
         }
     }
+
+    /// Returns the value hash of the given Substate (if it exists) together with a proof of its
+    /// inclusion (or exclusion) at the given state version, or `None` if that version's tree is
+    /// not available (see [`get_substate_proof_at_version()`]).
+    pub fn get_substate_proof_at_version(
+        &self,
+        version: Version,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> Option<(Option<Hash>, SubstateProof)> {
+        get_substate_proof_at_version(self, version, partition_key, sort_key)
+    }
+
+    /// Returns the value hashes of all Substates at the given state version, or `None` if that
+    /// version's tree is not available (i.e. was garbage-collected, or does not exist yet).
+    pub fn list_substate_hashes_at_version(
+        &self,
+        version: Version,
+    ) -> Option<IndexMap<DbPartitionKey, IndexMap<DbSortKey, Hash>>> {
+        if version < self.first_retained_version.get() {
+            return None;
+        }
+        list_substate_hashes_at_retained_version(self, version)
+    }
+
+    /// Deletes all tree parts which are not needed by the trees of the given version onwards, and
+    /// returns the number of deleted nodes.
+    ///
+    /// This is done automatically as new versions are put, unless the [`StateTreeRetention`] keeps
+    /// all versions.
+    pub fn garbage_collect(&self, first_retained_version: Version) -> usize {
+        // Draining from the front of a `VecDeque` only costs the drained parts
+        let collectable = {
+            let mut stale_part_buffer = self.stale_part_buffer.borrow_mut();
+            let collectable_count =
+                stale_part_buffer.partition_point(|(stale_since_version, _)| {
+                    *stale_since_version <= first_retained_version
+                });
+            stale_part_buffer
+                .drain(..collectable_count)
+                .collect::<Vec<_>>()
+        };
+        let deleted_count = collectable
+            .into_iter()
+            .map(|(_, part)| self.delete_tree_part(part))
+            .sum();
+        if first_retained_version > self.first_retained_version.get() {
+            self.first_retained_version.set(first_retained_version);
+        }
+        deleted_count
+    }
+
+    fn delete_tree_part(&self, part: StaleTreePart) -> usize {
+        let mut deleted_count = 0;
+        let mut queue = VecDeque::new();
+        let recursive = match part {
+            StaleTreePart::Node(node_key) => {
+                queue.push_back(node_key);
+                false
+            }
+            StaleTreePart::Subtree(node_key) => {
+                queue.push_back(node_key);
+                true
+            }
+        };
+
+        while let Some(node_key) = queue.pop_front() {
+            self.associated_substates.borrow_mut().remove(&node_key);
+            if let Some(value) = self.tree_nodes.borrow_mut().remove(&node_key) {
+                deleted_count += 1;
+                if !recursive {
+                    continue;
+                }
+                match value {
+                    TreeNodeV1::Internal(x) => {
+                        for child in x.children {
+                            queue
+                                .push_back(node_key.gen_child_node_key(child.version, child.nibble))
+                        }
+                    }
+                    TreeNodeV1::Leaf(_) => {}
+                    TreeNodeV1::Null => {}
+                }
+            }
+        }
+        deleted_count
+    }
 }
 
 impl Default for TypedInMemoryTreeStore {
@@ -273,36 +402,12 @@ impl WriteableTreeStore for TypedInMemoryTreeStore {
         }
     }
 
-    fn record_stale_tree_part(&self, part: StaleTreePart) {
-        if self.pruning_enabled {
-            match part {
-                StaleTreePart::Node(node_key) => {
-                    self.tree_nodes.borrow_mut().remove(&node_key);
-                }
-                StaleTreePart::Subtree(node_key) => {
-                    let mut queue = VecDeque::new();
-                    queue.push_back(node_key);
-
-                    while let Some(node_key) = queue.pop_front() {
-                        if let Some(value) = self.tree_nodes.borrow_mut().remove(&node_key) {
-                            match value {
-                                TreeNodeV1::Internal(x) => {
-                                    for child in x.children {
-                                        queue.push_back(
-                                            node_key
-                                                .gen_child_node_key(child.version, child.nibble),
-                                        )
-                                    }
-                                }
-                                TreeNodeV1::Leaf(_) => {}
-                                TreeNodeV1::Null => {}
-                            }
-                        }
-                    }
-                }
-            }
-        } else {
-            self.stale_part_buffer.borrow_mut().push(part);
+    fn record_stale_tree_part(&self, stale_since_version: Version, part: StaleTreePart) {
+        self.stale_part_buffer
+            .borrow_mut()
+            .push_back((stale_since_version, part));
+        if self.retention != StateTreeRetention::AllVersions {
+            self.garbage_collect(self.retention.first_retained_version(stale_since_version));
         }
     }
 }
@@ -360,7 +465,7 @@ impl WriteableTreeStore for SerializedInMemoryTreeStore {
         // intentionally empty
     }
 
-    fn record_stale_tree_part(&self, part: StaleTreePart) {
+    fn record_stale_tree_part(&self, _stale_since_version: Version, part: StaleTreePart) {
         self.stale_part_buffer
             .borrow_mut()
             .push(scrypto_encode(&part).unwrap());
@@ -150,7 +150,7 @@ impl SparseMerkleProof {
 
 /// A proof that can be used to authenticate an element in a Sparse Merkle Tree given trusted root
 /// hash. For example, `TransactionInfoToAccountProof` can be constructed on top of this structure.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Sbor)]
 pub struct SparseMerkleProof {
     /// This proof can be used to authenticate whether a given leaf exists in the tree or not.
     ///     - If this is `Some(leaf_node)`
@@ -239,7 +239,7 @@ impl SparseMerkleRangeProof {
 }
 
 // SOURCE: https://github.com/aptos-labs/aptos-core/blob/1.0.4/types/src/proof/mod.rs#L97
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Sbor)]
 pub struct SparseMerkleLeafNode {
     key: LeafKey,
     value_hash: Hash,
@@ -276,7 +276,7 @@ impl SparseMerkleInternalNode {
         }
     }
 
-    fn hash(&self) -> Hash {
+    pub fn hash(&self) -> Hash {
         hash([self.left_child.0, self.right_child.0].concat())
     }
 }
@@ -672,7 +672,7 @@ impl<'a> NibbleIterator<'a> {
 
 // INITIAL-MODIFICATION: We will use this type (instead of `Hash`) to allow for arbitrary key length
 /// A leaf key (i.e. a complete nibble path).
-#[derive(Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd, Sbor)]
 pub struct LeafKey {
     /// The underlying bytes.
     /// All leaf keys of the same tree must be of the same length - otherwise the tree's behavior
@@ -1,3 +1,4 @@
+use crate::state_tree::substate_proof::SubstateProof;
 use crate::state_tree::tree_store::{TypedInMemoryTreeStore, Version};
 use crate::state_tree::{list_substate_hashes_at_version, put_at_next_version};
 use radix_common::prelude::*;
@@ -33,6 +34,20 @@ impl<D> StateTreeUpdatingDatabase<D> {
         list_substate_hashes_at_version(&self.tree_store, self.current_version)
     }
 
+    /// Returns a proof of the given Substate's inclusion (or exclusion) under the
+    /// [`Self::get_current_root_hash()`].
+    pub fn get_substate_proof(
+        &self,
+        partition_key: &DbPartitionKey,
+        sort_key: &DbSortKey,
+    ) -> SubstateProof {
+        let (_, proof) = self
+            .tree_store
+            .get_substate_proof_at_version(self.current_version, partition_key, sort_key)
+            .expect("current state tree root must exist");
+        proof
+    }
+
     fn update_with(&mut self, db_updates: &DatabaseUpdates) {
         self.current_hash = put_at_next_version(
             &self.tree_store,
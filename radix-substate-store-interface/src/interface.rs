@@ -733,3 +733,36 @@ pub trait ListableSubstateDatabaseExtensions: ListableSubstateDatabase {
         Box::new(iterator)
     }
 }
+
+pub type DbEventIndexKey = Vec<u8>;
+
+pub type DbEventIndexValue = Vec<u8>;
+
+/// A raw, ordered key-value interface for an index of committed events, kept next to a substate
+/// database. The key layout is owned by the layer interpreting the events.
+pub trait EventIndexStore {
+    /// Gets the value stored under the given key, if any.
+    fn get_event_index_entry(&self, key: &[u8]) -> Option<DbEventIndexValue>;
+
+    /// Iterates over the entries with keys greater than or equal to the given key, in key order.
+    fn list_event_index_entries_from(
+        &self,
+        from_key: &[u8],
+    ) -> Box<dyn Iterator<Item = (DbEventIndexKey, DbEventIndexValue)> + '_>;
+
+    /// Writes the given entries, replacing any existing values under the same keys.
+    fn put_event_index_entries(&mut self, entries: Vec<(DbEventIndexKey, DbEventIndexValue)>);
+}
+
+/// A substate database keeping an [`EventIndexStore`] of its own, which can commit the index
+/// entries of a transaction atomically with its database updates.
+pub trait CommittableSubstateDatabaseWithEventIndex:
+    CommittableSubstateDatabase + EventIndexStore
+{
+    /// Commits the given database updates together with the given event index entries.
+    fn commit_with_event_index_entries(
+        &mut self,
+        database_updates: &DatabaseUpdates,
+        event_index_entries: Vec<(DbEventIndexKey, DbEventIndexValue)>,
+    );
+}
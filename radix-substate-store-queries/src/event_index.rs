@@ -0,0 +1,364 @@
+//! An index of the application events of committed transactions, kept in an [`EventIndexStore`]
+//! next to the substate database, which can be queried by emitter, blueprint, event name and
+//! version range.
+//!
+//! Each transaction is indexed under its version in the ledger, e.g. the state version. A substate
+//! database which keeps the index itself can commit it atomically with the transaction, see
+//! [`EventIndexStoreExtensions::commit_and_index_events`]. The index has the following layout:
+//! * `[METADATA_PREFIX]` - the last indexed version.
+//! * `[EVENTS_PREFIX][version][event index]` - the [`IndexedEventValue`].
+//! * `[BY_EMITTER_PREFIX][hash(emitter)][version][event index]` - empty.
+//! * `[BY_BLUEPRINT_PREFIX][hash(blueprint id)][version][event index]` - empty.
+//!
+//! The versions and event indices are big-endian, so that the entries are ordered by them.
+
+use crate::typed_native_events::*;
+use core::ops::{Bound, RangeBounds};
+use radix_common::prelude::*;
+use radix_engine::system::system_db_reader::{SystemDatabaseReader, SystemReaderError};
+use radix_engine_interface::blueprints::package::BlueprintPayloadDef;
+use radix_engine_interface::prelude::*;
+use radix_rust::copy_u8_array;
+use radix_substate_store_interface::interface::*;
+use sbor::representations::*;
+
+const METADATA_PREFIX: u8 = 0;
+const EVENTS_PREFIX: u8 = 1;
+const BY_EMITTER_PREFIX: u8 = 2;
+const BY_BLUEPRINT_PREFIX: u8 = 3;
+
+/// The length of the `[version][event index]` suffix of the index keys.
+const EVENT_KEY_SUFFIX_LENGTH: usize = 8 + 4;
+
+/// The value of an event entry in the index.
+#[derive(Debug, Clone, PartialEq, Eq, ScryptoSbor)]
+pub struct IndexedEventValue {
+    pub type_identifier: EventTypeIdentifier,
+    /// The blueprint of the emitter, as of the commit of the event. This is [`None`] if it could
+    /// not be resolved, e.g. because the emitting object was deleted in the same transaction.
+    pub blueprint_id: Option<BlueprintId>,
+    pub payload: Vec<u8>,
+}
+
+/// An event read from the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedEvent {
+    pub version: u64,
+    /// The index of the event among the application events of its transaction.
+    pub index: u32,
+    pub type_identifier: EventTypeIdentifier,
+    pub blueprint_id: Option<BlueprintId>,
+    pub payload: Vec<u8>,
+}
+
+impl IndexedEvent {
+    pub fn emitter(&self) -> &Emitter {
+        &self.type_identifier.0
+    }
+
+    pub fn event_name(&self) -> &str {
+        self.type_identifier.1.as_str()
+    }
+
+    pub fn decode<T: ScryptoDecode>(&self) -> Result<T, DecodeError> {
+        scrypto_decode(&self.payload)
+    }
+
+    /// Decodes the payload of an event emitted by a native blueprint.
+    pub fn to_typed_native_event(&self) -> Result<TypedNativeEvent, TypedNativeEventError> {
+        to_typed_native_event(&self.type_identifier, &self.payload)
+    }
+
+    /// Formats the payload with the event's schema, as registered by the blueprint of its emitter.
+    pub fn to_annotated_string<S: SubstateDatabase + ?Sized>(
+        &self,
+        reader: &SystemDatabaseReader<S>,
+        address_bech32_encoder: Option<&AddressBech32Encoder>,
+    ) -> Result<String, SystemReaderError> {
+        let blueprint_id = self
+            .blueprint_id
+            .as_ref()
+            .ok_or(SystemReaderError::BlueprintDoesNotExist)?;
+        let (type_id, schema) = get_event_schema(reader, blueprint_id, self.event_name())?;
+        let display_parameters = ValueDisplayParameters::Annotated {
+            display_mode: DisplayMode::RustLike(RustLikeOptions::full()),
+            print_mode: PrintMode::SingleLine,
+            custom_context: ScryptoValueDisplayContext::with_optional_bech32(
+                address_bech32_encoder,
+            ),
+            schema: schema.v1(),
+            type_id,
+            depth_limit: SCRYPTO_SBOR_V1_MAX_DEPTH,
+        };
+        let payload = ScryptoRawPayload::new_from_valid_slice(&self.payload);
+        Ok(payload.to_string(display_parameters))
+    }
+}
+
+/// Resolves the schema of the given event of the given blueprint.
+pub fn get_event_schema<S: SubstateDatabase + ?Sized>(
+    reader: &SystemDatabaseReader<S>,
+    blueprint_id: &BlueprintId,
+    event_name: &str,
+) -> Result<(LocalTypeId, Rc<VersionedScryptoSchema>), SystemReaderError> {
+    let definition = reader.get_blueprint_definition(blueprint_id)?;
+    match definition.interface.get_event_payload_def(event_name) {
+        Some(BlueprintPayloadDef::Static(type_identifier)) => {
+            let schema = reader.get_schema(
+                blueprint_id.package_address.as_node_id(),
+                &type_identifier.0,
+            )?;
+            Ok((type_identifier.1, schema))
+        }
+        Some(BlueprintPayloadDef::Generic(_)) => Err(SystemReaderError::TargetNotSupported),
+        None => Err(SystemReaderError::PayloadDoesNotExist),
+    }
+}
+
+/// Resolves the blueprints of the emitters of the given application events, for indexing them.
+///
+/// This must be called after the transaction which emitted the events is committed to the given
+/// substate database, so that the objects created by the transaction can be resolved.
+pub fn resolve_events<S: SubstateDatabase + ?Sized>(
+    substate_db: &S,
+    events: &[(EventTypeIdentifier, Vec<u8>)],
+) -> Vec<IndexedEventValue> {
+    resolve_events_with_reader(&SystemDatabaseReader::new(substate_db), events)
+}
+
+/// Like [`resolve_events`], but for a transaction which is not committed yet, given its state
+/// updates.
+pub fn resolve_uncommitted_events<S: SubstateDatabase + ?Sized>(
+    substate_db: &S,
+    state_updates: &StateUpdates,
+    events: &[(EventTypeIdentifier, Vec<u8>)],
+) -> Vec<IndexedEventValue> {
+    resolve_events_with_reader(
+        &SystemDatabaseReader::new_with_overlay(substate_db, state_updates),
+        events,
+    )
+}
+
+fn resolve_events_with_reader<S: SubstateDatabase + ?Sized>(
+    reader: &SystemDatabaseReader<S>,
+    events: &[(EventTypeIdentifier, Vec<u8>)],
+) -> Vec<IndexedEventValue> {
+    events
+        .iter()
+        .map(|(type_identifier, payload)| {
+            let blueprint_id = match &type_identifier.0 {
+                Emitter::Function(blueprint_id) => Some(blueprint_id.clone()),
+                Emitter::Method(node_id, module_id) => {
+                    reader.get_blueprint_id(node_id, *module_id).ok()
+                }
+            };
+            IndexedEventValue {
+                type_identifier: type_identifier.clone(),
+                blueprint_id,
+                payload: payload.clone(),
+            }
+        })
+        .collect()
+}
+
+/// A filter over the indexed events.
+///
+/// Queries with an emitter or a blueprint use the respective secondary index, and the other
+/// criteria are checked against the matching events. A query with just an event name and/or a
+/// version range goes through all events in the range.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventQuery {
+    pub emitter: Option<Emitter>,
+    pub blueprint_id: Option<BlueprintId>,
+    pub event_name: Option<String>,
+    pub from_version: Option<u64>,
+    /// Inclusive.
+    pub to_version: Option<u64>,
+}
+
+impl EventQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn emitter(mut self, emitter: Emitter) -> Self {
+        self.emitter = Some(emitter);
+        self
+    }
+
+    /// Filters the events emitted by a module of the given node, e.g. `ModuleId::Main` of a vault.
+    pub fn method_emitter(self, node_id: impl Into<NodeId>, module_id: ModuleId) -> Self {
+        self.emitter(Emitter::Method(node_id.into(), module_id))
+    }
+
+    pub fn blueprint(mut self, blueprint_id: BlueprintId) -> Self {
+        self.blueprint_id = Some(blueprint_id);
+        self
+    }
+
+    pub fn event_name(mut self, event_name: impl Into<String>) -> Self {
+        self.event_name = Some(event_name.into());
+        self
+    }
+
+    pub fn versions(mut self, versions: impl RangeBounds<u64>) -> Self {
+        self.from_version = match versions.start_bound() {
+            Bound::Included(version) => Some(*version),
+            Bound::Excluded(version) => Some(version.saturating_add(1)),
+            Bound::Unbounded => None,
+        };
+        self.to_version = match versions.end_bound() {
+            Bound::Included(version) => Some(*version),
+            Bound::Excluded(version) => Some(version.saturating_sub(1)),
+            Bound::Unbounded => None,
+        };
+        self
+    }
+
+    fn matches(&self, event: &IndexedEvent) -> bool {
+        self.emitter
+            .as_ref()
+            .is_none_or(|emitter| event.emitter() == emitter)
+            && self
+                .blueprint_id
+                .as_ref()
+                .is_none_or(|blueprint_id| event.blueprint_id.as_ref() == Some(blueprint_id))
+            && self
+                .event_name
+                .as_ref()
+                .is_none_or(|event_name| event.event_name() == event_name)
+    }
+}
+
+impl<T: EventIndexStore + ?Sized> EventIndexStoreExtensions for T {}
+
+pub trait EventIndexStoreExtensions: EventIndexStore {
+    /// Returns the last indexed version, or [`None`] if nothing was indexed yet.
+    fn get_last_indexed_version(&self) -> Option<u64> {
+        self.get_event_index_entry(&[METADATA_PREFIX])
+            .map(|value| u64::from_be_bytes(value.try_into().expect("Invalid version")))
+    }
+
+    /// Indexes the events of a transaction, as returned by [`resolve_events`], under the given
+    /// version.
+    fn index_events_at_version(&mut self, version: u64, events: Vec<IndexedEventValue>) {
+        let entries = self.create_event_index_entries(version, events);
+        self.put_event_index_entries(entries);
+    }
+
+    /// Commits the database updates of a transaction together with the index of its events, as
+    /// returned by [`resolve_uncommitted_events`], under the given version.
+    fn commit_and_index_events(
+        &mut self,
+        database_updates: &DatabaseUpdates,
+        version: u64,
+        events: Vec<IndexedEventValue>,
+    ) where
+        Self: CommittableSubstateDatabaseWithEventIndex,
+    {
+        let entries = self.create_event_index_entries(version, events);
+        self.commit_with_event_index_entries(database_updates, entries);
+    }
+
+    /// Creates the index entries of the events of a transaction under the given version.
+    fn create_event_index_entries(
+        &self,
+        version: u64,
+        events: Vec<IndexedEventValue>,
+    ) -> Vec<(DbEventIndexKey, DbEventIndexValue)> {
+        let mut entries = Vec::new();
+        for (index, event) in events.into_iter().enumerate() {
+            let suffix = encode_event_key_suffix(version, index as u32);
+            entries.push((
+                encode_secondary_key(BY_EMITTER_PREFIX, &event.type_identifier.0, &suffix),
+                vec![],
+            ));
+            if let Some(blueprint_id) = &event.blueprint_id {
+                entries.push((
+                    encode_secondary_key(BY_BLUEPRINT_PREFIX, blueprint_id, &suffix),
+                    vec![],
+                ));
+            }
+            entries.push((
+                [&[EVENTS_PREFIX], suffix.as_slice()].concat(),
+                scrypto_encode(&event).unwrap(),
+            ));
+        }
+
+        let last_indexed_version = self.get_last_indexed_version().unwrap_or(0).max(version);
+        entries.push((
+            vec![METADATA_PREFIX],
+            last_indexed_version.to_be_bytes().to_vec(),
+        ));
+        entries
+    }
+
+    /// Iterates over the indexed events matching the given query, ordered by version and index.
+    fn query_events(&self, query: &EventQuery) -> Box<dyn Iterator<Item = IndexedEvent> + '_> {
+        let prefix = if let Some(emitter) = &query.emitter {
+            encode_secondary_key(BY_EMITTER_PREFIX, emitter, &[])
+        } else if let Some(blueprint_id) = &query.blueprint_id {
+            encode_secondary_key(BY_BLUEPRINT_PREFIX, blueprint_id, &[])
+        } else {
+            vec![EVENTS_PREFIX]
+        };
+        let from_key = [
+            prefix.as_slice(),
+            &query.from_version.unwrap_or(0).to_be_bytes(),
+        ]
+        .concat();
+        let to_version = query.to_version.unwrap_or(u64::MAX);
+        let query = query.clone();
+
+        let iter = self
+            .list_event_index_entries_from(&from_key)
+            .take_while(move |(key, _)| {
+                key.len() == prefix.len() + EVENT_KEY_SUFFIX_LENGTH && key.starts_with(&prefix)
+            })
+            .map(|(key, value)| {
+                let suffix = &key[key.len() - EVENT_KEY_SUFFIX_LENGTH..];
+                let version = u64::from_be_bytes(copy_u8_array(&suffix[..8]));
+                let index = u32::from_be_bytes(copy_u8_array(&suffix[8..]));
+                (version, index, suffix.to_vec(), key[0], value)
+            })
+            .take_while(move |(version, ..)| *version <= to_version)
+            .map(|(version, index, suffix, key_prefix, value)| {
+                let value = if key_prefix == EVENTS_PREFIX {
+                    value
+                } else {
+                    self.get_event_index_entry(&[&[EVENTS_PREFIX], suffix.as_slice()].concat())
+                        .expect("Secondary index entry without an event")
+                };
+                let value: IndexedEventValue =
+                    scrypto_decode(&value).expect("Invalid event index entry");
+                IndexedEvent {
+                    version,
+                    index,
+                    type_identifier: value.type_identifier,
+                    blueprint_id: value.blueprint_id,
+                    payload: value.payload,
+                }
+            })
+            .filter(move |event| query.matches(event));
+
+        Box::new(iter)
+    }
+}
+
+fn encode_event_key_suffix(version: u64, index: u32) -> Vec<u8> {
+    let mut suffix = Vec::with_capacity(EVENT_KEY_SUFFIX_LENGTH);
+    suffix.extend(version.to_be_bytes());
+    suffix.extend(index.to_be_bytes());
+    suffix
+}
+
+fn encode_secondary_key<T: ScryptoEncode + ?Sized>(
+    prefix: u8,
+    value: &T,
+    suffix: &[u8],
+) -> Vec<u8> {
+    let mut key = vec![prefix];
+    key.extend(hash(scrypto_encode(value).unwrap()).as_slice());
+    key.extend(suffix);
+    key
+}
@@ -157,6 +157,7 @@ mod test {
             .configure_dugong(|_| DugongSettings {
                 native_entity_metadata_updates: UpdateSetting::Enabled(Default::default()),
                 system_logic_updates: UpdateSetting::Enabled(Default::default()),
+                vm_boot_to_enable_actor_index_collections: UpdateSetting::Disabled,
             })
             .from_bootstrap_to_latest();
         for protocol_update_executor in protocol_executor.each_protocol_update_executor(&db) {
@@ -448,6 +448,17 @@ impl<M: BuildableManifest> ManifestBuilder<M> {
         manifest
     }
 
+    /// Builds and validates the manifest, like [`build`][Self::build], and decompiles it into a
+    /// template in which the values of the given parameters are replaced by the parameters.
+    /// See [`decompile_template`] for details.
+    pub fn build_template(
+        self,
+        parameters: &[TemplateParameter],
+        network: &NetworkDefinition,
+    ) -> Result<String, TemplateDecompileError> {
+        decompile_template(&self.build(), parameters, network)
+    }
+
     /// Returns the built transaction manifest, without further validation.
     /// If you also wish to validate the manifest, use [`build`][Self::build] instead.
     pub fn build_no_validate(mut self) -> M {
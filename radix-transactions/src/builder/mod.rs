@@ -1,7 +1,9 @@
 mod manifest_builder;
 mod manifest_namer;
+mod partial_transaction_coordination;
 mod transaction_builder;
 
 pub use manifest_builder::*;
 pub use manifest_namer::*;
+pub use partial_transaction_coordination::*;
 pub use transaction_builder::*;
@@ -0,0 +1,445 @@
+use crate::internal_prelude::*;
+use crate::signing::TrySigner;
+use sbor::rust::fmt::Write;
+
+//====================================
+// This file contains helpers for building a transaction from partial transactions
+// which are signed by multiple parties, each on their own machine:
+// * The owner of a subintent builds an unsigned SignedPartialTransactionV2 with a
+//   PartialTransactionV2Builder, and sends it to each signer, who can inspect it with
+//   `summarize_partial_transaction`.
+// * Each signer adds their signature with `sign_partial_transaction`, and sends
+//   their copy back.
+// * The copies are combined with `merge_partial_transaction_signatures`.
+// * The parent intent is built with a TransactionV2Builder, adding the merged partial
+//   transactions as children, in the order given by `resolve_manifest_children`.
+//====================================
+
+#[derive(Debug, Clone)]
+pub enum PartialTransactionCoordinationError {
+    PrepareError(PrepareError),
+    SignerError(String),
+    DecompileError(DecompileError),
+    NoPartialTransactions,
+    /// A partial transaction to merge has a different root subintent, or different children,
+    /// to the first partial transaction.
+    MismatchedPartialTransactions {
+        expected: SubintentHash,
+        actual: SubintentHash,
+    },
+    /// The manifest uses a child for which no partial transaction was given.
+    MissingChild(SubintentHash),
+    /// A partial transaction was given which isn't a child used by the manifest.
+    UnusedChild(SubintentHash),
+}
+
+impl From<PrepareError> for PartialTransactionCoordinationError {
+    fn from(error: PrepareError) -> Self {
+        Self::PrepareError(error)
+    }
+}
+
+impl From<DecompileError> for PartialTransactionCoordinationError {
+    fn from(error: DecompileError) -> Self {
+        Self::DecompileError(error)
+    }
+}
+
+/// Adds a signature of the root subintent by the given signer.
+///
+/// The subintents of children must already be signed when the partial transaction is built, as
+/// their signatures are part of it.
+pub fn sign_partial_transaction<S: TrySigner>(
+    signed_partial_transaction: &mut SignedPartialTransactionV2,
+    signer: S,
+) -> Result<(), PartialTransactionCoordinationError> {
+    let hash = signed_partial_transaction
+        .prepare(PreparationSettings::latest_ref())?
+        .subintent_hash();
+    let signature = signer
+        .try_sign_with_public_key(&hash)
+        .map_err(|error| PartialTransactionCoordinationError::SignerError(format!("{error:?}")))?;
+    signed_partial_transaction
+        .root_subintent_signatures
+        .signatures
+        .push(IntentSignatureV1(signature));
+    Ok(())
+}
+
+/// Combines copies of the same partial transaction, which were each signed independently, into
+/// one with all of their signatures. Duplicate signatures are only kept once.
+pub fn merge_partial_transaction_signatures(
+    signed_partial_transactions: impl IntoIterator<Item = SignedPartialTransactionV2>,
+) -> Result<SignedPartialTransactionV2, PartialTransactionCoordinationError> {
+    let mut signed_partial_transactions = signed_partial_transactions.into_iter();
+    let mut merged = signed_partial_transactions
+        .next()
+        .ok_or(PartialTransactionCoordinationError::NoPartialTransactions)?;
+
+    for signed_partial_transaction in signed_partial_transactions {
+        if signed_partial_transaction.partial_transaction != merged.partial_transaction {
+            return Err(
+                PartialTransactionCoordinationError::MismatchedPartialTransactions {
+                    expected: merged
+                        .prepare(PreparationSettings::latest_ref())?
+                        .subintent_hash(),
+                    actual: signed_partial_transaction
+                        .prepare(PreparationSettings::latest_ref())?
+                        .subintent_hash(),
+                },
+            );
+        }
+        let SignedPartialTransactionV2 {
+            root_subintent_signatures,
+            non_root_subintent_signatures,
+            ..
+        } = signed_partial_transaction;
+        merge_signatures(
+            &mut merged.root_subintent_signatures,
+            root_subintent_signatures,
+        );
+        // The partial transactions match, so they have the same number of non-root subintents
+        for (merged_signatures, signatures) in merged
+            .non_root_subintent_signatures
+            .by_subintent
+            .iter_mut()
+            .zip(non_root_subintent_signatures.by_subintent)
+        {
+            merge_signatures(merged_signatures, signatures);
+        }
+    }
+
+    Ok(merged)
+}
+
+fn merge_signatures(merged: &mut IntentSignaturesV2, signatures: IntentSignaturesV2) {
+    for signature in signatures.signatures {
+        if !merged.signatures.contains(&signature) {
+            merged.signatures.push(signature);
+        }
+    }
+}
+
+/// Matches the given partial transactions to the children used by a parent manifest, returning
+/// them in the order of the manifest, ready to be added with
+/// [`TransactionV2Builder::add_signed_child`].
+///
+/// Unlike [`TransactionV2Builder::manifest`], which panics, this reports children which are
+/// missing or unused as errors.
+pub fn resolve_manifest_children(
+    manifest_children: &IndexSet<ChildSubintentSpecifier>,
+    signed_partial_transactions: impl IntoIterator<Item = SignedPartialTransactionV2>,
+) -> Result<Vec<(SubintentHash, SignedPartialTransactionV2)>, PartialTransactionCoordinationError> {
+    let mut by_hash = index_map_new();
+    for signed_partial_transaction in signed_partial_transactions {
+        let hash = signed_partial_transaction
+            .prepare(PreparationSettings::latest_ref())?
+            .subintent_hash();
+        if !manifest_children.contains(&ChildSubintentSpecifier { hash }) {
+            return Err(PartialTransactionCoordinationError::UnusedChild(hash));
+        }
+        by_hash.insert(hash, signed_partial_transaction);
+    }
+
+    manifest_children
+        .iter()
+        .map(|ChildSubintentSpecifier { hash }| {
+            by_hash
+                .swap_remove(hash)
+                .map(|signed_partial_transaction| (*hash, signed_partial_transaction))
+                .ok_or(PartialTransactionCoordinationError::MissingChild(*hash))
+        })
+        .collect()
+}
+
+/// Describes each subintent of a partial transaction for a person who is asked to sign it: the
+/// conditions under which it's valid, its message, its manifest, and who has signed it so far.
+pub fn summarize_partial_transaction(
+    signed_partial_transaction: &SignedPartialTransactionV2,
+    network: &NetworkDefinition,
+) -> Result<String, PartialTransactionCoordinationError> {
+    let prepared = signed_partial_transaction.prepare(PreparationSettings::latest_ref())?;
+    let hash_encoder = TransactionHashBech32Encoder::new(network);
+    let partial_transaction = &signed_partial_transaction.partial_transaction;
+
+    let subintents = core::iter::once((
+        prepared.subintent_hash(),
+        &partial_transaction.root_subintent,
+        &signed_partial_transaction.root_subintent_signatures,
+    ))
+    .chain(
+        prepared
+            .non_root_subintent_hashes()
+            .zip(partial_transaction.non_root_subintents.0.iter())
+            .zip(
+                signed_partial_transaction
+                    .non_root_subintent_signatures
+                    .by_subintent
+                    .iter(),
+            )
+            .map(|((hash, subintent), signatures)| (hash, subintent, signatures)),
+    );
+
+    let mut summary = String::new();
+    for (index, (hash, subintent, signatures)) in subintents.enumerate() {
+        summarize_subintent(
+            &mut summary,
+            index == 0,
+            hash,
+            subintent,
+            signatures,
+            network,
+            &hash_encoder,
+        )?;
+    }
+    Ok(summary)
+}
+
+fn summarize_subintent(
+    summary: &mut String,
+    is_root: bool,
+    hash: SubintentHash,
+    subintent: &SubintentV2,
+    signatures: &IntentSignaturesV2,
+    network: &NetworkDefinition,
+    hash_encoder: &TransactionHashBech32Encoder,
+) -> Result<(), PartialTransactionCoordinationError> {
+    let encode_hash = |hash: &SubintentHash| {
+        hash_encoder
+            .encode(hash)
+            .unwrap_or_else(|_| hash.0.to_string())
+    };
+    let IntentCoreV2 {
+        header,
+        message,
+        children,
+        ..
+    } = &subintent.intent_core;
+
+    // Writing to a string can't fail
+    let kind = if is_root { "root" } else { "child" };
+    writeln!(summary, "Subintent {} ({})", encode_hash(&hash), kind).unwrap();
+    writeln!(summary, "  Network id: {}", header.network_id).unwrap();
+    writeln!(
+        summary,
+        "  Valid from epoch {} until before epoch {}",
+        header.start_epoch_inclusive.number(),
+        header.end_epoch_exclusive.number()
+    )
+    .unwrap();
+    if let Some(min) = &header.min_proposer_timestamp_inclusive {
+        writeln!(
+            summary,
+            "  Valid from unix time {}s",
+            min.seconds_since_unix_epoch
+        )
+        .unwrap();
+    }
+    if let Some(max) = &header.max_proposer_timestamp_exclusive {
+        writeln!(
+            summary,
+            "  Valid until before unix time {}s",
+            max.seconds_since_unix_epoch
+        )
+        .unwrap();
+    }
+    writeln!(
+        summary,
+        "  Intent discriminator: {}",
+        header.intent_discriminator
+    )
+    .unwrap();
+
+    match message {
+        MessageV2::None => writeln!(summary, "  Message: none"),
+        MessageV2::Plaintext(PlaintextMessageV1 {
+            mime_type,
+            message: MessageContentsV1::String(message),
+        }) => writeln!(summary, "  Message ({}): {:?}", mime_type, message),
+        MessageV2::Plaintext(PlaintextMessageV1 {
+            mime_type,
+            message: MessageContentsV1::Bytes(message),
+        }) => writeln!(
+            summary,
+            "  Message ({}): {} bytes",
+            mime_type,
+            message.len()
+        ),
+        MessageV2::Encrypted(_) => writeln!(summary, "  Message: encrypted"),
+    }
+    .unwrap();
+
+    for child in children.children.iter() {
+        writeln!(summary, "  Child: {}", encode_hash(&child.hash)).unwrap();
+    }
+
+    if signatures.signatures.is_empty() {
+        writeln!(summary, "  Signed by: nobody yet").unwrap();
+    }
+    for IntentSignatureV1(signature) in signatures.signatures.iter() {
+        let public_key: Option<PublicKey> = match signature {
+            SignatureWithPublicKeyV1::Secp256k1 { signature } => {
+                verify_and_recover_secp256k1(hash.as_hash(), signature).map(Into::into)
+            }
+            SignatureWithPublicKeyV1::Ed25519 {
+                public_key,
+                signature,
+            } => verify_ed25519(hash.as_hash(), public_key, signature)
+                .then_some((*public_key).into()),
+        };
+        match public_key {
+            Some(public_key) => writeln!(summary, "  Signed by: {:?}", public_key),
+            None => writeln!(summary, "  Signed by: an invalid signature"),
+        }
+        .unwrap();
+    }
+
+    let manifest = SubintentManifestV2::from_intent_core(&subintent.intent_core);
+    writeln!(summary, "  Manifest:").unwrap();
+    for line in decompile(&manifest, network)?.lines() {
+        writeln!(summary, "    {}", line).unwrap();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unsigned_subintent(
+        child: Option<DetailedSignedPartialTransactionV2>,
+    ) -> SignedPartialTransactionV2 {
+        let mut builder = PartialTransactionV2Builder::new().intent_header(IntentHeaderV2 {
+            network_id: NetworkDefinition::simulator().id,
+            start_epoch_inclusive: Epoch::of(0),
+            end_epoch_exclusive: Epoch::of(10),
+            min_proposer_timestamp_inclusive: None,
+            max_proposer_timestamp_exclusive: None,
+            intent_discriminator: 1,
+        });
+        if let Some(child) = child {
+            builder = builder.add_signed_child("child", child);
+        }
+        builder
+            .manifest_builder(|builder| builder.yield_to_parent(()))
+            .build()
+            .partial_transaction
+    }
+
+    #[test]
+    fn independently_signed_copies_are_merged_and_validate_in_a_parent() {
+        // Arrange
+        let alice = Secp256k1PrivateKey::from_u64(1).unwrap();
+        let bob = Ed25519PrivateKey::from_u64(2).unwrap();
+        let notary = Secp256k1PrivateKey::from_u64(3).unwrap();
+        let unsigned = unsigned_subintent(None);
+
+        // Act
+        let mut signed_by_alice = unsigned.clone();
+        sign_partial_transaction(&mut signed_by_alice, &alice).unwrap();
+        let mut signed_by_bob = unsigned.clone();
+        sign_partial_transaction(&mut signed_by_bob, &bob).unwrap();
+        let merged = merge_partial_transaction_signatures([
+            signed_by_alice.clone(),
+            signed_by_bob,
+            signed_by_alice,
+        ])
+        .unwrap();
+
+        let manifest = ManifestBuilder::new_v2()
+            .use_child(
+                "child",
+                merged
+                    .prepare(PreparationSettings::latest_ref())
+                    .unwrap()
+                    .subintent_hash(),
+            )
+            .yield_to_child("child", ())
+            .build();
+        let children = resolve_manifest_children(&manifest.children, [merged.clone()]).unwrap();
+        let mut builder =
+            TransactionV2Builder::new_with_test_defaults().notary_public_key(notary.public_key());
+        for (hash, child) in children {
+            builder = builder.add_signed_child(format!("child_{}", hash.0), child);
+        }
+        let transaction = builder
+            .manifest(manifest)
+            .notarize(&notary)
+            .build_minimal_no_validate();
+
+        // Assert
+        assert_eq!(merged.root_subintent_signatures.signatures.len(), 2);
+        transaction
+            .prepare_and_validate(&TransactionValidator::new_with_latest_config(
+                &NetworkDefinition::simulator(),
+            ))
+            .unwrap();
+    }
+
+    #[test]
+    fn different_partial_transactions_are_not_merged() {
+        let mut other = unsigned_subintent(None);
+        other
+            .partial_transaction
+            .root_subintent
+            .intent_core
+            .header
+            .intent_discriminator = 2;
+
+        assert!(matches!(
+            merge_partial_transaction_signatures([unsigned_subintent(None), other]),
+            Err(PartialTransactionCoordinationError::MismatchedPartialTransactions { .. })
+        ));
+    }
+
+    #[test]
+    fn children_must_match_the_manifest() {
+        let child = unsigned_subintent(None);
+        let child_hash = child
+            .prepare(PreparationSettings::latest_ref())
+            .unwrap()
+            .subintent_hash();
+        let manifest = ManifestBuilder::new_v2()
+            .use_child("child", child_hash)
+            .yield_to_child("child", ())
+            .build();
+
+        assert!(matches!(
+            resolve_manifest_children(&manifest.children, []),
+            Err(PartialTransactionCoordinationError::MissingChild(hash)) if hash == child_hash
+        ));
+        assert!(matches!(
+            resolve_manifest_children(&Default::default(), [child]),
+            Err(PartialTransactionCoordinationError::UnusedChild(hash)) if hash == child_hash
+        ));
+    }
+
+    #[test]
+    fn summary_describes_each_subintent_and_its_signers() {
+        let signer = Secp256k1PrivateKey::from_u64(1).unwrap();
+        let mut child = PartialTransactionV2Builder::new()
+            .intent_header(IntentHeaderV2 {
+                network_id: NetworkDefinition::simulator().id,
+                start_epoch_inclusive: Epoch::of(1),
+                end_epoch_exclusive: Epoch::of(10),
+                min_proposer_timestamp_inclusive: None,
+                max_proposer_timestamp_exclusive: None,
+                intent_discriminator: 7,
+            })
+            .manifest_builder(|builder| builder.yield_to_parent(()))
+            .sign(&signer)
+            .build();
+        child.object_names = TransactionObjectNames::unknown_with_subintent_count(0);
+        let parent = unsigned_subintent(Some(child));
+
+        let summary =
+            summarize_partial_transaction(&parent, &NetworkDefinition::simulator()).unwrap();
+
+        assert!(summary.contains("(root)"));
+        assert!(summary.contains("(child)"));
+        assert!(summary.contains("Intent discriminator: 7"));
+        assert!(summary.contains("Signed by: nobody yet"));
+        assert!(summary.contains(&format!("{:?}", PublicKey::from(signer.public_key()))));
+        assert!(summary.contains("YIELD_TO_PARENT;"));
+    }
+}
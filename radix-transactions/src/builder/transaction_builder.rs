@@ -1,6 +1,6 @@
 use crate::internal_prelude::*;
 use crate::model::*;
-use crate::signing::Signer;
+use crate::signing::{AsyncTrySigner, Signer, TrySigner};
 
 //====================================
 // This file contains:
@@ -362,6 +362,25 @@ impl PartialTransactionV2Builder {
         self
     }
 
+    /// Signs with a signer which can fail, such as an [`ExternalSigner`].
+    ///
+    /// [`ExternalSigner`]: crate::signing::ExternalSigner
+    pub fn try_sign<S: TrySigner>(mut self, signer: S) -> Result<Self, S::Error> {
+        let hash = self.subintent_hash();
+        self.root_subintent_signatures
+            .push(IntentSignatureV1(signer.try_sign_with_public_key(&hash)?));
+        Ok(self)
+    }
+
+    /// Signs with a signer which signs asynchronously, see [`AsyncTrySigner`].
+    pub async fn try_sign_async<S: AsyncTrySigner>(mut self, signer: S) -> Result<Self, S::Error> {
+        let hash = self.subintent_hash();
+        let signature = signer.try_sign_with_public_key_async(&hash).await?;
+        self.root_subintent_signatures
+            .push(IntentSignatureV1(signature));
+        Ok(self)
+    }
+
     pub fn add_signature(mut self, signature: SignatureWithPublicKeyV1) -> Self {
         self.root_subintent_signatures
             .push(IntentSignatureV1(signature));
@@ -816,6 +835,25 @@ impl TransactionV2Builder {
         self
     }
 
+    /// Signs with a signer which can fail, such as an [`ExternalSigner`].
+    ///
+    /// [`ExternalSigner`]: crate::signing::ExternalSigner
+    pub fn try_sign<S: TrySigner>(mut self, signer: S) -> Result<Self, S::Error> {
+        let hash = self.intent_hash();
+        self.transaction_intent_signatures
+            .push(IntentSignatureV1(signer.try_sign_with_public_key(&hash)?));
+        Ok(self)
+    }
+
+    /// Signs with a signer which signs asynchronously, see [`AsyncTrySigner`].
+    pub async fn try_sign_async<S: AsyncTrySigner>(mut self, signer: S) -> Result<Self, S::Error> {
+        let hash = self.intent_hash();
+        let signature = signer.try_sign_with_public_key_async(&hash).await?;
+        self.transaction_intent_signatures
+            .push(IntentSignatureV1(signature));
+        Ok(self)
+    }
+
     pub fn add_signature(mut self, signature: SignatureWithPublicKeyV1) -> Self {
         self.transaction_intent_signatures
             .push(IntentSignatureV1(signature));
@@ -863,6 +901,32 @@ impl TransactionV2Builder {
         self
     }
 
+    /// Notarizes with a signer which can fail, such as an [`ExternalSigner`].
+    ///
+    /// [`ExternalSigner`]: crate::signing::ExternalSigner
+    pub fn try_notarize<S: TrySigner>(mut self, signer: &S) -> Result<Self, S::Error> {
+        let hash = self
+            .create_prepared_signed_transaction_intent()
+            .signed_transaction_intent_hash();
+        self.notary_signature = Some(NotarySignatureV2(
+            signer.try_sign_without_public_key(&hash)?,
+        ));
+        Ok(self)
+    }
+
+    /// Notarizes with a signer which signs asynchronously, see [`AsyncTrySigner`].
+    pub async fn try_notarize_async<S: AsyncTrySigner>(
+        mut self,
+        signer: &S,
+    ) -> Result<Self, S::Error> {
+        let hash = self
+            .create_prepared_signed_transaction_intent()
+            .signed_transaction_intent_hash();
+        let signature = signer.try_sign_without_public_key_async(&hash).await?;
+        self.notary_signature = Some(NotarySignatureV2(signature));
+        Ok(self)
+    }
+
     pub fn notary_signature(mut self, signature: SignatureV1) -> Self {
         self.notary_signature = Some(NotarySignatureV2(signature));
         self
@@ -1084,4 +1148,40 @@ mod tests {
                 .notary_is_signatory
         );
     }
+
+    #[test]
+    fn async_signers_sign_like_sync_signers() {
+        fn complete<F: core::future::Future>(future: F) -> F::Output {
+            let mut future = core::pin::pin!(future);
+            let mut context = core::task::Context::from_waker(core::task::Waker::noop());
+            match future.as_mut().poll(&mut context) {
+                core::task::Poll::Ready(output) => output,
+                core::task::Poll::Pending => panic!("Signing should complete immediately"),
+            }
+        }
+
+        let signer = Secp256k1PrivateKey::from_u64(1).unwrap();
+        let notary = Secp256k1PrivateKey::from_u64(2).unwrap();
+        let builder = || {
+            TransactionV2Builder::new_with_test_defaults()
+                .notary_public_key(notary.public_key())
+                .manifest(ManifestBuilder::new_v2().drop_auth_zone_proofs().build())
+        };
+
+        let signed_async = complete(async {
+            builder()
+                .try_sign_async(&signer)
+                .await
+                .unwrap()
+                .try_notarize_async(&notary)
+                .await
+                .unwrap()
+                .build()
+        });
+
+        assert_eq!(
+            signed_async,
+            builder().sign(&signer).notarize(&notary).build()
+        );
+    }
 }
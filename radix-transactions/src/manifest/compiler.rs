@@ -5,6 +5,7 @@ pub enum CompileError {
     LexerError(lexer::LexerError),
     ParserError(parser::ParserError),
     GeneratorError(generator::GeneratorError),
+    TemplateError(template::TemplateError),
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -13,6 +14,17 @@ pub enum CompileErrorDiagnosticsStyle {
     TextTerminalColors,
 }
 
+/// The location and description of a [`CompileError`], for tools which present errors in a
+/// different form than the text snippets of [`compile_error_diagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileErrorDetails {
+    pub span: token::Span,
+    /// A description of the error including context about what triggered it.
+    pub title: String,
+    /// A short description of the error, to be displayed next to the span.
+    pub label: String,
+}
+
 pub fn compile_error_diagnostics(
     s: &str,
     err: CompileError,
@@ -22,6 +34,16 @@ pub fn compile_error_diagnostics(
         CompileError::LexerError(err) => lexer::lexer_error_diagnostics(s, err, style),
         CompileError::ParserError(err) => parser::parser_error_diagnostics(s, err, style),
         CompileError::GeneratorError(err) => generator::generator_error_diagnostics(s, err, style),
+        CompileError::TemplateError(err) => template::template_error_diagnostics(s, err, style),
+    }
+}
+
+pub fn compile_error_details(err: CompileError) -> CompileErrorDetails {
+    match err {
+        CompileError::LexerError(err) => lexer::lexer_error_details(err),
+        CompileError::ParserError(err) => parser::parser_error_details(err),
+        CompileError::GeneratorError(err) => generator::generator_error_details(err),
+        CompileError::TemplateError(err) => template::template_error_details(err),
     }
 }
 
@@ -85,11 +107,106 @@ pub fn compile_manifest<M: BuildableManifest>(
     s: &str,
     network: &NetworkDefinition,
     blobs: impl IsBlobProvider,
+) -> Result<M, CompileError> {
+    let tokens = lexer::tokenize(s).map_err(CompileError::LexerError)?;
+    generate_manifest_from_tokens(tokens, network, blobs)
+}
+
+pub fn compile_any_manifest_template_with_pretty_error(
+    template_string: &str,
+    manifest_kind: ManifestKind,
+    arguments: &template::TemplateArguments,
+    fragments: &impl template::IsTemplateFragmentProvider,
+    network: &NetworkDefinition,
+    blobs: impl IsBlobProvider,
+    error_style: CompileErrorDiagnosticsStyle,
+) -> Result<AnyManifest, String> {
+    compile_any_manifest_template(
+        template_string,
+        manifest_kind,
+        arguments,
+        fragments,
+        network,
+        blobs,
+    )
+    .map_err(|err| compile_error_diagnostics(template_string, err, error_style))
+}
+
+pub fn compile_any_manifest_template(
+    template_string: &str,
+    manifest_kind: ManifestKind,
+    arguments: &template::TemplateArguments,
+    fragments: &impl template::IsTemplateFragmentProvider,
+    network: &NetworkDefinition,
+    blobs: impl IsBlobProvider,
+) -> Result<AnyManifest, CompileError> {
+    let manifest = match manifest_kind {
+        ManifestKind::V1 => compile_manifest_template::<TransactionManifestV1>(
+            template_string,
+            arguments,
+            fragments,
+            network,
+            blobs,
+        )?
+        .into(),
+        ManifestKind::SystemV1 => compile_manifest_template::<SystemTransactionManifestV1>(
+            template_string,
+            arguments,
+            fragments,
+            network,
+            blobs,
+        )?
+        .into(),
+        ManifestKind::V2 => compile_manifest_template::<TransactionManifestV2>(
+            template_string,
+            arguments,
+            fragments,
+            network,
+            blobs,
+        )?
+        .into(),
+        ManifestKind::SubintentV2 => compile_manifest_template::<SubintentManifestV2>(
+            template_string,
+            arguments,
+            fragments,
+            network,
+            blobs,
+        )?
+        .into(),
+    };
+
+    Ok(manifest)
+}
+
+/// Compiles a manifest template, after instantiating it with the given arguments and fragments.
+/// See [`template::instantiate_template`] for the template syntax.
+///
+/// Template errors, such as arguments which don't match the type of their parameter, are reported
+/// before the manifest is generated, and located in the template.
+pub fn compile_manifest_template<M: BuildableManifest>(
+    s: &str,
+    arguments: &template::TemplateArguments,
+    fragments: &impl template::IsTemplateFragmentProvider,
+    network: &NetworkDefinition,
+    blobs: impl IsBlobProvider,
 ) -> Result<M, CompileError> {
     let address_bech32_decoder = AddressBech32Decoder::new(network);
-    let transaction_bech32_decoder = TransactionHashBech32Decoder::new(network);
 
     let tokens = lexer::tokenize(s).map_err(CompileError::LexerError)?;
+    let tokens =
+        template::instantiate_template(tokens, arguments, fragments, &address_bech32_decoder)
+            .map_err(CompileError::TemplateError)?;
+    generate_manifest_from_tokens(tokens, network, blobs)
+}
+
+fn generate_manifest_from_tokens<M: BuildableManifest>(
+    tokens: Vec<token::TokenWithSpan>,
+    network: &NetworkDefinition,
+    blobs: impl IsBlobProvider,
+) -> Result<M, CompileError> {
+    let address_bech32_decoder = AddressBech32Decoder::new(network);
+    let transaction_bech32_decoder = TransactionHashBech32Decoder::new(network);
+
     let instructions = parser::Parser::new(tokens, parser::PARSER_MAX_DEPTH)
         .map_err(CompileError::ParserError)?
         .parse_manifest()
@@ -0,0 +1,633 @@
+use crate::internal_prelude::*;
+use crate::manifest::token::{Position, Token, TokenWithSpan};
+
+/// The indent of instruction arguments and of the fields of composite values, matching the
+/// decompiler.
+const INDENT: usize = 4;
+
+/// The value kinds and aliases whose fields are always placed on separate lines, as output by the
+/// decompiler. Other values, such as `Address("...")` or `Decimal("1")`, stay on a single line.
+const MULTI_LINE_VALUE_IDENTS: [&str; 7] = ["Enum", "Array", "Tuple", "Map", "Some", "Ok", "Err"];
+
+/// Formats a manifest in the canonical layout of the decompiler, while preserving its comments.
+///
+/// * Each argument of an instruction is placed on its own line, followed by the `;` on its own
+///   line.
+/// * The fields of composite values are placed on their own lines, separated by commas.
+/// * Comments stay next to the instruction or value that they precede or trail, and single blank
+///   lines between instructions or values are kept.
+/// * Addresses are written in lowercase, the canonical form of Bech32m.
+///
+/// Anything else, such as the spelling of strings and value aliases, is kept as written. The
+/// manifest must be syntactically valid, but isn't compiled, so names and addresses are neither
+/// resolved nor validated, and string arguments are never rewritten.
+pub fn format_manifest(s: &str) -> Result<String, CompileError> {
+    let tokens = lexer::tokenize(s).map_err(CompileError::LexerError)?;
+    parser::Parser::new(tokens.clone(), parser::PARSER_MAX_DEPTH)
+        .and_then(|mut parser| parser.parse_manifest())
+        .map_err(CompileError::ParserError)?;
+
+    let mut formatter = ManifestFormatter::new(s, tokens);
+    let (instructions, final_comments) = formatter.parse_manifest();
+
+    let mut output = String::new();
+    for (i, instruction) in instructions.iter().enumerate() {
+        write_instruction(&mut output, instruction, i == 0);
+    }
+    for comment in final_comments {
+        output.push_str(&comment);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Whether the manifest is already formatted, as used by check modes which report unformatted
+/// files rather than rewriting them.
+pub fn is_manifest_formatted(s: &str) -> Result<bool, CompileError> {
+    format_manifest(s).map(|formatted| formatted == s)
+}
+
+struct Comment {
+    text: String,
+    position: Position,
+}
+
+/// An instruction, or a value inside of an instruction or a composite value, along with its
+/// comments.
+struct Item {
+    blank_line_before: bool,
+    leading_comments: Vec<String>,
+    node: Node,
+    trailing_comment: Option<String>,
+}
+
+enum Node {
+    Text(String),
+    /// An instruction, or a value with fields such as `Tuple(...)` or `Address("...")`.
+    Group {
+        head: String,
+        is_multi_line: bool,
+        head_comment: Option<String>,
+        children: Vec<Item>,
+        closing_comments: Vec<String>,
+    },
+    MapEntry(Box<Node>, Box<Node>),
+}
+
+impl Node {
+    fn has_comments(&self) -> bool {
+        match self {
+            Node::Text(_) => false,
+            Node::Group {
+                head_comment,
+                children,
+                closing_comments,
+                ..
+            } => {
+                head_comment.is_some()
+                    || !closing_comments.is_empty()
+                    || children.iter().any(|child| {
+                        !child.leading_comments.is_empty()
+                            || child.trailing_comment.is_some()
+                            || child.node.has_comments()
+                    })
+            }
+            Node::MapEntry(key, value) => key.has_comments() || value.has_comments(),
+        }
+    }
+}
+
+/// Walks the tokens of a manifest which is known to parse, so it doesn't need to report errors.
+struct ManifestFormatter {
+    chars: Vec<char>,
+    tokens: Vec<TokenWithSpan>,
+    comments: Vec<Comment>,
+    next_token: usize,
+    next_comment: usize,
+}
+
+impl ManifestFormatter {
+    fn new(s: &str, tokens: Vec<TokenWithSpan>) -> Self {
+        let chars: Vec<char> = s.chars().collect();
+
+        // Only whitespace and comments can be found between tokens
+        let mut comments = Vec::new();
+        let mut position = Position {
+            full_index: 0,
+            line_idx: 0,
+            line_char_index: 0,
+        };
+        let gap_ends = tokens
+            .iter()
+            .map(|token| (token.span.start.full_index, token.span.end))
+            .chain(core::iter::once((chars.len(), position)));
+        for (gap_end, token_end) in gap_ends {
+            while position.full_index < gap_end {
+                if chars[position.full_index] == '#' {
+                    let start = position;
+                    while position.full_index < gap_end && chars[position.full_index] != '\n' {
+                        position = position.advance(chars[position.full_index]);
+                    }
+                    let text: String = chars[start.full_index..position.full_index]
+                        .iter()
+                        .collect();
+                    comments.push(Comment {
+                        text: text.trim_end().to_string(),
+                        position: start,
+                    });
+                } else {
+                    position = position.advance(chars[position.full_index]);
+                }
+            }
+            position = token_end;
+        }
+
+        Self {
+            chars,
+            tokens,
+            comments,
+            next_token: 0,
+            next_comment: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next_token).map(|token| &token.token)
+    }
+
+    fn advance(&mut self) -> Option<&TokenWithSpan> {
+        let token = self.tokens.get(self.next_token)?;
+        self.next_token += 1;
+        Some(token)
+    }
+
+    fn text(&self, token: &TokenWithSpan) -> String {
+        self.chars[token.span.start.full_index..token.span.end.full_index]
+            .iter()
+            .collect()
+    }
+
+    /// The line on which the previous token ends.
+    fn previous_line_idx(&self) -> Option<usize> {
+        self.next_token
+            .checked_sub(1)
+            .map(|index| self.tokens[index].span.end.line_idx)
+    }
+
+    /// The position of the next token, or of the end of the manifest.
+    fn next_position(&self) -> usize {
+        self.tokens
+            .get(self.next_token)
+            .map_or(self.chars.len(), |token| token.span.start.full_index)
+    }
+
+    /// Takes the comments before the next token. A comment on the same line as the previous token
+    /// is returned separately, to be kept at the end of that line.
+    fn take_comments(&mut self) -> (Option<String>, Vec<Comment>) {
+        let next_position = self.next_position();
+        let previous_line_idx = self.previous_line_idx();
+        let mut trailing_comment = None;
+        let mut comments = Vec::new();
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            if comment.position.full_index >= next_position {
+                break;
+            }
+            self.next_comment += 1;
+            let comment = Comment {
+                text: comment.text.clone(),
+                position: comment.position,
+            };
+            if trailing_comment.is_none()
+                && comments.is_empty()
+                && Some(comment.position.line_idx) == previous_line_idx
+            {
+                trailing_comment = Some(comment.text);
+            } else {
+                comments.push(comment);
+            }
+        }
+        (trailing_comment, comments)
+    }
+
+    /// Parses the instructions, and returns them along with the comments at the end of the
+    /// manifest.
+    fn parse_manifest(&mut self) -> (Vec<Item>, Vec<String>) {
+        let mut instructions: Vec<Item> = Vec::new();
+        while self.peek().is_some() {
+            let (trailing_comment, item) =
+                self.parse_item(|formatter| formatter.parse_group(Token::Semicolon, false));
+            if let Some(previous) = instructions.last_mut() {
+                previous.trailing_comment = trailing_comment;
+            }
+            instructions.push(item);
+        }
+
+        let (trailing_comment, comments) = self.take_comments();
+        if let Some(previous) = instructions.last_mut() {
+            previous.trailing_comment = trailing_comment;
+        }
+        let final_comments = comments.into_iter().map(|comment| comment.text).collect();
+        (instructions, final_comments)
+    }
+
+    /// Parses an item along with its leading comments, and returns the comment trailing the
+    /// previous token separately, as it belongs to whatever came before the item.
+    fn parse_item(&mut self, parse_node: impl FnOnce(&mut Self) -> Node) -> (Option<String>, Item) {
+        let previous_line_idx = self.previous_line_idx();
+        let (trailing_comment, leading_comments) = self.take_comments();
+        let first_line_idx = leading_comments
+            .first()
+            .map(|comment| comment.position.line_idx)
+            .or_else(|| {
+                self.tokens
+                    .get(self.next_token)
+                    .map(|token| token.span.start.line_idx)
+            });
+        let blank_line_before = match (previous_line_idx, first_line_idx) {
+            (Some(previous), Some(first)) => first > previous + 1,
+            _ => false,
+        };
+
+        let node = parse_node(self);
+        let item = Item {
+            blank_line_before,
+            leading_comments: leading_comments
+                .into_iter()
+                .map(|comment| comment.text)
+                .collect(),
+            node,
+            trailing_comment: None,
+        };
+        (trailing_comment, item)
+    }
+
+    fn parse_value(&mut self) -> Node {
+        let key = self.parse_single_value();
+        if self.peek() == Some(&Token::FatArrow) {
+            self.advance();
+            let value = self.parse_single_value();
+            Node::MapEntry(Box::new(key), Box::new(value))
+        } else {
+            key
+        }
+    }
+
+    fn parse_single_value(&mut self) -> Node {
+        match self.peek() {
+            Some(Token::Ident(_)) => {}
+            _ => return self.parse_text(),
+        }
+        if matches!(
+            self.tokens
+                .get(self.next_token + 1)
+                .map(|token| &token.token),
+            Some(Token::LessThan) | Some(Token::OpenParenthesis)
+        ) {
+            self.parse_group(Token::CloseParenthesis, true)
+        } else {
+            self.parse_text()
+        }
+    }
+
+    fn parse_text(&mut self) -> Node {
+        let text = self
+            .advance()
+            .cloned()
+            .map(|token| self.text(&token))
+            .unwrap_or_default();
+        Node::Text(text)
+    }
+
+    /// Parses an identifier with optional generics followed by values, up to the closing token.
+    /// Instructions are followed directly by their arguments, and values by their fields in
+    /// parentheses.
+    fn parse_group(&mut self, closing_token: Token, is_value: bool) -> Node {
+        let Node::Text(ident) = self.parse_text() else {
+            unreachable!("Text is always parsed as a text node")
+        };
+        let mut head = ident.clone();
+        if self.peek() == Some(&Token::LessThan) {
+            self.advance();
+            let mut generics = Vec::new();
+            while let Some(token) = self.advance().cloned() {
+                match token.token {
+                    Token::GreaterThan => break,
+                    Token::Comma => {}
+                    _ => generics.push(self.text(&token)),
+                }
+            }
+            head.push_str(&format!("<{}>", generics.join(", ")));
+        }
+        if is_value {
+            if self.peek() != Some(&Token::OpenParenthesis) {
+                return Node::Text(head);
+            }
+            self.advance();
+        }
+
+        let mut head_comment = None;
+        let mut children: Vec<Item> = Vec::new();
+        while self.peek().is_some() && self.peek() != Some(&closing_token) {
+            let (trailing_comment, item) = self.parse_item(Self::parse_value);
+            match children.last_mut() {
+                Some(previous) => previous.trailing_comment = trailing_comment,
+                None => head_comment = trailing_comment,
+            }
+            children.push(item);
+            if self.peek() == Some(&Token::Comma) {
+                self.advance();
+            }
+        }
+
+        let (trailing_comment, comments) = self.take_comments();
+        match children.last_mut() {
+            Some(previous) => previous.trailing_comment = trailing_comment,
+            None => head_comment = trailing_comment,
+        }
+        self.advance();
+
+        if ident == "Address" {
+            if let [Item {
+                node: Node::Text(address),
+                ..
+            }] = children.as_mut_slice()
+            {
+                // Bech32m addresses are either all lowercase or all uppercase, so anything which
+                // contains a lowercase character is left as written
+                if !address.contains(|c: char| c.is_ascii_lowercase()) {
+                    *address = address.to_lowercase();
+                }
+            }
+        }
+
+        Node::Group {
+            head,
+            is_multi_line: !is_value || MULTI_LINE_VALUE_IDENTS.contains(&ident.as_str()),
+            head_comment,
+            children,
+            closing_comments: comments.into_iter().map(|comment| comment.text).collect(),
+        }
+    }
+}
+
+fn write_instruction(output: &mut String, instruction: &Item, is_first: bool) {
+    if instruction.blank_line_before && !is_first {
+        output.push('\n');
+    }
+    for comment in &instruction.leading_comments {
+        output.push_str(comment);
+        output.push('\n');
+    }
+    if let Node::Group {
+        head,
+        head_comment,
+        children,
+        closing_comments,
+        ..
+    } = &instruction.node
+    {
+        output.push_str(head);
+        write_comment(output, head_comment);
+        write_children(output, children, closing_comments, INDENT, "");
+        if head_comment.is_some() || !children.is_empty() || !closing_comments.is_empty() {
+            output.push('\n');
+        }
+        output.push(';');
+    }
+    write_comment(output, &instruction.trailing_comment);
+    output.push('\n');
+}
+
+/// Writes each child on its own line at the indent, followed by the closing comments.
+fn write_children(
+    output: &mut String,
+    children: &[Item],
+    closing_comments: &[String],
+    indent: usize,
+    separator: &str,
+) {
+    let indentation = " ".repeat(indent);
+    for (i, child) in children.iter().enumerate() {
+        if child.blank_line_before && i > 0 {
+            output.push('\n');
+        }
+        for comment in &child.leading_comments {
+            output.push('\n');
+            output.push_str(&indentation);
+            output.push_str(comment);
+        }
+        output.push('\n');
+        output.push_str(&indentation);
+        write_node(output, &child.node, indent);
+        if i + 1 < children.len() {
+            output.push_str(separator);
+        }
+        write_comment(output, &child.trailing_comment);
+    }
+    for comment in closing_comments {
+        output.push('\n');
+        output.push_str(&indentation);
+        output.push_str(comment);
+    }
+}
+
+fn write_node(output: &mut String, node: &Node, indent: usize) {
+    match node {
+        Node::Text(text) => output.push_str(text),
+        Node::MapEntry(key, value) => {
+            write_node(output, key, indent);
+            output.push_str(" => ");
+            write_node(output, value, indent);
+        }
+        Node::Group {
+            head,
+            head_comment,
+            children,
+            closing_comments,
+            ..
+        } if children.is_empty() && head_comment.is_none() && closing_comments.is_empty() => {
+            output.push_str(head);
+            output.push_str("()");
+        }
+        Node::Group {
+            head,
+            is_multi_line,
+            head_comment,
+            children,
+            closing_comments,
+        } if *is_multi_line || node.has_comments() => {
+            output.push_str(head);
+            output.push('(');
+            write_comment(output, head_comment);
+            write_children(output, children, closing_comments, indent + INDENT, ",");
+            output.push('\n');
+            output.push_str(&" ".repeat(indent));
+            output.push(')');
+        }
+        Node::Group { head, children, .. } => {
+            output.push_str(head);
+            output.push('(');
+            for (i, child) in children.iter().enumerate() {
+                if i > 0 {
+                    output.push_str(", ");
+                }
+                write_node(output, &child.node, indent);
+            }
+            output.push(')');
+        }
+    }
+}
+
+fn write_comment(output: &mut String, comment: &Option<String>) {
+    if let Some(comment) = comment {
+        output.push(' ');
+        output.push_str(comment);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decompiler_layout_is_applied() {
+        let manifest = r#"CALL_METHOD Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh") "lock_fee" Decimal("10");
+TAKE_ALL_FROM_WORKTOP   Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3")
+        Bucket("xrd") ;
+CALL_METHOD Address("${account}") "deposit_batch" Array<Bucket>(Bucket("xrd")) Tuple(Enum<0u8>(), Map<String, U8>("a" => 1u8, "b" => 2u8));
+DROP_ALL_PROOFS;
+"#;
+
+        let formatted = format_manifest(manifest).unwrap();
+
+        assert_eq!(
+            formatted,
+            r#"CALL_METHOD
+    Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh")
+    "lock_fee"
+    Decimal("10")
+;
+TAKE_ALL_FROM_WORKTOP
+    Address("resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3")
+    Bucket("xrd")
+;
+CALL_METHOD
+    Address("${account}")
+    "deposit_batch"
+    Array<Bucket>(
+        Bucket("xrd")
+    )
+    Tuple(
+        Enum<0u8>(),
+        Map<String, U8>(
+            "a" => 1u8,
+            "b" => 2u8
+        )
+    )
+;
+DROP_ALL_PROOFS;
+"#
+        );
+        assert!(is_manifest_formatted(&formatted).unwrap());
+    }
+
+    #[test]
+    fn test_comments_and_blank_lines_are_preserved() {
+        let manifest = r#"# Lock the fee
+CALL_METHOD Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh")
+    "lock_fee" # on the faucet
+    Decimal("10");
+
+
+CALL_METHOD
+    Address("${account}")
+    "aliases"
+
+    # enum
+    Some( # with a value
+        "hello")
+    None
+    # the end
+; # done
+# trailing comment
+"#;
+
+        let formatted = format_manifest(manifest).unwrap();
+
+        assert_eq!(
+            formatted,
+            r#"# Lock the fee
+CALL_METHOD
+    Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh")
+    "lock_fee" # on the faucet
+    Decimal("10")
+;
+
+CALL_METHOD
+    Address("${account}")
+    "aliases"
+
+    # enum
+    Some( # with a value
+        "hello"
+    )
+    None
+    # the end
+; # done
+# trailing comment
+"#
+        );
+        assert!(is_manifest_formatted(&formatted).unwrap());
+    }
+
+    #[test]
+    fn test_comments_inside_single_line_values_break_them() {
+        let manifest = r#"CALL_METHOD Address( # the faucet
+    "component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh") "free";"#;
+
+        let formatted = format_manifest(manifest).unwrap();
+
+        assert_eq!(
+            formatted,
+            r#"CALL_METHOD
+    Address( # the faucet
+        "component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh"
+    )
+    "free"
+;
+"#
+        );
+    }
+
+    #[test]
+    fn test_addresses_are_lowercased() {
+        let manifest = r#"CALL_METHOD Address("COMPONENT_SIM1CPTXXXXXXXXXFAUCETXXXXXXXXX000527798379XXXXXXXXXHKREFH") "free";"#;
+
+        let formatted = format_manifest(manifest).unwrap();
+
+        assert!(formatted.contains(
+            r#"Address("component_sim1cptxxxxxxxxxfaucetxxxxxxxxx000527798379xxxxxxxxxhkrefh")"#
+        ));
+    }
+
+    #[test]
+    fn test_example_manifests_are_unchanged_by_reformatting() {
+        let manifest = include_str!("../../examples/values/values.rtm");
+
+        let formatted = format_manifest(manifest).unwrap();
+
+        assert_eq!(format_manifest(&formatted).unwrap(), formatted);
+        assert_eq!(
+            lexer::tokenize(&formatted).unwrap().len(),
+            lexer::tokenize(manifest).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_invalid_manifests_are_not_formatted() {
+        assert!(matches!(
+            format_manifest("CALL_METHOD Address(\"a\") \"free\""),
+            Err(CompileError::ParserError(_))
+        ));
+    }
+}
@@ -8,7 +8,7 @@ use crate::data::{from_decimal, from_non_fungible_local_id, from_precise_decimal
 use crate::errors::*;
 use crate::internal_prelude::*;
 use crate::manifest::ast;
-use crate::manifest::compiler::CompileErrorDiagnosticsStyle;
+use crate::manifest::compiler::{CompileErrorDetails, CompileErrorDiagnosticsStyle};
 use crate::manifest::diagnostic_snippets::create_snippet;
 use crate::manifest::token::Span;
 use crate::model::*;
@@ -2068,6 +2068,11 @@ pub fn generator_error_diagnostics(
     err: GeneratorError,
     style: CompileErrorDiagnosticsStyle,
 ) -> String {
+    let details = generator_error_details(err);
+    create_snippet(s, &details.span, &details.title, &details.label, style)
+}
+
+pub fn generator_error_details(err: GeneratorError) -> CompileErrorDetails {
     // The title should be a little longer, and include context about what triggered
     // the error. The label is inline next to arrows pointing to the span which is
     // invalid, so can be shorter.
@@ -2297,7 +2302,11 @@ pub fn generator_error_diagnostics(
         }
     };
 
-    create_snippet(s, &err.span, &title, &label, style)
+    CompileErrorDetails {
+        span: err.span,
+        title,
+        label,
+    }
 }
 
 #[cfg(test)]
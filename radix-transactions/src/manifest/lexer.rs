@@ -1,4 +1,4 @@
-use crate::manifest::compiler::CompileErrorDiagnosticsStyle;
+use crate::manifest::compiler::{CompileErrorDetails, CompileErrorDiagnosticsStyle};
 use crate::manifest::diagnostic_snippets::create_snippet;
 use crate::manifest::token::{Position, Span, Token, TokenWithSpan};
 use sbor::prelude::*;
@@ -8,6 +8,7 @@ pub enum ExpectedChar {
     Exact(char),
     OneOf(Vec<char>),
     HexDigit,
+    Letter,
     DigitLetterQuotePunctuation,
 }
 
@@ -157,7 +158,8 @@ impl Lexer {
             '-' | '0'..='9' => self.tokenize_number(),
             '"' => self.tokenize_string(),
             'a'..='z' | 'A'..='Z' => self.tokenize_identifier(),
-            '{' | '}' | '(' | ')' | '<' | '>' | ',' | ';' | '&' | '=' => {
+            '$' => self.tokenize_parameter(),
+            '{' | '}' | '(' | ')' | '<' | '>' | ',' | ';' | ':' | '&' | '=' => {
                 self.tokenize_punctuation()
             }
             c => Err(LexerError::unexpected_char(
@@ -367,6 +369,27 @@ impl Lexer {
         Ok(self.new_token(token, start, self.current))
     }
 
+    fn tokenize_parameter(&mut self) -> Result<TokenWithSpan, LexerError> {
+        let start = self.current;
+        assert_eq!(self.advance()?, '$');
+
+        // Unlike identifiers, parameter names can't contain `:`, which separates them from their
+        // type in template parameter declarations
+        let mut name = String::from(self.advance_matching(
+            |c| c.is_ascii_alphabetic() || c == '_',
+            ExpectedChar::Letter,
+        )?);
+        while !self.is_eof() {
+            let next_char = self.peek()?;
+            if !(next_char.is_ascii_alphanumeric() || next_char == '_') {
+                break;
+            }
+            name.push(self.advance()?);
+        }
+
+        Ok(self.new_token(Token::Parameter(name), start, self.current))
+    }
+
     fn tokenize_punctuation(&mut self) -> Result<TokenWithSpan, LexerError> {
         let token_start = self.current;
 
@@ -377,6 +400,7 @@ impl Lexer {
             '>' => Token::GreaterThan,
             ',' => Token::Comma,
             ';' => Token::Semicolon,
+            ':' => Token::Colon,
             '=' => {
                 self.advance_expected('>')?;
                 Token::FatArrow
@@ -385,7 +409,7 @@ impl Lexer {
                 return Err(LexerError::unexpected_char(
                     token_start,
                     c,
-                    ExpectedChar::OneOf(vec!['(', ')', '<', '>', ',', ';', '=']),
+                    ExpectedChar::OneOf(vec!['(', ')', '<', '>', ',', ';', ':', '=']),
                 ))
             }
         };
@@ -406,6 +430,11 @@ pub fn lexer_error_diagnostics(
     err: LexerError,
     style: CompileErrorDiagnosticsStyle,
 ) -> String {
+    let details = lexer_error_details(err);
+    create_snippet(s, &details.span, &details.title, &details.label, style)
+}
+
+pub fn lexer_error_details(err: LexerError) -> CompileErrorDetails {
     let (title, label) = match err.error_kind {
         LexerErrorKind::UnexpectedEof => (
             "unexpected end of file".to_string(),
@@ -424,7 +453,8 @@ pub fn lexer_error_diagnostics(
                     }
                 }
                 ExpectedChar::HexDigit => "hex digit".to_string(),
-                ExpectedChar::DigitLetterQuotePunctuation => "digit, letter, quotation mark or one of punctuation characters '(', ')', '<', '>', ',', ';', '='".to_string(),
+                ExpectedChar::Letter => "letter or '_'".to_string(),
+                ExpectedChar::DigitLetterQuotePunctuation => "digit, letter, quotation mark, '$' or one of punctuation characters '(', ')', '<', '>', ',', ';', ':', '='".to_string(),
             };
             (
                 format!("unexpected character {:?}, expected {}", c, expected),
@@ -452,7 +482,11 @@ pub fn lexer_error_diagnostics(
             "missing unicode surrogate pair".to_string(),
         ),
     };
-    create_snippet(s, &err.span, &title, &label, style)
+    CompileErrorDetails {
+        span: err.span,
+        title,
+        label,
+    }
 }
 
 #[cfg(test)]
@@ -676,6 +710,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parameter() {
+        lex_ok!(
+            "PARAMETER $account_1: ComponentAddress;",
+            vec![
+                Token::Ident("PARAMETER".into()),
+                Token::Parameter("account_1".into()),
+                Token::Colon,
+                Token::Ident("ComponentAddress".into()),
+                Token::Semicolon,
+            ]
+        );
+        lex_error!(
+            "$1",
+            LexerError {
+                error_kind: LexerErrorKind::UnexpectedChar('1', ExpectedChar::Letter),
+                span: span!(start = (1, 0, 1), end = (2, 0, 2))
+            }
+        );
+    }
+
+    #[test]
+    fn test_existing_manifests_have_no_parameters_or_colons() {
+        // `$` and `:` only became tokens with manifest templates, so this checks that no
+        // existing manifest is lexed differently because of them
+        fn collect_manifests(dir: &std::path::Path, manifests: &mut Vec<std::path::PathBuf>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    collect_manifests(&path, manifests);
+                } else if path.extension().is_some_and(|extension| extension == "rtm") {
+                    manifests.push(path);
+                }
+            }
+        }
+
+        let mut manifests = Vec::new();
+        collect_manifests(
+            &std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("examples"),
+            &mut manifests,
+        );
+        assert!(!manifests.is_empty());
+
+        for path in manifests {
+            let manifest = crate::manifest::e2e::apply_address_replacements(
+                std::fs::read_to_string(&path).unwrap(),
+            );
+            let tokens = tokenize(&manifest)
+                .unwrap_or_else(|err| panic!("Failed to tokenize {:?}: {:?}", path, err));
+            for token in tokens {
+                assert!(
+                    !matches!(token.token, Token::Parameter(_) | Token::Colon),
+                    "Unexpected token {} in {:?}",
+                    token.token,
+                    path
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_unicode() {
         lex_ok!(r#""\u2764""#, vec![Token::StringLiteral("❤".to_string())]);
@@ -8,6 +8,7 @@ pub mod diagnostic_snippets;
 #[cfg(feature = "std")]
 pub mod dumper;
 pub mod e2e;
+pub mod formatter;
 pub mod generator;
 pub mod lexer;
 mod manifest_enums;
@@ -18,6 +19,7 @@ mod manifest_traits;
 pub mod parser;
 mod static_manifest_interpreter;
 pub mod static_resource_movements;
+pub mod template;
 pub mod token;
 
 pub use any_instruction::*;
@@ -25,9 +27,14 @@ pub use any_manifest::*;
 pub use blob_provider::*;
 pub use compiler::*;
 pub use decompiler::{decompile, decompile_any, DecompileError};
+pub use formatter::{format_manifest, is_manifest_formatted};
 pub use manifest_enums::*;
 pub use manifest_instruction_effects::*;
 pub use manifest_instructions::*;
 pub use manifest_naming::*;
 pub use manifest_traits::*;
 pub use static_manifest_interpreter::*;
+pub use template::{
+    decompile_template, IsTemplateFragmentProvider, TemplateArguments, TemplateDecompileError,
+    TemplateFragmentProvider, TemplateParameter, TemplateParameterType,
+};
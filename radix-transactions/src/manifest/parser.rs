@@ -1,6 +1,6 @@
 use crate::manifest::ast::ValueKind;
 use crate::manifest::ast::*;
-use crate::manifest::compiler::CompileErrorDiagnosticsStyle;
+use crate::manifest::compiler::{CompileErrorDetails, CompileErrorDiagnosticsStyle};
 use crate::manifest::diagnostic_snippets::create_snippet;
 use crate::manifest::manifest_enums::KNOWN_ENUM_DISCRIMINATORS;
 use crate::manifest::token::{Position, Span, Token, TokenWithSpan};
@@ -47,6 +47,9 @@ pub enum TokenType {
     Value,
     ValueKind,
     EnumDiscriminator,
+    TemplateParameter,
+    TemplateParameterType,
+    TemplateFragmentName,
     Exact(Token),
 }
 
@@ -59,6 +62,9 @@ impl fmt::Display for TokenType {
             TokenType::EnumDiscriminator => {
                 write!(f, "a u8 enum discriminator or valid discriminator alias")
             }
+            TokenType::TemplateParameter => write!(f, "a template parameter, such as '$account'"),
+            TokenType::TemplateParameterType => write!(f, "a template parameter type"),
+            TokenType::TemplateFragmentName => write!(f, "the name of a fragment as a string"),
             TokenType::Exact(token) => write!(f, "exactly {}", token),
         }
     }
@@ -1113,6 +1119,11 @@ pub fn parser_error_diagnostics(
     err: ParserError,
     style: CompileErrorDiagnosticsStyle,
 ) -> String {
+    let details = parser_error_details(err);
+    create_snippet(s, &details.span, &details.title, &details.label, style)
+}
+
+pub fn parser_error_details(err: ParserError) -> CompileErrorDetails {
     let (title, label) = match err.error_kind {
         ParserErrorKind::UnexpectedEof => (
             "unexpected end of file".to_string(),
@@ -1151,7 +1162,11 @@ pub fn parser_error_diagnostics(
         }
     };
 
-    create_snippet(s, &err.span, &title, &label, style)
+    CompileErrorDetails {
+        span: err.span,
+        title,
+        label,
+    }
 }
 
 #[cfg(test)]
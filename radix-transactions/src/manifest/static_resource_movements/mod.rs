@@ -1,11 +1,13 @@
 mod effect;
 mod error;
+mod summary;
 mod typed_invocation;
 mod types;
 mod visitor;
 
 pub use effect::*;
 pub use error::*;
+pub use summary::*;
 pub use typed_invocation::*;
 pub use types::*;
 pub use visitor::*;
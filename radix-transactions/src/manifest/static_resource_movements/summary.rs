@@ -0,0 +1,120 @@
+use super::*;
+use crate::internal_prelude::*;
+
+/// A summary of the account withdraws and deposits of a manifest, as statically determined by the
+/// [`StaticResourceMovementsVisitor`], for showing to a user before they sign the manifest.
+///
+/// The bounds are conservative: the withdraws are exact, and the deposits are bounds which the
+/// actual deposits are guaranteed to fall within if the transaction commits successfully.
+///
+/// Use [`display`][ContextualDisplay::display] with an [`AddressBech32Encoder`] to get a textual
+/// summary.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ResourceMovementsSummary {
+    pub account_withdraws: IndexMap<ComponentAddress, NetWithdraws>,
+    pub account_deposits: IndexMap<ComponentAddress, NetDeposits>,
+}
+
+impl ResourceMovementsSummary {
+    pub fn from_manifest(
+        manifest: &impl ReadableManifest,
+    ) -> Result<Self, StaticResourceMovementsError> {
+        let interpreter = StaticManifestInterpreter::new(ValidationRuleset::all(), manifest);
+        let mut visitor = StaticResourceMovementsVisitor::new(manifest.is_subintent());
+        interpreter.validate_and_apply_visitor(&mut visitor)?;
+        let (account_withdraws, account_deposits) = visitor.output().resolve_account_changes()?;
+        Ok(Self {
+            account_withdraws,
+            account_deposits,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.account_withdraws.is_empty() && self.account_deposits.is_empty()
+    }
+}
+
+impl<'a> ContextualDisplay<AddressDisplayContext<'a>> for ResourceMovementsSummary {
+    type Error = fmt::Error;
+
+    fn contextual_format(
+        &self,
+        f: &mut fmt::Formatter,
+        context: &AddressDisplayContext<'a>,
+    ) -> Result<(), Self::Error> {
+        if self.is_empty() {
+            return writeln!(f, "No withdraws from or deposits into accounts");
+        }
+
+        for (account, withdraws) in self.account_withdraws.iter() {
+            writeln!(f, "Withdraws from {}:", account.display(*context))?;
+            for (resource, withdraw) in withdraws.resources() {
+                let amount = match withdraw {
+                    NetWithdraw::Fungible { total_amount } => total_amount.to_string(),
+                    NetWithdraw::NonFungible {
+                        known_ids,
+                        additional_unknown_ids,
+                    } => describe_ids(known_ids, *additional_unknown_ids),
+                };
+                writeln!(f, "  - {} of {}", amount, resource.display(*context))?;
+            }
+        }
+
+        for (account, deposits) in self.account_deposits.iter() {
+            writeln!(f, "Deposits into {}:", account.display(*context))?;
+            for (resource, bounds) in deposits.specified_resources.iter() {
+                writeln!(
+                    f,
+                    "  - {} of {}",
+                    describe_bounds(bounds),
+                    resource.display(*context)
+                )?;
+            }
+            if deposits.unspecified_resources.may_be_present() {
+                writeln!(f, "  - possibly other, unspecified resources")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn describe_ids(known_ids: &IndexSet<NonFungibleLocalId>, additional_unknown_ids: usize) -> String {
+    let mut parts: Vec<String> = known_ids.iter().map(|id| id.to_string()).collect();
+    if additional_unknown_ids > 0 {
+        parts.push(format!("{} unknown", additional_unknown_ids));
+    }
+    format!("non-fungibles {}", parts.join(", "))
+}
+
+fn describe_bounds(bounds: &ResourceBounds) -> String {
+    if let Some(ids) = bounds.get_exact_ids() {
+        return describe_ids(ids, 0);
+    }
+    if let Some(amount) = bounds.get_exact_amount() {
+        return format!("exactly {}", amount);
+    }
+
+    let amount = match bounds.numeric_bounds() {
+        (LowerBound::NonZero, UpperBound::Unbounded) => "a non-zero amount".to_string(),
+        (LowerBound::NonZero, UpperBound::Inclusive(upper)) => {
+            format!("a non-zero amount of at most {}", upper)
+        }
+        (LowerBound::Inclusive(lower), UpperBound::Unbounded) if lower.is_zero() => {
+            "any amount".to_string()
+        }
+        (LowerBound::Inclusive(lower), UpperBound::Unbounded) => format!("at least {}", lower),
+        (LowerBound::Inclusive(lower), UpperBound::Inclusive(upper)) if lower.is_zero() => {
+            format!("at most {}", upper)
+        }
+        (LowerBound::Inclusive(lower), UpperBound::Inclusive(upper)) => {
+            format!("between {} and {}", lower, upper)
+        }
+    };
+    let required_ids = bounds.required_ids();
+    if required_ids.is_empty() {
+        amount
+    } else {
+        format!("{} including {}", amount, describe_ids(required_ids, 0))
+    }
+}
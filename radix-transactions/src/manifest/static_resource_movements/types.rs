@@ -1622,6 +1622,10 @@ impl NetWithdraws {
         Default::default()
     }
 
+    pub fn resources(&self) -> &IndexMap<ResourceAddress, NetWithdraw> {
+        &self.resources
+    }
+
     pub fn set_fungible(
         mut self,
         resource_address: ResourceAddress,
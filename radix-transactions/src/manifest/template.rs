@@ -0,0 +1,936 @@
+use crate::internal_prelude::*;
+use crate::manifest::compiler::{CompileErrorDetails, CompileErrorDiagnosticsStyle};
+use crate::manifest::decompiler::{decompile, DecompileError};
+use crate::manifest::diagnostic_snippets::create_snippet;
+use crate::manifest::lexer::{lexer_error_details, tokenize, LexerError};
+use crate::manifest::parser::TokenType;
+use crate::manifest::token::{Span, Token, TokenWithSpan};
+
+/// The maximum depth of nested `INCLUDE` statements, which also stops includes from recursing.
+pub const TEMPLATE_MAX_INCLUDE_DEPTH: usize = 8;
+
+/// The values of the parameters of a template, by parameter name (without the `$`).
+///
+/// Each value is written as in a parameter file, e.g. `account_sim1...` for a `ComponentAddress`
+/// or `1.5` for a `Decimal`, and is checked against the declared type of its parameter.
+pub type TemplateArguments = IndexMap<String, String>;
+
+//========
+// Traits
+//========
+
+pub trait IsTemplateFragmentProvider {
+    /// Returns the source of the fragment with the given name, as used in `INCLUDE "<name>";`.
+    fn get_fragment(&self, name: &str) -> Option<String>;
+}
+
+//===========================
+// Default Fragment Provider
+//===========================
+
+#[derive(Default, Debug, Clone)]
+pub struct TemplateFragmentProvider(IndexMap<String, String>);
+
+impl TemplateFragmentProvider {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn new_with_fragments(fragments: IndexMap<String, String>) -> Self {
+        Self(fragments)
+    }
+
+    pub fn add_fragment(&mut self, name: impl Into<String>, source: impl Into<String>) {
+        self.0.insert(name.into(), source.into());
+    }
+}
+
+impl IsTemplateFragmentProvider for TemplateFragmentProvider {
+    fn get_fragment(&self, name: &str) -> Option<String> {
+        self.0.get(name).cloned()
+    }
+}
+
+//=======
+// Types
+//=======
+
+/// The type of a template parameter, which determines both the accepted argument values and the
+/// manifest value which replaces each use of the parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateParameterType {
+    Bool,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    String,
+    Decimal,
+    PreciseDecimal,
+    Address,
+    PackageAddress,
+    ComponentAddress,
+    ResourceAddress,
+    NonFungibleLocalId,
+    NonFungibleGlobalId,
+}
+
+impl TemplateParameterType {
+    pub fn from_ident(ident: &str) -> Option<Self> {
+        let value = match ident {
+            "Bool" => Self::Bool,
+            "I8" => Self::I8,
+            "I16" => Self::I16,
+            "I32" => Self::I32,
+            "I64" => Self::I64,
+            "I128" => Self::I128,
+            "U8" => Self::U8,
+            "U16" => Self::U16,
+            "U32" => Self::U32,
+            "U64" => Self::U64,
+            "U128" => Self::U128,
+            "String" => Self::String,
+            "Decimal" => Self::Decimal,
+            "PreciseDecimal" => Self::PreciseDecimal,
+            "Address" => Self::Address,
+            "PackageAddress" => Self::PackageAddress,
+            "ComponentAddress" => Self::ComponentAddress,
+            "ResourceAddress" => Self::ResourceAddress,
+            "NonFungibleLocalId" => Self::NonFungibleLocalId,
+            "NonFungibleGlobalId" => Self::NonFungibleGlobalId,
+            _ => {
+                return None;
+            }
+        };
+        Some(value)
+    }
+
+    pub fn ident(self) -> &'static str {
+        match self {
+            Self::Bool => "Bool",
+            Self::I8 => "I8",
+            Self::I16 => "I16",
+            Self::I32 => "I32",
+            Self::I64 => "I64",
+            Self::I128 => "I128",
+            Self::U8 => "U8",
+            Self::U16 => "U16",
+            Self::U32 => "U32",
+            Self::U64 => "U64",
+            Self::U128 => "U128",
+            Self::String => "String",
+            Self::Decimal => "Decimal",
+            Self::PreciseDecimal => "PreciseDecimal",
+            Self::Address => "Address",
+            Self::PackageAddress => "PackageAddress",
+            Self::ComponentAddress => "ComponentAddress",
+            Self::ResourceAddress => "ResourceAddress",
+            Self::NonFungibleLocalId => "NonFungibleLocalId",
+            Self::NonFungibleGlobalId => "NonFungibleGlobalId",
+        }
+    }
+
+    /// Checks an argument against this type, and returns the tokens of the manifest value which
+    /// it stands for, or the reason why it's invalid.
+    fn instantiate(
+        self,
+        argument: &str,
+        address_bech32_decoder: &AddressBech32Decoder,
+    ) -> Result<Vec<Token>, String> {
+        fn parse<T: FromStr>(argument: &str, map: fn(T) -> Token) -> Result<Vec<Token>, String>
+        where
+            T::Err: Debug,
+        {
+            argument
+                .parse::<T>()
+                .map(|value| vec![map(value)])
+                .map_err(|err| format!("{:?}", err))
+        }
+
+        fn check_address<T: for<'a> TryFrom<&'a [u8]>>(
+            argument: &str,
+            address_bech32_decoder: &AddressBech32Decoder,
+        ) -> Result<(), String> {
+            let (_, full_data) = address_bech32_decoder
+                .validate_and_decode(argument)
+                .map_err(|err| format!("{:?}", err))?;
+            T::try_from(full_data.as_ref())
+                .map(|_| ())
+                .map_err(|_| "wrong kind of address".to_string())
+        }
+
+        match self {
+            Self::Bool => parse(argument, Token::BoolLiteral),
+            Self::I8 => parse(argument, Token::I8Literal),
+            Self::I16 => parse(argument, Token::I16Literal),
+            Self::I32 => parse(argument, Token::I32Literal),
+            Self::I64 => parse(argument, Token::I64Literal),
+            Self::I128 => parse(argument, Token::I128Literal),
+            Self::U8 => parse(argument, Token::U8Literal),
+            Self::U16 => parse(argument, Token::U16Literal),
+            Self::U32 => parse(argument, Token::U32Literal),
+            Self::U64 => parse(argument, Token::U64Literal),
+            Self::U128 => parse(argument, Token::U128Literal),
+            Self::String => Ok(vec![Token::StringLiteral(argument.to_string())]),
+            Self::Decimal => Decimal::from_str(argument)
+                .map(|_| value_tokens("Decimal", argument))
+                .map_err(|err| format!("{:?}", err)),
+            Self::PreciseDecimal => PreciseDecimal::from_str(argument)
+                .map(|_| value_tokens("PreciseDecimal", argument))
+                .map_err(|err| format!("{:?}", err)),
+            Self::Address => check_address::<GlobalAddress>(argument, address_bech32_decoder)
+                .map(|_| value_tokens("Address", argument)),
+            Self::PackageAddress => {
+                check_address::<PackageAddress>(argument, address_bech32_decoder)
+                    .map(|_| value_tokens("Address", argument))
+            }
+            Self::ComponentAddress => {
+                check_address::<ComponentAddress>(argument, address_bech32_decoder)
+                    .map(|_| value_tokens("Address", argument))
+            }
+            Self::ResourceAddress => {
+                check_address::<ResourceAddress>(argument, address_bech32_decoder)
+                    .map(|_| value_tokens("Address", argument))
+            }
+            Self::NonFungibleLocalId => NonFungibleLocalId::from_str(argument)
+                .map(|_| value_tokens("NonFungibleLocalId", argument))
+                .map_err(|err| format!("{:?}", err)),
+            Self::NonFungibleGlobalId => {
+                NonFungibleGlobalId::try_from_canonical_string(address_bech32_decoder, argument)
+                    .map(|_| value_tokens("NonFungibleGlobalId", argument))
+                    .map_err(|err| format!("{:?}", err))
+            }
+        }
+    }
+}
+
+fn value_tokens(value_ident: &str, argument: &str) -> Vec<Token> {
+    vec![
+        Token::Ident(value_ident.to_string()),
+        Token::OpenParenthesis,
+        Token::StringLiteral(argument.to_string()),
+        Token::CloseParenthesis,
+    ]
+}
+
+//========
+// Errors
+//========
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateErrorKind {
+    UnexpectedEof,
+    UnexpectedToken {
+        expected: TokenType,
+        actual: Token,
+    },
+    UnknownParameterType(String),
+    ConflictingParameterDeclaration {
+        name: String,
+        declared: TemplateParameterType,
+        actual: TemplateParameterType,
+    },
+    UndeclaredParameter(String),
+    MissingArgument(String),
+    InvalidArgument {
+        name: String,
+        expected: TemplateParameterType,
+        argument: String,
+        reason: String,
+    },
+    FragmentNotFound(String),
+    FragmentLexerError {
+        name: String,
+        error: LexerError,
+    },
+    MaxIncludeDepthExceeded(usize),
+}
+
+/// An error in a template. Errors in included fragments are located at the `INCLUDE` statement
+/// of the template, as the fragment source isn't available to the diagnostics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    pub error_kind: TemplateErrorKind,
+    pub span: Span,
+}
+
+//===============
+// Instantiation
+//===============
+
+/// Instantiates the tokens of a manifest template, resulting in the tokens of a regular manifest.
+///
+/// Templates extend manifests with two statements, which may appear wherever an instruction can:
+///
+/// * `PARAMETER $name: Type;` declares a typed parameter, which can then be used in place of any
+///   value with `$name`. Each use is replaced by the argument with the same name, after checking
+///   it against the type.
+/// * `INCLUDE "name";` is replaced by the statements of the fragment with the given name. Fragments
+///   may declare their own parameters, or use those of the template.
+///
+/// Parameters may be declared more than once, as long as their type is the same. Arguments for
+/// parameters which aren't declared are ignored, so that one parameter file can serve several
+/// templates.
+pub fn instantiate_template(
+    tokens: Vec<TokenWithSpan>,
+    arguments: &TemplateArguments,
+    fragments: &impl IsTemplateFragmentProvider,
+    address_bech32_decoder: &AddressBech32Decoder,
+) -> Result<Vec<TokenWithSpan>, TemplateError> {
+    let mut expander = TemplateExpander {
+        fragments,
+        parameters: index_map_new(),
+        tokens: Vec::new(),
+    };
+    expander.expand(tokens, None, 0)?;
+
+    let mut values = index_map_new();
+    for (name, (parameter_type, span)) in expander.parameters {
+        let argument = arguments.get(&name).ok_or_else(|| TemplateError {
+            error_kind: TemplateErrorKind::MissingArgument(name.clone()),
+            span,
+        })?;
+        let value = parameter_type
+            .instantiate(argument, address_bech32_decoder)
+            .map_err(|reason| TemplateError {
+                error_kind: TemplateErrorKind::InvalidArgument {
+                    name: name.clone(),
+                    expected: parameter_type,
+                    argument: argument.clone(),
+                    reason,
+                },
+                span,
+            })?;
+        values.insert(name, value);
+    }
+
+    let mut instantiated = Vec::with_capacity(expander.tokens.len());
+    for token in expander.tokens {
+        let Token::Parameter(name) = &token.token else {
+            instantiated.push(token);
+            continue;
+        };
+        let value = values.get(name).ok_or_else(|| TemplateError {
+            error_kind: TemplateErrorKind::UndeclaredParameter(name.clone()),
+            span: token.span,
+        })?;
+        instantiated.extend(value.iter().map(|value_token| TokenWithSpan {
+            token: value_token.clone(),
+            span: token.span,
+        }));
+    }
+
+    Ok(instantiated)
+}
+
+struct TemplateExpander<'a, F: IsTemplateFragmentProvider> {
+    fragments: &'a F,
+    /// The declared parameters, with the span of their first declaration.
+    parameters: IndexMap<String, (TemplateParameterType, Span)>,
+    /// The expanded tokens, which still contain the uses of parameters.
+    tokens: Vec<TokenWithSpan>,
+}
+
+impl<'a, F: IsTemplateFragmentProvider> TemplateExpander<'a, F> {
+    /// Expands the statements of the template or an included fragment. The tokens of fragments
+    /// are given the span of the `INCLUDE` statement in the template.
+    fn expand(
+        &mut self,
+        tokens: Vec<TokenWithSpan>,
+        include_span: Option<Span>,
+        depth: usize,
+    ) -> Result<(), TemplateError> {
+        let mut tokens = tokens.into_iter().map(|mut token| {
+            if let Some(span) = include_span {
+                token.span = span;
+            }
+            token
+        });
+        let mut is_statement_start = true;
+        while let Some(token) = tokens.next() {
+            match &token.token {
+                Token::Ident(ident) if is_statement_start && ident == "PARAMETER" => {
+                    self.declare_parameter(&mut tokens, token.span)?;
+                }
+                Token::Ident(ident) if is_statement_start && ident == "INCLUDE" => {
+                    let (name, span) = Self::next_fragment_name(&mut tokens, token.span)?;
+                    Self::next_exact(&mut tokens, Token::Semicolon, span)?;
+                    if depth >= TEMPLATE_MAX_INCLUDE_DEPTH {
+                        return Err(TemplateError {
+                            error_kind: TemplateErrorKind::MaxIncludeDepthExceeded(
+                                TEMPLATE_MAX_INCLUDE_DEPTH,
+                            ),
+                            span,
+                        });
+                    }
+                    let fragment =
+                        self.fragments
+                            .get_fragment(&name)
+                            .ok_or_else(|| TemplateError {
+                                error_kind: TemplateErrorKind::FragmentNotFound(name.clone()),
+                                span,
+                            })?;
+                    let fragment_tokens = tokenize(&fragment).map_err(|error| TemplateError {
+                        error_kind: TemplateErrorKind::FragmentLexerError { name, error },
+                        span,
+                    })?;
+                    self.expand(
+                        fragment_tokens,
+                        Some(include_span.unwrap_or(span)),
+                        depth + 1,
+                    )?;
+                }
+                _ => {
+                    is_statement_start = token.token == Token::Semicolon;
+                    self.tokens.push(token);
+                    continue;
+                }
+            }
+            is_statement_start = true;
+        }
+        Ok(())
+    }
+
+    fn declare_parameter(
+        &mut self,
+        tokens: &mut impl Iterator<Item = TokenWithSpan>,
+        statement_span: Span,
+    ) -> Result<(), TemplateError> {
+        let token = Self::next(tokens, statement_span)?;
+        let Token::Parameter(name) = token.token else {
+            return Err(TemplateError {
+                error_kind: TemplateErrorKind::UnexpectedToken {
+                    expected: TokenType::TemplateParameter,
+                    actual: token.token,
+                },
+                span: token.span,
+            });
+        };
+        let start = token.span.start;
+        let colon = Self::next_exact(tokens, Token::Colon, token.span)?;
+        let token = Self::next(tokens, colon.span)?;
+        let parameter_type = match &token.token {
+            Token::Ident(ident) => {
+                TemplateParameterType::from_ident(ident).ok_or_else(|| TemplateError {
+                    error_kind: TemplateErrorKind::UnknownParameterType(ident.clone()),
+                    span: token.span,
+                })?
+            }
+            _ => {
+                return Err(TemplateError {
+                    error_kind: TemplateErrorKind::UnexpectedToken {
+                        expected: TokenType::TemplateParameterType,
+                        actual: token.token,
+                    },
+                    span: token.span,
+                })
+            }
+        };
+        let span = Span {
+            start,
+            end: token.span.end,
+        };
+        Self::next_exact(tokens, Token::Semicolon, token.span)?;
+
+        match self.parameters.get(&name) {
+            Some((declared, _)) if *declared != parameter_type => Err(TemplateError {
+                error_kind: TemplateErrorKind::ConflictingParameterDeclaration {
+                    name,
+                    declared: *declared,
+                    actual: parameter_type,
+                },
+                span,
+            }),
+            Some(_) => Ok(()),
+            None => {
+                self.parameters.insert(name, (parameter_type, span));
+                Ok(())
+            }
+        }
+    }
+
+    fn next_fragment_name(
+        tokens: &mut impl Iterator<Item = TokenWithSpan>,
+        previous_span: Span,
+    ) -> Result<(String, Span), TemplateError> {
+        let token = Self::next(tokens, previous_span)?;
+        match token.token {
+            Token::StringLiteral(name) => Ok((name, token.span)),
+            actual => Err(TemplateError {
+                error_kind: TemplateErrorKind::UnexpectedToken {
+                    expected: TokenType::TemplateFragmentName,
+                    actual,
+                },
+                span: token.span,
+            }),
+        }
+    }
+
+    fn next_exact(
+        tokens: &mut impl Iterator<Item = TokenWithSpan>,
+        expected: Token,
+        previous_span: Span,
+    ) -> Result<TokenWithSpan, TemplateError> {
+        let token = Self::next(tokens, previous_span)?;
+        if token.token != expected {
+            return Err(TemplateError {
+                error_kind: TemplateErrorKind::UnexpectedToken {
+                    expected: TokenType::Exact(expected),
+                    actual: token.token,
+                },
+                span: token.span,
+            });
+        }
+        Ok(token)
+    }
+
+    fn next(
+        tokens: &mut impl Iterator<Item = TokenWithSpan>,
+        previous_span: Span,
+    ) -> Result<TokenWithSpan, TemplateError> {
+        tokens.next().ok_or(TemplateError {
+            error_kind: TemplateErrorKind::UnexpectedEof,
+            span: Span {
+                start: previous_span.end,
+                end: previous_span.end,
+            },
+        })
+    }
+}
+
+//===============
+// Decompilation
+//===============
+
+/// A parameter of a template decompiled from a manifest, with the argument whose value it stands
+/// for.
+///
+/// The argument is written as in a parameter file, and in the form in which the decompiler writes
+/// the value, e.g. `1.5` rather than `1.50` for a `Decimal`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateParameter {
+    pub name: String,
+    pub parameter_type: TemplateParameterType,
+    pub argument: String,
+}
+
+impl TemplateParameter {
+    pub fn new(
+        name: impl Into<String>,
+        parameter_type: TemplateParameterType,
+        argument: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            parameter_type,
+            argument: argument.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum TemplateDecompileError {
+    DecompileError(DecompileError),
+    InvalidParameterName(String),
+    InvalidArgument {
+        name: String,
+        expected: TemplateParameterType,
+        argument: String,
+        reason: String,
+    },
+}
+
+/// Decompiles a manifest into a template, which declares the given parameters and uses them in
+/// place of every value matching their argument.
+///
+/// Instantiating the template with the same arguments results in the original manifest.
+pub fn decompile_template(
+    manifest: &impl TypedReadableManifest,
+    parameters: &[TemplateParameter],
+    network: &NetworkDefinition,
+) -> Result<String, TemplateDecompileError> {
+    let address_bech32_decoder = AddressBech32Decoder::new(network);
+    let mut values = Vec::with_capacity(parameters.len());
+    for parameter in parameters {
+        let mut name_chars = parameter.name.chars();
+        let is_valid_name = name_chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name_chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_valid_name {
+            return Err(TemplateDecompileError::InvalidParameterName(
+                parameter.name.clone(),
+            ));
+        }
+        let value = parameter
+            .parameter_type
+            .instantiate(&parameter.argument, &address_bech32_decoder)
+            .map_err(|reason| TemplateDecompileError::InvalidArgument {
+                name: parameter.name.clone(),
+                expected: parameter.parameter_type,
+                argument: parameter.argument.clone(),
+                reason,
+            })?;
+        values.push((parameter, value));
+    }
+
+    let manifest = decompile(manifest, network).map_err(TemplateDecompileError::DecompileError)?;
+    let manifest_chars = manifest.chars().collect::<Vec<_>>();
+    let tokens = tokenize(&manifest).expect("Decompiled manifest should be valid");
+
+    let mut template = String::new();
+    for parameter in parameters {
+        template.push_str(&format!(
+            "PARAMETER ${}: {};\n",
+            parameter.name,
+            parameter.parameter_type.ident()
+        ));
+    }
+    if !parameters.is_empty() {
+        template.push('\n');
+    }
+
+    // Spans index chars, so the decompiled manifest is copied char by char around the values
+    // which are replaced by parameters
+    let mut copied_up_to = 0;
+    let mut index = 0;
+    while index < tokens.len() {
+        let matching_parameter = values.iter().find(|(_, value)| {
+            tokens[index..]
+                .iter()
+                .map(|token| &token.token)
+                .take(value.len())
+                .eq(value.iter())
+        });
+        let Some((parameter, value)) = matching_parameter else {
+            index += 1;
+            continue;
+        };
+        template.extend(&manifest_chars[copied_up_to..tokens[index].span.start.full_index]);
+        template.push('$');
+        template.push_str(&parameter.name);
+        index += value.len();
+        copied_up_to = tokens[index - 1].span.end.full_index;
+    }
+    template.extend(&manifest_chars[copied_up_to..]);
+
+    Ok(template)
+}
+
+//=============
+// Diagnostics
+//=============
+
+pub fn template_error_diagnostics(
+    s: &str,
+    err: TemplateError,
+    style: CompileErrorDiagnosticsStyle,
+) -> String {
+    let details = template_error_details(err);
+    create_snippet(s, &details.span, &details.title, &details.label, style)
+}
+
+pub fn template_error_details(err: TemplateError) -> CompileErrorDetails {
+    let (title, label) = match err.error_kind {
+        TemplateErrorKind::UnexpectedEof => (
+            "unexpected end of file".to_string(),
+            "unexpected end of file".to_string(),
+        ),
+        TemplateErrorKind::UnexpectedToken { expected, actual } => {
+            let title = format!("expected {}, found {}", expected, actual);
+            let label = format!("expected {}", expected);
+            (title, label)
+        }
+        TemplateErrorKind::UnknownParameterType(actual) => {
+            let title = format!("unknown template parameter type '{}'", actual);
+            (title, "unknown template parameter type".to_string())
+        }
+        TemplateErrorKind::ConflictingParameterDeclaration {
+            name,
+            declared,
+            actual,
+        } => {
+            let title = format!(
+                "parameter '${}' is declared as {:?}, but was previously declared as {:?}",
+                name, actual, declared
+            );
+            let label = format!("previously declared as {:?}", declared);
+            (title, label)
+        }
+        TemplateErrorKind::UndeclaredParameter(name) => {
+            let title = format!("parameter '${}' is not declared", name);
+            (title, "undeclared parameter".to_string())
+        }
+        TemplateErrorKind::MissingArgument(name) => {
+            let title = format!("no argument is given for parameter '${}'", name);
+            (title, "missing argument".to_string())
+        }
+        TemplateErrorKind::InvalidArgument {
+            name,
+            expected,
+            argument,
+            reason,
+        } => {
+            let title = format!(
+                "argument {:?} for parameter '${}' is not a valid {:?} - {}",
+                argument, name, expected, reason
+            );
+            let label = format!("expected {:?}", expected);
+            (title, label)
+        }
+        TemplateErrorKind::FragmentNotFound(name) => {
+            let title = format!("fragment {:?} not found", name);
+            (title, "fragment not found".to_string())
+        }
+        TemplateErrorKind::FragmentLexerError { name, error } => {
+            let details = lexer_error_details(error);
+            let title = format!(
+                "{} in fragment {:?} at line {}",
+                details.title,
+                name,
+                details.span.start.line_number()
+            );
+            (title, "invalid fragment".to_string())
+        }
+        TemplateErrorKind::MaxIncludeDepthExceeded(max) => {
+            let title = format!("included fragments are nested deeper than max {}", max);
+            (title, "max include depth exceeded".to_string())
+        }
+    };
+
+    CompileErrorDetails {
+        span: err.span,
+        title,
+        label,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACCOUNT: &str = "account_sim1cyvgx33089ukm2pl97pv4max0x40ruvfy4lt60yvya744cve475w0q";
+    const XRD_ADDRESS: &str = "resource_sim1tknxxxxxxxxxradxrdxxxxxxxxx009923554798xxxxxxxxxakj8n3";
+
+    fn compile(
+        template: &str,
+        arguments: &[(&str, &str)],
+        fragments: TemplateFragmentProvider,
+    ) -> Result<TransactionManifestV1, CompileError> {
+        let arguments = arguments
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        compile_manifest_template(
+            template,
+            &arguments,
+            &fragments,
+            &NetworkDefinition::simulator(),
+            BlobProvider::default(),
+        )
+    }
+
+    fn template_error_kind(
+        result: Result<TransactionManifestV1, CompileError>,
+    ) -> TemplateErrorKind {
+        match result {
+            Err(CompileError::TemplateError(err)) => err.error_kind,
+            other => panic!("Expected a template error, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_template_is_instantiated_with_typed_arguments() {
+        let template = r#"
+            PARAMETER $account: ComponentAddress;
+            PARAMETER $amount: Decimal;
+            CALL_METHOD $account "withdraw" Address("${xrd}") $amount;
+            CALL_METHOD $account "deposit_batch" Expression("ENTIRE_WORKTOP");
+        "#;
+        let manifest = r#"
+            CALL_METHOD Address("${account}") "withdraw" Address("${xrd}") Decimal("1.5");
+            CALL_METHOD Address("${account}") "deposit_batch" Expression("ENTIRE_WORKTOP");
+        "#;
+        let replace = |s: &str| {
+            s.replace("${account}", ACCOUNT)
+                .replace("${xrd}", XRD_ADDRESS)
+        };
+
+        assert_eq!(
+            compile(
+                &replace(template),
+                &[("account", ACCOUNT), ("amount", "1.5"), ("unused", "1")],
+                TemplateFragmentProvider::new(),
+            ),
+            compile_manifest(
+                &replace(manifest),
+                &NetworkDefinition::simulator(),
+                BlobProvider::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_fragments_are_included_with_their_parameters() {
+        let mut fragments = TemplateFragmentProvider::new();
+        fragments.add_fragment(
+            "lock_fee.rtm",
+            "PARAMETER $account: ComponentAddress;\nPARAMETER $fee: Decimal;\nCALL_METHOD $account \"lock_fee\" $fee;",
+        );
+        fragments.add_fragment("outer.rtm", "INCLUDE \"lock_fee.rtm\";");
+        let template = r#"
+            PARAMETER $account: ComponentAddress;
+            INCLUDE "outer.rtm";
+            CALL_METHOD $account "deposit_batch" Expression("ENTIRE_WORKTOP");
+        "#;
+
+        let manifest =
+            compile(template, &[("account", ACCOUNT), ("fee", "10")], fragments).unwrap();
+        assert_eq!(manifest.instructions.len(), 2);
+        assert!(matches!(
+            &manifest.instructions[0],
+            InstructionV1::CallMethod(CallMethod { method_name, .. }) if method_name == "lock_fee"
+        ));
+    }
+
+    #[test]
+    fn test_arguments_are_type_checked() {
+        let template = "PARAMETER $account: ComponentAddress;\nCALL_METHOD $account \"free\";";
+
+        assert!(matches!(
+            template_error_kind(compile(
+                template,
+                &[("account", XRD_ADDRESS)],
+                TemplateFragmentProvider::new()
+            )),
+            TemplateErrorKind::InvalidArgument {
+                expected: TemplateParameterType::ComponentAddress,
+                ..
+            }
+        ));
+        assert_eq!(
+            template_error_kind(compile(template, &[], TemplateFragmentProvider::new())),
+            TemplateErrorKind::MissingArgument("account".to_string())
+        );
+        assert!(matches!(
+            template_error_kind(compile(
+                "PARAMETER $amount: U8;\nPARAMETER $amount: Decimal;",
+                &[("amount", "1")],
+                TemplateFragmentProvider::new()
+            )),
+            TemplateErrorKind::ConflictingParameterDeclaration { .. }
+        ));
+    }
+
+    #[test]
+    fn test_errors_are_located_in_the_template() {
+        let template = "PARAMETER $account: ComponentAddress;\nCALL_METHOD $acount \"free\";";
+        let result = compile(
+            template,
+            &[("account", ACCOUNT)],
+            TemplateFragmentProvider::new(),
+        );
+        let Err(CompileError::TemplateError(err)) = result else {
+            panic!("Expected a template error, found {:?}", result);
+        };
+        assert_eq!(
+            err.error_kind,
+            TemplateErrorKind::UndeclaredParameter("acount".to_string())
+        );
+        assert_eq!(
+            (err.span.start.line_idx, err.span.start.line_char_index),
+            (1, 12)
+        );
+        assert_eq!(
+            (err.span.end.line_idx, err.span.end.line_char_index),
+            (1, 19)
+        );
+
+        let diagnostics = compile_error_diagnostics(
+            template,
+            CompileError::TemplateError(err),
+            CompileErrorDiagnosticsStyle::PlainText,
+        );
+        assert!(diagnostics.contains("parameter '$acount' is not declared"));
+    }
+
+    #[test]
+    fn test_recursive_includes_are_rejected() {
+        let mut fragments = TemplateFragmentProvider::new();
+        fragments.add_fragment("loop.rtm", "INCLUDE \"loop.rtm\";");
+
+        assert_eq!(
+            template_error_kind(compile("INCLUDE \"loop.rtm\";", &[], fragments)),
+            TemplateErrorKind::MaxIncludeDepthExceeded(TEMPLATE_MAX_INCLUDE_DEPTH)
+        );
+        assert_eq!(
+            template_error_kind(compile(
+                "INCLUDE \"missing.rtm\";",
+                &[],
+                TemplateFragmentProvider::new()
+            )),
+            TemplateErrorKind::FragmentNotFound("missing.rtm".to_string())
+        );
+    }
+
+    #[test]
+    fn test_template_built_with_manifest_builder_instantiates_to_the_same_manifest() {
+        let network = NetworkDefinition::simulator();
+        let address_bech32_decoder = AddressBech32Decoder::new(&network);
+        let account = ComponentAddress::try_from_bech32(&address_bech32_decoder, ACCOUNT).unwrap();
+        let manifest = || {
+            ManifestBuilder::new()
+                .lock_fee(account, 10)
+                .withdraw_from_account(account, XRD, dec!("1.5"))
+                .try_deposit_entire_worktop_or_abort(account, None)
+        };
+        let parameters = [
+            TemplateParameter::new("account", TemplateParameterType::ComponentAddress, ACCOUNT),
+            TemplateParameter::new("amount", TemplateParameterType::Decimal, "1.5"),
+        ];
+
+        let template = manifest().build_template(&parameters, &network).unwrap();
+
+        assert!(template
+            .starts_with("PARAMETER $account: ComponentAddress;\nPARAMETER $amount: Decimal;\n"));
+        assert!(!template.contains(ACCOUNT));
+        assert!(template.contains("$amount"));
+        assert_eq!(
+            compile(
+                &template,
+                &[("account", ACCOUNT), ("amount", "1.5")],
+                TemplateFragmentProvider::new(),
+            ),
+            Ok(manifest().build())
+        );
+    }
+
+    #[test]
+    fn test_template_parameters_are_checked_when_decompiling() {
+        let manifest = ManifestBuilder::new().drop_all_proofs().build();
+        let network = NetworkDefinition::simulator();
+
+        assert!(matches!(
+            decompile_template(
+                &manifest,
+                &[TemplateParameter::new("1st", TemplateParameterType::U8, "1")],
+                &network
+            ),
+            Err(TemplateDecompileError::InvalidParameterName(name)) if name == "1st"
+        ));
+        assert!(matches!(
+            decompile_template(
+                &manifest,
+                &[TemplateParameter::new("account", TemplateParameterType::ComponentAddress, XRD_ADDRESS)],
+                &network
+            ),
+            Err(TemplateDecompileError::InvalidArgument { name, .. }) if name == "account"
+        ));
+    }
+}
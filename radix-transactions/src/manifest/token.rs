@@ -80,6 +80,8 @@ pub enum Token {
     StringLiteral(String),
 
     Ident(String),
+    /// A reference to a template parameter, such as `$account`, without the `$`.
+    Parameter(String),
 
     /* Punctuations */
     OpenParenthesis,
@@ -88,6 +90,7 @@ pub enum Token {
     GreaterThan,
     Comma,
     Semicolon,
+    Colon,
     FatArrow,
 }
 
@@ -107,12 +110,14 @@ impl fmt::Display for Token {
             Token::U128Literal(value) => write!(f, "'{:?}u128'", value),
             Token::StringLiteral(value) => write!(f, "{:?}", value),
             Token::Ident(value) => write!(f, "'{}'", value),
+            Token::Parameter(value) => write!(f, "'${}'", value),
             Token::OpenParenthesis => write!(f, "'('"),
             Token::CloseParenthesis => write!(f, "')'",),
             Token::LessThan => write!(f, "'<'"),
             Token::GreaterThan => write!(f, "'>'",),
             Token::Comma => write!(f, "','"),
             Token::Semicolon => write!(f, "';'",),
+            Token::Colon => write!(f, "':'"),
             Token::FatArrow => write!(f, "'=>'"),
         }
     }
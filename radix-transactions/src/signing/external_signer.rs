@@ -0,0 +1,397 @@
+use crate::internal_prelude::*;
+use crate::signing::{Signer, TrySigner};
+use radix_common::prelude::IsHash;
+use std::cell::RefCell;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+#[derive(Debug)]
+pub enum ExternalSignerError {
+    IoError(io::Error),
+    /// The external signer answered a request with `ERR <reason>`.
+    Refused(String),
+    /// The external signer answered a request with something other than `OK` or `ERR`, or with a
+    /// malformed public key or signature.
+    InvalidResponse(String),
+    /// The external signer returned a signature which doesn't match its public key.
+    InvalidSignature,
+}
+
+impl From<io::Error> for ExternalSignerError {
+    fn from(error: io::Error) -> Self {
+        Self::IoError(error)
+    }
+}
+
+//=============
+// Connections
+//=============
+
+pub trait ExternalSignerConnection {
+    /// Sends a request and returns the response, both as single lines without the line break.
+    fn request(&mut self, request: &str) -> io::Result<String>;
+}
+
+/// A connection over a pair of streams, such as a Unix socket.
+pub struct LineConnection<R: BufRead, W: Write> {
+    reader: R,
+    writer: W,
+}
+
+impl<R: BufRead, W: Write> LineConnection<R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        Self { reader, writer }
+    }
+}
+
+impl<R: BufRead, W: Write> ExternalSignerConnection for LineConnection<R, W> {
+    fn request(&mut self, request: &str) -> io::Result<String> {
+        send_line_and_read_line(&mut self.reader, &mut self.writer, request)
+    }
+}
+
+/// A connection to the standard input and output of a child process, which is stopped with the
+/// connection by closing its standard input.
+pub struct ProcessConnection {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ProcessConnection {
+    pub fn spawn(command: &mut Command) -> io::Result<Self> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take();
+        let stdout = BufReader::new(child.stdout.take().expect("Stdout is piped"));
+        Ok(Self {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+}
+
+impl ExternalSignerConnection for ProcessConnection {
+    fn request(&mut self, request: &str) -> io::Result<String> {
+        let stdin = self.stdin.as_mut().expect("Stdin is only closed on drop");
+        send_line_and_read_line(&mut self.stdout, stdin, request)
+    }
+}
+
+impl Drop for ProcessConnection {
+    fn drop(&mut self) {
+        drop(self.stdin.take());
+        let _ = self.child.wait();
+    }
+}
+
+fn send_line_and_read_line(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    line: &str,
+) -> io::Result<String> {
+    writeln!(writer, "{}", line)?;
+    writer.flush()?;
+
+    let mut response = String::new();
+    if reader.read_line(&mut response)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(response.trim_end_matches(['\r', '\n']).to_string())
+}
+
+//========
+// Signer
+//========
+
+/// A signer whose keys are held by another process, such as a signing daemon in front of an HSM.
+///
+/// The [`ExternalSigner`] talks to the other process with a line-based text protocol, where each
+/// request is answered by a single response:
+///
+/// ```text
+/// > PUBLIC_KEY
+/// < OK Secp256k1 <public key hex>
+/// > SIGN <message hash hex>
+/// < OK <signature hex>
+/// ```
+///
+/// The public key is `Secp256k1` (33 bytes, compressed) or `Ed25519` (32 bytes), and the signature
+/// has the encoding of the corresponding [`SignatureV1`]. Any request may instead be answered with
+/// `ERR <reason>`, e.g. when the operator declines to sign.
+///
+/// [`serve_external_signer`] implements the other side of the protocol for any in-process
+/// [`Signer`], which can stand in for a signing daemon in tests, or wrap a key in a daemon.
+///
+/// The signer blocks for the duration of each request. Async applications can instead implement
+/// [`AsyncTrySigner`] for a non-blocking connection, and sign with e.g.
+/// [`TransactionV2Builder::try_sign_async`].
+///
+/// [`AsyncTrySigner`]: crate::signing::AsyncTrySigner
+/// [`TransactionV2Builder::try_sign_async`]: crate::builder::TransactionV2Builder::try_sign_async
+pub struct ExternalSigner<C: ExternalSignerConnection> {
+    connection: RefCell<C>,
+    public_key: PublicKey,
+}
+
+impl ExternalSigner<ProcessConnection> {
+    /// Starts the given command, and talks to it over its standard input and output.
+    pub fn spawn(command: &mut Command) -> Result<Self, ExternalSignerError> {
+        Self::new(ProcessConnection::spawn(command)?)
+    }
+}
+
+#[cfg(unix)]
+impl
+    ExternalSigner<
+        LineConnection<BufReader<std::os::unix::net::UnixStream>, std::os::unix::net::UnixStream>,
+    >
+{
+    /// Connects to a signer listening on the Unix socket at the given path.
+    pub fn connect_unix(path: impl AsRef<std::path::Path>) -> Result<Self, ExternalSignerError> {
+        let stream = std::os::unix::net::UnixStream::connect(path)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        Self::new(LineConnection::new(reader, stream))
+    }
+}
+
+impl<C: ExternalSignerConnection> ExternalSigner<C> {
+    /// Creates a signer from a connection, after requesting its public key.
+    pub fn new(mut connection: C) -> Result<Self, ExternalSignerError> {
+        let response = Self::request(&mut connection, "PUBLIC_KEY")?;
+        let public_key = response
+            .split_once(' ')
+            .and_then(|(key_type, key_hex)| {
+                let bytes = hex::decode(key_hex).ok()?;
+                let public_key = match key_type {
+                    "Secp256k1" => Secp256k1PublicKey::try_from(bytes.as_slice()).ok()?.into(),
+                    "Ed25519" => Ed25519PublicKey::try_from(bytes.as_slice()).ok()?.into(),
+                    _ => return None,
+                };
+                Some(public_key)
+            })
+            .ok_or(ExternalSignerError::InvalidResponse(response))?;
+        Ok(Self {
+            connection: RefCell::new(connection),
+            public_key,
+        })
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn request(connection: &mut C, request: &str) -> Result<String, ExternalSignerError> {
+        let response = connection.request(request)?;
+        if let Some(result) = response.strip_prefix("OK ") {
+            Ok(result.to_string())
+        } else if let Some(reason) = response.strip_prefix("ERR") {
+            Err(ExternalSignerError::Refused(
+                reason.trim_start().to_string(),
+            ))
+        } else {
+            Err(ExternalSignerError::InvalidResponse(response))
+        }
+    }
+
+    fn sign(&self, message_hash: &impl IsHash) -> Result<SignatureV1, ExternalSignerError> {
+        let request = format!("SIGN {}", hex::encode(message_hash.as_slice()));
+        let response = Self::request(&mut self.connection.borrow_mut(), &request)?;
+        let bytes = hex::decode(&response)
+            .map_err(|_| ExternalSignerError::InvalidResponse(response.clone()))?;
+
+        let hash = message_hash.as_hash();
+        match &self.public_key {
+            PublicKey::Secp256k1(public_key) => {
+                let signature = Secp256k1Signature::try_from(bytes.as_slice())
+                    .map_err(|_| ExternalSignerError::InvalidResponse(response))?;
+                if verify_secp256k1(hash, public_key, &signature) {
+                    Ok(signature.into())
+                } else {
+                    Err(ExternalSignerError::InvalidSignature)
+                }
+            }
+            PublicKey::Ed25519(public_key) => {
+                let signature = Ed25519Signature::try_from(bytes.as_slice())
+                    .map_err(|_| ExternalSignerError::InvalidResponse(response))?;
+                if verify_ed25519(hash, public_key, &signature) {
+                    Ok(signature.into())
+                } else {
+                    Err(ExternalSignerError::InvalidSignature)
+                }
+            }
+        }
+    }
+}
+
+impl<C: ExternalSignerConnection> TrySigner for ExternalSigner<C> {
+    type Error = ExternalSignerError;
+
+    fn try_sign_without_public_key(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureV1, Self::Error> {
+        self.sign(message_hash)
+    }
+
+    fn try_sign_with_public_key(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureWithPublicKeyV1, Self::Error> {
+        let signature_with_public_key = match (self.sign(message_hash)?, self.public_key) {
+            (SignatureV1::Secp256k1(signature), _) => signature.into(),
+            (SignatureV1::Ed25519(signature), PublicKey::Ed25519(public_key)) => {
+                (public_key, signature).into()
+            }
+            (SignatureV1::Ed25519(_), PublicKey::Secp256k1(_)) => {
+                unreachable!("Signatures are checked against the public key")
+            }
+        };
+        Ok(signature_with_public_key)
+    }
+}
+
+impl<C: ExternalSignerConnection> TrySigner for &ExternalSigner<C> {
+    type Error = ExternalSignerError;
+
+    fn try_sign_without_public_key(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureV1, Self::Error> {
+        (*self).try_sign_without_public_key(message_hash)
+    }
+
+    fn try_sign_with_public_key(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureWithPublicKeyV1, Self::Error> {
+        (*self).try_sign_with_public_key(message_hash)
+    }
+}
+
+//========
+// Server
+//========
+
+/// Answers the requests of an [`ExternalSigner`] with the given signer, until the reader reaches
+/// the end of its input.
+pub fn serve_external_signer(
+    signer: &impl Signer,
+    reader: impl BufRead,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    for request in reader.lines() {
+        let request = request?;
+        let response = match request.split_once(' ').unwrap_or((request.as_str(), "")) {
+            ("PUBLIC_KEY", "") => match signer.public_key() {
+                PublicKey::Secp256k1(public_key) => {
+                    format!("OK Secp256k1 {}", hex::encode(public_key.0))
+                }
+                PublicKey::Ed25519(public_key) => {
+                    format!("OK Ed25519 {}", hex::encode(public_key.0))
+                }
+            },
+            ("SIGN", hash_hex) => match Hash::from_str(hash_hex) {
+                Ok(hash) => match signer.sign_without_public_key(&hash) {
+                    SignatureV1::Secp256k1(signature) => {
+                        format!("OK {}", hex::encode(signature.0))
+                    }
+                    SignatureV1::Ed25519(signature) => format!("OK {}", hex::encode(signature.0)),
+                },
+                Err(_) => "ERR invalid message hash".to_string(),
+            },
+            _ => "ERR unknown request".to_string(),
+        };
+        writeln!(writer, "{}", response)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+
+    fn start_stand_in<S: Signer + Send + 'static>(
+        signer: S,
+    ) -> ExternalSigner<LineConnection<BufReader<UnixStream>, UnixStream>> {
+        let (client, server) = UnixStream::pair().unwrap();
+        std::thread::spawn(move || {
+            let reader = BufReader::new(server.try_clone().unwrap());
+            serve_external_signer(&signer, reader, server).unwrap();
+        });
+        let reader = BufReader::new(client.try_clone().unwrap());
+        ExternalSigner::new(LineConnection::new(reader, client)).unwrap()
+    }
+
+    #[test]
+    fn external_signer_signs_like_the_signer_it_stands_in_for() {
+        let message_hash = hash("message");
+        for private_key in [
+            PrivateKey::from(Secp256k1PrivateKey::from_u64(1).unwrap()),
+            PrivateKey::from(Ed25519PrivateKey::from_u64(1).unwrap()),
+        ] {
+            let expected_public_key = private_key.public_key();
+            let expected_signature = private_key.sign_with_public_key(&message_hash);
+
+            let signer = start_stand_in(private_key);
+
+            assert_eq!(signer.public_key(), expected_public_key);
+            assert_eq!(
+                signer.try_sign_with_public_key(&message_hash).unwrap(),
+                expected_signature
+            );
+        }
+    }
+
+    #[test]
+    fn transactions_can_be_signed_and_notarized_by_external_signers() {
+        let signer = start_stand_in(Secp256k1PrivateKey::from_u64(1).unwrap());
+        let notary = start_stand_in(Ed25519PrivateKey::from_u64(2).unwrap());
+
+        // Building validates the signatures
+        TransactionV2Builder::new_with_test_defaults()
+            .notary_public_key(notary.public_key())
+            .manifest(ManifestBuilder::new_v2().drop_auth_zone_proofs().build())
+            .try_sign(&signer)
+            .unwrap()
+            .try_notarize(&notary)
+            .unwrap()
+            .build();
+    }
+
+    #[test]
+    fn external_signer_rejects_signatures_of_other_keys() {
+        let (client, server) = UnixStream::pair().unwrap();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(server.try_clone().unwrap());
+            let mut writer = server;
+            let public_key = Secp256k1PrivateKey::from_u64(1).unwrap().public_key();
+            let other_key = Secp256k1PrivateKey::from_u64(2).unwrap();
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            writeln!(writer, "OK Secp256k1 {}", hex::encode(public_key.0)).unwrap();
+            request.clear();
+            reader.read_line(&mut request).unwrap();
+            let signature = other_key.sign(&hash("message"));
+            writeln!(writer, "OK {}", hex::encode(signature.0)).unwrap();
+            reader.read_line(&mut request).unwrap();
+            writeln!(writer, "ERR declined by operator").unwrap();
+        });
+        let reader = BufReader::new(client.try_clone().unwrap());
+        let signer = ExternalSigner::new(LineConnection::new(reader, client)).unwrap();
+
+        assert!(matches!(
+            signer.try_sign_with_public_key(&hash("message")),
+            Err(ExternalSignerError::InvalidSignature)
+        ));
+        assert!(matches!(
+            signer.try_sign_with_public_key(&hash("message")),
+            Err(ExternalSignerError::Refused(reason)) if reason == "declined by operator"
+        ));
+    }
+}
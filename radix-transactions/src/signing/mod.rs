@@ -1,3 +1,7 @@
+#[cfg(feature = "std")]
+mod external_signer;
 mod signer;
 
+#[cfg(feature = "std")]
+pub use external_signer::*;
 pub use signer::*;
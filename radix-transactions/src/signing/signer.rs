@@ -1,4 +1,6 @@
 use crate::internal_prelude::*;
+use core::convert::Infallible;
+use core::future::Future;
 use radix_common::prelude::IsHash;
 
 pub enum PrivateKey {
@@ -94,3 +96,72 @@ impl Signer for PrivateKey {
         self.public_key()
     }
 }
+
+/// A signer which can fail, such as one which holds its keys in another process.
+///
+/// All [`Signer`]s are also infallible [`TrySigner`]s.
+pub trait TrySigner {
+    type Error: Debug;
+
+    fn try_sign_without_public_key(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureV1, Self::Error>;
+    fn try_sign_with_public_key(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureWithPublicKeyV1, Self::Error>;
+}
+
+impl<S: Signer> TrySigner for S {
+    type Error = Infallible;
+
+    fn try_sign_without_public_key(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureV1, Self::Error> {
+        Ok(self.sign_without_public_key(message_hash))
+    }
+
+    fn try_sign_with_public_key(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureWithPublicKeyV1, Self::Error> {
+        Ok(self.sign_with_public_key(message_hash))
+    }
+}
+
+/// A signer which signs asynchronously, such as one which holds its keys in a remote service,
+/// for use by async applications.
+///
+/// All [`TrySigner`]s are also [`AsyncTrySigner`]s, whose futures complete immediately.
+pub trait AsyncTrySigner {
+    type Error: Debug;
+
+    fn try_sign_without_public_key_async(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> impl Future<Output = Result<SignatureV1, Self::Error>>;
+    fn try_sign_with_public_key_async(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> impl Future<Output = Result<SignatureWithPublicKeyV1, Self::Error>>;
+}
+
+impl<S: TrySigner> AsyncTrySigner for S {
+    type Error = S::Error;
+
+    async fn try_sign_without_public_key_async(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureV1, Self::Error> {
+        self.try_sign_without_public_key(message_hash)
+    }
+
+    async fn try_sign_with_public_key_async(
+        &self,
+        message_hash: &impl IsHash,
+    ) -> Result<SignatureWithPublicKeyV1, Self::Error> {
+        self.try_sign_with_public_key(message_hash)
+    }
+}
@@ -396,6 +396,19 @@ mod serde_serialization {
             unreachable!("No custom values exist")
         }
     }
+
+    impl DeserializableCustomExtension for NoCustomExtension {
+        type CustomValue = NoCustomValue;
+        type CustomDeserializationContext<'a> = ();
+
+        fn deserialize_custom_value(
+            _: &Self::CustomDeserializationContext<'_>,
+            _: Self::CustomValueKind,
+            _: &str,
+        ) -> Result<Self::CustomValue, String> {
+            unreachable!("No custom values exist")
+        }
+    }
 }
 
 #[cfg(test)]
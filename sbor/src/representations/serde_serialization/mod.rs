@@ -21,14 +21,19 @@
 //!     // efficient in some cases.
 //!     let json = serde_json::to_string(&serializable).unwrap();
 //! ```
+//!
+//! The Programmatic and Natural representations can also be deserialized back into a payload, with
+//! the help of a schema - see [`deserialize_payload`] and [`deserialize_value`].
 
 // Imports and Exports
 mod contextual_serialize;
+mod serde_deserializer;
 mod serde_serializer;
 mod traits;
 mod value_map_aggregator;
 
 pub use contextual_serialize::*;
+pub use serde_deserializer::*;
 pub use serde_serializer::*;
 pub use traits::*;
 pub use value_map_aggregator::*;
@@ -0,0 +1,1400 @@
+use super::*;
+use crate::rust::prelude::*;
+use crate::*;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+
+pub type DeserializedValue<E> = Value<
+    <E as CustomExtension>::CustomValueKind,
+    <E as DeserializableCustomExtension>::CustomValue,
+>;
+
+pub struct DeserializationContext<'s, 'a, E: DeserializableCustomExtension> {
+    pub schema: &'s Schema<E::CustomSchema>,
+    pub mode: SerializationMode,
+    pub custom_context: E::CustomDeserializationContext<'a>,
+}
+
+pub enum DeserializationParameters<'s, 'a, E: DeserializableCustomExtension> {
+    Schemaless {
+        mode: SerializationMode,
+        custom_context: E::CustomDeserializationContext<'a>,
+        depth_limit: usize,
+    },
+    WithSchema {
+        mode: SerializationMode,
+        custom_context: E::CustomDeserializationContext<'a>,
+        schema: &'s Schema<E::CustomSchema>,
+        type_id: LocalTypeId,
+        depth_limit: usize,
+    },
+}
+
+impl<'s, 'a, E: DeserializableCustomExtension> DeserializationParameters<'s, 'a, E> {
+    pub fn into_context_params(self) -> (DeserializationContext<'s, 'a, E>, LocalTypeId, usize) {
+        match self {
+            DeserializationParameters::Schemaless {
+                mode,
+                custom_context,
+                depth_limit,
+            } => (
+                DeserializationContext {
+                    schema: E::CustomSchema::empty_schema(),
+                    mode,
+                    custom_context,
+                },
+                LocalTypeId::any(),
+                depth_limit,
+            ),
+            DeserializationParameters::WithSchema {
+                mode,
+                custom_context,
+                schema,
+                type_id,
+                depth_limit,
+            } => (
+                DeserializationContext {
+                    schema,
+                    mode,
+                    custom_context,
+                },
+                type_id,
+                depth_limit,
+            ),
+        }
+    }
+}
+
+/// An error encountered when converting a serde representation back into an SBOR value.
+///
+/// The `path` locates the offending part of the input, in a JSONPath-like notation (e.g.
+/// `$.fields[1].value`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeserializationError {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for DeserializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at {})", self.message, self.path)
+    }
+}
+
+/// Deserializes a value from its serde representation (eg JSON), as produced by the
+/// [`SerializationMode::Programmatic`] or [`SerializationMode::Natural`] serialization of a
+/// payload.
+///
+/// * The Programmatic representation is self-describing, so it can be deserialized either with or
+///   without a schema. If a schema is provided, the value is checked against it as it's built.
+/// * The Natural representation requires a schema, since it omits the kinds of most values.
+/// * The (deprecated) Model representation is not supported.
+///
+/// Note that this function does not apply the schema's type validations (eg lengths or numeric
+/// ranges) - see [`deserialize_payload`] for a variant which produces a validated payload.
+pub fn deserialize_value<'de, D: Deserializer<'de>, E: DeserializableCustomExtension>(
+    deserializer: D,
+    parameters: DeserializationParameters<'_, '_, E>,
+) -> Result<DeserializedValue<E>, D::Error> {
+    let tree = SerdeValueTree::deserialize(deserializer)?;
+    let (context, type_id, depth_limit) = parameters.into_context_params();
+    ValueTreeDeserializer::new(&context, depth_limit)
+        .deserialize_value_tree(&tree, type_id, ChildContext::Default)
+        .map_err(de::Error::custom)
+}
+
+/// Deserializes a value from its serde representation (eg JSON) - see [`deserialize_value`] - and
+/// then encodes it into a payload, which is validated against the schema.
+pub fn deserialize_payload<'de, D: Deserializer<'de>, E>(
+    deserializer: D,
+    parameters: DeserializationParameters<'_, '_, E>,
+) -> Result<Vec<u8>, D::Error>
+where
+    E: DeserializableCustomExtension + ValidatableCustomExtension<()>,
+    E::CustomValue: for<'b> Encode<E::CustomValueKind, VecEncoder<'b, E::CustomValueKind>>,
+{
+    let tree = SerdeValueTree::deserialize(deserializer)?;
+    let (context, type_id, depth_limit) = parameters.into_context_params();
+    let value = ValueTreeDeserializer::new(&context, depth_limit)
+        .deserialize_value_tree(&tree, type_id, ChildContext::Default)
+        .map_err(de::Error::custom)?;
+
+    let mut payload = Vec::with_capacity(512);
+    VecEncoder::<E::CustomValueKind>::new(&mut payload, depth_limit)
+        .encode_payload(&value, E::PAYLOAD_PREFIX)
+        .map_err(|error| de::Error::custom(format!("Could not encode value: {:?}", error)))?;
+    validate_payload_against_schema::<E, ()>(&payload, context.schema, type_id, &(), depth_limit)
+        .map_err(|error| de::Error::custom(error.error_message(context.schema)))?;
+    Ok(payload)
+}
+
+/// A self-describing, in-memory capture of the serde data model, so that the deserializer can
+/// look ahead (eg at the `kind` of an object) before interpreting a value.
+#[derive(Debug, Clone, PartialEq)]
+enum SerdeValueTree {
+    Null,
+    Bool(bool),
+    Integer(i128),
+    UnsignedInteger(u128),
+    Float(f64),
+    String(String),
+    Array(Vec<SerdeValueTree>),
+    Object(Vec<(String, SerdeValueTree)>),
+}
+
+impl SerdeValueTree {
+    fn json_type_name(&self) -> &'static str {
+        match self {
+            SerdeValueTree::Null => "null",
+            SerdeValueTree::Bool(_) => "a boolean",
+            SerdeValueTree::Integer(_) | SerdeValueTree::UnsignedInteger(_) => "an integer",
+            SerdeValueTree::Float(_) => "a float",
+            SerdeValueTree::String(_) => "a string",
+            SerdeValueTree::Array(_) => "an array",
+            SerdeValueTree::Object(_) => "an object",
+        }
+    }
+
+    fn get_field(&self, name: &str) -> Option<&SerdeValueTree> {
+        match self {
+            SerdeValueTree::Object(fields) => fields
+                .iter()
+                .find(|(field_name, _)| field_name == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            SerdeValueTree::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Both the JSON number and the stringified number are accepted for any integer.
+    fn as_integer_string(&self) -> Option<String> {
+        match self {
+            SerdeValueTree::Integer(value) => Some(value.to_string()),
+            SerdeValueTree::UnsignedInteger(value) => Some(value.to_string()),
+            SerdeValueTree::String(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SerdeValueTree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(SerdeValueTreeVisitor)
+    }
+}
+
+struct SerdeValueTreeVisitor;
+
+impl<'de> Visitor<'de> for SerdeValueTreeVisitor {
+    type Value = SerdeValueTree;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<Er: de::Error>(self, value: bool) -> Result<Self::Value, Er> {
+        Ok(SerdeValueTree::Bool(value))
+    }
+
+    fn visit_i64<Er: de::Error>(self, value: i64) -> Result<Self::Value, Er> {
+        Ok(SerdeValueTree::Integer(value as i128))
+    }
+
+    fn visit_i128<Er: de::Error>(self, value: i128) -> Result<Self::Value, Er> {
+        Ok(SerdeValueTree::Integer(value))
+    }
+
+    fn visit_u64<Er: de::Error>(self, value: u64) -> Result<Self::Value, Er> {
+        Ok(SerdeValueTree::UnsignedInteger(value as u128))
+    }
+
+    fn visit_u128<Er: de::Error>(self, value: u128) -> Result<Self::Value, Er> {
+        Ok(SerdeValueTree::UnsignedInteger(value))
+    }
+
+    fn visit_f64<Er: de::Error>(self, value: f64) -> Result<Self::Value, Er> {
+        Ok(SerdeValueTree::Float(value))
+    }
+
+    fn visit_str<Er: de::Error>(self, value: &str) -> Result<Self::Value, Er> {
+        Ok(SerdeValueTree::String(value.to_string()))
+    }
+
+    fn visit_string<Er: de::Error>(self, value: String) -> Result<Self::Value, Er> {
+        Ok(SerdeValueTree::String(value))
+    }
+
+    fn visit_none<Er: de::Error>(self) -> Result<Self::Value, Er> {
+        Ok(SerdeValueTree::Null)
+    }
+
+    fn visit_unit<Er: de::Error>(self) -> Result<Self::Value, Er> {
+        Ok(SerdeValueTree::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        SerdeValueTree::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut elements = Vec::new();
+        while let Some(element) = seq.next_element()? {
+            elements.push(element);
+        }
+        Ok(SerdeValueTree::Array(elements))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut fields = Vec::new();
+        while let Some((key, value)) = map.next_entry::<String, SerdeValueTree>()? {
+            fields.push((key, value));
+        }
+        Ok(SerdeValueTree::Object(fields))
+    }
+}
+
+/// Mirrors [`ValueContext`] of the serializer - in Programmatic mode, a named field's object
+/// additionally carries a `field_name`, which has to match the schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChildContext<'n> {
+    Default,
+    NamedField(&'n str),
+}
+
+struct ValueTreeDeserializer<'c, 's, 'a, E: DeserializableCustomExtension> {
+    context: &'c DeserializationContext<'s, 'a, E>,
+    depth_limit: usize,
+    depth: usize,
+    path: Vec<String>,
+}
+
+type DeserializationResult<T> = Result<T, DeserializationError>;
+
+impl<'c, 's, 'a, E: DeserializableCustomExtension> ValueTreeDeserializer<'c, 's, 'a, E> {
+    fn new(context: &'c DeserializationContext<'s, 'a, E>, depth_limit: usize) -> Self {
+        Self {
+            context,
+            depth_limit,
+            depth: 0,
+            path: vec![],
+        }
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> DeserializationResult<T> {
+        let mut path = String::from("$");
+        for segment in &self.path {
+            path.push_str(segment);
+        }
+        Err(DeserializationError {
+            path,
+            message: message.into(),
+        })
+    }
+
+    fn with_path_segment<T>(
+        &mut self,
+        segment: String,
+        action: impl FnOnce(&mut Self) -> DeserializationResult<T>,
+    ) -> DeserializationResult<T> {
+        self.path.push(segment);
+        let result = action(self);
+        self.path.pop();
+        result
+    }
+
+    fn with_field<T>(
+        &mut self,
+        name: &str,
+        action: impl FnOnce(&mut Self) -> DeserializationResult<T>,
+    ) -> DeserializationResult<T> {
+        self.with_path_segment(format!(".{}", name), action)
+    }
+
+    fn with_index<T>(
+        &mut self,
+        index: usize,
+        action: impl FnOnce(&mut Self) -> DeserializationResult<T>,
+    ) -> DeserializationResult<T> {
+        self.with_path_segment(format!("[{}]", index), action)
+    }
+
+    /// Locates the given (required) field of an object, and applies the action to it - with the
+    /// field's name appended to the current path.
+    fn with_required_field<'t, T>(
+        &mut self,
+        tree: &'t SerdeValueTree,
+        name: &str,
+        action: impl FnOnce(&mut Self, &'t SerdeValueTree) -> DeserializationResult<T>,
+    ) -> DeserializationResult<T> {
+        let Some(field) = tree.get_field(name) else {
+            return self.error(format!("Missing field `{}`", name));
+        };
+        self.with_field(name, |this| action(this, field))
+    }
+
+    fn deserialize_value_tree(
+        &mut self,
+        tree: &SerdeValueTree,
+        type_id: LocalTypeId,
+        child_context: ChildContext,
+    ) -> DeserializationResult<DeserializedValue<E>> {
+        // The depth is measured as by the encoder of the deserialized value: the root value is at
+        // depth 1, and each child of a tuple, enum, array or map is one level deeper than its
+        // parent. Checking it while the value is built also bounds the recursion of the
+        // deserializer itself, whatever the shape of the input.
+        if self.depth >= self.depth_limit {
+            return self.error(format!("Exceeded the max depth of {}", self.depth_limit));
+        }
+        let schema = self.context.schema;
+        let Some(type_kind) = schema.resolve_type_kind(type_id) else {
+            return self.error(format!("Type {:?} is not in the schema", type_id));
+        };
+        self.depth += 1;
+        let result = match self.context.mode {
+            SerializationMode::Programmatic => {
+                self.deserialize_programmatic(tree, type_id, type_kind, child_context)
+            }
+            SerializationMode::Natural => self.deserialize_natural(tree, type_id, type_kind),
+            SerializationMode::Model => {
+                self.error("The deprecated Model serialization mode cannot be deserialized")
+            }
+        };
+        self.depth -= 1;
+        result
+    }
+
+    //==================
+    // Programmatic mode
+    //==================
+
+    fn deserialize_programmatic(
+        &mut self,
+        tree: &SerdeValueTree,
+        type_id: LocalTypeId,
+        type_kind: &LocalTypeKind<E::CustomSchema>,
+        child_context: ChildContext,
+    ) -> DeserializationResult<DeserializedValue<E>> {
+        let SerdeValueTree::Object(_) = tree else {
+            return self.error(format!(
+                "Expected an object with a `kind`, but found {}",
+                tree.json_type_name()
+            ));
+        };
+        // The `field_name` annotation is optional, but it must be correct if present
+        if let (ChildContext::NamedField(expected_field_name), Some(field_name)) =
+            (child_context, tree.get_field("field_name"))
+        {
+            self.with_field("field_name", |this| match field_name.as_str() {
+                Some(field_name) if field_name == expected_field_name => Ok(()),
+                Some(field_name) => this.error(format!(
+                    "Expected field `{}`, but found `{}`",
+                    expected_field_name, field_name
+                )),
+                None => this.error("Expected a string"),
+            })?;
+        }
+        let kind_name =
+            self.with_required_field(tree, "kind", |this, kind_name| match kind_name.as_str() {
+                Some(kind_name) => Ok(kind_name),
+                None => this.error("Expected a string"),
+            })?;
+
+        if kind_name == "Bytes" {
+            if !self.type_kind_accepts(type_kind, ValueKind::Array) {
+                return self.unexpected_kind_error(type_id, kind_name);
+            }
+            let bytes =
+                self.with_required_field(tree, "hex", |this, hex| this.deserialize_hex(hex))?;
+            return Ok(Self::bytes_value(bytes));
+        }
+
+        let Some(value_kind) = value_kind_from_name::<E::CustomValueKind>(kind_name) else {
+            return self.with_field("kind", |this| {
+                this.error(format!("Unknown value kind `{}`", kind_name))
+            });
+        };
+        if !self.type_kind_accepts(type_kind, value_kind) {
+            return self.unexpected_kind_error(type_id, kind_name);
+        }
+
+        match value_kind {
+            ValueKind::Tuple => {
+                let field_types = match type_kind {
+                    TypeKind::Tuple { field_types } => Some(field_types.as_slice()),
+                    _ => None,
+                };
+                let fields = self.with_required_field(tree, "fields", |this, fields| {
+                    let field_names = field_types.and_then(|field_types| {
+                        this.context
+                            .schema
+                            .resolve_matching_tuple_metadata(type_id, field_types.len())
+                            .field_names
+                    });
+                    this.deserialize_programmatic_fields(fields, field_types, field_names)
+                })?;
+                Ok(Value::Tuple { fields })
+            }
+            ValueKind::Enum => {
+                let discriminator =
+                    self.with_required_field(tree, "variant_id", |this, variant_id| {
+                        this.deserialize_integer::<u8>(variant_id)
+                    })?;
+                let variant_field_types = match type_kind {
+                    TypeKind::Enum { variants } => match variants.get(&discriminator) {
+                        Some(field_types) => Some(field_types.as_slice()),
+                        None => {
+                            return self.with_field("variant_id", |this| {
+                                this.error(format!(
+                                    "Variant {} does not exist in the enum",
+                                    discriminator
+                                ))
+                            })
+                        }
+                    },
+                    _ => None,
+                };
+                let fields = self.with_required_field(tree, "fields", |this, fields| {
+                    let field_names = variant_field_types.and_then(|field_types| {
+                        this.context
+                            .schema
+                            .resolve_matching_enum_metadata(
+                                type_id,
+                                discriminator,
+                                field_types.len(),
+                            )
+                            .field_names
+                    });
+                    this.deserialize_programmatic_fields(fields, variant_field_types, field_names)
+                })?;
+                Ok(Value::Enum {
+                    discriminator,
+                    fields,
+                })
+            }
+            ValueKind::Array => {
+                let element_type = match type_kind {
+                    TypeKind::Array { element_type } => *element_type,
+                    _ => LocalTypeId::any(),
+                };
+                let element_value_kind =
+                    self.with_required_field(tree, "element_kind", |this, kind| {
+                        this.deserialize_value_kind(kind)
+                    })?;
+                self.with_required_field(tree, "elements", |this, elements| {
+                    let SerdeValueTree::Array(elements) = elements else {
+                        return this.error("Expected an array");
+                    };
+                    this.deserialize_array(elements, element_type, Some(element_value_kind))
+                })
+            }
+            ValueKind::Map => {
+                let (key_type, value_type) = match type_kind {
+                    TypeKind::Map {
+                        key_type,
+                        value_type,
+                    } => (*key_type, *value_type),
+                    _ => (LocalTypeId::any(), LocalTypeId::any()),
+                };
+                let key_value_kind = self.with_required_field(tree, "key_kind", |this, kind| {
+                    this.deserialize_value_kind(kind)
+                })?;
+                let value_value_kind =
+                    self.with_required_field(tree, "value_kind", |this, kind| {
+                        this.deserialize_value_kind(kind)
+                    })?;
+                self.with_required_field(tree, "entries", |this, entries| {
+                    let SerdeValueTree::Array(entries) = entries else {
+                        return this.error("Expected an array");
+                    };
+                    this.deserialize_map(
+                        entries,
+                        (key_type, Some(key_value_kind)),
+                        (value_type, Some(value_value_kind)),
+                    )
+                })
+            }
+            _ => self.with_required_field(tree, "value", |this, value| {
+                this.deserialize_terminal_value(value, value_kind)
+            }),
+        }
+    }
+
+    fn deserialize_programmatic_fields(
+        &mut self,
+        fields: &SerdeValueTree,
+        field_types: Option<&[LocalTypeId]>,
+        field_names: Option<&[Cow<'static, str>]>,
+    ) -> DeserializationResult<Vec<DeserializedValue<E>>> {
+        let SerdeValueTree::Array(fields) = fields else {
+            return self.error("Expected an array");
+        };
+        if let Some(field_types) = field_types {
+            if field_types.len() != fields.len() {
+                return self.error(format!(
+                    "Expected {} fields, but found {}",
+                    field_types.len(),
+                    fields.len()
+                ));
+            }
+        }
+        let mut values = Vec::with_capacity(fields.len());
+        for (index, field) in fields.iter().enumerate() {
+            let field_type = field_types
+                .map(|field_types| field_types[index])
+                .unwrap_or_else(LocalTypeId::any);
+            let child_context = match field_names {
+                Some(field_names) if field_names.len() == fields.len() => {
+                    ChildContext::NamedField(field_names[index].as_ref())
+                }
+                _ => ChildContext::Default,
+            };
+            values.push(self.with_index(index, |this| {
+                this.deserialize_value_tree(field, field_type, child_context)
+            })?);
+        }
+        Ok(values)
+    }
+
+    fn deserialize_value_kind(
+        &self,
+        tree: &SerdeValueTree,
+    ) -> DeserializationResult<ValueKind<E::CustomValueKind>> {
+        let Some(name) = tree.as_str() else {
+            return self.error("Expected a string");
+        };
+        match value_kind_from_name(name) {
+            Some(value_kind) => Ok(value_kind),
+            None => self.error(format!("Unknown value kind `{}`", name)),
+        }
+    }
+
+    //=============
+    // Natural mode
+    //=============
+
+    fn deserialize_natural(
+        &mut self,
+        tree: &SerdeValueTree,
+        type_id: LocalTypeId,
+        type_kind: &LocalTypeKind<E::CustomSchema>,
+    ) -> DeserializationResult<DeserializedValue<E>> {
+        // Some custom values always keep their kind tag (see `include_type_tag_in_simple_mode`),
+        // and some of them may even stand in place of a non-custom type (eg a manifest Blob in
+        // place of bytes) - so we check for the tag first.
+        if let Some(custom_value_kind) = self.tagged_custom_value_kind(tree, type_kind) {
+            return self.with_required_field(tree, "value", |this, value| {
+                this.deserialize_custom_value(value, custom_value_kind)
+            });
+        }
+
+        match type_kind {
+            TypeKind::Any => match tree {
+                SerdeValueTree::Bool(value) => Ok(Value::Bool { value: *value }),
+                SerdeValueTree::String(value) => Ok(Value::String {
+                    value: value.clone(),
+                }),
+                _ => self.error(
+                    "The Natural representation of this value cannot be deserialized without a schema",
+                ),
+            },
+            TypeKind::Bool => self.deserialize_terminal_value(tree, ValueKind::Bool),
+            TypeKind::I8 => self.deserialize_terminal_value(tree, ValueKind::I8),
+            TypeKind::I16 => self.deserialize_terminal_value(tree, ValueKind::I16),
+            TypeKind::I32 => self.deserialize_terminal_value(tree, ValueKind::I32),
+            TypeKind::I64 => self.deserialize_terminal_value(tree, ValueKind::I64),
+            TypeKind::I128 => self.deserialize_terminal_value(tree, ValueKind::I128),
+            TypeKind::U8 => self.deserialize_terminal_value(tree, ValueKind::U8),
+            TypeKind::U16 => self.deserialize_terminal_value(tree, ValueKind::U16),
+            TypeKind::U32 => self.deserialize_terminal_value(tree, ValueKind::U32),
+            TypeKind::U64 => self.deserialize_terminal_value(tree, ValueKind::U64),
+            TypeKind::U128 => self.deserialize_terminal_value(tree, ValueKind::U128),
+            TypeKind::String => self.deserialize_terminal_value(tree, ValueKind::String),
+            TypeKind::Array { element_type } => {
+                let element_value_kind = self.schema_value_kind(*element_type);
+                if element_value_kind == Some(ValueKind::U8) {
+                    let bytes = self.with_required_field(tree, "hex", |this, hex| {
+                        this.deserialize_hex(hex)
+                    })?;
+                    return Ok(Self::bytes_value(bytes));
+                }
+                let SerdeValueTree::Array(elements) = tree else {
+                    return self.error(format!(
+                        "Expected an array, but found {}",
+                        tree.json_type_name()
+                    ));
+                };
+                self.deserialize_array(elements, *element_type, element_value_kind)
+            }
+            TypeKind::Tuple { field_types } => {
+                let field_names = self
+                    .context
+                    .schema
+                    .resolve_matching_tuple_metadata(type_id, field_types.len())
+                    .field_names;
+                let fields = self.deserialize_natural_fields(tree, field_types, field_names)?;
+                Ok(Value::Tuple { fields })
+            }
+            TypeKind::Enum { variants } => {
+                let discriminator = self.deserialize_natural_discriminator(tree, type_id)?;
+                let Some(field_types) = variants.get(&discriminator) else {
+                    return self.with_field("variant_id", |this| {
+                        this.error(format!(
+                            "Variant {} does not exist in the enum",
+                            discriminator
+                        ))
+                    });
+                };
+                let field_names = self
+                    .context
+                    .schema
+                    .resolve_matching_enum_metadata(type_id, discriminator, field_types.len())
+                    .field_names;
+                let fields = match tree.get_field("fields") {
+                    Some(fields) => self.with_field("fields", |this| {
+                        this.deserialize_natural_fields(fields, field_types, field_names)
+                    })?,
+                    // The fields of a fieldless variant may be omitted
+                    None if field_types.is_empty() => vec![],
+                    None => return self.error("Missing field `fields`"),
+                };
+                Ok(Value::Enum {
+                    discriminator,
+                    fields,
+                })
+            }
+            TypeKind::Map {
+                key_type,
+                value_type,
+            } => {
+                let key_value_kind = self.schema_value_kind(*key_type);
+                let mut value_value_kind = self.schema_value_kind(*value_type);
+                match tree {
+                    // Maps with string keys are represented as JSON objects
+                    SerdeValueTree::Object(fields) if key_value_kind == Some(ValueKind::String) => {
+                        let mut entries = Vec::with_capacity(fields.len());
+                        for (key, value) in fields {
+                            let value = self.with_field(key, |this| {
+                                this.deserialize_child(value, *value_type, &mut value_value_kind)
+                            })?;
+                            entries.push((Value::String { value: key.clone() }, value));
+                        }
+                        Ok(Value::Map {
+                            key_value_kind: ValueKind::String,
+                            value_value_kind: self.resolve_children_value_kind(value_value_kind)?,
+                            entries,
+                        })
+                    }
+                    SerdeValueTree::Array(entries) => self.deserialize_map(
+                        entries,
+                        (*key_type, key_value_kind),
+                        (*value_type, value_value_kind),
+                    ),
+                    _ => self.error(format!(
+                        "Expected an array of entries, but found {}",
+                        tree.json_type_name()
+                    )),
+                }
+            }
+            TypeKind::Custom(_) => {
+                let Some(custom_value_kind) = self.unique_custom_value_kind(type_kind) else {
+                    return self.error(
+                        "Expected an object with a `kind` and a `value`, since the custom value kind is ambiguous",
+                    );
+                };
+                self.deserialize_custom_value(tree, custom_value_kind)
+            }
+        }
+    }
+
+    fn deserialize_natural_fields(
+        &mut self,
+        tree: &SerdeValueTree,
+        field_types: &[LocalTypeId],
+        field_names: Option<&[Cow<'static, str>]>,
+    ) -> DeserializationResult<Vec<DeserializedValue<E>>> {
+        match (tree, field_names) {
+            (SerdeValueTree::Object(fields), Some(field_names))
+                if field_names.len() == field_types.len() =>
+            {
+                if let Some((unknown_field_name, _)) = fields
+                    .iter()
+                    .find(|(name, _)| !field_names.iter().any(|expected| expected == name))
+                {
+                    return self.error(format!("Unknown field `{}`", unknown_field_name));
+                }
+                let mut values = Vec::with_capacity(field_types.len());
+                for (field_type, field_name) in field_types.iter().zip(field_names.iter()) {
+                    let field_name = field_name.as_ref();
+                    let Some(field) = tree.get_field(field_name) else {
+                        return self.error(format!("Missing field `{}`", field_name));
+                    };
+                    values.push(self.with_field(field_name, |this| {
+                        this.deserialize_value_tree(field, *field_type, ChildContext::Default)
+                    })?);
+                }
+                Ok(values)
+            }
+            (SerdeValueTree::Array(fields), _) => {
+                if field_types.len() != fields.len() {
+                    return self.error(format!(
+                        "Expected {} fields, but found {}",
+                        field_types.len(),
+                        fields.len()
+                    ));
+                }
+                let mut values = Vec::with_capacity(fields.len());
+                for (index, (field, field_type)) in fields.iter().zip(field_types).enumerate() {
+                    values.push(self.with_index(index, |this| {
+                        this.deserialize_value_tree(field, *field_type, ChildContext::Default)
+                    })?);
+                }
+                Ok(values)
+            }
+            _ => self.error(format!(
+                "Expected an array{} of fields, but found {}",
+                if field_names.is_some() {
+                    " or an object"
+                } else {
+                    ""
+                },
+                tree.json_type_name()
+            )),
+        }
+    }
+
+    fn deserialize_natural_discriminator(
+        &mut self,
+        tree: &SerdeValueTree,
+        type_id: LocalTypeId,
+    ) -> DeserializationResult<u8> {
+        let SerdeValueTree::Object(_) = tree else {
+            return self.error(format!(
+                "Expected an object with a `variant_id` or `variant_name`, but found {}",
+                tree.json_type_name()
+            ));
+        };
+        if let Some(variant_id) = tree.get_field("variant_id") {
+            return self.with_field("variant_id", |this| {
+                this.deserialize_integer::<u8>(variant_id)
+            });
+        }
+        let Some(variant_name) = tree.get_field("variant_name") else {
+            return self.error("Missing field `variant_id` or `variant_name`");
+        };
+        self.with_field("variant_name", |this| {
+            let Some(variant_name) = variant_name.as_str() else {
+                return this.error("Expected a string");
+            };
+            let variants = match this
+                .context
+                .schema
+                .resolve_type_metadata(type_id)
+                .and_then(|metadata| metadata.child_names.as_ref())
+            {
+                Some(ChildNames::EnumVariants(variants)) => variants,
+                _ => return this.error("The enum has no variant names in the schema"),
+            };
+            match variants
+                .iter()
+                .find(|(_, metadata)| metadata.get_name() == Some(variant_name))
+            {
+                Some((discriminator, _)) => Ok(*discriminator),
+                None => this.error(format!("Unknown variant `{}`", variant_name)),
+            }
+        })
+    }
+
+    /// Resolves the value kind of a type (as needed for the headers of arrays and maps), if the
+    /// schema allows for only a single one.
+    fn schema_value_kind(&self, type_id: LocalTypeId) -> Option<ValueKind<E::CustomValueKind>> {
+        match self.context.schema.resolve_type_kind(type_id) {
+            Some(TypeKind::Bool) => Some(ValueKind::Bool),
+            Some(TypeKind::I8) => Some(ValueKind::I8),
+            Some(TypeKind::I16) => Some(ValueKind::I16),
+            Some(TypeKind::I32) => Some(ValueKind::I32),
+            Some(TypeKind::I64) => Some(ValueKind::I64),
+            Some(TypeKind::I128) => Some(ValueKind::I128),
+            Some(TypeKind::U8) => Some(ValueKind::U8),
+            Some(TypeKind::U16) => Some(ValueKind::U16),
+            Some(TypeKind::U32) => Some(ValueKind::U32),
+            Some(TypeKind::U64) => Some(ValueKind::U64),
+            Some(TypeKind::U128) => Some(ValueKind::U128),
+            Some(TypeKind::String) => Some(ValueKind::String),
+            Some(TypeKind::Array { .. }) => Some(ValueKind::Array),
+            Some(TypeKind::Tuple { .. }) => Some(ValueKind::Tuple),
+            Some(TypeKind::Enum { .. }) => Some(ValueKind::Enum),
+            Some(TypeKind::Map { .. }) => Some(ValueKind::Map),
+            Some(type_kind @ TypeKind::Custom(_)) => self
+                .unique_custom_value_kind(type_kind)
+                .map(ValueKind::Custom),
+            Some(TypeKind::Any) | None => None,
+        }
+    }
+
+    fn tagged_custom_value_kind(
+        &self,
+        tree: &SerdeValueTree,
+        type_kind: &LocalTypeKind<E::CustomSchema>,
+    ) -> Option<E::CustomValueKind> {
+        let SerdeValueTree::Object(fields) = tree else {
+            return None;
+        };
+        let is_tagged_value = tree.get_field("value").is_some()
+            && fields
+                .iter()
+                .all(|(name, _)| matches!(name.as_str(), "kind" | "type_name" | "value"));
+        if !is_tagged_value {
+            return None;
+        }
+        let kind_name = tree.get_field("kind")?.as_str()?;
+        match value_kind_from_name::<E::CustomValueKind>(kind_name)? {
+            ValueKind::Custom(custom_value_kind)
+                if self.type_kind_accepts(type_kind, ValueKind::Custom(custom_value_kind)) =>
+            {
+                Some(custom_value_kind)
+            }
+            _ => None,
+        }
+    }
+
+    fn unique_custom_value_kind(
+        &self,
+        type_kind: &LocalTypeKind<E::CustomSchema>,
+    ) -> Option<E::CustomValueKind> {
+        let mut matching = all_custom_value_kinds::<E::CustomValueKind>().filter(|kind| {
+            E::custom_value_kind_matches_type_kind(self.context.schema, *kind, type_kind)
+        });
+        let first = matching.next()?;
+        match matching.next() {
+            Some(_) => None,
+            None => Some(first),
+        }
+    }
+
+    //=============
+    // Shared logic
+    //=============
+
+    fn type_kind_accepts(
+        &self,
+        type_kind: &LocalTypeKind<E::CustomSchema>,
+        value_kind: ValueKind<E::CustomValueKind>,
+    ) -> bool {
+        let schema = self.context.schema;
+        match (type_kind, value_kind) {
+            (TypeKind::Any, _) => true,
+            (TypeKind::Bool, ValueKind::Bool)
+            | (TypeKind::I8, ValueKind::I8)
+            | (TypeKind::I16, ValueKind::I16)
+            | (TypeKind::I32, ValueKind::I32)
+            | (TypeKind::I64, ValueKind::I64)
+            | (TypeKind::I128, ValueKind::I128)
+            | (TypeKind::U8, ValueKind::U8)
+            | (TypeKind::U16, ValueKind::U16)
+            | (TypeKind::U32, ValueKind::U32)
+            | (TypeKind::U64, ValueKind::U64)
+            | (TypeKind::U128, ValueKind::U128)
+            | (TypeKind::String, ValueKind::String)
+            | (TypeKind::Array { .. }, ValueKind::Array)
+            | (TypeKind::Tuple { .. }, ValueKind::Tuple)
+            | (TypeKind::Enum { .. }, ValueKind::Enum)
+            | (TypeKind::Map { .. }, ValueKind::Map) => true,
+            (_, ValueKind::Custom(custom_value_kind)) => {
+                E::custom_value_kind_matches_type_kind(schema, custom_value_kind, type_kind)
+            }
+            (TypeKind::Custom(custom_type_kind), non_custom_value_kind) => {
+                E::custom_type_kind_matches_non_custom_value_kind(
+                    schema,
+                    custom_type_kind,
+                    non_custom_value_kind,
+                )
+            }
+            _ => false,
+        }
+    }
+
+    fn unexpected_kind_error<T>(
+        &self,
+        type_id: LocalTypeId,
+        kind_name: &str,
+    ) -> DeserializationResult<T> {
+        let schema = self.context.schema;
+        let expected = match schema.resolve_type_name_from_metadata(type_id) {
+            Some(type_name) => format!(
+                "{} ({})",
+                type_name,
+                schema
+                    .resolve_type_kind(type_id)
+                    .map(|kind| kind.category_name())
+                    .unwrap_or("?")
+            ),
+            None => schema
+                .resolve_type_kind(type_id)
+                .map(|kind| kind.category_name())
+                .unwrap_or("?")
+                .to_string(),
+        };
+        self.error(format!(
+            "Expected a value of type {}, but found kind `{}`",
+            expected, kind_name
+        ))
+    }
+
+    /// Deserializes a child of an array or map, which has to match the value kind of its siblings.
+    /// The value kind is either known upfront (from the parent's header or from the schema), or
+    /// captured from the first child.
+    fn deserialize_child(
+        &mut self,
+        tree: &SerdeValueTree,
+        type_id: LocalTypeId,
+        value_kind: &mut Option<ValueKind<E::CustomValueKind>>,
+    ) -> DeserializationResult<DeserializedValue<E>> {
+        let value = self.deserialize_value_tree(tree, type_id, ChildContext::Default)?;
+        let actual_value_kind = value.get_value_kind();
+        match value_kind {
+            Some(expected_value_kind) if *expected_value_kind != actual_value_kind => {
+                return self.error(format!(
+                    "Expected a value of kind `{}` (like its siblings), but found `{}`",
+                    expected_value_kind, actual_value_kind
+                ));
+            }
+            Some(_) => {}
+            None => *value_kind = Some(actual_value_kind),
+        }
+        Ok(value)
+    }
+
+    fn resolve_children_value_kind(
+        &self,
+        value_kind: Option<ValueKind<E::CustomValueKind>>,
+    ) -> DeserializationResult<ValueKind<E::CustomValueKind>> {
+        match value_kind {
+            Some(value_kind) => Ok(value_kind),
+            None => self.error(
+                "The value kind of the collection's children cannot be determined from the schema, nor from the children",
+            ),
+        }
+    }
+
+    fn deserialize_array(
+        &mut self,
+        elements: &[SerdeValueTree],
+        element_type: LocalTypeId,
+        mut element_value_kind: Option<ValueKind<E::CustomValueKind>>,
+    ) -> DeserializationResult<DeserializedValue<E>> {
+        let mut values = Vec::with_capacity(elements.len());
+        for (index, element) in elements.iter().enumerate() {
+            values.push(self.with_index(index, |this| {
+                this.deserialize_child(element, element_type, &mut element_value_kind)
+            })?);
+        }
+        Ok(Value::Array {
+            element_value_kind: self.resolve_children_value_kind(element_value_kind)?,
+            elements: values,
+        })
+    }
+
+    fn deserialize_map(
+        &mut self,
+        entries: &[SerdeValueTree],
+        (key_type, mut key_value_kind): (LocalTypeId, Option<ValueKind<E::CustomValueKind>>),
+        (value_type, mut value_value_kind): (LocalTypeId, Option<ValueKind<E::CustomValueKind>>),
+    ) -> DeserializationResult<DeserializedValue<E>> {
+        let mut values = Vec::with_capacity(entries.len());
+        for (index, entry) in entries.iter().enumerate() {
+            values.push(self.with_index(index, |this| {
+                let key = this.with_required_field(entry, "key", |this, key| {
+                    this.deserialize_child(key, key_type, &mut key_value_kind)
+                })?;
+                let value = this.with_required_field(entry, "value", |this, value| {
+                    this.deserialize_child(value, value_type, &mut value_value_kind)
+                })?;
+                Ok((key, value))
+            })?);
+        }
+        Ok(Value::Map {
+            key_value_kind: self.resolve_children_value_kind(key_value_kind)?,
+            value_value_kind: self.resolve_children_value_kind(value_value_kind)?,
+            entries: values,
+        })
+    }
+
+    fn deserialize_terminal_value(
+        &self,
+        tree: &SerdeValueTree,
+        value_kind: ValueKind<E::CustomValueKind>,
+    ) -> DeserializationResult<DeserializedValue<E>> {
+        Ok(match value_kind {
+            ValueKind::Bool => match tree {
+                SerdeValueTree::Bool(value) => Value::Bool { value: *value },
+                _ => {
+                    return self.error(format!(
+                        "Expected a boolean, but found {}",
+                        tree.json_type_name()
+                    ))
+                }
+            },
+            ValueKind::I8 => Value::I8 {
+                value: self.deserialize_integer(tree)?,
+            },
+            ValueKind::I16 => Value::I16 {
+                value: self.deserialize_integer(tree)?,
+            },
+            ValueKind::I32 => Value::I32 {
+                value: self.deserialize_integer(tree)?,
+            },
+            ValueKind::I64 => Value::I64 {
+                value: self.deserialize_integer(tree)?,
+            },
+            ValueKind::I128 => Value::I128 {
+                value: self.deserialize_integer(tree)?,
+            },
+            ValueKind::U8 => Value::U8 {
+                value: self.deserialize_integer(tree)?,
+            },
+            ValueKind::U16 => Value::U16 {
+                value: self.deserialize_integer(tree)?,
+            },
+            ValueKind::U32 => Value::U32 {
+                value: self.deserialize_integer(tree)?,
+            },
+            ValueKind::U64 => Value::U64 {
+                value: self.deserialize_integer(tree)?,
+            },
+            ValueKind::U128 => Value::U128 {
+                value: self.deserialize_integer(tree)?,
+            },
+            ValueKind::String => match tree.as_str() {
+                Some(value) => Value::String {
+                    value: value.to_string(),
+                },
+                None => {
+                    return self.error(format!(
+                        "Expected a string, but found {}",
+                        tree.json_type_name()
+                    ))
+                }
+            },
+            ValueKind::Custom(custom_value_kind) => {
+                return self.deserialize_custom_value(tree, custom_value_kind)
+            }
+            ValueKind::Enum | ValueKind::Array | ValueKind::Tuple | ValueKind::Map => {
+                return self.error(format!("Kind `{}` is not a terminal value", value_kind))
+            }
+        })
+    }
+
+    fn deserialize_custom_value(
+        &self,
+        tree: &SerdeValueTree,
+        custom_value_kind: E::CustomValueKind,
+    ) -> DeserializationResult<DeserializedValue<E>> {
+        let Some(value) = tree.as_str() else {
+            return self.error(format!(
+                "Expected a string, but found {}",
+                tree.json_type_name()
+            ));
+        };
+        match E::deserialize_custom_value(&self.context.custom_context, custom_value_kind, value) {
+            Ok(value) => Ok(Value::Custom { value }),
+            Err(message) => self.error(message),
+        }
+    }
+
+    fn deserialize_integer<T: FromStr>(&self, tree: &SerdeValueTree) -> DeserializationResult<T> {
+        let Some(integer_string) = tree.as_integer_string() else {
+            return self.error(format!(
+                "Expected an integer or a string, but found {}",
+                tree.json_type_name()
+            ));
+        };
+        match integer_string.parse::<T>() {
+            Ok(value) => Ok(value),
+            Err(_) => self.error(format!(
+                "`{}` is not a valid {}",
+                integer_string,
+                core::any::type_name::<T>()
+            )),
+        }
+    }
+
+    fn deserialize_hex(&self, tree: &SerdeValueTree) -> DeserializationResult<Vec<u8>> {
+        let Some(hex) = tree.as_str() else {
+            return self.error(format!(
+                "Expected a hex string, but found {}",
+                tree.json_type_name()
+            ));
+        };
+        match hex::decode(hex) {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => self.error(format!("`{}` is not a valid hex string", hex)),
+        }
+    }
+
+    fn bytes_value(bytes: Vec<u8>) -> DeserializedValue<E> {
+        Value::Array {
+            element_value_kind: ValueKind::U8,
+            elements: bytes.into_iter().map(|value| Value::U8 { value }).collect(),
+        }
+    }
+}
+
+fn value_kind_from_name<X: CustomValueKind>(name: &str) -> Option<ValueKind<X>> {
+    (0..=u8::MAX)
+        .filter_map(ValueKind::<X>::from_u8)
+        .find(|value_kind| value_kind.to_string() == name)
+}
+
+fn all_custom_value_kinds<X: CustomValueKind>() -> impl Iterator<Item = X> {
+    (0..=u8::MAX).filter_map(X::from_u8)
+}
+
+#[cfg(test)]
+#[cfg(feature = "serde")] // Ensures that VS Code runs this module with the features serde tag!
+mod tests {
+    use super::*;
+    use radix_rust::ContextualSerialize;
+    use serde_json::{json, to_value, Value as JsonValue};
+
+    #[derive(Sbor, Debug, Clone, PartialEq, Eq, Hash)]
+    #[allow(clippy::enum_variant_names)]
+    enum TestEnum {
+        UnitVariant,
+        SingleFieldVariant { field: u8 },
+        TupleVariant(u64, String),
+    }
+
+    #[derive(Sbor, Debug, Clone, PartialEq, Eq)]
+    struct TestStruct {
+        small_number: i32,
+        big_number: u128,
+        bytes: Vec<u8>,
+        numbers: Vec<u16>,
+        labels: IndexMap<String, TestEnum>,
+        keyed: IndexMap<TestEnum, bool>,
+        optional: Option<String>,
+        variants: Vec<TestEnum>,
+        tuple: (i8, u64),
+    }
+
+    fn test_value() -> TestStruct {
+        TestStruct {
+            small_number: -12,
+            big_number: u128::MAX,
+            bytes: vec![0x3a, 0x92],
+            numbers: vec![1, 2, 3],
+            labels: indexmap! {
+                "hello".to_string() => TestEnum::UnitVariant,
+                "world".to_string() => TestEnum::SingleFieldVariant { field: 7 },
+            },
+            keyed: indexmap! {
+                TestEnum::TupleVariant(5, "five".to_string()) => true,
+            },
+            optional: Some("present".to_string()),
+            variants: vec![],
+            tuple: (-1, u64::MAX),
+        }
+    }
+
+    fn serialize_to_json(payload: &[u8], mode: SerializationMode) -> JsonValue {
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<TestStruct, NoCustomSchema>();
+        to_value(BasicRawPayload::new_from_valid_slice(payload).serializable(
+            SerializationParameters::WithSchema {
+                mode,
+                custom_context: (),
+                schema: schema.v1(),
+                type_id,
+                depth_limit: 64,
+            },
+        ))
+        .unwrap()
+    }
+
+    fn deserialize_from_json(json: JsonValue, mode: SerializationMode) -> Result<Vec<u8>, String> {
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<TestStruct, NoCustomSchema>();
+        deserialize_payload::<_, NoCustomExtension>(
+            json,
+            DeserializationParameters::WithSchema {
+                mode,
+                custom_context: (),
+                schema: schema.v1(),
+                type_id,
+                depth_limit: 64,
+            },
+        )
+        .map_err(|error| error.to_string())
+    }
+
+    #[test]
+    #[cfg(feature = "serde")] // Workaround for VS Code "Run Test" feature
+    fn programmatic_and_natural_json_round_trips_with_schema() {
+        let payload = basic_encode(&test_value()).unwrap();
+        for mode in [SerializationMode::Programmatic, SerializationMode::Natural] {
+            let json = serialize_to_json(&payload, mode);
+            assert_eq!(deserialize_from_json(json, mode), Ok(payload.clone()));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")] // Workaround for VS Code "Run Test" feature
+    fn programmatic_json_round_trips_without_schema() {
+        let value = BasicValue::Tuple {
+            fields: vec![
+                Value::I64 { value: i64::MIN },
+                Value::Array {
+                    element_value_kind: ValueKind::U8,
+                    elements: vec![Value::U8 { value: 0x3a }],
+                },
+                Value::Enum {
+                    discriminator: 2,
+                    fields: vec![Value::U32 { value: 153 }, Value::Bool { value: true }],
+                },
+                Value::Map {
+                    key_value_kind: ValueKind::U32,
+                    value_value_kind: ValueKind::String,
+                    entries: vec![(
+                        Value::U32 { value: 153 },
+                        Value::String {
+                            value: "hello".to_string(),
+                        },
+                    )],
+                },
+            ],
+        };
+        let payload = basic_encode(&value).unwrap();
+        let json = to_value(
+            BasicRawPayload::new_from_valid_slice(&payload).serializable(
+                SerializationParameters::Schemaless {
+                    mode: SerializationMode::Programmatic,
+                    custom_context: (),
+                    depth_limit: 64,
+                },
+            ),
+        )
+        .unwrap();
+
+        let deserialized = deserialize_value::<_, NoCustomExtension>(
+            json,
+            DeserializationParameters::Schemaless {
+                mode: SerializationMode::Programmatic,
+                custom_context: (),
+                depth_limit: 64,
+            },
+        )
+        .unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")] // Workaround for VS Code "Run Test" feature
+    fn natural_json_accepts_hand_written_input() {
+        let json = json!({
+            "tuple": [-1, "18446744073709551615"],
+            "variants": [
+                { "variant_name": "UnitVariant" },
+                { "variant_id": 2, "fields": ["5", "five"] },
+            ],
+            "optional": { "variant_name": "None" },
+            "keyed": [],
+            "labels": {},
+            "numbers": [1, "2"],
+            "bytes": { "hex": "" },
+            "big_number": 5,
+            "small_number": "-12",
+        });
+        let expected = TestStruct {
+            small_number: -12,
+            big_number: 5,
+            bytes: vec![],
+            numbers: vec![1, 2],
+            labels: indexmap! {},
+            keyed: indexmap! {},
+            optional: None,
+            variants: vec![
+                TestEnum::UnitVariant,
+                TestEnum::TupleVariant(5, "five".to_string()),
+            ],
+            tuple: (-1, u64::MAX),
+        };
+        assert_eq!(
+            deserialize_from_json(json, SerializationMode::Natural),
+            Ok(basic_encode(&expected).unwrap())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")] // Workaround for VS Code "Run Test" feature
+    fn invalid_json_is_rejected_with_a_path() {
+        let mut json = serialize_to_json(
+            &basic_encode(&test_value()).unwrap(),
+            SerializationMode::Natural,
+        );
+        json["labels"]["world"]["fields"]["field"] = json!(256);
+        assert_eq!(
+            deserialize_from_json(json.clone(), SerializationMode::Natural),
+            Err("`256` is not a valid u8 (at $.labels.world.fields.field)".to_string())
+        );
+
+        json["labels"]["world"]["fields"] = json!({ "unknown": 1 });
+        assert_eq!(
+            deserialize_from_json(json.clone(), SerializationMode::Natural),
+            Err("Unknown field `unknown` (at $.labels.world.fields)".to_string())
+        );
+
+        assert_eq!(
+            deserialize_from_json(json, SerializationMode::Programmatic),
+            Err("Missing field `kind` (at $)".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")] // Workaround for VS Code "Run Test" feature
+    fn depth_limit_matches_the_encoder() {
+        // A value of depth 4: three nested tuples around a u8
+        let value = BasicValue::Tuple {
+            fields: vec![Value::Tuple {
+                fields: vec![Value::Tuple {
+                    fields: vec![Value::U8 { value: 1 }],
+                }],
+            }],
+        };
+        let payload = basic_encode(&value).unwrap();
+        let json = to_value(
+            BasicRawPayload::new_from_valid_slice(&payload).serializable(
+                SerializationParameters::Schemaless {
+                    mode: SerializationMode::Programmatic,
+                    custom_context: (),
+                    depth_limit: 64,
+                },
+            ),
+        )
+        .unwrap();
+        let deserialize_with_depth_limit = |depth_limit| {
+            deserialize_payload::<_, NoCustomExtension>(
+                json.clone(),
+                DeserializationParameters::Schemaless {
+                    mode: SerializationMode::Programmatic,
+                    custom_context: (),
+                    depth_limit,
+                },
+            )
+            .map_err(|error| error.to_string())
+        };
+
+        assert!(basic_encode_with_depth_limit(&value, 3).is_err());
+        assert_eq!(
+            deserialize_with_depth_limit(3),
+            Err("Exceeded the max depth of 3 (at $.fields[0].fields[0].fields[0])".to_string())
+        );
+
+        assert!(basic_encode_with_depth_limit(&value, 4).is_ok());
+        assert_eq!(deserialize_with_depth_limit(4), Ok(payload));
+    }
+}
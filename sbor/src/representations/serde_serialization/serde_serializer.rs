@@ -38,27 +38,28 @@ pub enum SerializationMode {
     ///
     /// If a schema is available, variant names, type names and field names are added to the output.
     Model,
-    /// ==THIS FORMAT IS NOT INTENDED TO BE USED YET==
-    ///
     /// An API format designed for elegantly reading values with a well-known schema - intended for
     /// eg DApp Builders writing their front-ends.
     ///
     /// It outputs values in a “JSON-native” manner - designed primary for use with a schema,
     /// and for mapping into models like you’d find on an Open API schema.
     ///
-    /// Its JSON schema is dependent on its SBOR schema, and it's not intended to be invertible
-    /// without the SBOR schema. We could even consider generating an Open API schema for a given
-    /// SBOR schema (eg for a blueprint) and allow developers to have a strongly-typed API to their
-    /// blueprint.
+    /// Its JSON schema is dependent on its SBOR schema, and it's not invertible without the SBOR
+    /// schema - but given the schema, it can be deserialized back (see [`deserialize_payload`]).
     ///
-    /// Compared with Programmatic, it is more compact, but doesn't include type names / enum variant names.
+    /// Compared with Programmatic, it is more compact, but doesn't include type names.
     ///
-    /// It should favour simplicity for human comprehension, in particular:
+    /// It favours simplicity for human comprehension, in particular:
     /// * It uses a JSON object rather than an array where possible, even if this loses field ordering
     ///   EG for structs, and for maps with string keys.
     /// * If the concept which is being represented (eg number/amount or address) is clear
-    ///   to a human, information about the value kind is dropped.
-    /// * It prefers to use the JSON number type where the number can be confirmed to be <= JS max safe int.
+    ///   to a human, information about the value kind is dropped. Custom values which could be
+    ///   ambiguous keep a `{ "kind": .., "value": .. }` wrapper.
+    /// * It uses the JSON number type for integers of up to 32 bits, and strings for the larger
+    ///   ones (which may exceed JS max safe int).
+    /// * Byte arrays are output as `{ "hex": .. }`.
+    /// * Enums are output as `{ "variant_id": .., "variant_name": .., "fields": .. }`, where the
+    ///   `variant_name` is only present if known from the schema.
     Natural,
 }
 
@@ -17,3 +17,16 @@ pub trait SerializableCustomExtension: FormattableCustomExtension + Copy {
         value: <Self::CustomTraversal as CustomTraversal>::CustomTerminalValueRef<'de>,
     ) -> CustomTypeSerialization<'a, 't, 'de, 's1, 's2, Self>;
 }
+
+pub trait DeserializableCustomExtension: SerializableCustomExtension {
+    type CustomValue: CustomValue<Self::CustomValueKind>;
+    type CustomDeserializationContext<'a>: Copy;
+
+    /// Parses a custom value from the string content which [`Self::map_value_for_serialization`]
+    /// outputs for it (eg an address or a decimal).
+    fn deserialize_custom_value(
+        context: &Self::CustomDeserializationContext<'_>,
+        custom_value_kind: Self::CustomValueKind,
+        value: &str,
+    ) -> Result<Self::CustomValue, String>;
+}
@@ -96,7 +96,7 @@ pub trait CustomValue<X: CustomValueKind> {
 
 impl<X: CustomValueKind, Y: CustomValue<X>> Value<X, Y> {
     /// Returns the value kind of this value.
-    fn get_value_kind(&self) -> ValueKind<X> {
+    pub fn get_value_kind(&self) -> ValueKind<X> {
         match self {
             Value::Bool { .. } => ValueKind::Bool,
             Value::I8 { .. } => ValueKind::I8,
@@ -0,0 +1,878 @@
+//! This module converts the blueprint schemas of a package into JSON Schema definitions of the
+//! [`SerializationMode::Natural`] JSON representation of their payloads (i.e. the function inputs
+//! and outputs, the events, and the state), wrapped either in an OpenAPI document or in a
+//! standalone JSON Schema document.
+//!
+//! The named structs and enums of the schemas become re-usable definitions (referenced by `$ref`),
+//! while the unnamed types (e.g. tuples, vectors and maps) are inlined. Each payload also gets its
+//! own entry-point definition, named `<Blueprint>.<function>.Input`, `<Blueprint>.<function>.Output`,
+//! `<Blueprint>.events.<Event>`, `<Blueprint>.fields.<index>`, `<Blueprint>.collections.<index>.Key`
+//! (or `.Value`), or `<Blueprint>.types.<Type>`.
+
+use super::schema::{PackageSchemaResolver, SchemaError};
+use radix_blueprint_schema_init::*;
+use radix_common::prelude::*;
+use radix_engine_interface::blueprints::package::*;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JsonSchemaDocumentFormat {
+    /// An OpenAPI 3.1 document, with all the definitions under `components/schemas`.
+    OpenApi,
+    /// A standalone JSON Schema (draft 2020-12) document, with all the definitions under `$defs`.
+    JsonSchema,
+}
+
+impl JsonSchemaDocumentFormat {
+    fn definitions_path(&self) -> &'static str {
+        match self {
+            JsonSchemaDocumentFormat::OpenApi => "#/components/schemas/",
+            JsonSchemaDocumentFormat::JsonSchema => "#/$defs/",
+        }
+    }
+}
+
+pub fn package_json_schema_from_package_definition<S>(
+    package_definition: BTreeMap<BlueprintVersionKey, BlueprintDefinition>,
+    schema_resolver: &S,
+    address_bech32_encoder: &AddressBech32Encoder,
+    format: JsonSchemaDocumentFormat,
+) -> Result<JsonValue, SchemaError>
+where
+    S: PackageSchemaResolver,
+{
+    let mut generator = JsonSchemaGenerator::new(schema_resolver, address_bech32_encoder, format);
+
+    for (blueprint_key, blueprint_definition) in package_definition.into_iter() {
+        let blueprint_name = sanitize_definition_name(&blueprint_key.blueprint);
+        let BlueprintInterface {
+            state,
+            functions,
+            events,
+            types,
+            ..
+        } = blueprint_definition.interface;
+
+        for (function_name, function_schema) in functions {
+            let function_name = sanitize_definition_name(&function_name);
+            let input = generator.payload_schema(&function_schema.input)?;
+            generator.add_entry_point(format!("{blueprint_name}.{function_name}.Input"), input);
+            let output = generator.payload_schema(&function_schema.output)?;
+            generator.add_entry_point(format!("{blueprint_name}.{function_name}.Output"), output);
+        }
+
+        for (event_name, payload_def) in events {
+            let event_name = sanitize_definition_name(&event_name);
+            let event = generator.payload_schema(&payload_def)?;
+            generator.add_entry_point(format!("{blueprint_name}.events.{event_name}"), event);
+        }
+
+        if let Some((_, fields)) = state.fields {
+            for (field_index, field_schema) in fields.into_iter().enumerate() {
+                let field = generator.payload_schema(&field_schema.field)?;
+                generator.add_entry_point(format!("{blueprint_name}.fields.{field_index}"), field);
+            }
+        }
+
+        for (collection_index, (_, collection_schema)) in state.collections.into_iter().enumerate()
+        {
+            let (BlueprintCollectionSchema::KeyValueStore(key_value_schema)
+            | BlueprintCollectionSchema::Index(key_value_schema)
+            | BlueprintCollectionSchema::SortedIndex(key_value_schema)) = collection_schema;
+            let key = generator.payload_schema(&key_value_schema.key)?;
+            generator.add_entry_point(
+                format!("{blueprint_name}.collections.{collection_index}.Key"),
+                key,
+            );
+            let value = generator.payload_schema(&key_value_schema.value)?;
+            generator.add_entry_point(
+                format!("{blueprint_name}.collections.{collection_index}.Value"),
+                value,
+            );
+        }
+
+        for (type_name, scoped_type_id) in types {
+            let type_name = sanitize_definition_name(&type_name);
+            let type_schema = generator.type_schema(&scoped_type_id)?;
+            generator.add_entry_point(format!("{blueprint_name}.types.{type_name}"), type_schema);
+        }
+    }
+
+    Ok(generator.into_document(schema_resolver.package_address()))
+}
+
+/// Definitions are keyed by their [`ScopedTypeId`], except for the well-known types, which are the
+/// same in every schema.
+type DefinitionKey = (Option<SchemaHash>, LocalTypeId);
+
+struct JsonSchemaGenerator<'r, S: PackageSchemaResolver> {
+    schema_resolver: &'r S,
+    address_bech32_encoder: &'r AddressBech32Encoder,
+    format: JsonSchemaDocumentFormat,
+    definitions: JsonMap<String, JsonValue>,
+    definition_names: HashMap<DefinitionKey, String>,
+    inlined_types: Vec<DefinitionKey>,
+}
+
+impl<'r, S: PackageSchemaResolver> JsonSchemaGenerator<'r, S> {
+    fn new(
+        schema_resolver: &'r S,
+        address_bech32_encoder: &'r AddressBech32Encoder,
+        format: JsonSchemaDocumentFormat,
+    ) -> Self {
+        Self {
+            schema_resolver,
+            address_bech32_encoder,
+            format,
+            definitions: JsonMap::new(),
+            definition_names: HashMap::default(),
+            inlined_types: Vec::new(),
+        }
+    }
+
+    fn into_document(self, package_address: PackageAddress) -> JsonValue {
+        let title = format!(
+            "Blueprint payloads of package {}",
+            package_address.to_string(self.address_bech32_encoder)
+        );
+        match self.format {
+            JsonSchemaDocumentFormat::OpenApi => json!({
+                "openapi": "3.1.0",
+                "info": {
+                    "title": title,
+                    "version": "1.0.0",
+                },
+                "paths": {},
+                "components": {
+                    "schemas": self.definitions,
+                },
+            }),
+            JsonSchemaDocumentFormat::JsonSchema => json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": title,
+                "$defs": self.definitions,
+            }),
+        }
+    }
+
+    fn add_entry_point(&mut self, name: String, schema: JsonValue) {
+        self.definitions.insert(name, schema);
+    }
+
+    fn reference(&self, name: &str) -> JsonValue {
+        json!({ "$ref": format!("{}{}", self.format.definitions_path(), name) })
+    }
+
+    fn payload_schema(
+        &mut self,
+        payload_def: &BlueprintPayloadDef,
+    ) -> Result<JsonValue, SchemaError> {
+        match payload_def {
+            BlueprintPayloadDef::Static(scoped_type_id) => self.type_schema(scoped_type_id),
+            BlueprintPayloadDef::Generic(index) => Ok(json!({
+                "description": format!("The type of the instance's generic parameter {index}"),
+            })),
+        }
+    }
+
+    /// Returns either a reference to the type's definition (which gets created on first use), or
+    /// the type's inlined schema.
+    fn type_schema(&mut self, scoped_type_id: &ScopedTypeId) -> Result<JsonValue, SchemaError> {
+        let key = match scoped_type_id.1 {
+            LocalTypeId::WellKnown(..) => (None, scoped_type_id.1),
+            LocalTypeId::SchemaLocalIndex(..) => (Some(scoped_type_id.0), scoped_type_id.1),
+        };
+        if let Some(name) = self.definition_names.get(&key) {
+            return Ok(self.reference(name));
+        }
+
+        let type_kind = self.schema_resolver.resolve_type_kind(scoped_type_id)?;
+        let type_metadata = self.schema_resolver.resolve_type_metadata(scoped_type_id)?;
+
+        // Only the named structs and enums get their own definitions - with the exception of the
+        // generic `Option` and `Result` enums, whose names would be ambiguous.
+        let definition_name = match (&type_kind, type_metadata.get_name()) {
+            (_, Some("Option" | "Result")) => None,
+            (TypeKind::Tuple { .. } | TypeKind::Enum { .. }, Some(type_name)) => {
+                Some(self.allocate_definition_name(type_name))
+            }
+            _ => None,
+        };
+
+        match definition_name {
+            Some(name) => {
+                // The name is registered before descending, to allow for recursive types
+                self.definition_names.insert(key, name.clone());
+                let schema = self.kind_schema(scoped_type_id, type_kind, &type_metadata)?;
+                self.definitions.insert(name.clone(), schema);
+                Ok(self.reference(&name))
+            }
+            None => {
+                if self.inlined_types.contains(&key) {
+                    return Err(SchemaError::RecursiveUnnamedType(*scoped_type_id));
+                }
+                self.inlined_types.push(key);
+                let schema = self.kind_schema(scoped_type_id, type_kind, &type_metadata);
+                self.inlined_types.pop();
+                schema
+            }
+        }
+    }
+
+    fn allocate_definition_name(&self, type_name: &str) -> String {
+        let type_name = sanitize_definition_name(type_name);
+        let is_taken = |name: &String| {
+            self.definitions.contains_key(name) || self.definition_names.values().any(|n| n == name)
+        };
+        if !is_taken(&type_name) {
+            return type_name;
+        }
+        (2..)
+            .map(|index| format!("{type_name}_{index}"))
+            .find(|name| !is_taken(name))
+            .unwrap()
+    }
+
+    fn kind_schema(
+        &mut self,
+        scoped_type_id: &ScopedTypeId,
+        type_kind: LocalTypeKind<ScryptoCustomSchema>,
+        type_metadata: &TypeMetadata,
+    ) -> Result<JsonValue, SchemaError> {
+        let child = |local_type_id: LocalTypeId| ScopedTypeId(scoped_type_id.0, local_type_id);
+        let type_validation = self
+            .schema_resolver
+            .resolve_type_validation(scoped_type_id)?;
+
+        let schema = match type_kind {
+            TypeKind::Any => json!({
+                "description": "Any value (its representation depends on its kind)",
+            }),
+            TypeKind::Bool => json!({ "type": "boolean" }),
+            TypeKind::I8 => small_integer_schema(match &type_validation {
+                TypeValidation::I8(validation) => *validation,
+                _ => NumericValidation::none(),
+            }),
+            TypeKind::I16 => small_integer_schema(match &type_validation {
+                TypeValidation::I16(validation) => *validation,
+                _ => NumericValidation::none(),
+            }),
+            TypeKind::I32 => small_integer_schema(match &type_validation {
+                TypeValidation::I32(validation) => *validation,
+                _ => NumericValidation::none(),
+            }),
+            TypeKind::U8 => small_integer_schema(match &type_validation {
+                TypeValidation::U8(validation) => *validation,
+                _ => NumericValidation::none(),
+            }),
+            TypeKind::U16 => small_integer_schema(match &type_validation {
+                TypeValidation::U16(validation) => *validation,
+                _ => NumericValidation::none(),
+            }),
+            TypeKind::U32 => small_integer_schema(match &type_validation {
+                TypeValidation::U32(validation) => *validation,
+                _ => NumericValidation::none(),
+            }),
+            TypeKind::I64 | TypeKind::I128 => large_integer_schema("^-?[0-9]+$", &type_validation),
+            TypeKind::U64 | TypeKind::U128 => large_integer_schema("^[0-9]+$", &type_validation),
+            TypeKind::String => json!({ "type": "string" }),
+            TypeKind::Array { element_type } => {
+                let length_validation = match &type_validation {
+                    TypeValidation::Array(length_validation) => *length_validation,
+                    _ => LengthValidation::none(),
+                };
+                let element_type = child(element_type);
+                match self.schema_resolver.resolve_type_kind(&element_type)? {
+                    TypeKind::U8 => bytes_schema(&length_validation),
+                    _ => {
+                        let mut schema = json!({
+                            "type": "array",
+                            "items": self.type_schema(&element_type)?,
+                        });
+                        add_length_bounds(&mut schema, "Items", &length_validation);
+                        schema
+                    }
+                }
+            }
+            TypeKind::Tuple { field_types } => self.fields_schema(
+                scoped_type_id,
+                &field_types,
+                type_metadata.get_field_names(),
+            )?,
+            TypeKind::Enum { variants } => {
+                let mut variant_schemas = Vec::with_capacity(variants.len());
+                for (variant_id, field_types) in variants {
+                    let variant_metadata = type_metadata.get_enum_variant_data(variant_id);
+                    let fields = self.fields_schema(
+                        scoped_type_id,
+                        &field_types,
+                        variant_metadata.and_then(|metadata| metadata.get_field_names()),
+                    )?;
+                    let mut properties = JsonMap::new();
+                    properties.insert("variant_id".into(), json!({ "const": variant_id }));
+                    if let Some(variant_name) = variant_metadata.and_then(|m| m.get_name()) {
+                        properties.insert("variant_name".into(), json!({ "const": variant_name }));
+                    }
+                    properties.insert("fields".into(), fields);
+                    variant_schemas.push(json!({
+                        "type": "object",
+                        "properties": properties,
+                        "required": ["variant_id", "fields"],
+                        "additionalProperties": false,
+                    }));
+                }
+                json!({ "oneOf": variant_schemas })
+            }
+            TypeKind::Map {
+                key_type,
+                value_type,
+            } => {
+                let length_validation = match &type_validation {
+                    TypeValidation::Map(length_validation) => *length_validation,
+                    _ => LengthValidation::none(),
+                };
+                let key_type = child(key_type);
+                let value_schema = self.type_schema(&child(value_type))?;
+                // Just like the serializer, we use a JSON object only for the String-keyed maps
+                match self.schema_resolver.resolve_type_kind(&key_type)? {
+                    TypeKind::String => {
+                        let mut schema = json!({
+                            "type": "object",
+                            "additionalProperties": value_schema,
+                        });
+                        add_length_bounds(&mut schema, "Properties", &length_validation);
+                        schema
+                    }
+                    _ => {
+                        let mut schema = json!({
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "key": self.type_schema(&key_type)?,
+                                    "value": value_schema,
+                                },
+                                "required": ["key", "value"],
+                                "additionalProperties": false,
+                            },
+                        });
+                        add_length_bounds(&mut schema, "Items", &length_validation);
+                        schema
+                    }
+                }
+            }
+            TypeKind::Custom(custom_type_kind) => self.custom_kind_schema(
+                custom_type_kind,
+                type_metadata.get_name(),
+                &type_validation,
+            ),
+        };
+        Ok(schema)
+    }
+
+    /// Returns the schema of a struct's (or an enum variant's) fields - i.e. an object if all of
+    /// the fields are named, and an array otherwise.
+    fn fields_schema(
+        &mut self,
+        scoped_type_id: &ScopedTypeId,
+        field_types: &[LocalTypeId],
+        field_names: Option<&[Cow<'static, str>]>,
+    ) -> Result<JsonValue, SchemaError> {
+        let field_schemas = field_types
+            .iter()
+            .map(|field_type| self.type_schema(&ScopedTypeId(scoped_type_id.0, *field_type)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let schema = match field_names {
+            Some(field_names) if field_names.len() == field_types.len() => json!({
+                "type": "object",
+                "properties": field_names
+                    .iter()
+                    .map(|field_name| field_name.to_string())
+                    .zip(field_schemas)
+                    .collect::<JsonMap<_, _>>(),
+                "required": field_names,
+                "additionalProperties": false,
+            }),
+            _ => json!({
+                "type": "array",
+                "prefixItems": field_schemas,
+                "items": false,
+                "minItems": field_types.len(),
+            }),
+        };
+        Ok(schema)
+    }
+
+    fn custom_kind_schema(
+        &self,
+        custom_type_kind: ScryptoCustomTypeKind,
+        type_name: Option<&str>,
+        type_validation: &TypeValidation<ScryptoCustomTypeValidation>,
+    ) -> JsonValue {
+        let (kind, description) = match custom_type_kind {
+            ScryptoCustomTypeKind::Decimal => {
+                return json!({ "type": "string", "pattern": DECIMAL_PATTERN });
+            }
+            ScryptoCustomTypeKind::PreciseDecimal => {
+                return json!({ "type": "string", "pattern": DECIMAL_PATTERN });
+            }
+            ScryptoCustomTypeKind::Reference => {
+                let description = match type_validation {
+                    TypeValidation::Custom(ScryptoCustomTypeValidation::Reference(validation)) => {
+                        self.describe_reference_validation(validation)
+                    }
+                    _ => "The address of an entity".to_string(),
+                };
+                ("Reference", description)
+            }
+            ScryptoCustomTypeKind::Own => {
+                let description = match type_validation {
+                    TypeValidation::Custom(ScryptoCustomTypeValidation::Own(validation)) => {
+                        self.describe_own_validation(validation)
+                    }
+                    _ => "The id of an owned entity".to_string(),
+                };
+                ("Own", description)
+            }
+            ScryptoCustomTypeKind::NonFungibleLocalId => (
+                "NonFungibleLocalId",
+                "A non-fungible local id, e.g. `<string>`, `#1#`, `[0a1b]` or `{...}`".to_string(),
+            ),
+        };
+
+        // These custom values are wrapped in an object, which also carries the value's type name
+        // (if it is known from the schema)
+        let mut properties = JsonMap::new();
+        properties.insert("kind".into(), json!({ "const": kind }));
+        if let Some(type_name) = type_name {
+            properties.insert("type_name".into(), json!({ "const": type_name }));
+        }
+        properties.insert(
+            "value".into(),
+            match custom_type_kind {
+                ScryptoCustomTypeKind::NonFungibleLocalId => json!({
+                    "type": "string",
+                    "pattern": NON_FUNGIBLE_LOCAL_ID_PATTERN,
+                }),
+                _ => json!({ "type": "string" }),
+            },
+        );
+        json!({
+            "type": "object",
+            "description": description,
+            "properties": properties,
+            "required": ["kind", "value"],
+            "additionalProperties": false,
+        })
+    }
+
+    fn describe_reference_validation(&self, validation: &ReferenceValidation) -> String {
+        match validation {
+            ReferenceValidation::IsGlobal => "The address of a global entity".to_string(),
+            ReferenceValidation::IsGlobalPackage => "A package address".to_string(),
+            ReferenceValidation::IsGlobalComponent => "A component address".to_string(),
+            ReferenceValidation::IsGlobalResourceManager => "A resource address".to_string(),
+            ReferenceValidation::IsGlobalTyped(package_address, blueprint_name) => format!(
+                "The address of a global `{}` component{}",
+                blueprint_name,
+                self.describe_package(package_address)
+            ),
+            ReferenceValidation::IsInternal => "The id of an internal entity".to_string(),
+            ReferenceValidation::IsInternalTyped(package_address, blueprint_name) => format!(
+                "The id of an internal `{}` object{}",
+                blueprint_name,
+                self.describe_package(package_address)
+            ),
+        }
+    }
+
+    fn describe_own_validation(&self, validation: &OwnValidation) -> String {
+        match validation {
+            OwnValidation::IsBucket => "The id of a bucket".to_string(),
+            OwnValidation::IsProof => "The id of a proof".to_string(),
+            OwnValidation::IsVault => "The id of a vault".to_string(),
+            OwnValidation::IsKeyValueStore => "The id of a key-value store".to_string(),
+            OwnValidation::IsGlobalAddressReservation => {
+                "The id of a global address reservation".to_string()
+            }
+            OwnValidation::IsTypedObject(package_address, blueprint_name) => format!(
+                "The id of an owned `{}` object{}",
+                blueprint_name,
+                self.describe_package(package_address)
+            ),
+        }
+    }
+
+    fn describe_package(&self, package_address: &Option<PackageAddress>) -> String {
+        match package_address {
+            Some(package_address) => format!(
+                " of package {}",
+                package_address.to_string(self.address_bech32_encoder)
+            ),
+            None => String::new(),
+        }
+    }
+}
+
+const DECIMAL_PATTERN: &str = "^-?[0-9]+(\\.[0-9]+)?$";
+
+const NON_FUNGIBLE_LOCAL_ID_PATTERN: &str =
+    "^(<[a-zA-Z0-9_]+>|#[0-9]+#|\\[[0-9a-f]+\\]|\\{[0-9a-f]{16}(-[0-9a-f]{16}){3}\\})$";
+
+/// The integers of up to 32 bits are represented as JSON numbers.
+fn small_integer_schema<T>(validation: NumericValidation<T>) -> JsonValue
+where
+    T: NumericValidationBound + Into<i64>,
+{
+    json!({
+        "type": "integer",
+        "minimum": validation.min.unwrap_or(T::MIN_VALUE).into(),
+        "maximum": validation.max.unwrap_or(T::MAX_VALUE).into(),
+    })
+}
+
+/// The larger integers are represented as strings, since they may exceed the JSON numbers' safe
+/// range (and thus their bounds can only be described).
+fn large_integer_schema(
+    pattern: &str,
+    type_validation: &TypeValidation<ScryptoCustomTypeValidation>,
+) -> JsonValue {
+    let bounds = match type_validation {
+        TypeValidation::I64(validation) => describe_bounds(validation),
+        TypeValidation::I128(validation) => describe_bounds(validation),
+        TypeValidation::U64(validation) => describe_bounds(validation),
+        TypeValidation::U128(validation) => describe_bounds(validation),
+        _ => None,
+    };
+    let mut schema = json!({ "type": "string", "pattern": pattern });
+    if let Some(bounds) = bounds {
+        schema["description"] = json!(bounds);
+    }
+    schema
+}
+
+fn describe_bounds<T: NumericValidationBound + Display>(
+    validation: &NumericValidation<T>,
+) -> Option<String> {
+    match (&validation.min, &validation.max) {
+        (Some(min), Some(max)) => Some(format!("An integer between {min} and {max}")),
+        (Some(min), None) => Some(format!("An integer of at least {min}")),
+        (None, Some(max)) => Some(format!("An integer of at most {max}")),
+        (None, None) => None,
+    }
+}
+
+/// The byte arrays are represented as `{ "hex": .. }` objects.
+fn bytes_schema(length_validation: &LengthValidation) -> JsonValue {
+    let repetition = match (length_validation.min, length_validation.max) {
+        (None, None) => "*".to_string(),
+        (min, Some(max)) => format!("{{{},{}}}", min.unwrap_or(0), max),
+        (Some(min), None) => format!("{{{},}}", min),
+    };
+    json!({
+        "type": "object",
+        "properties": {
+            "hex": {
+                "type": "string",
+                "pattern": format!("^([0-9a-f]{{2}}){repetition}$"),
+            },
+        },
+        "required": ["hex"],
+        "additionalProperties": false,
+    })
+}
+
+fn add_length_bounds(schema: &mut JsonValue, suffix: &str, length_validation: &LengthValidation) {
+    if let Some(min) = length_validation.min {
+        schema[format!("min{suffix}")] = json!(min);
+    }
+    if let Some(max) = length_validation.max {
+        schema[format!("max{suffix}")] = json!(max);
+    }
+}
+
+/// The OpenAPI component names must match `^[a-zA-Z0-9.\-_]+$` - and we reserve the `.` for the
+/// entry-point definitions.
+fn sanitize_definition_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(ScryptoSbor)]
+    struct SwapInput {
+        amount: Decimal,
+        recipients: IndexMap<String, u64>,
+        memo: Option<Vec<u8>>,
+        route: Route,
+    }
+
+    #[derive(ScryptoSbor)]
+    struct Route {
+        hops: Vec<Route>,
+        weight: u8,
+    }
+
+    #[derive(ScryptoSbor)]
+    enum SwapEvent {
+        Swapped { amount: Decimal },
+        Cancelled,
+    }
+
+    struct TestSchemaResolver(SchemaHash, Rc<VersionedScryptoSchema>);
+
+    impl PackageSchemaResolver for TestSchemaResolver {
+        fn lookup_schema(&self, schema_hash: &SchemaHash) -> Option<Rc<VersionedScryptoSchema>> {
+            (*schema_hash == self.0).then(|| self.1.clone())
+        }
+
+        fn resolve_type_kind(
+            &self,
+            type_identifier: &ScopedTypeId,
+        ) -> Result<LocalTypeKind<ScryptoCustomSchema>, SchemaError> {
+            self.1
+                .v1()
+                .resolve_type_kind(type_identifier.1)
+                .cloned()
+                .ok_or(SchemaError::NonExistentLocalTypeIndex(type_identifier.1))
+        }
+
+        fn resolve_type_metadata(
+            &self,
+            type_identifier: &ScopedTypeId,
+        ) -> Result<TypeMetadata, SchemaError> {
+            self.1
+                .v1()
+                .resolve_type_metadata(type_identifier.1)
+                .cloned()
+                .ok_or(SchemaError::NonExistentLocalTypeIndex(type_identifier.1))
+        }
+
+        fn resolve_type_validation(
+            &self,
+            type_identifier: &ScopedTypeId,
+        ) -> Result<TypeValidation<ScryptoCustomTypeValidation>, SchemaError> {
+            self.1
+                .v1()
+                .resolve_type_validation(type_identifier.1)
+                .cloned()
+                .ok_or(SchemaError::NonExistentLocalTypeIndex(type_identifier.1))
+        }
+
+        fn package_address(&self) -> PackageAddress {
+            FAUCET_PACKAGE
+        }
+    }
+
+    fn test_package() -> (
+        BTreeMap<BlueprintVersionKey, BlueprintDefinition>,
+        TestSchemaResolver,
+    ) {
+        let mut aggregator = TypeAggregator::<ScryptoCustomTypeKind>::new();
+        let input = aggregator.add_child_type_and_descendents::<SwapInput>();
+        let output = aggregator.add_child_type_and_descendents::<Vec<NonFungibleLocalId>>();
+        let event = aggregator.add_child_type_and_descendents::<SwapEvent>();
+        let field = aggregator.add_child_type_and_descendents::<(ResourceAddress, u64)>();
+        let key = aggregator.add_child_type_and_descendents::<u32>();
+        let value = aggregator.add_child_type_and_descendents::<Route>();
+        let schema = generate_full_schema(aggregator);
+        let schema_hash = schema.generate_schema_hash();
+        let payload_def = |type_id| BlueprintPayloadDef::Static(ScopedTypeId(schema_hash, type_id));
+
+        let definition = BlueprintDefinition {
+            interface: BlueprintInterface {
+                blueprint_type: BlueprintType::Outer,
+                is_transient: false,
+                generics: vec![],
+                feature_set: indexset!(),
+                state: IndexedStateSchema {
+                    fields: Some((
+                        PartitionDescription::Logical(PartitionOffset(0)),
+                        vec![FieldSchema {
+                            field: payload_def(field),
+                            condition: Condition::Always,
+                            transience: FieldTransience::NotTransient,
+                        }],
+                    )),
+                    collections: vec![(
+                        PartitionDescription::Logical(PartitionOffset(1)),
+                        BlueprintCollectionSchema::KeyValueStore(BlueprintKeyValueSchema {
+                            key: payload_def(key),
+                            value: payload_def(value),
+                            allow_ownership: false,
+                        }),
+                    )],
+                    num_logical_partitions: 2,
+                },
+                functions: indexmap!(
+                    "swap".to_string() => FunctionSchema {
+                        receiver: Some(ReceiverInfo::normal_ref_mut()),
+                        input: payload_def(input),
+                        output: payload_def(output),
+                    }
+                ),
+                events: indexmap!("SwapEvent".to_string() => payload_def(event)),
+                types: indexmap!(),
+            },
+            function_exports: indexmap!(),
+            hook_exports: indexmap!(),
+        };
+        (
+            btreemap!(BlueprintVersionKey::new_default("Swapper") => definition),
+            TestSchemaResolver(schema_hash, Rc::new(schema)),
+        )
+    }
+
+    #[test]
+    fn json_schema_describes_the_natural_representation_of_payloads() {
+        let (package_definition, schema_resolver) = test_package();
+        let document = package_json_schema_from_package_definition(
+            package_definition,
+            &schema_resolver,
+            &AddressBech32Encoder::for_simulator(),
+            JsonSchemaDocumentFormat::JsonSchema,
+        )
+        .unwrap();
+        let definitions = &document["$defs"];
+
+        assert_eq!(
+            definitions["Swapper.swap.Input"],
+            json!({ "$ref": "#/$defs/SwapInput" })
+        );
+        assert_eq!(
+            definitions["SwapInput"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "amount": { "type": "string", "pattern": DECIMAL_PATTERN },
+                    "recipients": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string", "pattern": "^[0-9]+$" },
+                    },
+                    "memo": {
+                        "oneOf": [
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "variant_id": { "const": 0 },
+                                    "variant_name": { "const": "None" },
+                                    "fields": {
+                                        "type": "array",
+                                        "prefixItems": [],
+                                        "items": false,
+                                        "minItems": 0,
+                                    },
+                                },
+                                "required": ["variant_id", "fields"],
+                                "additionalProperties": false,
+                            },
+                            {
+                                "type": "object",
+                                "properties": {
+                                    "variant_id": { "const": 1 },
+                                    "variant_name": { "const": "Some" },
+                                    "fields": {
+                                        "type": "array",
+                                        "prefixItems": [{
+                                            "type": "object",
+                                            "properties": {
+                                                "hex": {
+                                                    "type": "string",
+                                                    "pattern": "^([0-9a-f]{2})*$",
+                                                },
+                                            },
+                                            "required": ["hex"],
+                                            "additionalProperties": false,
+                                        }],
+                                        "items": false,
+                                        "minItems": 1,
+                                    },
+                                },
+                                "required": ["variant_id", "fields"],
+                                "additionalProperties": false,
+                            },
+                        ],
+                    },
+                    "route": { "$ref": "#/$defs/Route" },
+                },
+                "required": ["amount", "recipients", "memo", "route"],
+                "additionalProperties": false,
+            })
+        );
+
+        // A recursive type refers to its own definition
+        assert_eq!(
+            definitions["Route"]["properties"]["hops"],
+            json!({ "type": "array", "items": { "$ref": "#/$defs/Route" } })
+        );
+        assert_eq!(
+            definitions["Route"]["properties"]["weight"],
+            json!({ "type": "integer", "minimum": 0, "maximum": 255 })
+        );
+
+        // The tagged custom values include their type name, if known
+        assert_eq!(
+            definitions["Swapper.fields.0"]["prefixItems"][0],
+            json!({
+                "type": "object",
+                "description": "A resource address",
+                "properties": {
+                    "kind": { "const": "Reference" },
+                    "type_name": { "const": "ResourceAddress" },
+                    "value": { "type": "string" },
+                },
+                "required": ["kind", "value"],
+                "additionalProperties": false,
+            })
+        );
+        assert_eq!(
+            definitions["Swapper.swap.Output"]["items"]["properties"]["kind"],
+            json!({ "const": "NonFungibleLocalId" })
+        );
+        assert_eq!(
+            definitions["Swapper.events.SwapEvent"],
+            json!({ "$ref": "#/$defs/SwapEvent" })
+        );
+        assert_eq!(
+            definitions["Swapper.collections.0.Value"],
+            json!({ "$ref": "#/$defs/Route" })
+        );
+    }
+
+    #[test]
+    fn open_api_document_refers_to_its_components() {
+        let (package_definition, schema_resolver) = test_package();
+        let document = package_json_schema_from_package_definition(
+            package_definition,
+            &schema_resolver,
+            &AddressBech32Encoder::for_simulator(),
+            JsonSchemaDocumentFormat::OpenApi,
+        )
+        .unwrap();
+
+        assert_eq!(document["openapi"], json!("3.1.0"));
+        assert_eq!(
+            document["components"]["schemas"]["Swapper.swap.Input"],
+            json!({ "$ref": "#/components/schemas/SwapInput" })
+        );
+        assert_eq!(
+            document["components"]["schemas"]["SwapEvent"]["oneOf"][0]["properties"]["fields"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "amount": { "type": "string", "pattern": DECIMAL_PATTERN },
+                },
+                "required": ["amount"],
+                "additionalProperties": false,
+            })
+        );
+    }
+}
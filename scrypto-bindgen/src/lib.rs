@@ -1,4 +1,5 @@
 pub mod ast;
+pub mod json_schema;
 pub mod macros;
 pub mod schema;
 pub mod translation;
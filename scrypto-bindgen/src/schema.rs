@@ -230,6 +230,7 @@ pub enum SchemaError {
     FailedToGetSchemaFromSchemaHash,
     GenericTypeRefsNotSupported,
     NoNameFound,
+    RecursiveUnnamedType(ScopedTypeId),
 }
 
 impl Display for SchemaError {
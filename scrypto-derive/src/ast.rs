@@ -3,7 +3,7 @@ use syn::punctuated::Punctuated;
 use syn::token::{As, Brace, Paren};
 use syn::{
     braced, parenthesized, Attribute, Ident, ItemConst, ItemImpl, ItemMacro, ItemStruct, ItemUse,
-    Path, Result, Token, Visibility,
+    Path, Result, Token, Type, Visibility,
 };
 
 /// Represents a blueprint which is a module with an optional set of attributes
@@ -122,6 +122,39 @@ impl Parse for TypesInner {
     }
 }
 
+#[allow(dead_code)] // Fields for tokens from parse for completeness
+pub struct CollectionsInner {
+    pub paren_token: Paren,
+    pub collections: Punctuated<CollectionDeclaration, Token![,]>,
+}
+
+impl Parse for CollectionsInner {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            paren_token: parenthesized!(content in input),
+            collections: content.parse_terminated(CollectionDeclaration::parse)?,
+        })
+    }
+}
+
+#[allow(dead_code)] // Fields for tokens from parse for completeness
+pub struct CollectionDeclaration {
+    pub ident: Ident,
+    pub colon_token: Token![:],
+    pub ty: Type,
+}
+
+impl Parse for CollectionDeclaration {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            ident: input.parse()?,
+            colon_token: input.parse()?,
+            ty: input.parse()?,
+        })
+    }
+}
+
 pub struct AliasableType {
     pub path: Path,
     pub alias: Option<Ident>,
@@ -256,6 +256,44 @@ pub fn derive_sensible_identifier_from_path(path: &Path) -> Result<String> {
     }
 }
 
+/// Extracts the key and value types of a collection declared in the `#[collections(...)]`
+/// attribute, together with the variant of `BlueprintCollectionSchema` and the Scrypto type which
+/// back it.
+fn parse_collection_type(ty: &Type) -> Result<(Ident, Path, Type, Type)> {
+    let error = || {
+        Error::new(
+            ty.span(),
+            "Collections must be declared as `IndexedStore<K, V>` or `SortedStore<K, V>`",
+        )
+    };
+    let Type::Path(type_path) = ty else {
+        return Err(error());
+    };
+    let segment = type_path.path.segments.last().ok_or_else(error)?;
+    let (schema_variant, store_path): (Ident, Path) = match segment.ident.to_string().as_str() {
+        "IndexedStore" => (
+            format_ident!("Index"),
+            parse_quote! { ::scrypto::prelude::IndexedStore },
+        ),
+        "SortedStore" => (
+            format_ident!("SortedIndex"),
+            parse_quote! { ::scrypto::prelude::SortedStore },
+        ),
+        _ => return Err(error()),
+    };
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return Err(error());
+    };
+    let mut types = arguments.args.iter().map(|argument| match argument {
+        GenericArgument::Type(ty) => Ok(ty.clone()),
+        _ => Err(error()),
+    });
+    match (types.next(), types.next(), types.next()) {
+        (Some(key), Some(value), None) => Ok((schema_variant, store_path, key?, value?)),
+        _ => Err(error()),
+    }
+}
+
 pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
     trace!("handle_blueprint() starts");
 
@@ -686,6 +724,36 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
         }
     }
 
+    let mut collection_statements = Vec::<ItemConst>::new();
+    let mut collection_schemas = Vec::<TokenStream>::new();
+    for attribute in &blueprint.attributes {
+        if attribute.path.is_ident("collections") {
+            let collections_inner = parse2::<ast::CollectionsInner>(attribute.tokens.clone())?;
+            for collection in collections_inner.collections {
+                let ident = collection.ident;
+                let ty = collection.ty;
+                let (schema_variant, store_path, key_type, value_type) =
+                    parse_collection_type(&ty)?;
+                let collection_index = u8::try_from(collection_schemas.len())
+                    .map_err(|_| Error::new(ident.span(), "Too many collections"))?;
+                collection_statements.push(parse_quote! {
+                    pub const #ident: #ty = #store_path::new(#collection_index);
+                });
+                collection_schemas.push(quote! {
+                    {
+                        let key = aggregator.add_child_type_and_descendents::<#key_type>();
+                        let value = aggregator.add_child_type_and_descendents::<#value_type>();
+                        BlueprintCollectionSchema::#schema_variant(BlueprintKeyValueSchema {
+                            key: TypeRef::Static(key),
+                            value: TypeRef::Static(value),
+                            allow_ownership: true,
+                        })
+                    }
+                });
+            }
+        }
+    }
+
     #[cfg(feature = "no-schema")]
     let output_schema = quote! {};
     #[cfg(not(feature = "no-schema"))]
@@ -753,7 +821,8 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
                             ));
                         }
                     }
-                } else if attribute.path.is_ident("types") {
+                } else if attribute.path.is_ident("types") || attribute.path.is_ident("collections")
+                {
                 }
                 // None of the attributes to apply at the top-level of blueprint macros matched. So,
                 // we provide an error to the user that they're using an incorrect attribute macro
@@ -793,9 +862,12 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
                     let type_index = aggregator.add_child_type_and_descendents::<#bp_ident>();
                     fields.push(FieldSchema::static_field(type_index));
 
+                    // Aggregate collections
+                    let collections = vec![#(#collection_schemas),*];
+
                     let state = BlueprintStateSchemaInit {
                         fields,
-                        collections: Vec::new(),
+                        collections,
                     };
 
                     // Aggregate functions
@@ -1032,6 +1104,8 @@ pub fn handle_blueprint(input: TokenStream) -> Result<TokenStream> {
 
             #(#const_statements)*
 
+            #(#collection_statements)*
+
             #(#import_statements)*
 
             #(#macro_statements)*
@@ -1995,9 +2069,11 @@ mod tests {
                             let type_index = aggregator.add_child_type_and_descendents::<Test>();
                             fields.push(FieldSchema::static_field(type_index));
 
+                            let collections = vec![];
+
                             let state = BlueprintStateSchemaInit {
                                 fields,
-                                collections: Vec::new(),
+                                collections,
                             };
 
                             let functions = {
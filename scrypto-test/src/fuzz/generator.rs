@@ -0,0 +1,543 @@
+//! This module implements the generation of random [`ScryptoValue`]s from a schema.
+
+use super::*;
+use crate::prelude::*;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+/// A generator of random [`ScryptoValue`]s that conform to a type in a Scrypto schema.
+///
+/// Simple values (integers, strings, decimals, and non-fungible local ids) are generated at random
+/// with a bias towards edge-cases such as zero and the bounds of the type. References are picked
+/// from the pool of global addresses that the generator has been given, filtered by the validation
+/// of the type when possible. Owned [`Bucket`]s and [`Proof`]s can't exist before the invocation
+/// is made, so the generator records a [`FuzzOwnedValue`] for each one of them and places a
+/// placeholder [`Own`] in the generated value which is replaced by a real node when the value is
+/// materialized in a [`TestEnvironment`].
+pub struct ScryptoValueGenerator<'a> {
+    rng: &'a mut ChaCha8Rng,
+    resources: &'a [ResourceAddress],
+    references: &'a [GlobalAddress],
+    max_collection_length: usize,
+    max_depth: usize,
+    owned_values: Vec<FuzzOwnedValue>,
+}
+
+impl<'a> ScryptoValueGenerator<'a> {
+    pub fn new(
+        rng: &'a mut ChaCha8Rng,
+        resources: &'a [ResourceAddress],
+        references: &'a [GlobalAddress],
+    ) -> Self {
+        Self {
+            rng,
+            resources,
+            references,
+            max_collection_length: 4,
+            max_depth: 8,
+            owned_values: Vec::new(),
+        }
+    }
+
+    /// Sets the maximum number of elements in generated arrays, maps and strings.
+    pub fn with_max_collection_length(mut self, max_collection_length: usize) -> Self {
+        self.max_collection_length = max_collection_length;
+        self
+    }
+
+    /// Sets the depth after which collections are generated empty and enums are generated with
+    /// their smallest variant to ensure that recursive types terminate.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Generates a value of the given type and returns it along with the owned values that must
+    /// be created before it can be used.
+    pub fn generate(
+        mut self,
+        schema: &ScryptoSchema,
+        type_id: LocalTypeId,
+    ) -> Result<(ScryptoValue, Vec<FuzzOwnedValue>), FuzzError> {
+        let value = self.generate_value(schema, type_id, 0)?;
+        Ok((value, self.owned_values))
+    }
+
+    fn generate_value(
+        &mut self,
+        schema: &ScryptoSchema,
+        type_id: LocalTypeId,
+        depth: usize,
+    ) -> Result<ScryptoValue, FuzzError> {
+        let type_kind = schema
+            .resolve_type_kind(type_id)
+            .ok_or(FuzzError::TypeNotFoundInSchema(type_id))?;
+        let validation = schema
+            .resolve_type_validation(type_id)
+            .unwrap_or(&TypeValidation::None);
+
+        let value = match type_kind {
+            TypeKind::Any => return Err(FuzzError::UnsupportedType(type_kind.label().name())),
+            TypeKind::Bool => ScryptoValue::Bool {
+                value: self.rng.gen(),
+            },
+            TypeKind::I8 => ScryptoValue::I8 {
+                value: generate_integer!(self, i8, validation, TypeValidation::I8),
+            },
+            TypeKind::I16 => ScryptoValue::I16 {
+                value: generate_integer!(self, i16, validation, TypeValidation::I16),
+            },
+            TypeKind::I32 => ScryptoValue::I32 {
+                value: generate_integer!(self, i32, validation, TypeValidation::I32),
+            },
+            TypeKind::I64 => ScryptoValue::I64 {
+                value: generate_integer!(self, i64, validation, TypeValidation::I64),
+            },
+            TypeKind::I128 => ScryptoValue::I128 {
+                value: generate_integer!(self, i128, validation, TypeValidation::I128),
+            },
+            TypeKind::U8 => ScryptoValue::U8 {
+                value: generate_integer!(self, u8, validation, TypeValidation::U8),
+            },
+            TypeKind::U16 => ScryptoValue::U16 {
+                value: generate_integer!(self, u16, validation, TypeValidation::U16),
+            },
+            TypeKind::U32 => ScryptoValue::U32 {
+                value: generate_integer!(self, u32, validation, TypeValidation::U32),
+            },
+            TypeKind::U64 => ScryptoValue::U64 {
+                value: generate_integer!(self, u64, validation, TypeValidation::U64),
+            },
+            TypeKind::U128 => ScryptoValue::U128 {
+                value: generate_integer!(self, u128, validation, TypeValidation::U128),
+            },
+            TypeKind::String => {
+                let length = self.next_length(validation, depth)?;
+                ScryptoValue::String {
+                    value: (0..length)
+                        .map(|_| self.rng.sample(rand::distributions::Alphanumeric) as char)
+                        .collect(),
+                }
+            }
+            TypeKind::Array { element_type } => {
+                let element_value_kind = value_kind_of(schema, *element_type)?;
+                let length = self.next_length(validation, depth)?;
+                let elements = (0..length)
+                    .map(|_| self.generate_value(schema, *element_type, depth + 1))
+                    .collect::<Result<Vec<_>, _>>()?;
+                ScryptoValue::Array {
+                    element_value_kind,
+                    elements,
+                }
+            }
+            TypeKind::Tuple { field_types } => ScryptoValue::Tuple {
+                fields: field_types
+                    .iter()
+                    .map(|field_type| self.generate_value(schema, *field_type, depth + 1))
+                    .collect::<Result<Vec<_>, _>>()?,
+            },
+            TypeKind::Enum { variants } => {
+                let (discriminator, field_types) = if depth >= self.max_depth {
+                    variants
+                        .iter()
+                        .min_by_key(|(_, field_types)| field_types.len())
+                        .ok_or(FuzzError::EnumWithoutVariants(type_id))?
+                } else {
+                    variants
+                        .get_index(self.rng.gen_range(0..variants.len().max(1)))
+                        .ok_or(FuzzError::EnumWithoutVariants(type_id))?
+                };
+                ScryptoValue::Enum {
+                    discriminator: *discriminator,
+                    fields: field_types
+                        .iter()
+                        .map(|field_type| self.generate_value(schema, *field_type, depth + 1))
+                        .collect::<Result<Vec<_>, _>>()?,
+                }
+            }
+            TypeKind::Map {
+                key_type,
+                value_type,
+            } => {
+                let key_value_kind = value_kind_of(schema, *key_type)?;
+                let value_value_kind = value_kind_of(schema, *value_type)?;
+                let length = self.next_length(validation, depth)?;
+
+                // Keys that encode to the same bytes are only included once as maps with duplicate
+                // keys are rejected when decoded.
+                let mut encoded_keys = IndexSet::new();
+                let mut entries = Vec::new();
+                for _ in 0..length {
+                    let key = self.generate_value(schema, *key_type, depth + 1)?;
+                    if encoded_keys.insert(scrypto_encode(&key).expect("Generated key is valid")) {
+                        let value = self.generate_value(schema, *value_type, depth + 1)?;
+                        entries.push((key, value));
+                    }
+                }
+                ScryptoValue::Map {
+                    key_value_kind,
+                    value_value_kind,
+                    entries,
+                }
+            }
+            TypeKind::Custom(ScryptoCustomTypeKind::Decimal) => ScryptoValue::Custom {
+                value: ScryptoCustomValue::Decimal(self.next_decimal()),
+            },
+            TypeKind::Custom(ScryptoCustomTypeKind::PreciseDecimal) => ScryptoValue::Custom {
+                value: ScryptoCustomValue::PreciseDecimal(self.next_decimal().into()),
+            },
+            TypeKind::Custom(ScryptoCustomTypeKind::NonFungibleLocalId) => ScryptoValue::Custom {
+                value: ScryptoCustomValue::NonFungibleLocalId(NonFungibleLocalId::integer(
+                    self.rng.gen_range(0..16),
+                )),
+            },
+            TypeKind::Custom(ScryptoCustomTypeKind::Reference) => {
+                let TypeValidation::Custom(ScryptoCustomTypeValidation::Reference(validation)) =
+                    validation
+                else {
+                    return Err(FuzzError::UnsupportedType("Reference"));
+                };
+                ScryptoValue::Custom {
+                    value: ScryptoCustomValue::Reference(Reference(
+                        self.next_reference(validation)?,
+                    )),
+                }
+            }
+            TypeKind::Custom(ScryptoCustomTypeKind::Own) => {
+                let TypeValidation::Custom(ScryptoCustomTypeValidation::Own(validation)) =
+                    validation
+                else {
+                    return Err(FuzzError::UnsupportedType("Own"));
+                };
+                let owned_value = self.next_owned_value(validation)?;
+                self.owned_values.push(owned_value);
+                ScryptoValue::Custom {
+                    value: ScryptoCustomValue::Own(Own(placeholder_node_id(
+                        self.owned_values.len() - 1,
+                    ))),
+                }
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn next_length(
+        &mut self,
+        validation: &TypeValidation<ScryptoCustomTypeValidation>,
+        depth: usize,
+    ) -> Result<usize, FuzzError> {
+        let (min, max) = match validation {
+            TypeValidation::String(LengthValidation { min, max })
+            | TypeValidation::Array(LengthValidation { min, max })
+            | TypeValidation::Map(LengthValidation { min, max }) => {
+                let min = min.unwrap_or(0) as usize;
+                let max = max.map_or(self.max_collection_length.max(min), |max| {
+                    (max as usize).min(self.max_collection_length.max(min))
+                });
+                (min, max)
+            }
+            _ => (0, self.max_collection_length),
+        };
+
+        if min > max {
+            return Err(FuzzError::UnsatisfiableValidation);
+        }
+        if depth >= self.max_depth {
+            Ok(min)
+        } else {
+            Ok(self.rng.gen_range(min..=max))
+        }
+    }
+
+    fn next_decimal(&mut self) -> Decimal {
+        match self.rng.gen_range(0u8..8) {
+            0 => Decimal::ZERO,
+            1 => Decimal::ONE,
+            2 => Decimal::ONE_ATTO,
+            3 => Decimal::from(self.rng.gen_range(0u64..u64::MAX)),
+            _ => {
+                let whole = self.rng.gen_range(0u64..1000);
+                let fraction = self.rng.gen_range(0u64..1_000_000);
+                Decimal::from(whole) + Decimal::from(fraction) / Decimal::from(1_000_000u64)
+            }
+        }
+    }
+
+    fn next_reference(&mut self, validation: &ReferenceValidation) -> Result<NodeId, FuzzError> {
+        let candidates = self
+            .references
+            .iter()
+            .map(|address| *address.as_node_id())
+            .filter(|node_id| match validation {
+                ReferenceValidation::IsGlobal => true,
+                ReferenceValidation::IsGlobalPackage => node_id.is_global_package(),
+                ReferenceValidation::IsGlobalComponent => node_id.is_global_component(),
+                ReferenceValidation::IsGlobalResourceManager => {
+                    node_id.is_global_resource_manager()
+                }
+                ReferenceValidation::IsGlobalTyped(Some(RESOURCE_PACKAGE), blueprint_name)
+                    if blueprint_name == FUNGIBLE_RESOURCE_MANAGER_BLUEPRINT =>
+                {
+                    node_id.is_global_fungible_resource_manager()
+                }
+                ReferenceValidation::IsGlobalTyped(Some(RESOURCE_PACKAGE), blueprint_name)
+                    if blueprint_name == NON_FUNGIBLE_RESOURCE_MANAGER_BLUEPRINT =>
+                {
+                    node_id.is_global_non_fungible_resource_manager()
+                }
+                ReferenceValidation::IsGlobalTyped(..) => node_id.is_global(),
+                ReferenceValidation::IsInternal | ReferenceValidation::IsInternalTyped(..) => false,
+            })
+            .collect::<Vec<_>>();
+
+        if candidates.is_empty() {
+            Err(FuzzError::NoMatchingReference(validation.clone()))
+        } else {
+            Ok(candidates[self.rng.gen_range(0..candidates.len())])
+        }
+    }
+
+    fn next_owned_value(
+        &mut self,
+        validation: &OwnValidation,
+    ) -> Result<FuzzOwnedValue, FuzzError> {
+        let is_bucket = match validation {
+            OwnValidation::IsBucket => true,
+            OwnValidation::IsProof => false,
+            OwnValidation::IsTypedObject(Some(RESOURCE_PACKAGE), blueprint_name)
+                if blueprint_name == FUNGIBLE_BUCKET_BLUEPRINT =>
+            {
+                true
+            }
+            OwnValidation::IsTypedObject(Some(RESOURCE_PACKAGE), blueprint_name)
+                if blueprint_name == FUNGIBLE_PROOF_BLUEPRINT =>
+            {
+                false
+            }
+            _ => return Err(FuzzError::UnsupportedOwnedValue(validation.clone())),
+        };
+
+        let fungibles = self
+            .resources
+            .iter()
+            .filter(|resource_address| resource_address.is_fungible())
+            .collect::<Vec<_>>();
+        if fungibles.is_empty() {
+            return Err(FuzzError::UnsupportedOwnedValue(validation.clone()));
+        }
+        let resource_address = *fungibles[self.rng.gen_range(0..fungibles.len())];
+        let amount = self.next_decimal();
+
+        Ok(if is_bucket {
+            FuzzOwnedValue::Bucket {
+                resource_address,
+                amount,
+            }
+        } else {
+            FuzzOwnedValue::Proof {
+                resource_address,
+                amount,
+            }
+        })
+    }
+}
+
+macro_rules! generate_integer {
+    ($self: expr, $ty: ty, $validation: expr, $variant: path) => {{
+        let (min, max) = match $validation {
+            $variant(NumericValidation { min, max }) => {
+                (min.unwrap_or(<$ty>::MIN), max.unwrap_or(<$ty>::MAX))
+            }
+            _ => (<$ty>::MIN, <$ty>::MAX),
+        };
+        if min > max {
+            return Err(FuzzError::UnsatisfiableValidation);
+        }
+        match $self.rng.gen_range(0u8..8) {
+            0 => min,
+            1 => max,
+            2 => (0 as $ty).clamp(min, max),
+            3 => (1 as $ty).clamp(min, max),
+            _ => $self.rng.gen_range(min..=max),
+        }
+    }};
+}
+use generate_integer;
+
+/// Determines the value kind of the values of a type, used for the element kinds of arrays and
+/// maps.
+fn value_kind_of(
+    schema: &ScryptoSchema,
+    type_id: LocalTypeId,
+) -> Result<ValueKind<ScryptoCustomValueKind>, FuzzError> {
+    let type_kind = schema
+        .resolve_type_kind(type_id)
+        .ok_or(FuzzError::TypeNotFoundInSchema(type_id))?;
+    let value_kind = match type_kind {
+        TypeKind::Any => return Err(FuzzError::UnsupportedType(type_kind.label().name())),
+        TypeKind::Bool => ValueKind::Bool,
+        TypeKind::I8 => ValueKind::I8,
+        TypeKind::I16 => ValueKind::I16,
+        TypeKind::I32 => ValueKind::I32,
+        TypeKind::I64 => ValueKind::I64,
+        TypeKind::I128 => ValueKind::I128,
+        TypeKind::U8 => ValueKind::U8,
+        TypeKind::U16 => ValueKind::U16,
+        TypeKind::U32 => ValueKind::U32,
+        TypeKind::U64 => ValueKind::U64,
+        TypeKind::U128 => ValueKind::U128,
+        TypeKind::String => ValueKind::String,
+        TypeKind::Array { .. } => ValueKind::Array,
+        TypeKind::Tuple { .. } => ValueKind::Tuple,
+        TypeKind::Enum { .. } => ValueKind::Enum,
+        TypeKind::Map { .. } => ValueKind::Map,
+        TypeKind::Custom(ScryptoCustomTypeKind::Reference) => {
+            ValueKind::Custom(ScryptoCustomValueKind::Reference)
+        }
+        TypeKind::Custom(ScryptoCustomTypeKind::Own) => {
+            ValueKind::Custom(ScryptoCustomValueKind::Own)
+        }
+        TypeKind::Custom(ScryptoCustomTypeKind::Decimal) => {
+            ValueKind::Custom(ScryptoCustomValueKind::Decimal)
+        }
+        TypeKind::Custom(ScryptoCustomTypeKind::PreciseDecimal) => {
+            ValueKind::Custom(ScryptoCustomValueKind::PreciseDecimal)
+        }
+        TypeKind::Custom(ScryptoCustomTypeKind::NonFungibleLocalId) => {
+            ValueKind::Custom(ScryptoCustomValueKind::NonFungibleLocalId)
+        }
+    };
+    Ok(value_kind)
+}
+
+/// The placeholder [`NodeId`] of the owned value at the given index. The entity type byte of the
+/// placeholder is zero which is not a valid entity type, so it can never clash with a real node.
+pub(super) fn placeholder_node_id(index: usize) -> NodeId {
+    let mut bytes = [0u8; NodeId::LENGTH];
+    bytes[NodeId::LENGTH - 4..].copy_from_slice(&(index as u32).to_be_bytes());
+    NodeId(bytes)
+}
+
+/// The index of the owned value that the [`NodeId`] is a placeholder for, if it's a placeholder.
+pub(super) fn placeholder_index(node_id: &NodeId) -> Option<usize> {
+    let bytes = node_id.as_bytes();
+    if bytes[..NodeId::LENGTH - 4].iter().all(|byte| *byte == 0) {
+        let mut index = [0u8; 4];
+        index.copy_from_slice(&bytes[NodeId::LENGTH - 4..]);
+        Some(u32::from_be_bytes(index) as usize)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::rand_core::SeedableRng;
+
+    #[derive(ScryptoSbor)]
+    #[allow(dead_code)]
+    struct Arguments {
+        flag: bool,
+        count: u32,
+        name: String,
+        amounts: Vec<Decimal>,
+        limit: Option<PreciseDecimal>,
+        ids: IndexMap<NonFungibleLocalId, (i8, u128)>,
+        resource: ResourceAddress,
+    }
+
+    #[test]
+    fn generated_values_are_valid_against_the_schema() {
+        // Arrange
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<Arguments, ScryptoCustomSchema>();
+        let resources = [XRD];
+        let references = [XRD.into()];
+
+        for seed in 0..64 {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+            // Act
+            let (value, owned_values) =
+                ScryptoValueGenerator::new(&mut rng, &resources, &references)
+                    .generate(schema.v1(), type_id)
+                    .unwrap();
+
+            // Assert
+            assert!(owned_values.is_empty());
+            let payload = scrypto_encode(&value).unwrap();
+            validate_payload_against_schema::<ScryptoCustomExtension, _>(
+                &payload,
+                schema.v1(),
+                type_id,
+                &(),
+                SCRYPTO_SBOR_V1_MAX_DEPTH,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn buckets_are_generated_as_placeholders() {
+        // Arrange
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<(Bucket, FungibleProof), ScryptoCustomSchema>();
+        let resources = [XRD];
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        // Act
+        let (value, owned_values) = ScryptoValueGenerator::new(&mut rng, &resources, &[])
+            .generate(schema.v1(), type_id)
+            .unwrap();
+
+        // Assert
+        assert!(matches!(
+            owned_values.as_slice(),
+            [
+                FuzzOwnedValue::Bucket {
+                    resource_address: XRD,
+                    ..
+                },
+                FuzzOwnedValue::Proof {
+                    resource_address: XRD,
+                    ..
+                }
+            ]
+        ));
+        let ScryptoValue::Tuple { fields } = value else {
+            panic!("Expected a tuple");
+        };
+        for (index, field) in fields.iter().enumerate() {
+            let ScryptoValue::Custom {
+                value: ScryptoCustomValue::Own(own),
+            } = field
+            else {
+                panic!("Expected an own");
+            };
+            assert_eq!(placeholder_index(&own.0), Some(index));
+        }
+    }
+
+    #[test]
+    fn references_without_candidates_can_not_be_generated() {
+        // Arrange
+        let (type_id, schema) =
+            generate_full_schema_from_single_type::<PackageAddress, ScryptoCustomSchema>();
+        let references = [XRD.into()];
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        // Act
+        let result =
+            ScryptoValueGenerator::new(&mut rng, &[], &references).generate(schema.v1(), type_id);
+
+        // Assert
+        assert!(matches!(
+            result,
+            Err(FuzzError::NoMatchingReference(
+                ReferenceValidation::IsGlobalPackage
+            ))
+        ));
+    }
+}
@@ -0,0 +1,558 @@
+//! This module implements the [`FuzzHarness`] which generates, runs, and shrinks sequences of
+//! method invocations.
+
+use super::*;
+use crate::prelude::*;
+use rand::Rng;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use sbor::representations::*;
+use scrypto::radix_blueprint_schema_init::RefTypes;
+
+type SetupFunction<C, D> =
+    Box<dyn Fn() -> Result<(TestEnvironment<D>, FuzzTarget<C>), RuntimeError>>;
+type InvariantFunction<C, D> = Box<dyn Fn(&mut TestEnvironment<D>, &C) -> Result<(), String>>;
+
+/// A property-based testing harness that invokes random sequences of methods on a component and
+/// checks a set of invariants after each invocation.
+///
+/// The harness calls the setup function once for every sequence it runs, including the sequences
+/// it replays when shrinking, so that every sequence starts from the same state. The setup function
+/// must therefore be deterministic: it must create the same entities at the same addresses every
+/// time it's called.
+pub struct FuzzHarness<C, D = InMemorySubstateDatabase>
+where
+    D: SubstateDatabase + CommittableSubstateDatabase + 'static,
+{
+    setup: SetupFunction<C, D>,
+    invariants: Vec<(String, InvariantFunction<C, D>)>,
+    config: FuzzConfig,
+}
+
+/// A method of the target component along with the schema of its input.
+struct FuzzMethod {
+    name: String,
+    schema: Rc<VersionedScryptoSchema>,
+    input_type_id: LocalTypeId,
+}
+
+/// The result of running a sequence of steps.
+enum SequenceOutcome {
+    /// All of the steps were invoked and all of the invariants held after each one of them.
+    Passed,
+    /// One of the steps returned an error which ends the sequence.
+    Rejected,
+    /// An invariant was violated.
+    Failed { invariant: String, message: String },
+}
+
+impl<C, D> FuzzHarness<C, D>
+where
+    D: SubstateDatabase + CommittableSubstateDatabase + 'static,
+{
+    pub fn new<F>(setup: F) -> Self
+    where
+        F: Fn() -> Result<(TestEnvironment<D>, FuzzTarget<C>), RuntimeError> + 'static,
+    {
+        Self {
+            setup: Box::new(setup),
+            invariants: Default::default(),
+            config: Default::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: FuzzConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Adds an invariant that's checked after each successful invocation. The invariant returns
+    /// an [`Err`] with a message describing the violation when it doesn't hold.
+    pub fn with_invariant<S, F>(mut self, name: S, invariant: F) -> Self
+    where
+        S: Into<String>,
+        F: Fn(&mut TestEnvironment<D>, &C) -> Result<(), String> + 'static,
+    {
+        self.invariants.push((name.into(), Box::new(invariant)));
+        self
+    }
+
+    /// Runs the configured number of random sequences and returns the first failure found after
+    /// shrinking it.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the setup function fails or if the arguments of none of the methods
+    /// of the component can be generated.
+    pub fn run(&self) -> Result<(), FuzzFailure> {
+        for run in 0..self.config.runs {
+            let seed = self.config.seed.wrapping_add(run);
+            if let Some((steps, invariant, message)) = self.run_random_sequence(seed) {
+                let original_length = steps.len();
+                let (steps, message) = self.shrink(steps, &invariant, message);
+                return Err(FuzzFailure {
+                    seed,
+                    invariant,
+                    message,
+                    original_length,
+                    steps,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the harness and panics with a report of the failure if an invariant is violated.
+    pub fn check(&self) {
+        if let Err(failure) = self.run() {
+            panic!("{failure}")
+        }
+    }
+
+    /// Replays a sequence of steps in a fresh environment, checking the invariants after each
+    /// step. This is used by the regression tests emitted by [`FuzzFailure::to_regression_test`].
+    pub fn replay(&self, steps: &[FuzzStep]) -> Result<(), FuzzFailure> {
+        match self.run_sequence(steps) {
+            SequenceOutcome::Passed | SequenceOutcome::Rejected => Ok(()),
+            SequenceOutcome::Failed { invariant, message } => Err(FuzzFailure {
+                seed: self.config.seed,
+                invariant,
+                message,
+                original_length: steps.len(),
+                steps: steps.to_vec(),
+            }),
+        }
+    }
+
+    fn run_random_sequence(&self, seed: u64) -> Option<(Vec<FuzzStep>, String, String)> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let (mut env, target) = self.set_up();
+        let mut methods = self.fuzzable_methods(&mut env, &target);
+        let mut generation_errors = Vec::new();
+
+        let mut steps = Vec::new();
+        while steps.len() < self.config.max_steps {
+            if methods.is_empty() {
+                panic!(
+                    "Arguments can't be generated for any of the methods of {:?}: {:?}",
+                    target.component_address, generation_errors
+                );
+            }
+
+            // Methods whose arguments can't be generated are dropped from the set of methods to
+            // invoke for the rest of the sequence.
+            let method_index = rng.gen_range(0..methods.len());
+            let step = match self.generate_step(&mut rng, &target, &methods[method_index]) {
+                Ok(step) => step,
+                Err(error) => {
+                    let method = methods.remove(method_index);
+                    generation_errors.push((method.name, error));
+                    continue;
+                }
+            };
+
+            steps.push(step);
+            match self.run_step(&mut env, &target, steps.last().unwrap()) {
+                SequenceOutcome::Passed => {}
+                SequenceOutcome::Rejected => return None,
+                SequenceOutcome::Failed { invariant, message } => {
+                    return Some((steps, invariant, message))
+                }
+            }
+        }
+
+        None
+    }
+
+    fn run_sequence(&self, steps: &[FuzzStep]) -> SequenceOutcome {
+        let (mut env, target) = self.set_up();
+        for step in steps {
+            match self.run_step(&mut env, &target, step) {
+                SequenceOutcome::Passed => {}
+                outcome => return outcome,
+            }
+        }
+        SequenceOutcome::Passed
+    }
+
+    fn run_step(
+        &self,
+        env: &mut TestEnvironment<D>,
+        target: &FuzzTarget<C>,
+        step: &FuzzStep,
+    ) -> SequenceOutcome {
+        if let Err(error) = self.invoke(env, target, step) {
+            return if self.config.fail_on_runtime_error {
+                SequenceOutcome::Failed {
+                    invariant: NO_RUNTIME_ERROR_INVARIANT.to_owned(),
+                    message: format!("{} returned {:?}", step.method_name, error),
+                }
+            } else {
+                SequenceOutcome::Rejected
+            };
+        }
+
+        for (invariant, check) in self.invariants.iter() {
+            if let Err(message) = check(env, &target.context) {
+                return SequenceOutcome::Failed {
+                    invariant: invariant.clone(),
+                    message,
+                };
+            }
+        }
+
+        SequenceOutcome::Passed
+    }
+
+    fn invoke(
+        &self,
+        env: &mut TestEnvironment<D>,
+        target: &FuzzTarget<C>,
+        step: &FuzzStep,
+    ) -> Result<(), RuntimeError> {
+        let mut owned_nodes = Vec::new();
+        for owned_value in step.owned_values.iter() {
+            let node_id = match owned_value {
+                FuzzOwnedValue::Bucket {
+                    resource_address,
+                    amount,
+                } => {
+                    BucketFactory::create_bucket(
+                        FactoryResourceSpecifier::Amount(*resource_address, *amount),
+                        self.config.creation_strategy,
+                        env,
+                    )?
+                    .0
+                     .0
+                }
+                FuzzOwnedValue::Proof {
+                    resource_address,
+                    amount,
+                } => {
+                    ProofFactory::create_proof(
+                        FactoryResourceSpecifier::Amount(*resource_address, *amount),
+                        self.config.creation_strategy,
+                        env,
+                    )?
+                    .0
+                     .0
+                }
+            };
+            owned_nodes.push(node_id);
+        }
+
+        let mut args = step.args.clone();
+        replace_placeholders(&mut args, &owned_nodes);
+        env.call_method(
+            target.component_address.as_node_id(),
+            &step.method_name,
+            scrypto_encode(&args).expect("Arguments can't be encoded"),
+        )?;
+        Ok(())
+    }
+
+    fn generate_step(
+        &self,
+        rng: &mut ChaCha8Rng,
+        target: &FuzzTarget<C>,
+        method: &FuzzMethod,
+    ) -> Result<FuzzStep, FuzzError> {
+        let (args, owned_values) =
+            ScryptoValueGenerator::new(rng, &target.resources, &target.references)
+                .with_max_collection_length(self.config.max_collection_length)
+                .generate(method.schema.v1(), method.input_type_id)?;
+        Ok(FuzzStep {
+            method_name: method.name.clone(),
+            args,
+            owned_values,
+        })
+    }
+
+    /// Shrinks a failing sequence by first removing chunks of steps and then simplifying the
+    /// arguments of the remaining steps, keeping each change that still violates the invariant.
+    fn shrink(
+        &self,
+        mut steps: Vec<FuzzStep>,
+        invariant: &str,
+        mut message: String,
+    ) -> (Vec<FuzzStep>, String) {
+        let mut replays = 0;
+        let mut still_fails = |candidate: &[FuzzStep]| -> Option<String> {
+            if replays >= self.config.max_shrink_replays {
+                return None;
+            }
+            replays += 1;
+            match self.run_sequence(candidate) {
+                SequenceOutcome::Failed {
+                    invariant: candidate_invariant,
+                    message,
+                } if candidate_invariant == invariant => Some(message),
+                _ => None,
+            }
+        };
+
+        'shrink: loop {
+            let mut chunk_size = (steps.len() / 2).max(1);
+            loop {
+                let mut start = 0;
+                while start < steps.len() {
+                    let mut candidate = steps.clone();
+                    candidate.drain(start..(start + chunk_size).min(steps.len()));
+                    if let Some(candidate_message) = still_fails(&candidate) {
+                        steps = candidate;
+                        message = candidate_message;
+                        continue 'shrink;
+                    }
+                    start += chunk_size;
+                }
+                if chunk_size == 1 {
+                    break;
+                }
+                chunk_size /= 2;
+            }
+
+            for index in 0..steps.len() {
+                for candidate_step in shrink_step(&steps[index]) {
+                    let mut candidate = steps.clone();
+                    candidate[index] = candidate_step;
+                    if let Some(candidate_message) = still_fails(&candidate) {
+                        steps = candidate;
+                        message = candidate_message;
+                        continue 'shrink;
+                    }
+                }
+            }
+
+            break;
+        }
+
+        (steps, message)
+    }
+
+    fn set_up(&self) -> (TestEnvironment<D>, FuzzTarget<C>) {
+        (self.setup)().expect("Setup of the fuzz harness failed")
+    }
+
+    fn fuzzable_methods(
+        &self,
+        env: &mut TestEnvironment<D>,
+        target: &FuzzTarget<C>,
+    ) -> Vec<FuzzMethod> {
+        let node_id = *target.component_address.as_node_id();
+        let (package_address, definition) = env
+            .with_kernel_mut(|kernel| {
+                let mut system_service = SystemService::new(kernel);
+                let blueprint_id = system_service
+                    .get_object_info(&node_id)?
+                    .blueprint_info
+                    .blueprint_id;
+                let package_address = blueprint_id.package_address;
+                system_service
+                    .get_blueprint_default_definition(blueprint_id)
+                    .map(|definition| (package_address, definition))
+            })
+            .expect("Blueprint definition of the target can't be read");
+
+        definition
+            .interface
+            .functions
+            .iter()
+            .filter(|(name, function)| {
+                function
+                    .receiver
+                    .as_ref()
+                    .is_some_and(|receiver| receiver.ref_types == RefTypes::NORMAL)
+                    && self
+                        .config
+                        .methods
+                        .as_ref()
+                        .is_none_or(|methods| methods.contains(*name))
+                    && !self.config.excluded_methods.contains(*name)
+            })
+            .filter_map(|(name, function)| {
+                let BlueprintPayloadDef::Static(ScopedTypeId(schema_hash, input_type_id)) =
+                    function.input
+                else {
+                    return None;
+                };
+                Some(FuzzMethod {
+                    name: name.clone(),
+                    schema: read_schema(env, package_address, schema_hash),
+                    input_type_id,
+                })
+            })
+            .collect()
+    }
+}
+
+impl FuzzFailure {
+    /// Emits the source code of a test that replays the minimal failing sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `test_name`: [`&str`] - The name of the test function.
+    /// * `harness_expression`: [`&str`] - A Rust expression that constructs the same
+    ///   [`FuzzHarness`] that found the failure, for example, a call to a function that the
+    ///   property test and the regression test share.
+    pub fn to_regression_test(&self, test_name: &str, harness_expression: &str) -> String {
+        let encoder = AddressBech32Encoder::for_simulator();
+        let mut source = String::new();
+        source.push_str("#[test]\n");
+        source.push_str(&format!("fn {test_name}() {{\n"));
+        source.push_str(&format!(
+            "    // Violates the {:?} invariant: {}\n",
+            self.invariant,
+            self.message.replace('\n', " ")
+        ));
+        source.push_str("    let steps = vec![\n");
+        for step in self.steps.iter() {
+            let args = IndexedScryptoValue::from_typed(&step.args);
+            source.push_str(&format!(
+                "        // {}{}\n",
+                step.method_name,
+                args.to_string(ValueDisplayParameters::Schemaless {
+                    display_mode: DisplayMode::RustLike(RustLikeOptions::full()),
+                    print_mode: PrintMode::SingleLine,
+                    custom_context: ScryptoValueDisplayContext::with_optional_bech32(Some(
+                        &encoder
+                    )),
+                    depth_limit: SCRYPTO_SBOR_V1_MAX_DEPTH,
+                })
+            ));
+            source.push_str(&format!(
+                "        FuzzStep::from_hex_args({:?}, {:?})",
+                step.method_name,
+                hex::encode(args.as_slice())
+            ));
+            for owned_value in step.owned_values.iter() {
+                let (method, resource_address, amount) = match owned_value {
+                    FuzzOwnedValue::Bucket {
+                        resource_address,
+                        amount,
+                    } => ("with_bucket", resource_address, amount),
+                    FuzzOwnedValue::Proof {
+                        resource_address,
+                        amount,
+                    } => ("with_proof", resource_address, amount),
+                };
+                source.push_str(&format!(
+                    "\n            .{method}(ResourceAddress::try_from_hex({:?}).unwrap(), dec!({:?}))",
+                    resource_address.to_hex(),
+                    amount.to_string()
+                ));
+            }
+            source.push_str(",\n");
+        }
+        source.push_str("    ];\n");
+        source.push_str(&format!(
+            "    {harness_expression}.replay(&steps).unwrap();\n"
+        ));
+        source.push_str("}\n");
+        source
+    }
+}
+
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Invariant {:?} was violated with seed {}: {}",
+            self.invariant, self.seed, self.message
+        )?;
+        writeln!(
+            f,
+            "The failing sequence was shrunk from {} to {} step(s). A regression test that \
+            replays it:\n",
+            self.original_length,
+            self.steps.len()
+        )?;
+        write!(
+            f,
+            "{}",
+            self.to_regression_test("fuzz_regression", "harness()")
+        )
+    }
+}
+
+/// Returns the candidate simplifications of a step: simpler arguments or smaller amounts of the
+/// owned values passed to it.
+fn shrink_step(step: &FuzzStep) -> Vec<FuzzStep> {
+    let mut candidates = shrink_value(&step.args)
+        .into_iter()
+        .map(|args| FuzzStep {
+            method_name: step.method_name.clone(),
+            args,
+            owned_values: step.owned_values.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    for (index, owned_value) in step.owned_values.iter().enumerate() {
+        let (FuzzOwnedValue::Bucket { amount, .. } | FuzzOwnedValue::Proof { amount, .. }) =
+            owned_value;
+        for candidate_amount in shrink_decimal(*amount) {
+            let mut candidate = step.clone();
+            match &mut candidate.owned_values[index] {
+                FuzzOwnedValue::Bucket { amount, .. } | FuzzOwnedValue::Proof { amount, .. } => {
+                    *amount = candidate_amount
+                }
+            }
+            candidates.push(candidate);
+        }
+    }
+
+    candidates
+}
+
+/// Replaces the placeholder [`Own`]s in a value with the nodes created for them.
+fn replace_placeholders(value: &mut ScryptoValue, owned_nodes: &[NodeId]) {
+    match value {
+        ScryptoValue::Custom {
+            value: ScryptoCustomValue::Own(own),
+        } => {
+            if let Some(index) = placeholder_index(&own.0) {
+                own.0 = *owned_nodes
+                    .get(index)
+                    .expect("Placeholder refers to an owned value that doesn't exist");
+            }
+        }
+        ScryptoValue::Enum { fields, .. } | ScryptoValue::Tuple { fields } => fields
+            .iter_mut()
+            .for_each(|field| replace_placeholders(field, owned_nodes)),
+        ScryptoValue::Array { elements, .. } => elements
+            .iter_mut()
+            .for_each(|element| replace_placeholders(element, owned_nodes)),
+        ScryptoValue::Map { entries, .. } => entries.iter_mut().for_each(|(key, value)| {
+            replace_placeholders(key, owned_nodes);
+            replace_placeholders(value, owned_nodes);
+        }),
+        _ => {}
+    }
+}
+
+fn read_schema<D>(
+    env: &mut TestEnvironment<D>,
+    package_address: PackageAddress,
+    schema_hash: SchemaHash,
+) -> Rc<VersionedScryptoSchema>
+where
+    D: SubstateDatabase + CommittableSubstateDatabase + 'static,
+{
+    env.with_kernel_mut(|kernel| {
+        let handle = kernel.kernel_open_substate(
+            package_address.as_node_id(),
+            SCHEMAS_PARTITION,
+            &SubstateKey::Map(scrypto_encode(&schema_hash).unwrap()),
+            LockFlags::read_only(),
+            SystemLockData::default(),
+        )?;
+        let substate: KeyValueEntrySubstate<VersionedScryptoSchema> =
+            kernel.kernel_read_substate(handle)?.as_typed().unwrap();
+        kernel.kernel_close_substate(handle)?;
+        Ok::<_, RuntimeError>(Rc::new(
+            substate
+                .into_value()
+                .expect("Schema of the blueprint doesn't exist"),
+        ))
+    })
+    .expect("Schema of the blueprint can't be read")
+}
@@ -0,0 +1,53 @@
+//! This module implements a property-based testing harness over the [`TestEnvironment`].
+//!
+//! The [`FuzzHarness`] sets up a fresh [`TestEnvironment`] and a component to test through a
+//! user-supplied setup function, and then invokes random sequences of methods on the component.
+//! The arguments of each invocation are generated from the schema of the blueprint by the
+//! [`ScryptoValueGenerator`]. After each successful invocation all of the invariants registered on
+//! the harness are checked. When an invariant is violated, the sequence of invocations is shrunk to
+//! a minimal sequence that still violates the same invariant, which is returned as a
+//! [`FuzzFailure`] that can be replayed or emitted as the source code of a regression test.
+//!
+//! ```no_run
+//! use scrypto_test::prelude::*;
+//!
+//! let harness = FuzzHarness::new(|| {
+//!     let mut env = TestEnvironment::new();
+//!     env.disable_auth_module();
+//!     let account = env.call_function_typed::<_, ComponentAddress>(
+//!         ACCOUNT_PACKAGE,
+//!         ACCOUNT_BLUEPRINT,
+//!         ACCOUNT_CREATE_ADVANCED_IDENT,
+//!         &AccountCreateAdvancedInput {
+//!             owner_role: OwnerRole::None,
+//!             address_reservation: None,
+//!         },
+//!     )?;
+//!     Ok((env, FuzzTarget::new(account, account).with_resource(XRD)))
+//! })
+//! .with_config(FuzzConfig::default().with_methods(["deposit", "withdraw"]))
+//! .with_invariant("balance is bounded", |env, account| {
+//!     let balance = env
+//!         .call_method_typed::<_, _, Decimal>(*account, "balance", &(XRD,))
+//!         .map_err(|error| format!("{error:?}"))?;
+//!     if balance < dec!(1000000) {
+//!         Ok(())
+//!     } else {
+//!         Err(format!("balance is {balance}"))
+//!     }
+//! });
+//!
+//! harness.check();
+//! ```
+//!
+//! [`TestEnvironment`]: crate::prelude::TestEnvironment
+
+mod generator;
+mod harness;
+mod shrink;
+mod types;
+
+pub use generator::*;
+pub use harness::*;
+pub use shrink::*;
+pub use types::*;
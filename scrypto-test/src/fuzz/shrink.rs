@@ -0,0 +1,299 @@
+//! This module implements the shrinking of [`ScryptoValue`]s into simpler candidate values.
+
+use crate::prelude::*;
+
+/// Returns the candidate simplifications of a value, each of which is a single simplification step
+/// away from the value and ordered from the most to the least aggressive.
+///
+/// Candidates are not guaranteed to conform to the schema of the value, for example, removing an
+/// element from an array may violate its length validation. Such candidates are expected to be
+/// rejected when they're invoked and discarded by the shrinker.
+pub fn shrink_value(value: &ScryptoValue) -> Vec<ScryptoValue> {
+    match value {
+        ScryptoValue::Bool { value } => {
+            if *value {
+                vec![ScryptoValue::Bool { value: false }]
+            } else {
+                vec![]
+            }
+        }
+        ScryptoValue::I8 { value } => shrink_integer!(*value, i8, I8),
+        ScryptoValue::I16 { value } => shrink_integer!(*value, i16, I16),
+        ScryptoValue::I32 { value } => shrink_integer!(*value, i32, I32),
+        ScryptoValue::I64 { value } => shrink_integer!(*value, i64, I64),
+        ScryptoValue::I128 { value } => shrink_integer!(*value, i128, I128),
+        ScryptoValue::U8 { value } => shrink_integer!(*value, u8, U8),
+        ScryptoValue::U16 { value } => shrink_integer!(*value, u16, U16),
+        ScryptoValue::U32 { value } => shrink_integer!(*value, u32, U32),
+        ScryptoValue::U64 { value } => shrink_integer!(*value, u64, U64),
+        ScryptoValue::U128 { value } => shrink_integer!(*value, u128, U128),
+        ScryptoValue::String { value } => {
+            let length = value.chars().count();
+            let candidates: [String; 3] = [
+                String::new(),
+                value.chars().take(length / 2).collect(),
+                value.chars().skip(1).collect(),
+            ];
+            let mut shrunk = Vec::<String>::new();
+            for candidate in candidates {
+                if candidate.len() < value.len() && !shrunk.contains(&candidate) {
+                    shrunk.push(candidate)
+                }
+            }
+            shrunk
+                .into_iter()
+                .map(|value| ScryptoValue::String { value })
+                .collect()
+        }
+        ScryptoValue::Enum {
+            discriminator,
+            fields,
+        } => shrink_elements(fields)
+            .into_iter()
+            .map(|fields| ScryptoValue::Enum {
+                discriminator: *discriminator,
+                fields,
+            })
+            .collect(),
+        ScryptoValue::Tuple { fields } => shrink_elements(fields)
+            .into_iter()
+            .map(|fields| ScryptoValue::Tuple { fields })
+            .collect(),
+        ScryptoValue::Array {
+            element_value_kind,
+            elements,
+        } => {
+            let mut candidates = (0..elements.len())
+                .map(|index| {
+                    let mut elements = elements.clone();
+                    elements.remove(index);
+                    elements
+                })
+                .collect::<Vec<_>>();
+            candidates.extend(shrink_elements(elements));
+            candidates
+                .into_iter()
+                .map(|elements| ScryptoValue::Array {
+                    element_value_kind: *element_value_kind,
+                    elements,
+                })
+                .collect()
+        }
+        ScryptoValue::Map {
+            key_value_kind,
+            value_value_kind,
+            entries,
+        } => {
+            let mut candidates = (0..entries.len())
+                .map(|index| {
+                    let mut entries = entries.clone();
+                    entries.remove(index);
+                    entries
+                })
+                .collect::<Vec<_>>();
+            for (index, (key, value)) in entries.iter().enumerate() {
+                for candidate in shrink_value(value) {
+                    let mut entries = entries.clone();
+                    entries[index] = (key.clone(), candidate);
+                    candidates.push(entries);
+                }
+            }
+            candidates
+                .into_iter()
+                .map(|entries| ScryptoValue::Map {
+                    key_value_kind: *key_value_kind,
+                    value_value_kind: *value_value_kind,
+                    entries,
+                })
+                .collect()
+        }
+        ScryptoValue::Custom {
+            value: ScryptoCustomValue::Decimal(value),
+        } => shrink_decimal(*value)
+            .into_iter()
+            .map(|value| ScryptoValue::Custom {
+                value: ScryptoCustomValue::Decimal(value),
+            })
+            .collect(),
+        ScryptoValue::Custom {
+            value: ScryptoCustomValue::PreciseDecimal(value),
+        } => shrink_decimal_like!(*value, PreciseDecimal)
+            .into_iter()
+            .map(|value| ScryptoValue::Custom {
+                value: ScryptoCustomValue::PreciseDecimal(value),
+            })
+            .collect(),
+        ScryptoValue::Custom {
+            value: ScryptoCustomValue::NonFungibleLocalId(NonFungibleLocalId::Integer(id)),
+        } => {
+            let id = id.value();
+            [0, id / 2]
+                .into_iter()
+                .filter(|candidate| *candidate < id)
+                .map(|candidate| ScryptoValue::Custom {
+                    value: ScryptoCustomValue::NonFungibleLocalId(NonFungibleLocalId::integer(
+                        candidate,
+                    )),
+                })
+                .collect()
+        }
+        ScryptoValue::Custom { .. } => vec![],
+    }
+}
+
+/// Returns the candidate simplifications of a decimal, ordered from the most to the least
+/// aggressive. Only candidates closer to zero than the decimal itself are returned.
+pub fn shrink_decimal(value: Decimal) -> Vec<Decimal> {
+    shrink_decimal_like!(value, Decimal)
+}
+
+/// Returns the candidates obtained by shrinking a single element of a list of elements.
+fn shrink_elements(elements: &[ScryptoValue]) -> Vec<Vec<ScryptoValue>> {
+    let mut candidates = Vec::new();
+    for (index, element) in elements.iter().enumerate() {
+        for candidate in shrink_value(element) {
+            let mut elements = elements.to_vec();
+            elements[index] = candidate;
+            candidates.push(elements);
+        }
+    }
+    candidates
+}
+
+macro_rules! shrink_decimal_like {
+    ($value: expr, $ty: ty) => {{
+        let value: $ty = $value;
+        let candidates = [
+            Some(<$ty>::ZERO),
+            Some(<$ty>::ONE),
+            value.checked_round(0, RoundingMode::ToZero),
+            value.checked_div(2),
+        ];
+        let mut shrunk = Vec::<$ty>::new();
+        for candidate in candidates.into_iter().flatten() {
+            let is_simpler = if value.is_negative() {
+                candidate > value
+            } else {
+                candidate < value
+            };
+            if is_simpler && !shrunk.contains(&candidate) {
+                shrunk.push(candidate)
+            }
+        }
+        shrunk
+    }};
+}
+use shrink_decimal_like;
+
+macro_rules! shrink_integer {
+    ($value: expr, $ty: ty, $variant: ident) => {{
+        let value: $ty = $value;
+        let mut candidates = Vec::<$ty>::new();
+        for candidate in [0 as $ty, value / 2, value - value.signum_or_zero()] {
+            let is_simpler = candidate.abs_diff(0) < value.abs_diff(0);
+            if is_simpler && !candidates.contains(&candidate) {
+                candidates.push(candidate)
+            }
+        }
+        candidates
+            .into_iter()
+            .map(|value| ScryptoValue::$variant { value })
+            .collect()
+    }};
+}
+use shrink_integer;
+
+/// The signum of an integer that works for both signed and unsigned integers.
+trait SignumOrZero {
+    fn signum_or_zero(self) -> Self;
+}
+
+macro_rules! impl_signum_or_zero {
+    (signed: $($ty: ty),*) => {
+        $(
+            impl SignumOrZero for $ty {
+                fn signum_or_zero(self) -> Self {
+                    self.signum()
+                }
+            }
+        )*
+    };
+    (unsigned: $($ty: ty),*) => {
+        $(
+            impl SignumOrZero for $ty {
+                fn signum_or_zero(self) -> Self {
+                    if self == 0 { 0 } else { 1 }
+                }
+            }
+        )*
+    };
+}
+impl_signum_or_zero!(signed: i8, i16, i32, i64, i128);
+impl_signum_or_zero!(unsigned: u8, u16, u32, u64, u128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_shrink_towards_zero() {
+        assert_eq!(
+            shrink_value(&ScryptoValue::I32 { value: -10 }),
+            vec![
+                ScryptoValue::I32 { value: 0 },
+                ScryptoValue::I32 { value: -5 },
+                ScryptoValue::I32 { value: -9 },
+            ]
+        );
+        assert_eq!(
+            shrink_value(&ScryptoValue::U8 { value: 1 }),
+            vec![ScryptoValue::U8 { value: 0 }]
+        );
+        assert!(shrink_value(&ScryptoValue::U64 { value: 0 }).is_empty());
+    }
+
+    #[test]
+    fn decimals_shrink_towards_zero() {
+        assert_eq!(
+            shrink_decimal(dec!("10.5")),
+            vec![dec!(0), dec!(1), dec!(10), dec!("5.25")]
+        );
+        assert_eq!(shrink_decimal(dec!("0.5")), vec![dec!(0), dec!("0.25")]);
+        assert!(shrink_decimal(dec!(0)).is_empty());
+    }
+
+    #[test]
+    fn arrays_shrink_by_removing_and_shrinking_elements() {
+        // Arrange
+        let value = ScryptoValue::Array {
+            element_value_kind: ValueKind::Bool,
+            elements: vec![
+                ScryptoValue::Bool { value: true },
+                ScryptoValue::Bool { value: false },
+            ],
+        };
+
+        // Act
+        let candidates = shrink_value(&value);
+
+        // Assert
+        let elements = candidates
+            .into_iter()
+            .map(|candidate| match candidate {
+                ScryptoValue::Array { elements, .. } => elements,
+                _ => panic!("Expected an array"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            elements,
+            vec![
+                vec![ScryptoValue::Bool { value: false }],
+                vec![ScryptoValue::Bool { value: true }],
+                vec![
+                    ScryptoValue::Bool { value: false },
+                    ScryptoValue::Bool { value: false }
+                ],
+            ]
+        );
+    }
+}
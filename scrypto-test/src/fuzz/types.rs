@@ -0,0 +1,227 @@
+//! This module defines the types used by the fuzz harness.
+
+use crate::prelude::*;
+
+/// The configuration of a [`FuzzHarness`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzConfig {
+    /// The seed of the first run, each subsequent run uses the next seed.
+    pub seed: u64,
+    /// The number of sequences to generate and run.
+    pub runs: u64,
+    /// The maximum number of method invocations in a single sequence.
+    pub max_steps: usize,
+    /// The maximum number of elements in generated arrays, maps and strings.
+    pub max_collection_length: usize,
+    /// The maximum number of sequences to replay while shrinking a failing sequence.
+    pub max_shrink_replays: usize,
+    /// The methods to invoke, all methods of the blueprint are invoked if this is [`None`].
+    pub methods: Option<IndexSet<String>>,
+    /// The methods to never invoke.
+    pub excluded_methods: IndexSet<String>,
+    /// Controls whether an invocation that returns an error is treated as a failure. When `false`,
+    /// such an invocation ends the sequence since the state of the environment can no longer be
+    /// relied upon.
+    pub fail_on_runtime_error: bool,
+    /// The strategy used to create the buckets and proofs passed to methods.
+    pub creation_strategy: CreationStrategy,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            runs: 32,
+            max_steps: 16,
+            max_collection_length: 4,
+            max_shrink_replays: 256,
+            methods: None,
+            excluded_methods: Default::default(),
+            fail_on_runtime_error: false,
+            creation_strategy: CreationStrategy::DisableAuthAndMint,
+        }
+    }
+}
+
+impl FuzzConfig {
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_runs(mut self, runs: u64) -> Self {
+        self.runs = runs;
+        self
+    }
+
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn with_max_collection_length(mut self, max_collection_length: usize) -> Self {
+        self.max_collection_length = max_collection_length;
+        self
+    }
+
+    pub fn with_max_shrink_replays(mut self, max_shrink_replays: usize) -> Self {
+        self.max_shrink_replays = max_shrink_replays;
+        self
+    }
+
+    pub fn with_methods<I, S>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.methods = Some(methods.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn with_excluded_methods<I, S>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.excluded_methods = methods.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_fail_on_runtime_error(mut self, fail_on_runtime_error: bool) -> Self {
+        self.fail_on_runtime_error = fail_on_runtime_error;
+        self
+    }
+
+    pub fn with_creation_strategy(mut self, creation_strategy: CreationStrategy) -> Self {
+        self.creation_strategy = creation_strategy;
+        self
+    }
+}
+
+/// The component that a [`FuzzHarness`] invokes methods on along with the addresses that can be
+/// used as arguments and a user-defined context that's passed to the invariants.
+#[derive(Clone, Debug)]
+pub struct FuzzTarget<C> {
+    pub component_address: ComponentAddress,
+    pub resources: Vec<ResourceAddress>,
+    pub references: Vec<GlobalAddress>,
+    pub context: C,
+}
+
+impl<C> FuzzTarget<C> {
+    pub fn new(component_address: ComponentAddress, context: C) -> Self {
+        Self {
+            component_address,
+            resources: Default::default(),
+            references: vec![component_address.into()],
+            context,
+        }
+    }
+
+    /// Adds a resource that buckets and proofs passed to methods can be created from. The resource
+    /// can also be passed to methods by reference.
+    pub fn with_resource(mut self, resource_address: ResourceAddress) -> Self {
+        self.resources.push(resource_address);
+        self.references.push(resource_address.into());
+        self
+    }
+
+    /// Adds a global address that can be passed to methods by reference.
+    pub fn with_reference<A: Into<GlobalAddress>>(mut self, address: A) -> Self {
+        self.references.push(address.into());
+        self
+    }
+}
+
+/// A value owned by the caller that must be created before a method is invoked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FuzzOwnedValue {
+    Bucket {
+        resource_address: ResourceAddress,
+        amount: Decimal,
+    },
+    Proof {
+        resource_address: ResourceAddress,
+        amount: Decimal,
+    },
+}
+
+/// A single method invocation in a sequence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzStep {
+    pub method_name: String,
+    /// The arguments of the method where owned values are placeholders for the values in
+    /// [`FuzzStep::owned_values`].
+    pub args: ScryptoValue,
+    pub owned_values: Vec<FuzzOwnedValue>,
+}
+
+impl FuzzStep {
+    pub fn new<S: Into<String>>(method_name: S, args: ScryptoValue) -> Self {
+        Self {
+            method_name: method_name.into(),
+            args,
+            owned_values: Default::default(),
+        }
+    }
+
+    /// Creates a step from hex-encoded arguments as emitted in regression tests.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the arguments are not valid hex or not a valid Scrypto payload.
+    pub fn from_hex_args<S: Into<String>>(method_name: S, args: &str) -> Self {
+        let args = hex::decode(args).expect("Arguments are not valid hex");
+        Self::new(
+            method_name,
+            scrypto_decode(&args).expect("Arguments are not a valid Scrypto payload"),
+        )
+    }
+
+    pub fn with_bucket(mut self, resource_address: ResourceAddress, amount: Decimal) -> Self {
+        self.owned_values.push(FuzzOwnedValue::Bucket {
+            resource_address,
+            amount,
+        });
+        self
+    }
+
+    pub fn with_proof(mut self, resource_address: ResourceAddress, amount: Decimal) -> Self {
+        self.owned_values.push(FuzzOwnedValue::Proof {
+            resource_address,
+            amount,
+        });
+        self
+    }
+}
+
+/// A sequence of method invocations that violates an invariant.
+#[derive(Clone, Debug)]
+pub struct FuzzFailure {
+    /// The seed of the run in which the failure was found.
+    pub seed: u64,
+    /// The name of the invariant that was violated.
+    pub invariant: String,
+    /// The message returned by the invariant for the minimal sequence.
+    pub message: String,
+    /// The number of steps in the sequence before it was shrunk.
+    pub original_length: usize,
+    /// The minimal sequence of steps that violates the invariant.
+    pub steps: Vec<FuzzStep>,
+}
+
+/// The name of the pseudo-invariant that's violated when an invocation returns an error and
+/// [`FuzzConfig::fail_on_runtime_error`] is set.
+pub const NO_RUNTIME_ERROR_INVARIANT: &str = "no runtime error";
+
+/// An error encountered while generating the arguments of a method.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FuzzError {
+    TypeNotFoundInSchema(LocalTypeId),
+    UnsupportedType(&'static str),
+    UnsupportedOwnedValue(OwnValidation),
+    NoMatchingReference(ReferenceValidation),
+    EnumWithoutVariants(LocalTypeId),
+    UnsatisfiableValidation,
+    GenericPayloadsAreNotSupported,
+}
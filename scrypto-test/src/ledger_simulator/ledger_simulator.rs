@@ -12,7 +12,7 @@ use radix_engine::system::system_substates::FieldSubstate;
 use radix_engine::system::type_info::TypeInfoSubstate;
 use radix_engine::transaction::*;
 use radix_engine::updates::*;
-use radix_engine::vm::wasm::DefaultWasmEngine;
+use radix_engine::vm::wasm::{DefaultWasmEngine, InstrumentedCodeCache};
 use radix_engine::vm::{NativeVmExtension, NoExtension, ScryptoVm};
 use radix_engine_interface::api::ModuleId;
 use radix_engine_interface::blueprints::account::ACCOUNT_SECURIFY_IDENT;
@@ -21,7 +21,7 @@ use radix_engine_interface::blueprints::pool::{
     OneResourcePoolInstantiateManifestInput, ONE_RESOURCE_POOL_INSTANTIATE_IDENT,
 };
 use radix_engine_interface::prelude::{dec, freeze_roles, rule};
-use radix_substate_store_impls::memory_db::InMemorySubstateDatabase;
+use radix_substate_store_impls::memory_db::{InMemoryEventIndexStore, InMemorySubstateDatabase};
 use radix_substate_store_impls::state_tree_support::StateTreeUpdatingDatabase;
 use radix_substate_store_interface::interface::*;
 use radix_substate_store_queries::query::{ResourceAccounter, StateTreeTraverser, VaultFinder};
@@ -30,6 +30,7 @@ use radix_substate_store_queries::typed_substate_layout::*;
 use radix_transactions::manifest::*;
 use radix_transactions::validation::*;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use super::Compile;
 
@@ -53,6 +54,8 @@ pub struct LedgerSimulatorBuilder<E, D> {
     with_kernel_trace: Option<bool>,
     with_cost_breakdown: Option<bool>,
     with_receipt_substate_check: bool,
+    instrumented_code_cache: Option<Arc<dyn InstrumentedCodeCache>>,
+    with_event_index: bool,
 }
 
 impl Default for LedgerSimulatorBuilder<NoExtension, InMemorySubstateDatabase> {
@@ -71,6 +74,8 @@ impl LedgerSimulatorBuilder<NoExtension, InMemorySubstateDatabase> {
             with_kernel_trace: None,
             with_cost_breakdown: None,
             with_receipt_substate_check: true,
+            instrumented_code_cache: None,
+            with_event_index: false,
         }
     }
 }
@@ -88,6 +93,8 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulatorBuilder<E, D> {
             with_kernel_trace: self.with_kernel_trace,
             with_cost_breakdown: self.with_cost_breakdown,
             with_receipt_substate_check: self.with_receipt_substate_check,
+            instrumented_code_cache: self.instrumented_code_cache,
+            with_event_index: self.with_event_index,
         }
     }
 
@@ -125,6 +132,34 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulatorBuilder<E, D> {
         self
     }
 
+    /// Uses the given cache for the code instrumented when packages are published (e.g. a
+    /// [`PersistentInstrumentedCodeCache`], to avoid re-instrumenting the same packages across test
+    /// runs).
+    ///
+    /// [`PersistentInstrumentedCodeCache`]: radix_engine::vm::wasm::PersistentInstrumentedCodeCache
+    pub fn with_instrumented_code_cache(
+        mut self,
+        instrumented_code_cache: impl InstrumentedCodeCache + 'static,
+    ) -> Self {
+        self.instrumented_code_cache = Some(Arc::new(instrumented_code_cache));
+        self
+    }
+
+    /// Indexes the application events of all committed transactions, so that they can be queried
+    /// with [`LedgerSimulator::query_events`]. Each transaction is indexed under its version, i.e.
+    /// its (one-based) position in [`LedgerSimulator::collected_events`].
+    pub fn with_event_index(mut self) -> Self {
+        self.with_event_index = true;
+        self
+    }
+
+    fn create_scrypto_vm(&self) -> ScryptoVm<DefaultWasmEngine> {
+        ScryptoVm {
+            instrumented_code_cache: self.instrumented_code_cache.clone(),
+            ..ScryptoVm::default()
+        }
+    }
+
     pub fn with_custom_extension<NE: NativeVmExtension>(
         self,
         extension: NE,
@@ -136,6 +171,8 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulatorBuilder<E, D> {
             with_kernel_trace: self.with_kernel_trace,
             with_cost_breakdown: self.with_cost_breakdown,
             with_receipt_substate_check: self.with_receipt_substate_check,
+            instrumented_code_cache: self.instrumented_code_cache,
+            with_event_index: self.with_event_index,
         }
     }
 
@@ -150,6 +187,8 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulatorBuilder<E, D> {
             with_kernel_trace: self.with_kernel_trace,
             with_cost_breakdown: self.with_cost_breakdown,
             with_receipt_substate_check: self.with_receipt_substate_check,
+            instrumented_code_cache: self.instrumented_code_cache,
+            with_event_index: self.with_event_index,
         }
     }
 
@@ -189,7 +228,7 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulatorBuilder<E, D> {
     ) -> LedgerSimulator<E, InMemorySubstateDatabase> {
         LedgerSimulator {
             vm_modules: VmModules {
-                scrypto_vm: ScryptoVm::default(),
+                scrypto_vm: self.create_scrypto_vm(),
                 vm_extension: self.custom_extension,
             },
             transaction_validator: snapshot.transaction_validator,
@@ -197,6 +236,7 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulatorBuilder<E, D> {
             next_private_key: snapshot.next_private_key,
             next_transaction_nonce: snapshot.next_transaction_nonce,
             collected_events: snapshot.collected_events,
+            event_index: snapshot.event_index,
             xrd_free_credits_used: snapshot.xrd_free_credits_used,
             with_kernel_trace: snapshot.with_kernel_trace,
             with_cost_breakdown: snapshot.with_cost_breakdown,
@@ -208,6 +248,8 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulatorBuilder<E, D> {
     pub fn build_and_get_post_genesis_epoch_change(
         self,
     ) -> (LedgerSimulator<E, D>, Option<EpochChangeEvent>) {
+        let scrypto_vm = self.create_scrypto_vm();
+
         //---------- Override configs for resource tracker ---------------
         let bootstrap_trace = false;
 
@@ -256,7 +298,7 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulatorBuilder<E, D> {
             events: vec![],
             genesis_next_epoch: None,
         };
-        let vm_modules = VmModules::default_with_extension(self.custom_extension);
+        let vm_modules = VmModules::new(scrypto_vm, self.custom_extension);
 
         // Protocol Updates
         self.protocol_executor.commit_each_protocol_update_advanced(
@@ -273,6 +315,17 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulatorBuilder<E, D> {
 
         let validator = TransactionValidator::new(&substate_db, &Self::network_definition());
 
+        let event_index = self.with_event_index.then(|| {
+            let mut event_index = InMemoryEventIndexStore::standard();
+            for (index, events) in hooks.events.iter().enumerate() {
+                event_index.index_events_at_version(
+                    index as u64 + 1,
+                    resolve_events(&substate_db, events),
+                );
+            }
+            event_index
+        });
+
         let runner = LedgerSimulator {
             vm_modules,
             database: substate_db,
@@ -280,6 +333,7 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulatorBuilder<E, D> {
             next_private_key,
             next_transaction_nonce,
             collected_events: hooks.events,
+            event_index,
             xrd_free_credits_used: false,
             with_kernel_trace,
             with_cost_breakdown,
@@ -304,6 +358,8 @@ pub struct LedgerSimulator<E: NativeVmExtension, D: TestDatabase> {
 
     /// Events collected from all the committed transactions
     collected_events: Vec<Vec<(EventTypeIdentifier, Vec<u8>)>>,
+    /// The index of the collected events, if enabled
+    event_index: Option<InMemoryEventIndexStore>,
     /// Track whether any of the committed transaction has used free credit
     xrd_free_credits_used: bool,
 
@@ -329,6 +385,7 @@ pub struct LedgerSimulatorSnapshot {
     next_private_key: u64,
     next_transaction_nonce: u32,
     collected_events: Vec<Vec<(EventTypeIdentifier, Vec<u8>)>>,
+    event_index: Option<InMemoryEventIndexStore>,
     xrd_free_credits_used: bool,
     with_kernel_trace: Option<bool>,
     with_cost_breakdown: Option<bool>,
@@ -343,6 +400,7 @@ impl<E: NativeVmExtension> LedgerSimulator<E, InMemorySubstateDatabase> {
             next_private_key: self.next_private_key,
             next_transaction_nonce: self.next_transaction_nonce,
             collected_events: self.collected_events.clone(),
+            event_index: self.event_index.clone(),
             xrd_free_credits_used: self.xrd_free_credits_used,
             with_kernel_trace: self.with_kernel_trace,
             with_cost_breakdown: self.with_cost_breakdown,
@@ -357,6 +415,7 @@ impl<E: NativeVmExtension> LedgerSimulator<E, InMemorySubstateDatabase> {
             next_private_key,
             next_transaction_nonce,
             collected_events,
+            event_index,
             xrd_free_credits_used,
             with_kernel_trace,
             with_cost_breakdown,
@@ -367,6 +426,7 @@ impl<E: NativeVmExtension> LedgerSimulator<E, InMemorySubstateDatabase> {
         self.next_private_key = next_private_key;
         self.next_transaction_nonce = next_transaction_nonce;
         self.collected_events = collected_events;
+        self.event_index = event_index;
         self.xrd_free_credits_used = xrd_free_credits_used;
         self.with_kernel_trace = with_kernel_trace;
         self.with_cost_breakdown = with_cost_breakdown;
@@ -403,6 +463,21 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulator<E, D> {
         self.collected_events.as_ref()
     }
 
+    /// Returns the indexed events matching the given query.
+    ///
+    /// Each committed transaction is indexed under the next version, starting from 1 with the
+    /// protocol update transactions run when the simulator was built.
+    ///
+    /// Panics if the simulator was not built
+    /// [`with_event_index`][LedgerSimulatorBuilder::with_event_index].
+    pub fn query_events(&self, query: &EventQuery) -> Vec<IndexedEvent> {
+        self.event_index
+            .as_ref()
+            .expect("The event index is not enabled")
+            .query_events(query)
+            .collect()
+    }
+
     pub fn next_private_key(&mut self) -> u64 {
         self.next_private_key += 1;
         self.next_private_key - 1
@@ -1295,6 +1370,12 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulator<E, D> {
             self.database.commit(&database_updates);
             self.collected_events
                 .push(commit.application_events.clone());
+            if let Some(event_index) = &mut self.event_index {
+                event_index.index_events_at_version(
+                    self.collected_events.len() as u64,
+                    resolve_events(&self.database, &commit.application_events),
+                );
+            }
 
             if self.with_receipt_substate_check {
                 assert_receipt_substate_changes_can_be_typed(commit);
@@ -1448,6 +1529,12 @@ impl<E: NativeVmExtension, D: TestDatabase> LedgerSimulator<E, D> {
             self.database.commit(&database_updates);
             self.collected_events
                 .push(commit.application_events.clone());
+            if let Some(event_index) = &mut self.event_index {
+                event_index.index_events_at_version(
+                    self.collected_events.len() as u64,
+                    resolve_events(&self.database, &commit.application_events),
+                );
+            }
 
             if self.with_receipt_substate_check {
                 assert_receipt_substate_changes_can_be_typed(commit);
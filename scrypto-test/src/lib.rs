@@ -89,6 +89,7 @@
 )]
 
 pub mod environment;
+pub mod fuzz;
 pub mod ledger_simulator;
 pub mod prelude;
 pub mod sdk;
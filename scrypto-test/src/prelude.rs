@@ -46,6 +46,9 @@ pub use radix_substate_store_impls::memory_db::*;
 pub use radix_substate_store_interface::db_key_mapper::*;
 pub use radix_substate_store_interface::interface::*;
 
+/* Radix Engine Store Queries */
+pub use radix_substate_store_queries::event_index::*;
+
 /* Radix Engine Interface */
 pub extern crate radix_common;
 pub use radix_engine_interface::api::actor_api::*;
@@ -103,6 +106,7 @@ pub use scrypto::prelude::{
 
 /* This Crate */
 pub use crate::environment::*;
+pub use crate::fuzz::*;
 pub use crate::ledger_simulator::*;
 pub use crate::sdk::*;
 pub use crate::{include_code, include_schema, this_package};
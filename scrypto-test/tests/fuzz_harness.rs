@@ -0,0 +1,112 @@
+use scrypto_test::prelude::*;
+
+fn account_harness() -> FuzzHarness<ComponentAddress> {
+    FuzzHarness::new(|| {
+        let mut env = TestEnvironment::new();
+        env.disable_auth_module();
+        let account = env.call_function_typed::<_, ComponentAddress>(
+            ACCOUNT_PACKAGE,
+            ACCOUNT_BLUEPRINT,
+            ACCOUNT_CREATE_ADVANCED_IDENT,
+            &AccountCreateAdvancedInput {
+                owner_role: OwnerRole::None,
+                address_reservation: None,
+            },
+        )?;
+        Ok((env, FuzzTarget::new(account, account).with_resource(XRD)))
+    })
+    .with_config(
+        FuzzConfig::default()
+            .with_runs(4)
+            .with_max_steps(4)
+            .with_methods([ACCOUNT_DEPOSIT_IDENT, ACCOUNT_WITHDRAW_IDENT]),
+    )
+}
+
+fn balance(env: &mut DefaultTestEnvironment, account: ComponentAddress) -> Decimal {
+    env.call_method_typed::<_, _, Decimal>(
+        account,
+        ACCOUNT_BALANCE_IDENT,
+        &AccountBalanceInput {
+            resource_address: XRD,
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn harness_passes_when_invariants_hold() {
+    // Arrange
+    let harness = account_harness().with_invariant("balance is not negative", |env, account| {
+        if balance(env, *account).is_negative() {
+            Err("balance is negative".to_owned())
+        } else {
+            Ok(())
+        }
+    });
+
+    // Act
+    let result = harness.run();
+
+    // Assert
+    assert!(result.is_ok())
+}
+
+#[test]
+fn violated_invariant_is_shrunk_to_a_minimal_sequence() {
+    // Arrange
+    let harness = account_harness().with_invariant("balance is below one", |env, account| {
+        let balance = balance(env, *account);
+        if balance < dec!(1) {
+            Ok(())
+        } else {
+            Err(format!("balance is {balance}"))
+        }
+    });
+
+    // Act
+    let failure = harness.run().unwrap_err();
+
+    // Assert
+    assert_eq!(failure.invariant, "balance is below one");
+    assert_eq!(failure.message, "balance is 1");
+    assert_eq!(
+        failure.steps,
+        vec![
+            FuzzStep::new(ACCOUNT_DEPOSIT_IDENT, failure.steps[0].args.clone())
+                .with_bucket(XRD, dec!(1))
+        ]
+    );
+}
+
+#[test]
+fn regression_test_replays_the_minimal_sequence() {
+    // Arrange
+    let harness = account_harness().with_invariant("balance is below one", |env, account| {
+        if balance(env, *account) < dec!(1) {
+            Ok(())
+        } else {
+            Err("balance is not below one".to_owned())
+        }
+    });
+    let failure = harness.run().unwrap_err();
+
+    // Act
+    let source = failure.to_regression_test("account_balance_regression", "account_harness()");
+
+    // Assert
+    assert!(source.contains("fn account_balance_regression()"));
+    assert!(source.contains("FuzzStep::from_hex_args(\"deposit\""));
+    assert!(source.contains(".with_bucket("));
+    assert!(source.contains("account_harness().replay(&steps).unwrap();"));
+
+    let steps = vec![FuzzStep::from_hex_args(
+        ACCOUNT_DEPOSIT_IDENT,
+        &hex::encode(scrypto_encode(&failure.steps[0].args).unwrap()),
+    )
+    .with_bucket(XRD, dec!(1))];
+    assert_eq!(
+        harness.replay(&steps).unwrap_err().invariant,
+        "balance is below one"
+    );
+}
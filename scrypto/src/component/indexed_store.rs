@@ -0,0 +1,114 @@
+use crate::engine::scrypto_env::ScryptoVmV1Api;
+use radix_common::data::scrypto::*;
+use radix_common::ScryptoSbor;
+use radix_engine_interface::api::{CollectionIndex, ACTOR_STATE_SELF};
+use sbor::rust::marker::PhantomData;
+use sbor::rust::prelude::*;
+
+/// An iterable key-value collection, stored in an index partition of the current component.
+///
+/// Unlike a [`KeyValueStore`](super::KeyValueStore), an index is not a separate object: it is part
+/// of the component's own state, declared on the blueprint with
+/// `#[collections(NAME: IndexedStore<K, V>)]`, which also defines a `NAME` constant to access it.
+///
+/// Its keys can be scanned (in an arbitrary, but deterministic order) or drained in batches,
+/// with every loaded entry being costed.
+pub struct IndexedStore<K, V> {
+    collection_index: CollectionIndex,
+    key: PhantomData<K>,
+    value: PhantomData<V>,
+}
+
+/// The position of a page of keys in an [`IndexedStore`], as returned by
+/// [`IndexedStore::keys_page()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ScryptoSbor)]
+pub struct IndexedStoreCursor {
+    offset: u32,
+}
+
+impl IndexedStoreCursor {
+    /// The cursor of the first page.
+    pub const START: Self = Self { offset: 0 };
+}
+
+impl<K: ScryptoEncode + ScryptoDecode, V: ScryptoEncode + ScryptoDecode> IndexedStore<K, V> {
+    /// Refers to the index collection at the given position in the blueprint's state schema.
+    ///
+    /// This is normally only called by the code generated for the `#[collections(...)]`
+    /// blueprint attribute.
+    pub const fn new(collection_index: CollectionIndex) -> Self {
+        Self {
+            collection_index,
+            key: PhantomData,
+            value: PhantomData,
+        }
+    }
+
+    /// Inserts a new key-value pair, replacing the previous value if the key already exists.
+    pub fn insert(&self, key: K, value: V) {
+        ScryptoVmV1Api::actor_index_insert(
+            ACTOR_STATE_SELF,
+            self.collection_index,
+            scrypto_encode(&key).unwrap(),
+            scrypto_encode(&value).unwrap(),
+        );
+    }
+
+    /// Removes an entry and returns its value, if it exists.
+    pub fn remove(&self, key: &K) -> Option<V> {
+        ScryptoVmV1Api::actor_index_remove(
+            ACTOR_STATE_SELF,
+            self.collection_index,
+            scrypto_encode(key).unwrap(),
+        )
+        .map(|value| scrypto_decode(&value).unwrap())
+    }
+
+    /// Returns up to `limit` keys of this index, starting from its beginning.
+    ///
+    /// Use [`keys_page()`](Self::keys_page) to page through a larger index, or
+    /// [`drain()`](Self::drain) to consume it in batches.
+    pub fn keys(&self, limit: u32) -> Vec<K> {
+        self.keys_page(IndexedStoreCursor::START, limit).0
+    }
+
+    /// Returns up to `limit` keys, starting at the given cursor, along with the cursor of the
+    /// next page (or `None` once the end of the index is reached).
+    ///
+    /// The engine can only scan an index from its beginning, so loading a page costs as much as
+    /// scanning all keys up to its end; only the keys of the page itself are decoded. The order
+    /// of keys is only stable while the index is not modified, so the index should not be
+    /// written to between pages.
+    pub fn keys_page(
+        &self,
+        cursor: IndexedStoreCursor,
+        limit: u32,
+    ) -> (Vec<K>, Option<IndexedStoreCursor>) {
+        let end = cursor.offset.saturating_add(limit);
+        let keys: Vec<K> =
+            ScryptoVmV1Api::actor_index_scan_keys(ACTOR_STATE_SELF, self.collection_index, end)
+                .into_iter()
+                .skip(cursor.offset as usize)
+                .map(|key| scrypto_decode(&key).unwrap())
+                .collect();
+        let next = if limit > 0 && keys.len() == limit as usize && end < u32::MAX {
+            Some(IndexedStoreCursor { offset: end })
+        } else {
+            None
+        };
+        (keys, next)
+    }
+
+    /// Removes up to `limit` entries from this index and returns them.
+    pub fn drain(&self, limit: u32) -> Vec<(K, V)> {
+        ScryptoVmV1Api::actor_index_drain(ACTOR_STATE_SELF, self.collection_index, limit)
+            .into_iter()
+            .map(|(key, value)| {
+                (
+                    scrypto_decode(&key).unwrap(),
+                    scrypto_decode(&value).unwrap(),
+                )
+            })
+            .collect()
+    }
+}
@@ -1,15 +1,19 @@
 #![allow(clippy::module_inception)]
 
 mod component;
+mod indexed_store;
 mod kv_store;
 mod kv_store_data_ref;
 mod object;
 mod package;
+mod sorted_store;
 mod stubs;
 
 pub use component::*;
+pub use indexed_store::*;
 pub use kv_store::*;
 pub use kv_store_data_ref::*;
 pub use object::*;
 pub use package::*;
+pub use sorted_store::*;
 pub use stubs::*;
@@ -0,0 +1,70 @@
+use crate::engine::scrypto_env::ScryptoVmV1Api;
+use radix_common::data::scrypto::*;
+use radix_engine_interface::api::{CollectionIndex, ACTOR_STATE_SELF};
+use sbor::rust::marker::PhantomData;
+use sbor::rust::prelude::*;
+
+/// An ordered key-value collection, stored in a sorted index partition of the current component.
+///
+/// It is declared on the blueprint with `#[collections(NAME: SortedStore<K, V>)]`, which also
+/// defines a `NAME` constant to access it.
+///
+/// Every entry is stored under a `u16` sort prefix and its key. Entries are ordered by the sort
+/// prefix only: entries sharing a sort prefix come in an arbitrary, but deterministic order
+/// (derived from a hash of the key), not in the order of their keys. This makes it suitable for
+/// priority queues such as order books, where the sort prefix is the priority (e.g. a price tick).
+pub struct SortedStore<K, V> {
+    collection_index: CollectionIndex,
+    key: PhantomData<K>,
+    value: PhantomData<V>,
+}
+
+impl<K: ScryptoEncode + ScryptoDecode, V: ScryptoEncode + ScryptoDecode> SortedStore<K, V> {
+    /// Refers to the sorted index collection at the given position in the blueprint's state
+    /// schema.
+    ///
+    /// This is normally only called by the code generated for the `#[collections(...)]`
+    /// blueprint attribute.
+    pub const fn new(collection_index: CollectionIndex) -> Self {
+        Self {
+            collection_index,
+            key: PhantomData,
+            value: PhantomData,
+        }
+    }
+
+    /// Inserts a new entry, replacing the previous value if the (sort prefix, key) pair already
+    /// exists.
+    pub fn insert(&self, sort_prefix: u16, key: K, value: V) {
+        ScryptoVmV1Api::actor_sorted_index_insert(
+            ACTOR_STATE_SELF,
+            self.collection_index,
+            (sort_prefix.to_be_bytes(), scrypto_encode(&key).unwrap()),
+            scrypto_encode(&value).unwrap(),
+        );
+    }
+
+    /// Removes an entry and returns its value, if it exists.
+    pub fn remove(&self, sort_prefix: u16, key: &K) -> Option<V> {
+        ScryptoVmV1Api::actor_sorted_index_remove(
+            ACTOR_STATE_SELF,
+            self.collection_index,
+            (sort_prefix.to_be_bytes(), scrypto_encode(key).unwrap()),
+        )
+        .map(|value| scrypto_decode(&value).unwrap())
+    }
+
+    /// Returns the first `count` entries, in order, as `(sort prefix, key, value)` triples.
+    pub fn first(&self, count: u32) -> Vec<(u16, K, V)> {
+        ScryptoVmV1Api::actor_sorted_index_scan(ACTOR_STATE_SELF, self.collection_index, count)
+            .into_iter()
+            .map(|((sort_prefix, key), value)| {
+                (
+                    u16::from_be_bytes(sort_prefix),
+                    scrypto_decode(&key).unwrap(),
+                    scrypto_decode(&value).unwrap(),
+                )
+            })
+            .collect()
+    }
+}
@@ -2,10 +2,11 @@ use crate::engine::wasm_api::*;
 use radix_common::crypto::Hash;
 use radix_common::data::scrypto::*;
 use radix_common::math::Decimal;
-use radix_common::types::GlobalAddressReservation;
+use radix_common::types::{GlobalAddressReservation, SortedKey};
 use radix_engine_interface::api::actor_api::EventFlags;
 use radix_engine_interface::api::key_value_entry_api::KeyValueEntryHandle;
 use radix_engine_interface::api::{ActorRefHandle, FieldValue};
+use radix_engine_interface::api::{ActorStateHandle, CollectionIndex};
 use radix_engine_interface::api::{AttachedModuleId, FieldIndex, LockFlags};
 use radix_engine_interface::types::PackageAddress;
 use radix_engine_interface::types::{BlueprintId, GlobalAddress};
@@ -227,6 +228,111 @@ impl ScryptoVmV1Api {
         };
     }
 
+    pub fn actor_index_insert(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    ) {
+        unsafe {
+            actor::actor_index_insert(
+                object_handle,
+                u32::from(collection_index),
+                key.as_ptr(),
+                key.len(),
+                value.as_ptr(),
+                value.len(),
+            )
+        };
+    }
+
+    pub fn actor_index_remove(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        key: Vec<u8>,
+    ) -> Option<Vec<u8>> {
+        let removed = copy_buffer(unsafe {
+            actor::actor_index_remove(
+                object_handle,
+                u32::from(collection_index),
+                key.as_ptr(),
+                key.len(),
+            )
+        });
+        scrypto_decode(&removed).unwrap()
+    }
+
+    pub fn actor_index_scan_keys(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        limit: u32,
+    ) -> Vec<Vec<u8>> {
+        let keys = copy_buffer(unsafe {
+            actor::actor_index_scan_keys(object_handle, u32::from(collection_index), limit)
+        });
+        scrypto_decode(&keys).unwrap()
+    }
+
+    pub fn actor_index_drain(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        limit: u32,
+    ) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let entries = copy_buffer(unsafe {
+            actor::actor_index_drain(object_handle, u32::from(collection_index), limit)
+        });
+        scrypto_decode(&entries).unwrap()
+    }
+
+    pub fn actor_sorted_index_insert(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        sorted_key: SortedKey,
+        value: Vec<u8>,
+    ) {
+        let (sort_prefix, key) = sorted_key;
+        unsafe {
+            actor::actor_sorted_index_insert(
+                object_handle,
+                u32::from(collection_index),
+                u32::from(u16::from_be_bytes(sort_prefix)),
+                key.as_ptr(),
+                key.len(),
+                value.as_ptr(),
+                value.len(),
+            )
+        };
+    }
+
+    pub fn actor_sorted_index_remove(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        sorted_key: SortedKey,
+    ) -> Option<Vec<u8>> {
+        let (sort_prefix, key) = sorted_key;
+        let removed = copy_buffer(unsafe {
+            actor::actor_sorted_index_remove(
+                object_handle,
+                u32::from(collection_index),
+                u32::from(u16::from_be_bytes(sort_prefix)),
+                key.as_ptr(),
+                key.len(),
+            )
+        });
+        scrypto_decode(&removed).unwrap()
+    }
+
+    pub fn actor_sorted_index_scan(
+        object_handle: ActorStateHandle,
+        collection_index: CollectionIndex,
+        count: u32,
+    ) -> Vec<(SortedKey, Vec<u8>)> {
+        let entries = copy_buffer(unsafe {
+            actor::actor_sorted_index_scan(object_handle, u32::from(collection_index), count)
+        });
+        scrypto_decode(&entries).unwrap()
+    }
+
     pub fn field_entry_read(lock_handle: SubstateHandle) -> Vec<u8> {
         copy_buffer(unsafe { field_entry::field_entry_read(lock_handle) })
     }
@@ -174,6 +174,65 @@ pub mod actor {
             event_data_len: usize,
             event_flags: u32,
         );
+
+        /// Inserts an entry into an index collection of the current actor
+        pub fn actor_index_insert(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            key_ptr: *const u8,
+            key_len: usize,
+            value_ptr: *const u8,
+            value_len: usize,
+        );
+
+        /// Removes an entry from an index collection of the current actor
+        pub fn actor_index_remove(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            key_ptr: *const u8,
+            key_len: usize,
+        ) -> Buffer;
+
+        /// Scans up to `limit` keys of an index collection of the current actor
+        pub fn actor_index_scan_keys(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            limit: u32,
+        ) -> Buffer;
+
+        /// Removes and returns up to `limit` entries of an index collection of the current actor
+        pub fn actor_index_drain(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            limit: u32,
+        ) -> Buffer;
+
+        /// Inserts an entry into a sorted index collection of the current actor
+        pub fn actor_sorted_index_insert(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            sort_prefix: u32,
+            key_ptr: *const u8,
+            key_len: usize,
+            value_ptr: *const u8,
+            value_len: usize,
+        );
+
+        /// Removes an entry from a sorted index collection of the current actor
+        pub fn actor_sorted_index_remove(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            sort_prefix: u32,
+            key_ptr: *const u8,
+            key_len: usize,
+        ) -> Buffer;
+
+        /// Scans the first `count` entries of a sorted index collection of the current actor
+        pub fn actor_sorted_index_scan(
+            actor_state_handle: ActorStateHandle,
+            collection_index: u32,
+            count: u32,
+        ) -> Buffer;
     }
 }
 